@@ -10,6 +10,6 @@ fn main() {
     }
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(not(target_os = "windows"))]
 fn main() {
 }
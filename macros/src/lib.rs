@@ -1,5 +1,7 @@
 mod log;
 mod loggable;
+mod service_message;
+mod timed;
 mod traceable;
 
 use proc_macro::TokenStream;
@@ -14,6 +16,16 @@ pub fn loggable(input: TokenStream) -> TokenStream {
     loggable::loggable_impl(input)
 }
 
+#[proc_macro_derive(ServiceMessage, attributes(message, response))]
+pub fn service_message(input: TokenStream) -> TokenStream {
+    service_message::service_message_impl(input)
+}
+
+#[proc_macro_attribute]
+pub fn timed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    timed::timed_impl(attr, item)
+}
+
 #[proc_macro]
 pub fn traceable(input: TokenStream) -> TokenStream {
     traceable::traceable_impl(input)
@@ -0,0 +1,217 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Ident, LitInt, Type};
+
+enum MessageKind {
+    Command,
+    Query(Type),
+    Event(EventOptions),
+}
+
+#[derive(Default)]
+struct EventOptions {
+    policy: Option<Ident>,
+    replay: Option<LitInt>,
+    coalesce: Option<Ident>,
+}
+
+fn parse_kind(input: &DeriveInput) -> syn::Result<MessageKind> {
+    let attr = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("message"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                "Expected #[message(command)], #[message(query = ...)] or #[message(event)]",
+            )
+        })?;
+
+    let mut kind = None;
+    let mut options = EventOptions::default();
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("command") {
+            kind = Some(MessageKind::Command);
+        } else if meta.path.is_ident("query") {
+            kind = Some(MessageKind::Query(meta.value()?.parse::<Type>()?));
+        } else if meta.path.is_ident("event") {
+            kind = Some(MessageKind::Event(EventOptions::default()));
+        } else if meta.path.is_ident("policy") {
+            options.policy = Some(meta.value()?.parse::<Ident>()?);
+        } else if meta.path.is_ident("replay") {
+            options.replay = Some(meta.value()?.parse::<LitInt>()?);
+        } else if meta.path.is_ident("coalesce") {
+            options.coalesce = Some(meta.value()?.parse::<Ident>()?);
+        } else {
+            return Err(meta.error("Unknown message option"));
+        }
+        Ok(())
+    })?;
+
+    let event_options =
+        options.policy.is_some() || options.replay.is_some() || options.coalesce.is_some();
+    match kind {
+        Some(MessageKind::Event(_)) => Ok(MessageKind::Event(options)),
+        Some(_) if event_options => Err(syn::Error::new_spanned(
+            attr,
+            "policy, replay and coalesce only apply to events",
+        )),
+        Some(kind) => Ok(kind),
+        None => Err(syn::Error::new_spanned(attr, "Expected command, query or event")),
+    }
+}
+
+fn command_impl(name: &Ident) -> TokenStream2 {
+    quote! {
+        impl crate::interface::communication::message::Message for #name {
+            type Response = ();
+        }
+
+        impl crate::interface::communication::command::Command for #name {}
+
+        impl<S> crate::interface::communication::message::ServiceMessage<S> for #name
+        where
+            S: crate::interface::communication::command::CommandHandler<#name>
+                + Send
+                + Sync
+                + 'static,
+        {
+            fn register(
+                registrar: crate::core::infrastructure::communication_manager::ServiceRegistrar<S>,
+            ) -> crate::core::infrastructure::communication_manager::ServiceRegistrar<S> {
+                registrar.command::<Self>()
+            }
+        }
+    }
+}
+
+fn query_impl(name: &Ident, response: &Type) -> TokenStream2 {
+    quote! {
+        impl crate::interface::communication::message::Message for #name {
+            type Response = #response;
+        }
+
+        impl crate::interface::communication::query::Query for #name {}
+
+        impl<S> crate::interface::communication::message::ServiceMessage<S> for #name
+        where
+            S: crate::interface::communication::query::QueryHandler<#name>
+                + Send
+                + Sync
+                + 'static,
+        {
+            fn register(
+                registrar: crate::core::infrastructure::communication_manager::ServiceRegistrar<S>,
+            ) -> crate::core::infrastructure::communication_manager::ServiceRegistrar<S> {
+                registrar.query::<Self>()
+            }
+        }
+    }
+}
+
+// Each variant of a query enum names what it answers with #[response(T)], the response enum
+// gets a variant of the same name holding it
+fn response_enum(input: &DeriveInput, response: &Type) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Ok(TokenStream2::new());
+    };
+    let message = "Expected the name of the response enum";
+    let Type::Path(path) = response else {
+        return Err(syn::Error::new_spanned(response, message));
+    };
+    let name = path
+        .path
+        .get_ident()
+        .ok_or_else(|| syn::Error::new_spanned(response, message))?;
+
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| {
+            let attr = variant
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("response"))
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(&variant.ident, "Expected #[response(...)]")
+                })?;
+            let ty = attr.parse_args::<Type>()?;
+            let ident = &variant.ident;
+            Ok(quote! { #ident(#ty) })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let vis = &input.vis;
+    let allows = input.attrs.iter().filter(|attr| attr.path().is_ident("allow"));
+
+    Ok(quote! {
+        #(#allows)*
+        #vis enum #name {
+            #(#variants,)*
+        }
+    })
+}
+
+fn event_impl(name: &Ident, options: &EventOptions) -> TokenStream2 {
+    let policy = options.policy.as_ref().map(|policy| {
+        quote! {
+            const OVERFLOW_POLICY:
+                crate::model::core::infrastructure::event_broadcaster::OverflowPolicy =
+                crate::model::core::infrastructure::event_broadcaster::OverflowPolicy::#policy;
+        }
+    });
+    let replay = options.replay.as_ref().map(|replay| {
+        quote! {
+            const REPLAY_CAPACITY: usize = #replay;
+        }
+    });
+    let coalesce = options.coalesce.as_ref().map(|field| {
+        quote! {
+            fn coalesce_key(&self) -> Option<uuid::Uuid> {
+                Some(self.#field)
+            }
+        }
+    });
+
+    quote! {
+        impl crate::interface::communication::event::Event for #name {
+            #policy
+            #replay
+            #coalesce
+        }
+
+        impl<S> crate::interface::communication::message::ServiceMessage<S> for #name
+        where
+            S: Send + Sync + 'static,
+        {
+            fn register(
+                registrar: crate::core::infrastructure::communication_manager::ServiceRegistrar<S>,
+            ) -> crate::core::infrastructure::communication_manager::ServiceRegistrar<S> {
+                registrar.event::<Self>()
+            }
+        }
+    }
+}
+
+pub fn service_message_impl(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let expanded = match parse_kind(&input) {
+        Ok(MessageKind::Command) => command_impl(name),
+        Ok(MessageKind::Query(response)) => match response_enum(&input, &response) {
+            Ok(response_enum) => {
+                let query = query_impl(name, &response);
+                quote! {
+                    #response_enum
+                    #query
+                }
+            }
+            Err(err) => err.to_compile_error(),
+        },
+        Ok(MessageKind::Event(options)) => event_impl(name, &options),
+        Err(err) => err.to_compile_error(),
+    };
+
+    TokenStream::from(expanded)
+}
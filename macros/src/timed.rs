@@ -0,0 +1,110 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, GenericArgument, ItemFn, LitStr, PathArguments, ReturnType, Type,
+    TypeParamBound,
+};
+
+pub fn timed_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let operation = if attr.is_empty() {
+        None
+    } else {
+        Some(parse_macro_input!(attr as LitStr))
+    };
+    let function = parse_macro_input!(item as ItemFn);
+
+    let attrs = &function.attrs;
+    let vis = &function.vis;
+    let sig = &function.sig;
+    let block = &function.block;
+    let operation = operation
+        .unwrap_or_else(|| LitStr::new(&sig.ident.to_string(), sig.ident.span()));
+    let ReturnType::Type(_, output) = &sig.output else {
+        let message = "#[timed] expects a function returning a Result";
+        return syn::Error::new_spanned(sig, message).to_compile_error().into();
+    };
+
+    let body = if sig.asyncness.is_some() {
+        // The body runs in its own async block, so its returns and ? end there and are measured too
+        let measured = measure(&operation, quote! {
+            async move {
+                let __timed_result: #output = #block;
+                __timed_result
+            }
+        });
+        quote! {
+            let __timed_result: #output = #measured;
+            __timed_result
+        }
+    } else if let Some(output) = future_output(output) {
+        // Already turned into a boxed future by #[async_trait], which is awaited in a new one
+        let measured = measure(&operation, quote! { #block });
+        quote! {
+            Box::pin(async move {
+                let __timed_result: #output = #measured;
+                __timed_result
+            })
+        }
+    } else {
+        let message = "#[timed] only applies to async functions";
+        return syn::Error::new_spanned(sig, message).to_compile_error().into();
+    };
+
+    quote! {
+        #(#attrs)*
+        #vis #sig {
+            #body
+        }
+    }
+    .into()
+}
+
+fn measure(operation: &LitStr, future: TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            let __timed_started = std::time::Instant::now();
+            let __timed_result = #future.await;
+            crate::utils::operation_metrics::record_operation(
+                module_path!(),
+                #operation,
+                __timed_started.elapsed(),
+                __timed_result.is_ok(),
+            );
+            __timed_result
+        }
+    }
+}
+
+// The Output of the Pin<Box<dyn Future<Output = T> + Send>> returned by an #[async_trait] method
+fn future_output(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                return None;
+            };
+            arguments.args.iter().find_map(|argument| match argument {
+                GenericArgument::Type(inner) => future_output(inner),
+                _ => None,
+            })
+        }
+        Type::TraitObject(trait_object) => trait_object.bounds.iter().find_map(|bound| {
+            let TypeParamBound::Trait(bound) = bound else {
+                return None;
+            };
+            let segment = bound.path.segments.last()?;
+            if segment.ident != "Future" {
+                return None;
+            }
+            let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                return None;
+            };
+            arguments.args.iter().find_map(|argument| match argument {
+                GenericArgument::AssocType(assoc) if assoc.ident == "Output" => Some(&assoc.ty),
+                _ => None,
+            })
+        }),
+        _ => None,
+    }
+}
@@ -0,0 +1,51 @@
+use crate::interface::core::io_priority::ProcessWeightTrait;
+use crate::platform::io_priority::ProcessWeight;
+use std::sync::{Arc, Mutex, PoisonError};
+
+// The process weight stays lowered while at least one background run is active
+pub struct BackgroundWeight {
+    state: Mutex<WeightState>,
+}
+
+#[derive(Default)]
+struct WeightState {
+    holders: usize,
+    weight: Option<ProcessWeight>,
+}
+
+impl BackgroundWeight {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(WeightState::default()),
+        }
+    }
+
+    pub fn hold(self: &Arc<Self>) -> BackgroundWeightHold {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.holders += 1;
+        if state.holders == 1 {
+            state.weight = ProcessWeight::lower();
+        }
+        BackgroundWeightHold {
+            background_weight: self.clone(),
+        }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.holders -= 1;
+        if state.holders == 0 {
+            state.weight = None;
+        }
+    }
+}
+
+pub struct BackgroundWeightHold {
+    background_weight: Arc<BackgroundWeight>,
+}
+
+impl Drop for BackgroundWeightHold {
+    fn drop(&mut self) {
+        self.background_weight.release();
+    }
+}
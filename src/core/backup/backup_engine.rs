@@ -1,52 +1,154 @@
+use crate::core::backup::background_weight::BackgroundWeight;
+use crate::core::backup::catalog_search_service::CatalogSearchService;
+use crate::core::backup::change_detector::ChangeDetector;
+use crate::core::backup::concurrency_controller::{ADJUST_INTERVAL, ConcurrencyController};
+use crate::core::backup::locked_files::LockedFiles;
 use crate::core::backup::progress_tracker::ProgressTracker;
+use crate::core::backup::manifest_service::ManifestService;
+use crate::core::backup::report_service::ReportService;
+use crate::core::backup::scan_service::ScanService;
+use crate::core::backup::share_reconnector::{ShareReconnector, ShareState};
+use crate::core::backup::sleep_inhibitor::SleepInhibitor;
+use crate::core::backup::storage_usage_service::StorageUsageService;
+use crate::core::backup::work_queue::WorkQueue;
 use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::core::infrastructure::database_manager::DatabaseManager;
 use crate::core::infrastructure::io_manager::IOManager;
 use crate::interface::communication::command::CommandHandler;
+use crate::interface::communication::event::Event;
 use crate::interface::communication::query::QueryHandler;
 use crate::interface::core::file_system::FileSystemTrait;
+use crate::interface::core::io_priority::BACKGROUND_IO;
+use crate::interface::repository::comparison_cache::ComparisonCacheRepository;
+use crate::interface::repository::directory_signature::DirectorySignatureRepository;
+use crate::interface::repository::event_journal::EventJournalRepository;
+use crate::interface::repository::execution_history::ExecutionHistoryRepository;
+use crate::interface::repository::renamed_entry::RenamedEntryRepository;
+use crate::model::core::backup::capability_report::{CapabilityReport, EndpointCapabilities};
+use crate::model::core::backup::case_sensitivity::CaseSensitivity;
+use crate::model::core::backup::catalog::CatalogAction;
+use crate::model::core::backup::change_set::{ChangeSet, DirectoryScope};
+use crate::model::core::backup::comparison_cache::{ComparisonCache, ComparisonRecord};
+use crate::model::core::backup::comparison_stats::{ComparisonCounters, ComparisonStats};
+use crate::model::core::backup::copy_progress::CopyProgress;
+use crate::model::core::backup::destination_capabilities::DestinationCapabilities;
+use crate::model::core::backup::directory_signature::DirectorySignature;
 use crate::model::core::backup::execution::*;
+use crate::model::core::backup::execution_counters::ExecutionCounters;
+use crate::model::core::backup::execution_history::{ExecutionHistory, StoredErrorPolicy};
+use crate::model::core::infrastructure::event_journal::{JournalEntry, JournalEvent};
+use crate::model::core::backup::manifest::MANIFEST_NAME;
+use crate::model::core::backup::progress_data::ProgressData;
+use crate::model::core::backup::transfer_counters::TransferCounters;
 use crate::model::core::backup::communication::*;
+use crate::model::core::schedule::communication::{
+    ScheduleManagerQuery, ScheduleManagerQueryResponse,
+};
+use crate::model::error::database::DatabaseError;
+use crate::model::error::io::IOError;
 use crate::model::error::system::SystemError;
 use crate::model::error::task::TaskError;
 use crate::model::error::Error;
+use crate::model::error::record::ErrorRecord;
+use crate::model::log::task::TaskLog;
+use crate::platform::file_system::{
+    available_space, is_locked, is_read_only_volume, normalize_path, resolve_volume_path,
+    supports_symlinks, volume_features,
+};
+use crate::utils::case_probe::{
+    is_case_insensitive, is_directory_case_insensitive, is_source_case_insensitive,
+};
+use crate::utils::portable_name::portable_name;
+use crate::utils::timestamp_probe::{sampled_resolution, written_resolution};
+use crate::utils::unicode_name::{comparison_key, folded_name, normalized_variants};
 use async_trait::async_trait;
+use chrono::Local;
 use crossbeam_queue::SegQueue;
-use dashmap::DashMap;
-use futures::future::join_all;
+use dashmap::{DashMap, DashSet};
+use futures::future::{FutureExt, JoinAll, join_all};
+use futures::stream::{self, StreamExt};
 use macros::log;
-use std::collections::{HashSet, VecDeque};
+use privilege::user::privileged;
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::mem;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
-use tokio::task::JoinHandle;
+use tokio::fs;
+use tokio::task::{JoinError, JoinHandle, spawn_blocking};
+use tokio::time::{MissedTickBehavior, interval, sleep, timeout};
 use tracing::error;
 use uuid::Uuid;
-use crate::model::core::gui::communication::{ExecutionErrors, ExecutionProgress, FolderProcess};
+use crate::model::core::gui::communication::{
+    DestinationFull, ExecutionErrors, ExecutionFailed, ExecutionProgress, FileProgress,
+    FolderProcess, VerificationFinished,
+};
+
+// Below this many entries in the destination root only an empty source is refused
+const MIRROR_GUARD_MIN_ENTRIES: usize = 10;
 
 pub struct BackupEngine {
     app_config: Arc<AppConfig>,
     io_manager: Arc<IOManager>,
+    database_manager: Arc<DatabaseManager>,
     communication_manager: Arc<CommunicationManager>,
     progress_tracker: Arc<ProgressTracker>,
+    change_detector: Arc<ChangeDetector>,
+    scan_service: Arc<ScanService>,
+    manifest_service: Arc<ManifestService>,
+    report_service: Arc<ReportService>,
+    catalog_search_service: Arc<CatalogSearchService>,
+    storage_usage_service: Arc<StorageUsageService>,
+    sleep_inhibitor: Arc<SleepInhibitor>,
+    background_weight: Arc<BackgroundWeight>,
     executions: Arc<DashMap<Uuid, Execution>>,
-    running_executions: Arc<DashMap<Uuid, (oneshot::Sender<()>, JoinHandle<()>)>>,
+    running_executions: Arc<DashMap<Uuid, (oneshot::Sender<StopRequest>, JoinHandle<()>)>>,
+    worker_limits: Arc<DashMap<Uuid, WorkerLimit>>,
+    concurrency_controllers: Arc<DashMap<Uuid, Arc<ConcurrencyController>>>,
 }
 
 impl BackupEngine {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         app_config: Arc<AppConfig>,
         io_manager: Arc<IOManager>,
+        database_manager: Arc<DatabaseManager>,
         communication_manager: Arc<CommunicationManager>,
         progress_tracker: Arc<ProgressTracker>,
+        change_detector: Arc<ChangeDetector>,
+        scan_service: Arc<ScanService>,
+        manifest_service: Arc<ManifestService>,
+        report_service: Arc<ReportService>,
+        catalog_search_service: Arc<CatalogSearchService>,
+        storage_usage_service: Arc<StorageUsageService>,
     ) -> Self {
+        let sleep_inhibitor = Arc::new(SleepInhibitor::new(&app_config));
         Self {
             app_config,
             io_manager,
+            database_manager,
             communication_manager,
             progress_tracker,
+            change_detector,
+            scan_service,
+            manifest_service,
+            report_service,
+            catalog_search_service,
+            storage_usage_service,
+            sleep_inhibitor,
+            background_weight: Arc::new(BackgroundWeight::new()),
             executions: Arc::new(DashMap::new()),
             running_executions: Arc::new(DashMap::new()),
+            worker_limits: Arc::new(DashMap::new()),
+            concurrency_controllers: Arc::new(DashMap::new()),
         }
     }
 
@@ -54,11 +156,17 @@ impl BackupEngine {
         let communication_manager = self.communication_manager.clone();
         communication_manager
             .with_service(self)
-            .command::<BackupCommand>()
-            .query::<BackupQuery>()
-            .event::<FolderProcess>()
-            .event::<ExecutionProgress>()
-            .event::<ExecutionErrors>()
+            .register_all::<(
+                BackupCommand,
+                BackupQuery,
+                FolderProcess,
+                ExecutionProgress,
+                FileProgress,
+                ExecutionErrors,
+                ExecutionFailed,
+                DestinationFull,
+                VerificationFinished,
+            )>()
             .build();
     }
 
@@ -70,7 +178,7 @@ impl BackupEngine {
             .collect();
         for uuid in keys {
             if let Some((_, (shutdown, handle))) = self.running_executions.remove(&uuid) {
-                if shutdown.send(()).is_err() {
+                if shutdown.send(StopRequest::Suspend).is_err() {
                     log!(SystemError::ShutdownSignalFailed);
                     continue;
                 }
@@ -81,6 +189,14 @@ impl BackupEngine {
         }
     }
 
+    // Runs cut off by a crash or shutdown come back suspended and resume from their checkpoint
+    pub async fn restore_executions(&self) {
+        for mut execution in self.progress_tracker.saved_executions().await {
+            execution.state = BackupState::Suspended;
+            self.executions.entry(execution.uuid).or_insert(execution);
+        }
+    }
+
     pub fn get_all_executions(&self) -> Vec<(Uuid, Execution)> {
         self.executions
             .iter()
@@ -88,12 +204,50 @@ impl BackupEngine {
             .collect()
     }
 
-    pub async fn add_execution(&self, execution: Execution) {
+    pub async fn add_execution(&self, execution: Execution) -> Result<(), Error> {
+        Self::validate_paths(&execution).await?;
         self.executions.insert(execution.uuid, execution);
+        Ok(())
     }
 
     pub async fn remove_execution(&self, uuid: &Uuid) {
-        self.executions.remove(uuid);
+        let removed = self.executions.remove(uuid);
+        self.worker_limits.remove(uuid);
+        // Its checkpoint would otherwise bring a suspended execution back on the next start
+        if let Some((_, execution)) = removed
+            && execution.state == BackupState::Suspended
+        {
+            self.to_execution_runner().discard_progress(*uuid, false).await;
+        }
+        if let Err(err) = self.release_change_journals().await {
+            error!("{}", err);
+        }
+    }
+
+    // Scheduled executions are covered by their schedule, which outlives them
+    pub async fn release_change_journals(&self) -> Result<(), Error> {
+        let response = self
+            .communication_manager
+            .send_query(ScheduleManagerQuery::GetSchedules)
+            .await?;
+        let ScheduleManagerQueryResponse::GetSchedules(schedules) = response else {
+            return Ok(());
+        };
+        let mut in_use: HashSet<PathBuf> = schedules
+            .iter()
+            .map(|schedule| normalize_path(&schedule.source_path))
+            .collect();
+        in_use.extend(
+            self.executions
+                .iter()
+                .filter(|execution| {
+                    execution.schedule_uuid.is_none()
+                        || self.running_executions.contains_key(&execution.uuid)
+                })
+                .map(|execution| normalize_path(&execution.source_path)),
+        );
+        self.change_detector.release_unused(&in_use);
+        Ok(())
     }
 
     pub async fn start_execution(&self, uuid: &Uuid) -> Result<(), Error> {
@@ -101,6 +255,23 @@ impl BackupEngine {
             Err(TaskError::IllegalRunState)?
         }
 
+        // Directories may have been moved or linked since the execution was added
+        let mut execution = self
+            .executions
+            .get(uuid)
+            .map(|execution| execution.clone())
+            .ok_or(TaskError::ExecutionNotFound)?;
+        if execution.state != BackupState::Pending {
+            Err(TaskError::IllegalRunState)?
+        }
+        Self::check_elevation(&execution)?;
+        let (source_path, destination_path) = Self::resolve_paths(&execution).await?;
+        execution.source_path = source_path.clone();
+        execution.destination_path = destination_path.clone();
+        Self::validate_paths(&execution).await?;
+        self.preflight_destination(&execution).await?;
+        self.preflight_mirror(&execution).await?;
+
         let mut ref_mut = self
             .executions
             .get_mut(uuid)
@@ -112,8 +283,10 @@ impl BackupEngine {
         execution.state = BackupState::Running;
 
         let execution_runner = self.to_execution_runner();
-        let execution = execution.clone();
+        let mut execution = execution.clone();
+        execution.destination_path = destination_path;
         let (tx, rx) = oneshot::channel();
+        execution.source_path = source_path;
         let handle = tokio::spawn(async move { execution_runner.run(execution, rx, false).await });
         self.running_executions.insert(*uuid, (tx, handle));
         Ok(())
@@ -136,7 +309,42 @@ impl BackupEngine {
             .remove(uuid)
             .ok_or(TaskError::ExecutionNotFound)?;
         shutdown
-            .send(())
+            .send(StopRequest::Suspend)
+            .map_err(|_| SystemError::ShutdownSignalFailed)?;
+        handle.await.map_err(SystemError::ThreadPanic)?;
+        Ok(())
+    }
+
+    pub async fn cancel_execution(&self, uuid: &Uuid, discard_partial: bool) -> Result<(), Error> {
+        let mut ref_mut = self
+            .executions
+            .get_mut(uuid)
+            .ok_or(TaskError::ExecutionNotFound)?;
+        let execution = ref_mut.value_mut();
+        let state = execution.state;
+        if state != BackupState::Running && state != BackupState::Suspended {
+            Err(TaskError::IllegalRunState)?
+        }
+        execution.state = BackupState::Canceled;
+        let execution = execution.clone();
+        drop(ref_mut);
+
+        // Without a runner there is no end of run to record the cancel
+        if state == BackupState::Suspended {
+            let execution_runner = self.to_execution_runner();
+            execution_runner.discard_progress(*uuid, discard_partial).await;
+            execution_runner
+                .journal(JournalEvent::ExecutionCanceled, &execution, "Canceled while suspended")
+                .await;
+            return Ok(());
+        }
+
+        let (_, (shutdown, handle)) = self
+            .running_executions
+            .remove(uuid)
+            .ok_or(TaskError::ExecutionNotFound)?;
+        shutdown
+            .send(StopRequest::Cancel { discard_partial })
             .map_err(|_| SystemError::ShutdownSignalFailed)?;
         handle.await.map_err(SystemError::ThreadPanic)?;
         Ok(())
@@ -147,6 +355,22 @@ impl BackupEngine {
             Err(TaskError::IllegalRunState)?
         }
 
+        let mut execution = self
+            .executions
+            .get(uuid)
+            .map(|execution| execution.clone())
+            .ok_or(TaskError::ExecutionNotFound)?;
+        if execution.state != BackupState::Suspended {
+            Err(TaskError::IllegalRunState)?
+        }
+        Self::check_elevation(&execution)?;
+        let (source_path, destination_path) = Self::resolve_paths(&execution).await?;
+        execution.source_path = source_path.clone();
+        execution.destination_path = destination_path.clone();
+        Self::validate_paths(&execution).await?;
+        self.preflight_destination(&execution).await?;
+        self.preflight_mirror(&execution).await?;
+
         let mut ref_mut = self
             .executions
             .get_mut(uuid)
@@ -158,241 +382,1865 @@ impl BackupEngine {
         execution.state = BackupState::Running;
 
         let execution_runner = self.to_execution_runner();
-        let execution = execution.clone();
+        let mut execution = execution.clone();
+        execution.destination_path = destination_path;
         let (tx, rx) = oneshot::channel();
+        execution.source_path = source_path;
         let handle = tokio::spawn(async move { execution_runner.run(execution, rx, true).await });
         self.running_executions.insert(*uuid, (tx, handle));
         Ok(())
     }
 
+    // The runner works on normalized roots, the stored execution keeps the paths as entered
+    async fn resolve_paths(execution: &Execution) -> Result<(PathBuf, PathBuf), Error> {
+        let destination = Self::resolve_destination(&execution.destination_path).await?;
+        Ok((
+            normalize_path(&execution.source_path),
+            normalize_path(&destination),
+        ))
+    }
+
+    // Volume GUID and label destinations follow the volume to whichever drive letter it got,
+    // the stored execution keeps the volume so every start looks it up again
+    async fn resolve_destination(destination: &Path) -> Result<PathBuf, Error> {
+        let path = destination.to_path_buf();
+        spawn_blocking(move || resolve_volume_path(&path))
+            .await
+            .map_err(SystemError::ThreadPanic)?
+            .ok_or_else(|| {
+                TaskError::VolumeNotPresent {
+                    path: destination.to_path_buf(),
+                }
+                .into()
+            })
+    }
+
+    fn check_elevation(execution: &Execution) -> Result<(), Error> {
+        if execution.options.elevated && !privileged() {
+            Err(TaskError::ElevationRequired)?
+        }
+        Ok(())
+    }
+
+    // Copying a tree into itself recurses without end, mirroring it deletes the source
+    async fn validate_paths(execution: &Execution) -> Result<(), Error> {
+        let source = Self::resolve_path(&normalize_path(&execution.source_path)).await;
+        let destination = Self::resolve_path(&normalize_path(&execution.destination_path)).await;
+        if source == destination {
+            Err(TaskError::IdenticalPaths { path: source })?
+        } else if destination.starts_with(&source) {
+            Err(TaskError::DestinationInsideSource {
+                src: source,
+                dst: destination,
+            })?
+        } else if source.starts_with(&destination) {
+            Err(TaskError::SourceInsideDestination {
+                src: source,
+                dst: destination,
+            })?
+        }
+        Ok(())
+    }
+
+    // Problems of the destination as a whole fail the start once instead of every file
+    async fn preflight_destination(&self, execution: &Execution) -> Result<(), Error> {
+        let destination = &execution.destination_path;
+
+        self.io_manager.create_directory(destination).await?;
+        if is_read_only_volume(destination) {
+            Err(TaskError::DestinationReadOnly {
+                path: destination.clone(),
+            })?
+        }
+
+        let probe_name = format!(".mirrorsphere-preflight-{}", Uuid::new_v4().simple());
+        let probe = destination.join(probe_name);
+        fs::write(&probe, [])
+            .await
+            .map_err(|err| TaskError::DestinationNotWritable(destination, err))?;
+        let _ = fs::remove_file(&probe).await;
+
+        if !execution.options.follow_symlinks && !supports_symlinks(destination) {
+            Err(TaskError::SymlinksUnsupported {
+                path: destination.clone(),
+            })?
+        }
+        Ok(())
+    }
+
+    // An unmounted or emptied source would have mirror mode wipe the destination
+    async fn preflight_mirror(&self, execution: &Execution) -> Result<(), Error> {
+        if !execution.options.mirror {
+            return Ok(());
+        }
+        let source = &execution.source_path;
+        let destination = &execution.destination_path;
+
+        let source_names = Self::entry_names(source)
+            .await
+            .map_err(|err| TaskError::SourceUnreadable(source, err))?;
+        let Ok(destination_names) = Self::entry_names(destination).await else {
+            return Ok(());
+        };
+        let total = destination_names.len();
+        let manifest_name = folded_name(OsStr::new(MANIFEST_NAME));
+        let deleted = destination_names
+            .difference(&source_names)
+            .filter(|name| **name != manifest_name)
+            .count();
+        if source_names.is_empty() && total > 0 {
+            Err(TaskError::SourceEmpty {
+                path: source.clone(),
+            })?
+        }
+        let limit = self.app_config.mirror_max_delete_percentage;
+        if total >= MIRROR_GUARD_MIN_ENTRIES && deleted * 100 > total * limit {
+            Err(TaskError::MirrorDeleteLimitExceeded {
+                path: destination.clone(),
+                deleted,
+                total,
+            })?
+        }
+        Ok(())
+    }
+
+    async fn entry_names(directory: &Path) -> io::Result<HashSet<String>> {
+        let mut entries = fs::read_dir(directory).await?;
+        let mut names = HashSet::new();
+        while let Some(entry) = entries.next_entry().await? {
+            names.insert(folded_name(&entry.file_name()));
+        }
+        Ok(names)
+    }
+
+    // Canonicalizes the longest existing ancestor, the destination may not exist yet
+    async fn resolve_path(path: &Path) -> PathBuf {
+        let mut missing = Vec::new();
+        let mut current = path;
+        loop {
+            if let Ok(resolved) = tokio::fs::canonicalize(current).await {
+                return missing.into_iter().rev().fold(resolved, |path, name| path.join(name));
+            }
+            match (current.parent(), current.file_name()) {
+                (Some(parent), Some(name)) => {
+                    missing.push(name);
+                    current = parent;
+                }
+                _ => return path.to_path_buf(),
+            }
+        }
+    }
+
+    // Runs apart from the execution, the outcome arrives as events once every file is checked
+    pub fn verify_execution(&self, uuid: &Uuid) -> Result<(), Error> {
+        let execution = self
+            .executions
+            .get(uuid)
+            .map(|execution| execution.clone())
+            .ok_or(TaskError::ExecutionNotFound)?;
+        if self.running_executions.contains_key(uuid) {
+            Err(TaskError::IllegalRunState)?
+        }
+
+        let manifest_service = self.manifest_service.clone();
+        let communication_manager = self.communication_manager.clone();
+        tokio::spawn(async move {
+            let (verified, errors) = match manifest_service
+                .verify(&execution.destination_path)
+                .await
+            {
+                Ok(outcome) => outcome,
+                Err(err) => (0, vec![err]),
+            };
+            let failed = errors.len();
+            if !errors.is_empty() {
+                let event = ExecutionErrors {
+                    uuid: execution.uuid,
+                    errors,
+                };
+                if let Err(err) = communication_manager.publish_event(event).await {
+                    error!("{}", err);
+                }
+            }
+            let event = VerificationFinished {
+                uuid: execution.uuid,
+                verified,
+                failed,
+            };
+            if let Err(err) = communication_manager.publish_event(event).await {
+                error!("{}", err);
+            }
+        });
+        Ok(())
+    }
+
+    pub async fn export_report(
+        &self,
+        history: &ExecutionHistory,
+        report_path: &Path,
+    ) -> Result<(), Error> {
+        let errors = self.database_manager.get_execution_errors(history.uuid).await?;
+        self.report_service.write(history, &errors, report_path).await
+    }
+
+    // Kept across suspend and resume, applied to the running workers right away
+    pub fn set_worker_limit(&self, uuid: &Uuid, worker_limit: WorkerLimit) -> Result<(), Error> {
+        if !self.executions.contains_key(uuid) {
+            Err(TaskError::ExecutionNotFound)?
+        }
+        self.worker_limits.insert(*uuid, worker_limit);
+        if let Some(concurrency_controller) = self.concurrency_controllers.get(uuid) {
+            concurrency_controller.set_limit(worker_limit);
+        }
+        Ok(())
+    }
+
     fn to_execution_runner(&self) -> ExecutionRunner {
         let config = self.app_config.clone();
         let io_manager = self.io_manager.clone();
+        let database_manager = self.database_manager.clone();
         let communication_manager = self.communication_manager.clone();
         let progress_tracker = self.progress_tracker.clone();
+        let change_detector = self.change_detector.clone();
+        let scan_service = self.scan_service.clone();
+        let manifest_service = self.manifest_service.clone();
+        let storage_usage_service = self.storage_usage_service.clone();
+        let sleep_inhibitor = self.sleep_inhibitor.clone();
+        let background_weight = self.background_weight.clone();
         let executions = self.executions.clone();
         let running_executions = self.running_executions.clone();
+        let worker_limits = self.worker_limits.clone();
+        let concurrency_controllers = self.concurrency_controllers.clone();
         ExecutionRunner::new(
             config,
             io_manager,
+            database_manager,
             communication_manager,
             progress_tracker,
+            change_detector,
+            scan_service,
+            manifest_service,
+            storage_usage_service,
+            sleep_inhibitor,
+            background_weight,
             executions,
             running_executions,
+            worker_limits,
+            concurrency_controllers,
         )
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StopRequest {
+    Suspend,
+    Cancel { discard_partial: bool },
+    Fail,
+}
+
+const ERROR_RATE_MIN_ENTRIES: usize = 1000;
+// Errors written into one journal entry, the history of the run stores the rest
+const JOURNAL_ERROR_LINES: usize = 20;
+
 struct ExecutionRunner {
     app_config: Arc<AppConfig>,
     io_manager: Arc<IOManager>,
+    database_manager: Arc<DatabaseManager>,
     communication_manager: Arc<CommunicationManager>,
     progress_tracker: Arc<ProgressTracker>,
+    change_detector: Arc<ChangeDetector>,
+    scan_service: Arc<ScanService>,
+    manifest_service: Arc<ManifestService>,
+    storage_usage_service: Arc<StorageUsageService>,
+    sleep_inhibitor: Arc<SleepInhibitor>,
+    background_weight: Arc<BackgroundWeight>,
     executions: Arc<DashMap<Uuid, Execution>>,
-    running_executions: Arc<DashMap<Uuid, (oneshot::Sender<()>, JoinHandle<()>)>>,
+    running_executions: Arc<DashMap<Uuid, (oneshot::Sender<StopRequest>, JoinHandle<()>)>>,
+    worker_limits: Arc<DashMap<Uuid, WorkerLimit>>,
+    concurrency_controllers: Arc<DashMap<Uuid, Arc<ConcurrencyController>>>,
 }
 
 impl ExecutionRunner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         app_config: Arc<AppConfig>,
         io_manager: Arc<IOManager>,
+        database_manager: Arc<DatabaseManager>,
         communication_manager: Arc<CommunicationManager>,
         progress_tracker: Arc<ProgressTracker>,
+        change_detector: Arc<ChangeDetector>,
+        scan_service: Arc<ScanService>,
+        manifest_service: Arc<ManifestService>,
+        storage_usage_service: Arc<StorageUsageService>,
+        sleep_inhibitor: Arc<SleepInhibitor>,
+        background_weight: Arc<BackgroundWeight>,
         executions: Arc<DashMap<Uuid, Execution>>,
-        running_executions: Arc<DashMap<Uuid, (oneshot::Sender<()>, JoinHandle<()>)>>,
+        running_executions: Arc<DashMap<Uuid, (oneshot::Sender<StopRequest>, JoinHandle<()>)>>,
+        worker_limits: Arc<DashMap<Uuid, WorkerLimit>>,
+        concurrency_controllers: Arc<DashMap<Uuid, Arc<ConcurrencyController>>>,
     ) -> Self {
         Self {
             app_config,
             io_manager,
+            database_manager,
             communication_manager,
             progress_tracker,
+            change_detector,
+            scan_service,
+            manifest_service,
+            storage_usage_service,
+            sleep_inhibitor,
+            background_weight,
             executions,
             running_executions,
+            worker_limits,
+            concurrency_controllers,
         }
     }
 
-    async fn run(&self, execution: Execution, mut shutdown: oneshot::Receiver<()>, resume: bool) {
+    async fn run(
+        &self,
+        execution: Execution,
+        mut shutdown: oneshot::Receiver<StopRequest>,
+        resume: bool,
+    ) {
+        let _sleep_hold = self.sleep_inhibitor.hold();
+        let _background_weight_hold = execution
+            .options
+            .background_io
+            .then(|| self.background_weight.hold());
         let config = &self.app_config;
         let progress_tracker = &self.progress_tracker;
+        let change_detector = &self.change_detector;
+        let started_at = Local::now().naive_local();
+        let (event, detail) = match resume {
+            true => (JournalEvent::ExecutionResumed, String::new()),
+            false => (
+                JournalEvent::ExecutionStarted,
+                format!(
+                    "{} to {}",
+                    execution.source_path.display(),
+                    execution.destination_path.display()
+                ),
+            ),
+        };
+        self.journal(event, &execution, detail).await;
 
-        let (mut current_level, mut errors) = if resume {
+        let resumed = if resume {
             progress_tracker.resume_execution(execution.uuid).await
         } else {
+            None
+        };
+        // An unreadable checkpoint walks the whole tree again rather than ending with nothing done
+        let progress_data = resumed.unwrap_or_else(|| {
             let source_root = execution.source_path.clone();
-            (vec![source_root], Vec::new())
+            ProgressData::new(
+                None,
+                vec![source_root],
+                Vec::new(),
+                (0, 0),
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+            )
+        });
+        // Entries that failed before a suspend are tried again, each failure is reported once
+        let mut reported = HashSet::new();
+        let mut errors: Vec<Error> = progress_data
+            .errors
+            .into_iter()
+            .filter(|record| reported.insert(record.clone()))
+            .map(Error::Restored)
+            .collect();
+        self.discard_unverified(progress_data.unverified_files, &mut errors)
+            .await;
+
+        let (change_cursor, change_set) = if resume {
+            (None, None)
+        } else {
+            let source_root = &execution.source_path;
+            let change_cursor = change_detector.snapshot(source_root).await;
+            let change_set = match execution.backup_type {
+                BackupType::Full => None,
+                BackupType::Incremental => change_detector.detect(&execution).await.map(Arc::new),
+            };
+            (change_cursor, change_set)
         };
 
-        let mut shutdown_flag = false;
-        while !current_level.is_empty() {
-            let global_queue = Arc::new(SegQueue::new());
+        let work_queue = Arc::new(WorkQueue::new(
+            execution.uuid,
+            progress_data.pending_directories,
+            progress_data.completed_entries,
+            progress_data.spill_range,
+            config.max_queued_directories,
+            &config.progress_path,
+        ));
+        let worker_errors = Arc::new(SegQueue::new());
+        let execution_counters = Arc::new(ExecutionCounters::new());
+        let comparison_counters = Arc::new(ComparisonCounters::new());
+        let transfer_counters = Arc::new(TransferCounters::new(execution.options.catalog));
+        let followed_directories = Arc::new(DashSet::new());
+        let destination_full = Arc::new(AtomicBool::new(false));
+        let share_reconnector = Arc::new(ShareReconnector::new(
+            execution.source_path.clone(),
+            execution.destination_path.clone(),
+            config.share_reconnect_attempts,
+        ));
+        let panicked_directories = Arc::new(DashSet::new());
+        let unrecovered = Arc::new(SegQueue::new());
+        let locked_files = Arc::new(LockedFiles::new());
+        for path in progress_data.locked_files {
+            locked_files.defer(path);
+        }
+        // Recorded before any work, a run cut off at any point comes back on the next start
+        self.save_checkpoint(
+            &execution,
+            &work_queue,
+            &[],
+            &locked_files,
+            &worker_errors,
+            &mut errors,
+            &mut reported,
+        )
+        .await;
+        let destination_root = &execution.destination_path;
+        if let Err(err) = self.io_manager.create_directory(destination_root).await {
+            errors.push(err);
+        }
+        let case_sensitivity = CaseSensitivity {
+            source_insensitive: is_source_case_insensitive(&execution.source_path).await,
+            destination_insensitive: is_case_insensitive(destination_root).await,
+        };
+        let (capabilities, capability_report) =
+            self.probe_capabilities(&execution, &worker_errors).await;
+        // Processed counts restart on resume, so totals of the whole tree would not match them
+        let (scan_totals, scan_handle) = if config.pre_scan && !resume {
+            let (scan_totals, scan_handle) =
+                self.scan_service.start(execution.source_path.clone());
+            (Some(scan_totals), Some(scan_handle))
+        } else {
+            (None, None)
+        };
+        let concurrency_controller = Arc::new(ConcurrencyController::new(
+            config.min_concurrency() as usize,
+            config.max_concurrency as usize,
+        ));
+        if let Some(worker_limit) = self.worker_limits.get(&execution.uuid) {
+            concurrency_controller.set_limit(*worker_limit);
+        }
+        self.concurrency_controllers
+            .insert(execution.uuid, concurrency_controller.clone());
+
+        let mut worker_handles = Vec::new();
+        let mut worker_shutdowns = Vec::new();
+        let mut copy_progresses = Vec::new();
+
+        for worker_index in 0..config.max_concurrency as usize {
+            let copy_progress = Arc::new(CopyProgress::new());
+            copy_progresses.push(copy_progress.clone());
+            let worker = self.to_worker(
+                worker_index,
+                concurrency_controller.clone(),
+                comparison_counters.clone(),
+                transfer_counters.clone(),
+                followed_directories.clone(),
+                case_sensitivity,
+                destination_full.clone(),
+                share_reconnector.clone(),
+                locked_files.clone(),
+                panicked_directories.clone(),
+                unrecovered.clone(),
+                capabilities,
+                copy_progress,
+                change_set.clone(),
+            );
+            let (tx, rx) = oneshot::channel();
+            let execution = execution.clone();
+            let work_queue = work_queue.clone();
+            let worker_errors = worker_errors.clone();
+            let execution_counters = execution_counters.clone();
+            let background_io = execution.options.background_io;
+            let handle = tokio::spawn(BACKGROUND_IO.scope(background_io, async move {
+                worker
+                    .run(execution, work_queue, worker_errors, execution_counters, rx)
+                    .await
+            }));
+            worker_shutdowns.push(tx);
+            worker_handles.push(handle);
+        }
+
+        let mut workers = join_all(worker_handles);
+        let mut checkpoint = interval(Duration::from_secs(config.checkpoint_interval as u64));
+        checkpoint.tick().await;
+        let mut adjustment = interval(ADJUST_INTERVAL);
+        adjustment.tick().await;
+        let mut report = interval(Duration::from_secs(config.ui_refresh_time as u64));
+        report.tick().await;
+        let events_per_second = config.progress_events_per_second.max(1);
+        let mut progress = interval(Duration::from_secs(1) / events_per_second);
+        progress.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        progress.tick().await;
+
+        let mut stop_request = None;
+        let mut failure = None;
+        let mut reported_files = 0;
+        let mut reported_scan = None;
+        let mut reported_comparison = ComparisonStats::default();
+        let mut reported_workers = 0;
+        let mut checkpointed_files = 0;
+        let workers_results = loop {
+            tokio::select! {
+                results = &mut workers => break results,
+                _ = adjustment.tick() => concurrency_controller.adjust(),
+                _ = report.tick() => self.publish_file_progress(&execution, &copy_progresses).await,
+                _ = progress.tick() => {
+                    let processed_files = execution_counters.processed_files();
+                    let scan = scan_totals.as_ref().map(|scan_totals| scan_totals.snapshot());
+                    let comparison = comparison_counters.snapshot();
+                    let active_workers = concurrency_controller.active_limit();
+                    if let Some(folder) = execution_counters.take_current_folder() {
+                        self.publish_event(FolderProcess {
+                            uuid: execution.uuid,
+                            folder,
+                        })
+                        .await;
+                    }
+                    if processed_files != reported_files
+                        || scan != reported_scan
+                        || comparison != reported_comparison
+                        || active_workers != reported_workers
+                    {
+                        reported_files = processed_files;
+                        reported_scan = scan;
+                        reported_comparison = comparison;
+                        reported_workers = active_workers;
+                        self.publish_event(ExecutionProgress {
+                            uuid: execution.uuid,
+                            processed_files,
+                            error_count: errors.len() + worker_errors.len(),
+                            scan,
+                            comparison,
+                            active_workers,
+                        })
+                        .await;
+                    }
+                    if config.checkpoint_files > 0
+                        && processed_files >= checkpointed_files + config.checkpoint_files
+                    {
+                        checkpointed_files = processed_files;
+                        checkpoint.reset();
+                        self.save_checkpoint(
+                            &execution,
+                            &work_queue,
+                            &copy_progresses,
+                            &locked_files,
+                            &worker_errors,
+                            &mut errors,
+                            &mut reported,
+                        )
+                        .await;
+                    }
+                    let strict_failure = self
+                        .strict_failure(
+                            &execution,
+                            &work_queue,
+                            &worker_errors,
+                            &mut errors,
+                            &mut reported,
+                        )
+                        .await;
+                    let error_count = errors.len() + worker_errors.len();
+                    if let Some(err) = strict_failure
+                        .or_else(|| Self::error_threshold(config, processed_files, error_count))
+                    {
+                        worker_errors.push(err.clone());
+                        failure = Some(err);
+                        stop_request = Some(StopRequest::Fail);
+                        break self
+                            .stop_workers(
+                                StopRequest::Fail,
+                                &mut worker_shutdowns,
+                                &copy_progresses,
+                                &mut workers,
+                            )
+                            .await;
+                    }
+                }
+                _ = checkpoint.tick() => {
+                    checkpointed_files = execution_counters.processed_files();
+                    self.save_checkpoint(
+                        &execution,
+                        &work_queue,
+                        &copy_progresses,
+                        &locked_files,
+                        &worker_errors,
+                        &mut errors,
+                        &mut reported,
+                    )
+                    .await;
+                }
+                request = &mut shutdown => {
+                    let request = request.unwrap_or(StopRequest::Suspend);
+                    stop_request = Some(request);
+                    break self
+                        .stop_workers(
+                            request,
+                            &mut worker_shutdowns,
+                            &copy_progresses,
+                            &mut workers,
+                        )
+                        .await;
+                }
+            }
+        };
 
-            for dir in current_level.clone() {
-                global_queue.push(dir);
+        // A directory that panicked twice or a worker lost outright leaves the tree unfinished
+        for result in workers_results {
+            if let Err(err) = result {
+                let err = SystemError::ThreadPanic(err);
+                log!(err.clone());
+                unrecovered.push(err.into());
             }
+        }
+        if let Some(err) = unrecovered.pop()
+            && !matches!(stop_request, Some(StopRequest::Fail | StopRequest::Cancel { .. }))
+        {
+            failure = Some(err);
+            stop_request = Some(StopRequest::Fail);
+        }
 
-            let mut worker_handles = Vec::new();
-            let mut worker_shutdowns = Vec::new();
+        // Suspended rather than failed, the run can be resumed once space has been freed
+        if stop_request.is_none() && destination_full.load(Ordering::Relaxed) {
+            stop_request = Some(StopRequest::Suspend);
+            let path = execution.destination_path.clone();
+            worker_errors.push(IOError::DestinationFull { path: path.clone() }.into());
+            self.publish_event(DestinationFull {
+                uuid: execution.uuid,
+                path,
+            })
+            .await;
+        }
+        // Suspended as well, resuming picks up where the share dropped
+        if stop_request.is_none()
+            && let Some(path) = share_reconnector.disconnected()
+        {
+            stop_request = Some(StopRequest::Suspend);
+            worker_errors.push(TaskError::ShareDisconnected { path: path.clone() }.into());
+        }
 
-            for _ in 0..config.max_concurrency {
-                let worker = self.to_worker();
-                let (tx, rx) = oneshot::channel();
-                let execution = execution.clone();
-                let queue = global_queue.clone();
-                let handle = tokio::spawn(async move { worker.run(execution, queue, rx).await });
-                worker_shutdowns.push(tx);
-                worker_handles.push(handle);
-            }
+        if let Some(scan_handle) = scan_handle {
+            scan_handle.abort();
+        }
 
-            let workers_results = tokio::select! {
-                results = join_all(&mut worker_handles) => results,
-                _ = &mut shutdown => {
-                    shutdown_flag = true;
-                    for shutdown in worker_shutdowns {
-                        if shutdown.send(()).is_err() {
-                            log!(SystemError::ShutdownSignalFailed);
-                        }
+        // Only a completed run describes the whole destination
+        if stop_request.is_none() && execution.options.manifest {
+            self.write_manifest(&execution, &worker_errors).await;
+        }
+
+        self.collect_errors(&execution, &worker_errors, &mut errors, &mut reported)
+            .await;
+        self.publish_event(ExecutionProgress {
+            uuid: execution.uuid,
+            processed_files: execution_counters.processed_files(),
+            error_count: errors.len(),
+            scan: scan_totals.map(|scan_totals| scan_totals.snapshot()),
+            comparison: comparison_counters.snapshot(),
+            active_workers: concurrency_controller.active_limit(),
+        })
+        .await;
+
+        let interrupted_files = copy_progresses
+            .iter()
+            .filter_map(|copy_progress| copy_progress.take_interrupted())
+            .collect();
+        let failure_message = failure.as_ref().map(ToString::to_string);
+        match stop_request {
+            Some(StopRequest::Suspend) => {
+                // Files set aside as locked are retried at the end of the resumed run
+                let deferred_files = locked_files.pending();
+                let saved = match work_queue.checkpoint().await {
+                    Ok((pending_directories, completed_entries, spill_range)) => {
+                        let progress_data = ProgressData::new(
+                            Some(self.stored_execution(&execution)),
+                            pending_directories,
+                            completed_entries,
+                            spill_range,
+                            errors.iter().map(ErrorRecord::from).collect(),
+                            interrupted_files,
+                            deferred_files,
+                        );
+                        progress_tracker
+                            .save_execution(execution.uuid, progress_data)
+                            .await
                     }
-                    join_all(&mut worker_handles).await
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = saved {
+                    error!("{}", err);
                 }
-            };
-
-            let mut next_level = Vec::new();
-            for result in workers_results {
-                match result {
-                    Ok((worker_next_level, worker_errors)) => {
-                        next_level.extend(worker_next_level);
-                        if !worker_errors.is_empty() {
-                            errors.extend(worker_errors.clone());
-                            let event = ExecutionErrors {
-                                uuid: execution.uuid,
-                                errors: worker_errors,
-                            };
-                            if let Err(err) = self
-                                .communication_manager
-                                .publish_event::<ExecutionErrors>(event)
-                                .await
-                            {
-                                error!("{}", err);
-                            }
-                        }
+            }
+            Some(StopRequest::Fail) => {
+                work_queue.remove_spill_file().await;
+                if let Err(err) = progress_tracker.remove_execution(execution.uuid).await {
+                    error!("{}", err);
+                }
+                if let Some(failure) = failure {
+                    error!("{}", failure);
+                    self.publish_event(ExecutionFailed {
+                        uuid: execution.uuid,
+                        error: failure,
+                    })
+                    .await;
+                }
+            }
+            Some(StopRequest::Cancel { discard_partial }) => {
+                work_queue.remove_spill_file().await;
+                if discard_partial {
+                    let mut discard_errors = Vec::new();
+                    self.discard_unverified(interrupted_files, &mut discard_errors)
+                        .await;
+                    for err in discard_errors {
+                        error!("{}", err);
                     }
-                    Err(err) => log!(SystemError::ThreadPanic(err)),
+                }
+                if let Err(err) = progress_tracker.remove_execution(execution.uuid).await {
+                    error!("{}", err);
                 }
             }
-
-            if shutdown_flag {
-                current_level.extend(next_level);
-                if let Err(err) = progress_tracker
-                    .save_execution(execution.uuid, current_level, errors)
-                    .await
-                {
+            None => {
+                work_queue.remove_spill_file().await;
+                // Failed entries must show up in the next change set, so the cursor only moves on
+                // after a run without errors
+                let saved = match (change_cursor, errors.is_empty()) {
+                    (Some(change_cursor), true) => {
+                        change_detector.commit(&execution, change_cursor).await
+                    }
+                    (_, true) => Ok(()),
+                    (_, false) => {
+                        let retry = match &change_set {
+                            Some(change_set) => change_set.forced(),
+                            None => vec![execution.source_path.clone()],
+                        };
+                        change_detector.retain(&execution, retry).await
+                    }
+                };
+                if let Err(err) = saved {
                     error!("{}", err);
                 }
-                break;
-            } else {
-                current_level = next_level;
             }
         }
 
         self.running_executions.remove(&execution.uuid);
+        self.concurrency_controllers.remove(&execution.uuid);
+
+        let state = match stop_request {
+            Some(StopRequest::Suspend) => BackupState::Suspended,
+            Some(StopRequest::Cancel { .. }) => BackupState::Canceled,
+            Some(StopRequest::Fail) => BackupState::Failed,
+            None => BackupState::Completed,
+        };
+        let transfer = transfer_counters.snapshot();
+        let history = ExecutionHistory {
+            uuid: Uuid::new_v4(),
+            execution_uuid: execution.uuid,
+            schedule_uuid: execution.schedule_uuid,
+            source_path: execution.source_path.clone(),
+            destination_path: execution.destination_path.clone(),
+            state,
+            started_at,
+            finished_at: Local::now().naive_local(),
+            files_scanned: execution_counters.processed_files(),
+            files_copied: transfer.copied_files,
+            entries_deleted: transfer.deleted_entries,
+            bytes_copied: transfer.copied_bytes,
+            error_count: errors.len(),
+            capabilities: Some(capability_report),
+        };
+        let stored_errors = Self::stored_errors(config, &errors);
+        if let Err(err) = self
+            .database_manager
+            .create_execution_history(&history, stored_errors)
+            .await
+        {
+            error!("{}", err);
+        } else if execution.options.catalog {
+            let entries = transfer_counters.take_catalog();
+            if let Err(err) = self
+                .database_manager
+                .create_execution_catalog(history.uuid, &entries)
+                .await
+            {
+                error!("{}", err);
+            }
+        }
+
+        let (event, detail) = match state {
+            BackupState::Suspended => (JournalEvent::ExecutionSuspended, String::new()),
+            BackupState::Canceled => (JournalEvent::ExecutionCanceled, String::new()),
+            BackupState::Failed => {
+                (JournalEvent::ExecutionFailed, failure_message.unwrap_or_default())
+            }
+            _ => (
+                JournalEvent::ExecutionCompleted,
+                format!(
+                    "{} files copied, {} entries deleted, {} errors",
+                    history.files_copied, history.entries_deleted, history.error_count
+                ),
+            ),
+        };
+        self.journal(event, &execution, detail).await;
+
+        // Walking the destination can take a while, the run is already finished by then
+        if state == BackupState::Completed {
+            let storage_usage_service = self.storage_usage_service.clone();
+            let destination_path = execution.destination_path.clone();
+            tokio::spawn(async move {
+                if let Err(err) = storage_usage_service.record(&destination_path).await {
+                    error!("{}", err);
+                }
+            });
+        }
 
         match self.executions.get_mut(&execution.uuid) {
-            Some(mut ref_mut) => {
-                let execution = ref_mut.value_mut();
-                if shutdown_flag {
-                    execution.state = BackupState::Suspended;
-                } else {
-                    execution.state = BackupState::Completed;
+            Some(mut ref_mut) => ref_mut.value_mut().state = state,
+            None => log!(TaskError::ExecutionNotFound),
+        }
+    }
+
+    // Metadata the destination filesystem rejects is dropped for the run with one warning,
+    // instead of failing for every file as from NTFS to exFAT or from ext4 to CIFS
+    async fn probe_capabilities(
+        &self,
+        execution: &Execution,
+        worker_errors: &SegQueue<Error>,
+    ) -> (DestinationCapabilities, CapabilityReport) {
+        let io_manager = &self.io_manager;
+        let source = &execution.source_path;
+        let destination = &execution.destination_path;
+
+        let mut report = CapabilityReport {
+            source: EndpointCapabilities {
+                features: volume_features(source),
+                timestamp_resolution: sampled_resolution(source).await,
+            },
+            destination: EndpointCapabilities {
+                features: volume_features(destination),
+                timestamp_resolution: Duration::ZERO,
+            },
+        };
+
+        let probe_name = format!(".mirrorsphere-probe-{}", Uuid::new_v4().simple());
+        let probe = destination.join(probe_name);
+        if io_manager.create_directory(&probe).await.is_err() {
+            let capabilities = DestinationCapabilities {
+                attributes: true,
+                permissions: true,
+                any_name: !cfg!(windows),
+                mtime_resolution: report.timestamp_resolution(),
+            };
+            return (capabilities, report);
+        }
+
+        let attributes = io_manager.copy_attributes(source, &probe).await;
+        let permissions = if execution.options.backup_permission {
+            io_manager.copy_permission(source, &probe).await
+        } else {
+            Ok(())
+        };
+
+        let reserved_name = probe.join("reserved:name");
+        let any_name = !cfg!(windows) && fs::write(&reserved_name, []).await.is_ok();
+        let _ = fs::remove_file(&reserved_name).await;
+
+        // Creating links on Windows also needs a privilege, the volume flag alone is not enough
+        report.destination.features.symlinks &= supports_symlinks(&probe);
+        report.destination.timestamp_resolution = written_resolution(&probe.join("mtime")).await;
+        let _ = fs::remove_dir(&probe).await;
+
+        let mut skipped = Vec::new();
+        if attributes.is_err() {
+            skipped.push("attributes");
+        }
+        if permissions.is_err() {
+            skipped.push("permissions");
+        }
+        if report.source.features.sparse && !report.destination.features.sparse {
+            skipped.push("sparse files");
+        }
+        let destination_resolution = report.destination.timestamp_resolution;
+        if destination_resolution >= Duration::from_secs(1)
+            && destination_resolution > report.source.timestamp_resolution
+        {
+            skipped.push("precise timestamps");
+        }
+        if !skipped.is_empty() {
+            worker_errors.push(
+                TaskError::CapabilitiesMissing {
+                    path: destination.clone(),
+                    features: skipped.join(", "),
                 }
+                .into(),
+            );
+        }
+
+        let capabilities = DestinationCapabilities {
+            attributes: attributes.is_ok(),
+            permissions: permissions.is_ok(),
+            any_name,
+            mtime_resolution: report.timestamp_resolution(),
+        };
+        (capabilities, report)
+    }
+
+    async fn stop_workers(
+        &self,
+        request: StopRequest,
+        worker_shutdowns: &mut Vec<oneshot::Sender<()>>,
+        copy_progresses: &[Arc<CopyProgress>],
+        workers: &mut JoinAll<JoinHandle<()>>,
+    ) -> Vec<Result<(), JoinError>> {
+        for shutdown in worker_shutdowns.drain(..) {
+            if shutdown.send(()).is_err() {
+                log!(SystemError::ShutdownSignalFailed);
             }
-            None => log!(TaskError::ExecutionNotFound),
         }
+        // Workers stop between entries, copies still running after the grace
+        // period are cut off and their destinations recorded for the resume
+        let grace_period = Duration::from_secs(self.app_config.shutdown_grace_period);
+        if request == StopRequest::Suspend
+            && let Ok(results) = timeout(grace_period, &mut *workers).await
+        {
+            return results;
+        }
+        for copy_progress in copy_progresses {
+            copy_progress.cancel();
+        }
+        workers.await
+    }
+
+    // A run failing on most entries, such as one whose destination went away, is aborted
+    // instead of grinding through the rest of the tree and ending as completed
+    fn error_threshold(config: &AppConfig, processed_files: usize, errors: usize) -> Option<Error> {
+        if config.max_errors > 0 && errors >= config.max_errors {
+            return Some(TaskError::ErrorLimitExceeded { errors }.into());
+        }
+        let entries = processed_files + errors;
+        if config.max_error_rate > 0.0
+            && entries >= ERROR_RATE_MIN_ENTRIES
+            && errors as f64 >= entries as f64 * config.max_error_rate
+        {
+            return Some(TaskError::ErrorRateExceeded { errors, entries }.into());
+        }
+        None
+    }
+
+    // Bounded so a run failing on every entry does not flood the database
+    fn stored_errors(config: &AppConfig, errors: &[Error]) -> Vec<(usize, ErrorRecord)> {
+        let limit = config.max_stored_errors.min(errors.len());
+        let skipped = match config.stored_error_policy {
+            StoredErrorPolicy::KeepFirst => 0,
+            StoredErrorPolicy::KeepLast => errors.len() - limit,
+        };
+        errors
+            .iter()
+            .enumerate()
+            .skip(skipped)
+            .take(limit)
+            .map(|(position, error)| (position, ErrorRecord::from(error)))
+            .collect()
+    }
+
+    // Warnings such as dropped metadata do not count, the first real error ends the run
+    async fn strict_failure(
+        &self,
+        execution: &Execution,
+        work_queue: &WorkQueue,
+        worker_errors: &SegQueue<Error>,
+        errors: &mut Vec<Error>,
+        reported: &mut HashSet<ErrorRecord>,
+    ) -> Option<Error> {
+        if !execution.options.strict || worker_errors.is_empty() {
+            return None;
+        }
+        let collected = errors.len();
+        self.collect_errors(execution, worker_errors, errors, reported)
+            .await;
+        let failure = errors[collected..]
+            .iter()
+            .find(|error| error.level() == tracing::Level::ERROR)?
+            .clone();
+        let directories = work_queue
+            .in_progress()
+            .iter()
+            .map(|directory| directory.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        log!(TaskLog::StrictModeStopped { directories });
+        Some(failure)
+    }
+
+    // Report the largest in-flight copy, small files finish before the next refresh anyway
+    async fn publish_file_progress(
+        &self,
+        execution: &Execution,
+        copy_progresses: &[Arc<CopyProgress>],
+    ) {
+        let largest = copy_progresses
+            .iter()
+            .filter_map(|copy_progress| copy_progress.snapshot())
+            .max_by_key(|(_, _, total_bytes)| *total_bytes);
+        let Some((file, copied_bytes, total_bytes)) = largest else {
+            return;
+        };
+        let event = FileProgress {
+            uuid: execution.uuid,
+            file,
+            copied_bytes,
+            total_bytes,
+        };
+        self.publish_event(event).await;
+    }
+
+    // A failed write is only logged, the run goes on without its journal entry
+    async fn journal(&self, event: JournalEvent, execution: &Execution, detail: impl Into<String>) {
+        let entry = JournalEntry::new(event, Some(execution.uuid), execution.schedule_uuid, detail);
+        if let Err(err) = self.database_manager.append_journal_entry(&entry).await {
+            error!("{}", err);
+        }
+    }
+
+    async fn publish_event<E: Event + 'static>(&self, event: E) {
+        if let Err(err) = self.communication_manager.publish_event::<E>(event).await {
+            error!("{}", err);
+        }
+    }
+
+    // Taken while the workers go on, so a crash resumes from here. The queue is read before the
+    // copies in flight, a copy finishing in between is then neither completed nor discarded
+    #[allow(clippy::too_many_arguments)]
+    async fn save_checkpoint(
+        &self,
+        execution: &Execution,
+        work_queue: &WorkQueue,
+        copy_progresses: &[Arc<CopyProgress>],
+        locked_files: &LockedFiles,
+        worker_errors: &SegQueue<Error>,
+        errors: &mut Vec<Error>,
+        reported: &mut HashSet<ErrorRecord>,
+    ) {
+        self.collect_errors(execution, worker_errors, errors, reported)
+            .await;
+        let (pending_directories, completed_entries, spill_range) =
+            match work_queue.checkpoint().await {
+                Ok(checkpoint) => checkpoint,
+                Err(err) => {
+                    error!("{}", err);
+                    return;
+                }
+            };
+        let unverified_files = copy_progresses
+            .iter()
+            .filter_map(|copy_progress| copy_progress.in_flight())
+            .collect();
+        let progress_data = ProgressData::new(
+            Some(self.stored_execution(execution)),
+            pending_directories,
+            completed_entries,
+            spill_range,
+            errors.iter().map(ErrorRecord::from).collect(),
+            unverified_files,
+            locked_files.pending(),
+        );
+        if let Err(err) = self
+            .progress_tracker
+            .save_execution(execution.uuid, progress_data)
+            .await
+        {
+            error!("{}", err);
+        }
+    }
+
+    // The runner works on resolved paths, the record keeps them as entered
+    fn stored_execution(&self, execution: &Execution) -> Execution {
+        self.executions
+            .get(&execution.uuid)
+            .map(|stored| stored.clone())
+            .unwrap_or_else(|| execution.clone())
+    }
+
+    // A canceled suspension never resumes, so its checkpoint and spilled directories go too
+    async fn discard_progress(&self, execution_uuid: Uuid, discard_partial: bool) {
+        let progress_data = self
+            .progress_tracker
+            .resume_execution(execution_uuid)
+            .await
+            .unwrap_or_default();
+        if discard_partial {
+            let mut errors = Vec::new();
+            self.discard_unverified(progress_data.unverified_files, &mut errors)
+                .await;
+            for err in errors {
+                error!("{}", err);
+            }
+        }
+        WorkQueue::new(
+            execution_uuid,
+            Vec::new(),
+            Vec::new(),
+            progress_data.spill_range,
+            0,
+            &self.app_config.progress_path,
+        )
+        .remove_spill_file()
+        .await;
+        if let Err(err) = self.progress_tracker.remove_execution(execution_uuid).await {
+            error!("{}", err);
+        }
+    }
+
+    // Partial copies from a cut off suspend are removed, so their directories copy them again
+    async fn discard_unverified(&self, unverified_files: Vec<PathBuf>, errors: &mut Vec<Error>) {
+        let io_manager = &self.io_manager;
+
+        for file in unverified_files {
+            match io_manager.entry_exists(&file).await {
+                Ok(true) => {
+                    if let Err(err) = io_manager.delete_file(&file).await {
+                        errors.push(err);
+                    }
+                }
+                Ok(false) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+
+    async fn write_manifest(&self, execution: &Execution, worker_errors: &SegQueue<Error>) {
+        let hash_type = match execution.comparison_mode {
+            Some(ComparisonMode::Thorough(hash_type)) => hash_type,
+            _ => HashType::BLAKE3,
+        };
+        match self
+            .manifest_service
+            .write(&execution.destination_path, hash_type)
+            .await
+        {
+            Ok(errors) => errors.into_iter().for_each(|error| worker_errors.push(error)),
+            Err(err) => worker_errors.push(err),
+        }
+    }
+
+    async fn collect_errors(
+        &self,
+        execution: &Execution,
+        worker_errors: &SegQueue<Error>,
+        errors: &mut Vec<Error>,
+        reported: &mut HashSet<ErrorRecord>,
+    ) {
+        let mut new_errors = Vec::new();
+        while let Some(error) = worker_errors.pop() {
+            if reported.insert(ErrorRecord::from(&error)) {
+                new_errors.push(error);
+            }
+        }
+        if new_errors.is_empty() {
+            return;
+        }
+        errors.extend(new_errors.clone());
+        let mut detail = new_errors
+            .iter()
+            .take(JOURNAL_ERROR_LINES)
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if new_errors.len() > JOURNAL_ERROR_LINES {
+            let more = new_errors.len() - JOURNAL_ERROR_LINES;
+            detail.push_str(&format!("\n{more} more"));
+        }
+        self.journal(JournalEvent::ExecutionErrors, execution, detail).await;
+        let event = ExecutionErrors {
+            uuid: execution.uuid,
+            errors: new_errors,
+        };
+        self.publish_event(event).await;
     }
 
-    fn to_worker(&self) -> Worker {
+    #[allow(clippy::too_many_arguments)]
+    fn to_worker(
+        &self,
+        worker_index: usize,
+        concurrency_controller: Arc<ConcurrencyController>,
+        comparison_counters: Arc<ComparisonCounters>,
+        transfer_counters: Arc<TransferCounters>,
+        followed_directories: Arc<DashSet<PathBuf>>,
+        case_sensitivity: CaseSensitivity,
+        destination_full: Arc<AtomicBool>,
+        share_reconnector: Arc<ShareReconnector>,
+        locked_files: Arc<LockedFiles>,
+        panicked_directories: Arc<DashSet<PathBuf>>,
+        unrecovered: Arc<SegQueue<Error>>,
+        capabilities: DestinationCapabilities,
+        copy_progress: Arc<CopyProgress>,
+        change_set: Option<Arc<ChangeSet>>,
+    ) -> Worker {
+        let config = self.app_config.clone();
         let io_manager = self.io_manager.clone();
-        Worker::new(io_manager)
+        let database_manager = self.database_manager.clone();
+        Worker::new(
+            worker_index,
+            config,
+            io_manager,
+            database_manager,
+            concurrency_controller,
+            comparison_counters,
+            transfer_counters,
+            followed_directories,
+            case_sensitivity,
+            destination_full,
+            share_reconnector,
+            locked_files,
+            panicked_directories,
+            unrecovered,
+            capabilities,
+            copy_progress,
+            change_set,
+        )
+    }
+}
+
+const SMALL_FILE_BATCH_SIZE: usize = 64;
+const LOCKED_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+enum FilePlan {
+    Unchanged,
+    Update {
+        copy: bool,
+        record: Option<ComparisonRecord>,
+    },
+}
+
+// Names of a source directory keyed the way the destination directory compares them
+struct SourceNames {
+    keys: HashSet<String>,
+    case_insensitive: bool,
+}
+
+impl SourceNames {
+    fn contains(&self, name: &OsStr) -> bool {
+        self.keys.contains(&comparison_key(name, self.case_insensitive))
     }
 }
 
 struct Worker {
+    worker_index: usize,
+    app_config: Arc<AppConfig>,
     io_manager: Arc<IOManager>,
+    database_manager: Arc<DatabaseManager>,
+    concurrency_controller: Arc<ConcurrencyController>,
+    comparison_counters: Arc<ComparisonCounters>,
+    transfer_counters: Arc<TransferCounters>,
+    // Directories reached through symlinks, shared by all workers of the execution
+    followed_directories: Arc<DashSet<PathBuf>>,
+    case_sensitivity: CaseSensitivity,
+    // Set by the first worker whose write fails for lack of space, every worker then stops
+    destination_full: Arc<AtomicBool>,
+    share_reconnector: Arc<ShareReconnector>,
+    locked_files: Arc<LockedFiles>,
+    // Directories whose processing panicked once, a second panic gives up on them
+    panicked_directories: Arc<DashSet<PathBuf>>,
+    // Failures that leave the tree incomplete, the execution ends Failed once workers finish
+    unrecovered: Arc<SegQueue<Error>>,
+    capabilities: DestinationCapabilities,
+    copy_progress: Arc<CopyProgress>,
+    change_set: Option<Arc<ChangeSet>>,
 }
 
 impl Worker {
-    pub fn new(io_manager: Arc<IOManager>) -> Self {
-        Self { io_manager }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        worker_index: usize,
+        app_config: Arc<AppConfig>,
+        io_manager: Arc<IOManager>,
+        database_manager: Arc<DatabaseManager>,
+        concurrency_controller: Arc<ConcurrencyController>,
+        comparison_counters: Arc<ComparisonCounters>,
+        transfer_counters: Arc<TransferCounters>,
+        followed_directories: Arc<DashSet<PathBuf>>,
+        case_sensitivity: CaseSensitivity,
+        destination_full: Arc<AtomicBool>,
+        share_reconnector: Arc<ShareReconnector>,
+        locked_files: Arc<LockedFiles>,
+        panicked_directories: Arc<DashSet<PathBuf>>,
+        unrecovered: Arc<SegQueue<Error>>,
+        capabilities: DestinationCapabilities,
+        copy_progress: Arc<CopyProgress>,
+        change_set: Option<Arc<ChangeSet>>,
+    ) -> Self {
+        Self {
+            worker_index,
+            app_config,
+            io_manager,
+            database_manager,
+            concurrency_controller,
+            comparison_counters,
+            transfer_counters,
+            followed_directories,
+            case_sensitivity,
+            destination_full,
+            share_reconnector,
+            locked_files,
+            panicked_directories,
+            unrecovered,
+            capabilities,
+            copy_progress,
+            change_set,
+        }
     }
 
     async fn run(
         &self,
         execution: Execution,
-        global_queue: Arc<SegQueue<PathBuf>>,
+        work_queue: Arc<WorkQueue>,
+        worker_errors: Arc<SegQueue<Error>>,
+        execution_counters: Arc<ExecutionCounters>,
         mut shutdown: oneshot::Receiver<()>,
-    ) -> (Vec<PathBuf>, Vec<Error>) {
-        let io_manager = &self.io_manager;
+    ) {
+        let idle_time = Duration::from_millis(self.app_config.internal_timestamp as u64);
+
+        loop {
+            if shutdown.try_recv().is_ok()
+                || self.is_destination_full()
+                || self.share_reconnector.is_disconnected()
+            {
+                break;
+            }
 
-        let mirror = execution.options.mirror;
+            if !self.concurrency_controller.is_active(self.worker_index) {
+                if work_queue.is_finished() {
+                    break;
+                }
+                sleep(idle_time).await;
+                continue;
+            }
 
-        let mut next_level = Vec::new();
-        let mut errors = Vec::new();
+            let current_dir = match work_queue.pop().await {
+                Ok(Some(current_dir)) => current_dir,
+                Ok(None) => {
+                    if work_queue.is_finished() {
+                        self.retry_locked(
+                            &execution,
+                            &worker_errors,
+                            &execution_counters,
+                            &mut shutdown,
+                        )
+                        .await;
+                        break;
+                    }
+                    sleep(idle_time).await;
+                    continue;
+                }
+                // The unread directories stay in the spill file, the run fails rather than
+                // finishing without them
+                Err(err) => {
+                    worker_errors.push(err.clone());
+                    self.unrecovered.push(err);
+                    break;
+                }
+            };
+
+            let mut errors = Vec::new();
+            // Caught per directory so one bad entry does not take the worker and its queue with it
+            let result = AssertUnwindSafe(self.process_directory(
+                &execution,
+                &work_queue,
+                &execution_counters,
+                &current_dir,
+                &mut errors,
+                &mut shutdown,
+            ))
+            .catch_unwind()
+            .await;
+            // The errors of a directory cut off by a dropped share are not its own, it is
+            // walked again once the share is back
+            if !errors.is_empty() && matches!(result, Ok(true)) {
+                match self.share_reconnector.restore().await {
+                    ShareState::Connected => {}
+                    ShareState::Reconnected => {
+                        work_queue.retry(&current_dir).await;
+                        continue;
+                    }
+                    ShareState::Disconnected => {
+                        work_queue.retry(&current_dir).await;
+                        break;
+                    }
+                }
+            }
+            for error in errors {
+                worker_errors.push(error);
+            }
+            let completed = match result {
+                Ok(completed) => completed,
+                Err(payload) => {
+                    self.recover_directory(&work_queue, &worker_errors, &current_dir, payload)
+                        .await;
+                    continue;
+                }
+            };
 
-        while let Some(current_dir) = global_queue.pop() {
-            if shutdown.try_recv().is_ok() {
+            if !completed {
                 break;
             }
+            work_queue.complete(&current_dir);
+        }
+    }
+
+    async fn recover_directory(
+        &self,
+        work_queue: &WorkQueue,
+        worker_errors: &SegQueue<Error>,
+        directory: &Path,
+        payload: Box<dyn Any + Send>,
+    ) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        if self.panicked_directories.insert(directory.to_path_buf()) {
+            log!(TaskError::DirectoryPanicked(directory, message));
+            work_queue.retry(directory).await;
+        } else {
+            let err: Error = TaskError::DirectoryPanicRepeated(directory, message).into();
+            worker_errors.push(err.clone());
+            self.unrecovered.push(err);
+            work_queue.complete(directory);
+        }
+    }
+
+    async fn process_directory(
+        &self,
+        execution: &Execution,
+        work_queue: &WorkQueue,
+        execution_counters: &ExecutionCounters,
+        current_dir: &Path,
+        errors: &mut Vec<Error>,
+        shutdown: &mut oneshot::Receiver<()>,
+    ) -> bool {
+        let io_manager = &self.io_manager;
+        execution_counters.enter_folder(current_dir);
+
+        let scope = self.directory_scope(current_dir);
+        if scope == DirectoryScope::Skip {
+            return true;
+        }
+
+        if scope == DirectoryScope::Descend {
+            if let Err(e) = self.descend_directory(current_dir, work_queue).await {
+                errors.push(e);
+            }
+            return true;
+        }
+
+        let signature = self.directory_signature(execution, current_dir).await;
+        if let Some(signature) = &signature
+            && self
+                .is_directory_unchanged(execution, current_dir, signature)
+                .await
+        {
+            if let Err(e) = self.descend_directory(current_dir, work_queue).await {
+                errors.push(e);
+            }
+            return true;
+        }
+
+        let mut entries = match io_manager.stream_directory(current_dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(e);
+                return true;
+            }
+        };
 
-            let entries = match io_manager.list_directory(&current_dir).await {
-                Ok(entries) => entries,
+        let mut comparison_cache = self.comparison_cache(execution, current_dir).await;
+        let mut processed_files = 0;
+        let mut small_files = Vec::new();
+        let mut seen_names = HashMap::new();
+        let case_insensitive = self.destination_case_insensitive(execution, current_dir).await;
+        loop {
+            if shutdown.try_recv().is_ok()
+                || self.is_destination_full()
+                || self.share_reconnector.is_disconnected()
+            {
+                execution_counters.add_processed(processed_files);
+                return false;
+            }
+            let entry = match entries.next_entry().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
                 Err(e) => {
                     errors.push(e);
-                    continue;
+                    break;
                 }
             };
+            if let Err(e) = self.check_name_collision(&mut seen_names, &entry, case_insensitive) {
+                errors.push(e);
+                continue;
+            }
+            if let Err(e) = self.check_portable_name(execution, &entry).await {
+                errors.push(e);
+                continue;
+            }
+            if work_queue.is_entry_completed(&entry) {
+                if let Some(comparison_cache) = comparison_cache.as_mut() {
+                    comparison_cache.carry_over(&entry);
+                }
+                continue;
+            }
+            if self.is_small_file(&entry).await {
+                small_files.push(entry);
+                if small_files.len() < SMALL_FILE_BATCH_SIZE {
+                    continue;
+                }
+                let completed = self
+                    .process_small_files(
+                        execution,
+                        work_queue,
+                        mem::take(&mut small_files),
+                        comparison_cache.as_mut(),
+                        &mut processed_files,
+                        errors,
+                        shutdown,
+                    )
+                    .await;
+                if !completed {
+                    execution_counters.add_processed(processed_files);
+                    return false;
+                }
+                continue;
+            }
+            let start = Instant::now();
+            let result = self
+                .process_entry(execution, &entry, comparison_cache.as_mut())
+                .await;
+            self.concurrency_controller.record(start.elapsed());
+            match result {
+                Ok(Some(path)) => {
+                    if self.directory_scope(&path) != DirectoryScope::Skip {
+                        work_queue.push(path).await;
+                    }
+                    work_queue.complete_entry(&entry);
+                }
+                Ok(None) => {
+                    processed_files += 1;
+                    work_queue.complete_entry(&entry);
+                }
+                Err(_) if self.copy_progress.is_cancelled() || self.is_destination_full() => {
+                    execution_counters.add_processed(processed_files);
+                    return false;
+                }
+                Err(e) => self.defer_locked(execution, e, errors),
+            }
+        }
+        let completed = self
+            .process_small_files(
+                execution,
+                work_queue,
+                small_files,
+                comparison_cache.as_mut(),
+                &mut processed_files,
+                errors,
+                shutdown,
+            )
+            .await;
+        execution_counters.add_processed(processed_files);
+        if !completed {
+            return false;
+        }
+        // Deferred locked files were neither copied nor recorded, the directory stays dirty
+        let dirty = self.locked_files.is_dirty(current_dir);
+        if let Some(comparison_cache) = comparison_cache
+            && !dirty
+        {
+            self.save_comparison_cache(execution, current_dir, comparison_cache)
+                .await;
+        }
+
+        let destination_dir = match self.calculate_destination_path(
+            current_dir,
+            &execution.source_path,
+            &execution.destination_path,
+        ) {
+            Ok(dir) => dir,
+            Err(e) => {
+                errors.push(e);
+                return true;
+            }
+        };
+        if execution.options.mirror {
+            let (_, mirror_errors) = self
+                .mirror_cleanup(execution, current_dir, &destination_dir)
+                .await;
+            errors.extend(mirror_errors);
+        }
+        // Entries created or deleted above touch the directory mtime, so its own attributes go last
+        if current_dir != execution.source_path
+            && let Err(e) = self
+                .copy_metadata(execution, current_dir, &destination_dir)
+                .await
+        {
+            errors.push(e);
+        }
+
+        if let Some(signature) = signature
+            && errors.is_empty()
+        {
+            match dirty {
+                true => self.locked_files.hold_signature(current_dir, signature),
+                false => {
+                    self.save_directory_signature(execution, current_dir, &signature)
+                        .await
+                }
+            }
+        }
+
+        true
+    }
+
+    async fn directory_signature(
+        &self,
+        execution: &Execution,
+        directory: &Path,
+    ) -> Option<DirectorySignature> {
+        if execution.backup_type != BackupType::Incremental
+            || execution.comparison_mode != Some(ComparisonMode::Standard)
+        {
+            return None;
+        }
+        match self.io_manager.directory_signature(directory).await {
+            Ok(signature) => Some(signature),
+            Err(err) => {
+                error!("{}", err);
+                None
+            }
+        }
+    }
+
+    async fn is_directory_unchanged(
+        &self,
+        execution: &Execution,
+        directory: &Path,
+        signature: &DirectorySignature,
+    ) -> bool {
+        let Ok(destination_dir) = self.calculate_destination_path(
+            directory,
+            &execution.source_path,
+            &execution.destination_path,
+        ) else {
+            return false;
+        };
+        if !destination_dir.exists() {
+            return false;
+        }
+        match self
+            .database_manager
+            .get_directory_signature(&execution.source_path, &execution.destination_path, directory)
+            .await
+        {
+            Ok(previous) => previous.as_ref() == Some(signature),
+            Err(err) => {
+                error!("{}", err);
+                false
+            }
+        }
+    }
+
+    async fn save_directory_signature(
+        &self,
+        execution: &Execution,
+        directory: &Path,
+        signature: &DirectorySignature,
+    ) {
+        if let Err(err) = self
+            .database_manager
+            .save_directory_signature(
+                &execution.source_path,
+                &execution.destination_path,
+                directory,
+                signature,
+            )
+            .await
+        {
+            error!("{}", err);
+        }
+    }
+
+    async fn comparison_cache(
+        &self,
+        execution: &Execution,
+        directory: &Path,
+    ) -> Option<ComparisonCache> {
+        if execution.backup_type != BackupType::Incremental
+            || !matches!(
+                execution.comparison_mode,
+                Some(ComparisonMode::Advanced | ComparisonMode::Thorough(_))
+            )
+        {
+            return None;
+        }
+        match self
+            .database_manager
+            .get_comparison_records(
+                &execution.source_path,
+                &execution.destination_path,
+                directory,
+                &ComparisonCache::options_key(execution),
+            )
+            .await
+        {
+            Ok(previous) => Some(ComparisonCache::new(previous)),
+            Err(err) => {
+                error!("{}", err);
+                None
+            }
+        }
+    }
+
+    async fn save_comparison_cache(
+        &self,
+        execution: &Execution,
+        directory: &Path,
+        comparison_cache: ComparisonCache,
+    ) {
+        if let Err(err) = self
+            .database_manager
+            .save_comparison_records(
+                &execution.source_path,
+                &execution.destination_path,
+                directory,
+                &ComparisonCache::options_key(execution),
+                comparison_cache.into_records(),
+            )
+            .await
+        {
+            error!("{}", err);
+        }
+    }
+
+    // Names differing only in case, in composed or decomposed spelling, or in sanitized
+    // characters land on the same destination entry or are matched as one, the later one is
+    // left out
+    fn check_name_collision(
+        &self,
+        seen_names: &mut HashMap<String, PathBuf>,
+        entry: &Path,
+        case_insensitive: bool,
+    ) -> Result<(), Error> {
+        let Some(file_name) = entry.file_name() else {
+            return Ok(());
+        };
+        let name = self
+            .portable_name(file_name)
+            .unwrap_or_else(|| file_name.to_os_string());
+        match seen_names.entry(comparison_key(&name, case_insensitive)) {
+            Entry::Occupied(existing) => Err(TaskError::NameCollision {
+                path: entry.to_path_buf(),
+                existing: existing.get().clone(),
+            })?,
+            Entry::Vacant(slot) => {
+                slot.insert(entry.to_path_buf());
+                Ok(())
+            }
+        }
+    }
+
+    async fn destination_case_insensitive(&self, execution: &Execution, source_dir: &Path) -> bool {
+        let volume_insensitive = self.case_sensitivity.destination_insensitive;
+        match self.calculate_destination_path(
+            source_dir,
+            &execution.source_path,
+            &execution.destination_path,
+        ) {
+            Ok(destination_dir) => {
+                is_directory_case_insensitive(&destination_dir, volume_insensitive).await
+            }
+            Err(_) => volume_insensitive,
+        }
+    }
+
+    // The original name is recorded so the entry can be matched and restored under it
+    async fn check_portable_name(&self, execution: &Execution, entry: &Path) -> Result<(), Error> {
+        if self.capabilities.any_name {
+            return Ok(());
+        }
+        let Some(file_name) = entry.file_name() else {
+            return Ok(());
+        };
+        if portable_name(file_name, &self.app_config.name_replacements).is_none() {
+            return Ok(());
+        }
+        if !self.app_config.sanitize_names {
+            Err(TaskError::UnportableName {
+                path: entry.to_path_buf(),
+            })?
+        }
+        let destination_path = self.calculate_destination_path(
+            entry,
+            &execution.source_path,
+            &execution.destination_path,
+        )?;
+        self.database_manager
+            .save_renamed_entry(
+                &execution.source_path,
+                &execution.destination_path,
+                &destination_path,
+                &file_name.to_string_lossy(),
+            )
+            .await
+    }
+
+    fn portable_name(&self, name: &OsStr) -> Option<OsString> {
+        if self.capabilities.any_name || !self.app_config.sanitize_names {
+            return None;
+        }
+        portable_name(name, &self.app_config.name_replacements)
+    }
+
+    fn directory_scope(&self, directory: &Path) -> DirectoryScope {
+        match &self.change_set {
+            Some(change_set) => change_set.scope(directory),
+            None => DirectoryScope::Full,
+        }
+    }
 
-            for entry in entries.iter() {
-                if shutdown.try_recv().is_ok() {
-                    break;
-                }
-                match self.process_entry(&execution, entry).await {
-                    Ok(Some(path)) => next_level.push(path),
-                    Ok(None) => {}
-                    Err(e) => errors.push(e),
-                }
-            }
+    async fn descend_directory(
+        &self,
+        directory: &Path,
+        work_queue: &WorkQueue,
+    ) -> Result<(), Error> {
+        let io_manager = &self.io_manager;
 
-            if mirror {
-                let source_entries = entries;
-                let destination_dir = match self.calculate_destination_path(
-                    &current_dir,
-                    &execution.source_path,
-                    &execution.destination_path,
-                ) {
-                    Ok(dir) => dir,
-                    Err(e) => {
-                        errors.push(e);
-                        continue;
-                    }
-                };
-                match io_manager.list_directory(&destination_dir).await {
-                    Ok(destination_entries) => {
-                        let (_, mirror_errors) = self
-                            .mirror_cleanup(source_entries, destination_entries)
-                            .await;
-                        errors.extend(mirror_errors);
-                    }
-                    Err(e) => errors.push(e),
-                }
+        let mut entries = io_manager.stream_directory(directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if work_queue.is_entry_completed(&entry) {
+                continue;
+            }
+            let is_symlink = io_manager.is_symlink(&entry).await.unwrap_or(false);
+            if !is_symlink
+                && entry.is_dir()
+                && self.directory_scope(&entry) != DirectoryScope::Skip
+            {
+                work_queue.push(entry.clone()).await;
+                work_queue.complete_entry(&entry);
             }
         }
-
-        (next_level, errors)
+        Ok(())
     }
 
     async fn process_entry(
         &self,
         execution: &Execution,
         current_path: &Path,
+        comparison_cache: Option<&mut ComparisonCache>,
     ) -> Result<Option<PathBuf>, Error> {
         let io_manager = &self.io_manager;
 
@@ -413,17 +2261,15 @@ impl Worker {
         }
 
         if source_path.is_dir() {
-            self.backup_directory(execution, source_path, destination_path)
-                .await
+            self.backup_directory(source_path, destination_path).await
         } else {
-            self.backup_file(execution, source_path, destination_path)
+            self.backup_file(execution, source_path, destination_path, comparison_cache)
                 .await
         }
     }
 
     async fn backup_directory(
         &self,
-        execution: &Execution,
         source_path: &Path,
         destination_path: &Path,
     ) -> Result<Option<PathBuf>, Error> {
@@ -431,49 +2277,230 @@ impl Worker {
 
         if !destination_path.exists() {
             io_manager.create_directory(destination_path).await?;
+            if let Some(change_set) = &self.change_set {
+                change_set.force(source_path);
+            }
         }
 
-        io_manager
-            .copy_attributes(source_path, destination_path)
+        Ok(Some(source_path.to_path_buf()))
+    }
+
+    async fn backup_file(
+        &self,
+        execution: &Execution,
+        source_path: &Path,
+        destination_path: &Path,
+        mut comparison_cache: Option<&mut ComparisonCache>,
+    ) -> Result<Option<PathBuf>, Error> {
+        let plan = self
+            .plan_file(
+                execution,
+                source_path,
+                destination_path,
+                comparison_cache.as_deref_mut(),
+            )
             .await?;
+        let FilePlan::Update { copy, record } = plan else {
+            return Ok(None);
+        };
 
-        if execution.options.backup_permission {
-            io_manager
-                .copy_permission(source_path, destination_path)
+        if copy {
+            let existed = self.existed_before_copy(destination_path).await;
+            self.copy_file(execution, source_path, destination_path)
                 .await?;
+            self.record_copy(execution, destination_path, existed).await;
         }
+        self.copy_metadata(execution, source_path, destination_path)
+            .await?;
 
-        Ok(Some(source_path.to_path_buf()))
+        if let (Some(comparison_cache), Some(record)) = (comparison_cache, record) {
+            comparison_cache.record(source_path, record);
+        }
+        Ok(None)
     }
 
-    async fn backup_file(
+    async fn plan_file(
         &self,
         execution: &Execution,
         source_path: &Path,
         destination_path: &Path,
-    ) -> Result<Option<PathBuf>, Error> {
+        comparison_cache: Option<&mut ComparisonCache>,
+    ) -> Result<FilePlan, Error> {
         let io_manager = &self.io_manager;
 
-        match execution.backup_type {
-            BackupType::Full => self.full_backup(source_path, destination_path).await?,
+        // Unchanged since the last verified run, attributes and hashes need not be compared again
+        let mut record = None;
+        if let Some(comparison_cache) = comparison_cache {
+            let current = io_manager.comparison_record(source_path).await?;
+            if comparison_cache.is_unchanged(source_path, &current)
+                && io_manager
+                    .standard_compare(
+                        source_path,
+                        destination_path,
+                        self.capabilities.mtime_resolution,
+                    )
+                    .await
+                    .unwrap_or(false)
+            {
+                comparison_cache.record(source_path, current);
+                return Ok(FilePlan::Unchanged);
+            }
+            record = Some(current);
+        }
+
+        let copy = match execution.backup_type {
+            BackupType::Full => true,
             BackupType::Incremental => {
                 let comparison_mode = execution.comparison_mode.ok_or(SystemError::UnknownError)?;
-                self.incremental_backup(source_path, destination_path, comparison_mode)
+                !self
+                    .compare_file(source_path, destination_path, comparison_mode)
                     .await?
             }
-        }
+        };
+        Ok(FilePlan::Update { copy, record })
+    }
 
-        io_manager
-            .copy_attributes(source_path, destination_path)
-            .await?;
+    async fn copy_metadata(
+        &self,
+        execution: &Execution,
+        source_path: &Path,
+        destination_path: &Path,
+    ) -> Result<(), Error> {
+        let io_manager = &self.io_manager;
 
-        if execution.options.backup_permission {
+        if self.capabilities.attributes {
+            io_manager
+                .copy_attributes(source_path, destination_path)
+                .await?;
+        }
+
+        if execution.options.backup_permission && self.capabilities.permissions {
             io_manager
                 .copy_permission(source_path, destination_path)
                 .await?;
         }
 
-        Ok(None)
+        Ok(())
+    }
+
+    async fn is_small_file(&self, path: &Path) -> bool {
+        let threshold = self.app_config.small_file_threshold;
+        if threshold == 0 {
+            return false;
+        }
+        matches!(
+            self.io_manager.regular_file_size(path).await,
+            Ok(Some(size)) if size < threshold
+        )
+    }
+
+    // Copies of a batch share one permit and one blocking task instead of one per file
+    #[allow(clippy::too_many_arguments)]
+    async fn process_small_files(
+        &self,
+        execution: &Execution,
+        work_queue: &WorkQueue,
+        small_files: Vec<PathBuf>,
+        mut comparison_cache: Option<&mut ComparisonCache>,
+        processed_files: &mut usize,
+        errors: &mut Vec<Error>,
+        shutdown: &mut oneshot::Receiver<()>,
+    ) -> bool {
+        for batch in small_files.chunks(SMALL_FILE_BATCH_SIZE) {
+            if shutdown.try_recv().is_ok() || self.is_destination_full() {
+                return false;
+            }
+            let start = Instant::now();
+
+            let mut planned = Vec::new();
+            let mut copies = Vec::new();
+            for source_path in batch {
+                let destination_path = match self.calculate_destination_path(
+                    source_path,
+                    &execution.source_path,
+                    &execution.destination_path,
+                ) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
+                let plan = self
+                    .plan_file(
+                        execution,
+                        source_path,
+                        &destination_path,
+                        comparison_cache.as_deref_mut(),
+                    )
+                    .await;
+                match plan {
+                    Ok(FilePlan::Unchanged) => {
+                        *processed_files += 1;
+                        work_queue.complete_entry(source_path);
+                    }
+                    Ok(FilePlan::Update { copy, record }) => {
+                        let mut existed = false;
+                        if copy {
+                            existed = self.existed_before_copy(&destination_path).await;
+                            copies.push((source_path.clone(), destination_path.clone()));
+                        }
+                        planned.push((source_path, destination_path, copy, existed, record));
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+
+            let mut copy_results = match self.io_manager.copy_small_files(copies).await {
+                Ok(copy_results) => copy_results.into_iter(),
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            for (source_path, destination_path, copy, existed, record) in planned {
+                if copy && let Some(Err(e)) = copy_results.next() {
+                    if self.is_destination_full()
+                        || self.out_of_space(source_path, &destination_path).await
+                    {
+                        self.destination_full.store(true, Ordering::Relaxed);
+                        return false;
+                    }
+                    // Locked files go through the single file path, which applies the retries
+                    let result = if self.is_source_locked(source_path).await {
+                        self.copy_file(execution, source_path, &destination_path).await
+                    } else {
+                        Err(e)
+                    };
+                    if let Err(e) = result {
+                        self.defer_locked(execution, e, errors);
+                        continue;
+                    }
+                }
+                if copy {
+                    self.record_copy(execution, &destination_path, existed)
+                        .await;
+                }
+                if let Err(e) = self
+                    .copy_metadata(execution, source_path, &destination_path)
+                    .await
+                {
+                    errors.push(e);
+                    continue;
+                }
+                if let (Some(comparison_cache), Some(record)) =
+                    (comparison_cache.as_deref_mut(), record)
+                {
+                    comparison_cache.record(source_path, record);
+                }
+                *processed_files += 1;
+                work_queue.complete_entry(source_path);
+            }
+
+            self.concurrency_controller
+                .record_batch(start.elapsed(), batch.len());
+        }
+        true
     }
 
     #[inline(always)]
@@ -501,7 +2528,7 @@ impl Worker {
         let io_manager = &self.io_manager;
 
         let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
+        let mut directories = Vec::new();
 
         queue.push_back((source_path.to_path_buf(), destination_path.to_path_buf()));
 
@@ -519,14 +2546,17 @@ impl Worker {
                 current_source.to_path_buf()
             };
 
-            if visited.contains(&canonical_path) {
-                continue;
-            }
-            visited.insert(canonical_path.clone());
-
             if canonical_path.is_dir() {
-                self.backup_directory(execution, &canonical_path, &current_dest)
-                    .await?;
+                // Each directory is walked once per execution, later links to it are kept as links
+                if !self.followed_directories.insert(canonical_path.clone()) {
+                    if is_symlink {
+                        self.copy_symlink(execution, &current_source, &current_dest)
+                            .await?;
+                    }
+                    continue;
+                }
+                self.backup_directory(&canonical_path, &current_dest).await?;
+                directories.push((canonical_path.clone(), current_dest.clone()));
 
                 let entries = io_manager.list_directory(&canonical_path).await?;
                 for entry in entries {
@@ -541,11 +2571,17 @@ impl Worker {
                     queue.push_back((entry, new_destination));
                 }
             } else {
-                self.backup_file(execution, &canonical_path, &current_dest)
+                self.backup_file(execution, &canonical_path, &current_dest, None)
                     .await?;
             }
         }
 
+        // Walked breadth first, so in reverse every directory comes after its subdirectories
+        for (source_dir, destination_dir) in directories.iter().rev() {
+            self.copy_metadata(execution, source_dir, destination_dir)
+                .await?;
+        }
+
         Ok(())
     }
 
@@ -561,89 +2597,351 @@ impl Worker {
             .copy_symlink(source_path, destination_path)
             .await?;
 
-        io_manager
-            .copy_attributes(source_path, destination_path)
-            .await?;
-
-        if execution.options.backup_permission {
-            io_manager
-                .copy_permission(source_path, destination_path)
-                .await?;
-        }
+        self.copy_metadata(execution, source_path, destination_path)
+            .await
+    }
 
-        Ok(())
+    async fn compare_file(
+        &self,
+        source_path: &Path,
+        destination_path: &Path,
+        comparison_mode: ComparisonMode,
+    ) -> Result<bool, Error> {
+        let outcome = self
+            .io_manager
+            .compare_files(
+                source_path,
+                destination_path,
+                comparison_mode,
+                self.capabilities.attributes,
+                self.capabilities.mtime_resolution,
+            )
+            .await?;
+        self.comparison_counters.record(outcome);
+        Ok(outcome.identical)
     }
 
-    #[inline(always)]
-    async fn full_backup(&self, source_path: &Path, destination_path: &Path) -> Result<(), Error> {
-        let io_manager = &self.io_manager;
-        io_manager.copy_file(source_path, destination_path).await
+    async fn copy_file(
+        &self,
+        execution: &Execution,
+        source_path: &Path,
+        destination_path: &Path,
+    ) -> Result<(), Error> {
+        let retries = execution.options.locked_files.retries();
+        let mut attempt = 0;
+        loop {
+            let result = self.copy_file_once(source_path, destination_path).await;
+            if result.is_ok()
+                || self.copy_progress.is_cancelled()
+                || self.is_destination_full()
+                || !self.is_source_locked(source_path).await
+            {
+                return result;
+            }
+            if attempt == retries {
+                Err(IOError::FileLocked {
+                    path: source_path.to_path_buf(),
+                })?
+            }
+            attempt += 1;
+            sleep(LOCKED_RETRY_DELAY * attempt).await;
+        }
     }
 
-    async fn incremental_backup(
+    async fn copy_file_once(
         &self,
         source_path: &Path,
         destination_path: &Path,
-        comparison_mode: ComparisonMode,
     ) -> Result<(), Error> {
         let io_manager = &self.io_manager;
 
-        let need_copy = !match comparison_mode {
-            ComparisonMode::Standard => {
-                io_manager
-                    .standard_compare(source_path, destination_path)
-                    .await
+        self.copy_progress.begin(source_path, destination_path);
+        let result = io_manager
+            .copy_file_with_progress(source_path, destination_path, self.copy_progress.clone())
+            .await;
+        self.copy_progress.finish();
+        if result.is_err() && self.copy_progress.is_cancelled() {
+            self.copy_progress.interrupt(destination_path);
+        } else if result.is_err() && self.out_of_space(source_path, destination_path).await {
+            self.destination_full.store(true, Ordering::Relaxed);
+            let _ = self.io_manager.delete_file(destination_path).await;
+            Err(IOError::DestinationFull {
+                path: destination_path.to_path_buf(),
+            })?
+        }
+        result
+    }
+
+    // Only looked up when the run keeps a catalog, which tells added files from updated ones
+    async fn existed_before_copy(&self, destination_path: &Path) -> bool {
+        self.transfer_counters.is_cataloging()
+            && fs::try_exists(destination_path).await.unwrap_or(false)
+    }
+
+    async fn record_copy(&self, execution: &Execution, destination_path: &Path, existed: bool) {
+        let bytes = fs::metadata(destination_path)
+            .await
+            .map_or(0, |metadata| metadata.len());
+        self.transfer_counters.add_copied(bytes);
+        self.concurrency_controller.add_bytes(bytes);
+        let action = if existed {
+            CatalogAction::Updated
+        } else {
+            CatalogAction::Added
+        };
+        self.record_catalog(execution, action, destination_path);
+    }
+
+    fn record_catalog(&self, execution: &Execution, action: CatalogAction, path: &Path) {
+        let relative_path = path
+            .strip_prefix(&execution.destination_path)
+            .unwrap_or(path);
+        self.transfer_counters.record(action, relative_path);
+    }
+
+    async fn is_source_locked(&self, source_path: &Path) -> bool {
+        let source_path = source_path.to_path_buf();
+        spawn_blocking(move || is_locked(&source_path))
+            .await
+            .unwrap_or(false)
+    }
+
+    // Only files under the source root can be found again by the end pass or after a resume
+    fn defer_locked(&self, execution: &Execution, error: Error, errors: &mut Vec<Error>) {
+        match error {
+            Error::IO(IOError::FileLocked { path })
+                if execution.options.locked_files == LockedFilePolicy::Skip
+                    && path.starts_with(&execution.source_path) =>
+            {
+                self.locked_files.defer(path)
             }
-            ComparisonMode::Advanced => {
-                io_manager
-                    .advance_compare(source_path, destination_path)
-                    .await
+            error => errors.push(error),
+        }
+    }
+
+    async fn retry_locked(
+        &self,
+        execution: &Execution,
+        worker_errors: &SegQueue<Error>,
+        execution_counters: &ExecutionCounters,
+        shutdown: &mut oneshot::Receiver<()>,
+    ) {
+        while let Some(source_path) = self.locked_files.pop() {
+            if shutdown.try_recv().is_ok() || self.is_destination_full() {
+                self.locked_files.requeue(source_path);
+                return;
             }
-            ComparisonMode::Thorough(hash_type) => {
-                io_manager
-                    .thorough_compare(source_path, destination_path, hash_type)
-                    .await
+            let result = match self.calculate_destination_path(
+                &source_path,
+                &execution.source_path,
+                &execution.destination_path,
+            ) {
+                Ok(destination_path) => {
+                    self.backup_file(execution, &source_path, &destination_path, None)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => {
+                    execution_counters.add_processed(1);
+                    if let Some((directory, signature)) = self.locked_files.copied(&source_path) {
+                        self.save_directory_signature(execution, &directory, &signature)
+                            .await;
+                    }
+                }
+                Err(_) if self.copy_progress.is_cancelled() || self.is_destination_full() => {
+                    self.locked_files.requeue(source_path);
+                    return;
+                }
+                Err(e) => {
+                    self.locked_files.give_up(&source_path);
+                    worker_errors.push(e);
+                }
             }
-        }?;
-
-        if need_copy {
-            io_manager.copy_file(source_path, destination_path).await
-        } else {
-            Ok(())
         }
     }
 
+    fn is_destination_full(&self) -> bool {
+        self.destination_full.load(Ordering::Relaxed)
+    }
+
+    // A failed write is put down to a full destination only when it lacks room for the file
+    async fn out_of_space(&self, source_path: &Path, destination_path: &Path) -> bool {
+        let Some(directory) = destination_path.parent().map(Path::to_path_buf) else {
+            return false;
+        };
+        let Ok(metadata) = fs::metadata(source_path).await else {
+            return false;
+        };
+        let available = spawn_blocking(move || available_space(&directory)).await;
+        matches!(available, Ok(Some(available)) if available < metadata.len().max(1))
+    }
+
+    // Probes the source per destination entry instead of keeping every source name around,
+    // extraneous entries are then deleted concurrently under the metadata semaphore.
+    // Links, junctions and mount points are removed as they are, the contents of their target
+    // never belong to the mirror
     async fn mirror_cleanup(
         &self,
-        source_entries: Vec<PathBuf>,
-        destination_entries: Vec<PathBuf>,
+        execution: &Execution,
+        source_dir: &Path,
+        destination_dir: &Path,
     ) -> ((), Vec<Error>) {
         let io_manager = &self.io_manager;
 
         let mut errors = Vec::new();
+        let mut extraneous = Vec::new();
 
-        let source_names: HashSet<_> = source_entries
-            .into_iter()
-            .filter_map(|path| path.file_name().map(|name| name.to_owned()))
-            .collect();
+        let mut destination_entries = match io_manager.stream_directory(destination_dir).await {
+            Ok(destination_entries) => destination_entries,
+            Err(e) => return ((), vec![e]),
+        };
+        let source_names = match self.source_names(source_dir, destination_dir).await {
+            Ok(source_names) => source_names,
+            Err(e) => return ((), vec![e]),
+        };
 
-        for dest_entry in destination_entries {
-            if let Some(file_name) = dest_entry.file_name() {
-                if !source_names.contains(file_name) {
-                    if dest_entry.is_dir() {
-                        if let Err(e) = io_manager.delete_directory(&dest_entry).await {
-                            errors.push(e);
-                        }
-                    } else if let Err(e) = io_manager.delete_file(&dest_entry).await {
-                        errors.push(e);
+        loop {
+            let dest_entry = match destination_entries.next_entry().await {
+                Ok(Some(dest_entry)) => dest_entry,
+                Ok(None) => break,
+                Err(e) => {
+                    errors.push(e);
+                    break;
+                }
+            };
+            let Some(file_name) = dest_entry.file_name() else {
+                continue;
+            };
+            if destination_dir == execution.destination_path && file_name == MANIFEST_NAME {
+                continue;
+            }
+            let exists = match self
+                .source_entry_exists(source_dir, file_name, source_names.as_ref())
+                .await
+            {
+                Ok(false) if !self.capabilities.any_name => {
+                    self.renamed_entry_exists(
+                        execution,
+                        source_dir,
+                        &dest_entry,
+                        source_names.as_ref(),
+                    )
+                    .await
+                }
+                exists => exists,
+            };
+            match exists {
+                Ok(true) => {}
+                Ok(false) => extraneous.push(dest_entry),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let mut deletions = stream::iter(extraneous)
+            .map(|dest_entry| async move {
+                let result = match io_manager.is_symlink(&dest_entry).await {
+                    Ok(true) => io_manager.delete_link(&dest_entry).await,
+                    Ok(false) if dest_entry.is_dir() => {
+                        io_manager.delete_directory(&dest_entry).await
                     }
+                    Ok(false) => io_manager.delete_file(&dest_entry).await,
+                    Err(e) => Err(e),
+                };
+                (dest_entry, result)
+            })
+            .buffer_unordered(self.app_config.max_metadata_operations.max(1));
+        while let Some((dest_entry, result)) = deletions.next().await {
+            match result {
+                Ok(_) => {
+                    self.transfer_counters.add_deleted();
+                    self.record_catalog(execution, CatalogAction::Deleted, &dest_entry);
                 }
+                Err(e) => errors.push(e),
             }
         }
 
         ((), errors)
     }
 
+    // Names on shares written from macOS are often decomposed while the source keeps them
+    // composed, matching only the raw bytes would delete and recopy them on every run
+    async fn source_entry_exists(
+        &self,
+        source_dir: &Path,
+        file_name: &OsStr,
+        source_names: Option<&SourceNames>,
+    ) -> Result<bool, Error> {
+        let io_manager = &self.io_manager;
+
+        if let Some(source_names) = source_names {
+            return Ok(source_names.contains(file_name));
+        }
+        if io_manager.entry_exists(&source_dir.join(file_name)).await? {
+            return Ok(true);
+        }
+        for variant in normalized_variants(file_name) {
+            if io_manager.entry_exists(&source_dir.join(variant)).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Looking a name up in the source follows the source's case rule, where the destination
+    // compares names differently they are matched against a listing under its rule instead
+    async fn source_names(
+        &self,
+        source_dir: &Path,
+        destination_dir: &Path,
+    ) -> Result<Option<SourceNames>, Error> {
+        let case_sensitivity = self.case_sensitivity;
+        let source_insensitive =
+            is_directory_case_insensitive(source_dir, case_sensitivity.source_insensitive).await;
+        let case_insensitive =
+            is_directory_case_insensitive(destination_dir, case_sensitivity.destination_insensitive)
+                .await;
+        if source_insensitive == case_insensitive {
+            return Ok(None);
+        }
+        let mut entries = self.io_manager.stream_directory(source_dir).await?;
+        let mut keys = HashSet::new();
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(file_name) = entry.file_name() {
+                keys.insert(comparison_key(file_name, case_insensitive));
+            }
+        }
+        Ok(Some(SourceNames {
+            keys,
+            case_insensitive,
+        }))
+    }
+
+    async fn renamed_entry_exists(
+        &self,
+        execution: &Execution,
+        source_dir: &Path,
+        destination_entry: &Path,
+        source_names: Option<&SourceNames>,
+    ) -> Result<bool, Error> {
+        let original_name = self
+            .database_manager
+            .get_renamed_entry(
+                &execution.source_path,
+                &execution.destination_path,
+                destination_entry,
+            )
+            .await?;
+        match original_name {
+            Some(original_name) => {
+                self.source_entry_exists(source_dir, OsStr::new(&original_name), source_names)
+                    .await
+            }
+            None => Ok(false),
+        }
+    }
+
     fn calculate_destination_path(
         &self,
         source_path: &Path,
@@ -653,7 +2951,14 @@ impl Worker {
         let relative_path = source_path
             .strip_prefix(source_root)
             .map_err(SystemError::UnexpectError)?;
-        Ok(destination_root.join(relative_path))
+        let mut destination_path = destination_root.to_path_buf();
+        for component in relative_path.iter() {
+            match self.portable_name(component) {
+                Some(portable) => destination_path.push(portable),
+                None => destination_path.push(component),
+            }
+        }
+        Ok(destination_path)
     }
 }
 
@@ -662,7 +2967,10 @@ impl CommandHandler<BackupCommand> for BackupEngine {
     async fn handle_command(&self, command: BackupCommand) -> Result<(), Error> {
         match command {
             BackupCommand::AddExecution(execution) => {
-                self.add_execution(execution).await;
+                if self.database_manager.is_read_only() {
+                    Err(DatabaseError::DatabaseReadOnly)?
+                }
+                self.add_execution(execution).await?;
             }
             BackupCommand::RemoveExecution(uuid) => {
                 self.remove_execution(&uuid).await;
@@ -676,6 +2984,24 @@ impl CommandHandler<BackupCommand> for BackupEngine {
             BackupCommand::ResumeExecution(uuid) => {
                 self.resume_execution(&uuid).await?;
             }
+            BackupCommand::CancelExecution(uuid, discard_partial) => {
+                self.cancel_execution(&uuid, discard_partial).await?;
+            }
+            BackupCommand::VerifyExecution(uuid) => {
+                self.verify_execution(&uuid)?;
+            }
+            BackupCommand::SetWorkerLimit(uuid, worker_limit) => {
+                self.set_worker_limit(&uuid, worker_limit)?;
+            }
+            BackupCommand::ExportReport(history, report_path) => {
+                self.export_report(&history, &report_path).await?;
+            }
+            BackupCommand::RestoreFile(found, target_directory) => {
+                self.catalog_search_service.restore(&found, &target_directory).await?;
+            }
+            BackupCommand::ReleaseChangeJournals => {
+                self.release_change_journals().await?;
+            }
         }
         Ok(())
     }
@@ -689,6 +3015,42 @@ impl QueryHandler<BackupQuery> for BackupEngine {
                 let executions = self.get_all_executions();
                 Ok(BackupQueryResponse::GetExecutions(executions))
             }
+            BackupQuery::GetHistory => {
+                let history = self.database_manager.get_all_execution_history().await?;
+                Ok(BackupQueryResponse::GetHistory(history))
+            }
+            BackupQuery::GetHistoryErrors(history_uuid) => {
+                let errors = self.database_manager.get_execution_errors(history_uuid).await?;
+                Ok(BackupQueryResponse::GetHistoryErrors(errors))
+            }
+            BackupQuery::GetCatalog(history_uuid) => {
+                let entries = self.database_manager.get_execution_catalog(history_uuid).await?;
+                Ok(BackupQueryResponse::GetCatalog(entries))
+            }
+            BackupQuery::GetBytesCopiedPerDay(range) => {
+                let days = self.database_manager.get_bytes_copied_per_day(range).await?;
+                Ok(BackupQueryResponse::GetBytesCopiedPerDay(days))
+            }
+            BackupQuery::GetErrorCountsPerSchedule(range) => {
+                let schedules = self.database_manager.get_error_counts_per_schedule(range).await?;
+                Ok(BackupQueryResponse::GetErrorCountsPerSchedule(schedules))
+            }
+            BackupQuery::GetAverageDuration(schedule_uuid) => {
+                let duration = self.database_manager.get_average_duration(schedule_uuid).await?;
+                Ok(BackupQueryResponse::GetAverageDuration(duration))
+            }
+            BackupQuery::GetStorageUsage(range) => {
+                let usage = self.storage_usage_service.usage(range).await?;
+                Ok(BackupQueryResponse::GetStorageUsage(usage))
+            }
+            BackupQuery::GetJournal(range) => {
+                let entries = self.database_manager.get_journal_entries(range).await?;
+                Ok(BackupQueryResponse::GetJournal(entries))
+            }
+            BackupQuery::SearchCatalogs(pattern) => {
+                let matches = self.catalog_search_service.search(&pattern).await?;
+                Ok(BackupQueryResponse::SearchCatalogs(matches))
+            }
         }
     }
 }
@@ -1,7 +1,14 @@
 use crate::core::backup::backup_engine::BackupEngine;
+use crate::core::backup::catalog_search_service::CatalogSearchService;
+use crate::core::backup::change_detector::ChangeDetector;
+use crate::core::backup::manifest_service::ManifestService;
 use crate::core::backup::progress_tracker::ProgressTracker;
+use crate::core::backup::report_service::ReportService;
+use crate::core::backup::scan_service::ScanService;
+use crate::core::backup::storage_usage_service::StorageUsageService;
 use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::core::infrastructure::database_manager::DatabaseManager;
 use crate::core::infrastructure::io_manager::IOManager;
 use std::sync::Arc;
 
@@ -13,14 +20,38 @@ impl BackupService {
     pub async fn new(
         app_config: Arc<AppConfig>,
         io_manager: Arc<IOManager>,
+        database_manager: Arc<DatabaseManager>,
         communication_manager: Arc<CommunicationManager>,
     ) -> Self {
-        let progress_tracker = Arc::new(ProgressTracker::new(io_manager.clone()));
+        let progress_tracker = Arc::new(ProgressTracker::new(
+            io_manager.clone(),
+            &app_config.progress_path,
+        ));
+        let change_detector = Arc::new(ChangeDetector::new(
+            io_manager.clone(),
+            &app_config.journal_path,
+        ));
+        let scan_service = Arc::new(ScanService::new(io_manager.clone()));
+        let manifest_service =
+            Arc::new(ManifestService::new(app_config.clone(), io_manager.clone()));
+        let report_service = Arc::new(ReportService::new());
+        let catalog_search_service = Arc::new(CatalogSearchService::new(database_manager.clone()));
+        let storage_usage_service = Arc::new(StorageUsageService::new(
+            app_config.clone(),
+            database_manager.clone(),
+        ));
         let backup_engine = Arc::new(BackupEngine::new(
             app_config,
             io_manager,
+            database_manager,
             communication_manager,
             progress_tracker,
+            change_detector,
+            scan_service,
+            manifest_service,
+            report_service,
+            catalog_search_service,
+            storage_usage_service,
         ));
         Self { backup_engine }
     }
@@ -30,6 +61,10 @@ impl BackupService {
         backup_engine.register_services().await;
     }
 
+    pub async fn restore_executions(&self) {
+        self.backup_engine.restore_executions().await;
+    }
+
     pub async fn shutdown(&self) {
         self.backup_engine.stop_all_executions().await;
     }
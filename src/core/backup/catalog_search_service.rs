@@ -0,0 +1,156 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::interface::repository::execution_history::ExecutionHistoryRepository;
+use crate::model::core::backup::catalog::{CatalogAction, CatalogMatch, MAX_CATALOG_MATCHES};
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::utils::wildcard::{has_wildcard, wildcard_match};
+use chrono::NaiveDateTime;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+
+// How a search pattern is compared with the files of one run
+struct CatalogMatcher {
+    pattern: String,
+    whole_path: bool,
+    exact: bool,
+}
+
+impl CatalogMatcher {
+    // An absolute path names one file under the source folder, so runs of other sources are
+    // skipped, a pattern with a separator is compared with the whole path and any other with
+    // the file name
+    fn for_run(pattern: &str, source_path: &Path) -> Option<Self> {
+        let pattern_path = Path::new(pattern);
+        if pattern_path.is_absolute() {
+            let relative = pattern_path.strip_prefix(source_path).ok()?;
+            return Some(Self {
+                pattern: normalize(relative),
+                whole_path: true,
+                exact: true,
+            });
+        }
+        let pattern = normalize(pattern_path);
+        Some(Self {
+            whole_path: pattern.contains('/'),
+            pattern,
+            exact: false,
+        })
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let text = if self.whole_path {
+            normalize(path)
+        } else {
+            match path.file_name() {
+                Some(file_name) => normalize(Path::new(file_name)),
+                None => return false,
+            }
+        };
+        if has_wildcard(&self.pattern) {
+            wildcard_match(&self.pattern, &text)
+        } else if self.exact {
+            text == self.pattern
+        } else {
+            text.contains(&self.pattern)
+        }
+    }
+}
+
+// Case and separators differ between platforms, neither should decide a search
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+pub struct CatalogSearchService {
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl CatalogSearchService {
+    pub fn new(database_manager: Arc<DatabaseManager>) -> Self {
+        Self { database_manager }
+    }
+
+    // Only runs that recorded their changed files are searched, a file backed up before that
+    // and never changed since is not found
+    pub async fn search(&self, pattern: &str) -> Result<Vec<CatalogMatch>, Error> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut matches: Vec<CatalogMatch> = Vec::new();
+        let mut positions: HashMap<(PathBuf, PathBuf), usize> = HashMap::new();
+        // Newest first, so the first run seen for a file is its latest one
+        let histories = self.database_manager.get_all_execution_history().await?;
+        for history in histories {
+            let Some(matcher) = CatalogMatcher::for_run(pattern, &history.source_path) else {
+                continue;
+            };
+            let entries = self.database_manager.get_execution_catalog(history.uuid).await?;
+            for entry in entries {
+                if !matcher.matches(&entry.path) {
+                    continue;
+                }
+                let copied = usize::from(entry.action != CatalogAction::Deleted);
+                let key = (history.destination_path.clone(), entry.path.clone());
+                match positions.get(&key) {
+                    Some(&position) => matches[position].versions += copied,
+                    None if matches.len() < MAX_CATALOG_MATCHES => {
+                        positions.insert(key, matches.len());
+                        matches.push(CatalogMatch {
+                            destination_path: history.destination_path.clone(),
+                            path: entry.path,
+                            action: entry.action,
+                            finished_at: history.finished_at,
+                            versions: copied,
+                            present: false,
+                            newest: false,
+                        });
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        let mut newest: HashMap<PathBuf, NaiveDateTime> = HashMap::new();
+        for found in &mut matches {
+            let file_path = found.destination_path.join(&found.path);
+            found.present = found.action != CatalogAction::Deleted
+                && fs::try_exists(&file_path).await.unwrap_or(false);
+            if found.present {
+                let latest = newest.entry(found.path.clone()).or_insert(found.finished_at);
+                *latest = (*latest).max(found.finished_at);
+            }
+        }
+        for found in &mut matches {
+            found.newest = found.present && newest.get(&found.path) == Some(&found.finished_at);
+        }
+        matches.sort_by_key(|found| (found.path.clone(), Reverse(found.finished_at)));
+        Ok(matches)
+    }
+
+    // Copied next to the other files of target_directory, an existing file is never replaced
+    pub async fn restore(
+        &self,
+        found: &CatalogMatch,
+        target_directory: &Path,
+    ) -> Result<(), Error> {
+        let source = found.destination_path.join(&found.path);
+        let Some(file_name) = found.path.file_name() else {
+            Err(IOError::FileDoesNotExist { path: source })?
+        };
+        let target = target_directory.join(file_name);
+        if fs::try_exists(&target).await.unwrap_or(false) {
+            Err(IOError::FileAlreadyExists {
+                path: target.clone(),
+            })?
+        }
+        fs::copy(&source, &target)
+            .await
+            .map_err(|err| IOError::CopyFileFailed(&source, &target, err))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::core::infrastructure::io_manager::IOManager;
+use crate::interface::core::change_journal::ChangeJournalTrait;
+use crate::interface::core::file_system::FileSystemTrait;
+use crate::model::core::backup::change_set::ChangeSet;
+use crate::model::core::backup::execution::Execution;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use crate::platform::change_journal::{ChangeCursor, ChangeJournal};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tracing::{error, info};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize)]
+struct SavedCursor {
+    cursor: ChangeCursor,
+    // Scopes walked in full by runs that ended with errors, walked in full again until a clean run
+    retry: Vec<PathBuf>,
+}
+
+pub struct ChangeDetector {
+    io_manager: Arc<IOManager>,
+    change_journal: ChangeJournal,
+    journal_path: PathBuf,
+}
+
+impl ChangeDetector {
+    pub fn new(io_manager: Arc<IOManager>, journal_path: &Path) -> Self {
+        Self {
+            io_manager,
+            change_journal: ChangeJournal::new(),
+            journal_path: journal_path.to_path_buf(),
+        }
+    }
+
+    pub async fn snapshot(&self, root: &Path) -> Option<ChangeCursor> {
+        match self.change_journal.current_cursor(root).await {
+            Ok(cursor) => Some(cursor),
+            Err(err) => {
+                info!("{}", err);
+                None
+            }
+        }
+    }
+
+    pub fn release_unused(&self, in_use: &HashSet<PathBuf>) {
+        self.change_journal.release_unused(in_use);
+    }
+
+    pub async fn detect(&self, execution: &Execution) -> Option<ChangeSet> {
+        let root = &execution.source_path;
+        let saved = self.read_cursor(execution).await.ok()?;
+        match self
+            .change_journal
+            .changed_directories(root, &saved.cursor)
+            .await
+        {
+            Ok(directories) => Some(ChangeSet::new(root, directories, saved.retry)),
+            Err(err) => {
+                error!("{}", err);
+                None
+            }
+        }
+    }
+
+    pub async fn commit(&self, execution: &Execution, cursor: ChangeCursor) -> Result<(), Error> {
+        let saved = SavedCursor {
+            cursor,
+            retry: Vec::new(),
+        };
+        self.write_cursor(execution, &saved).await
+    }
+
+    // A run with errors keeps the previous cursor, so the changes it saw are reported again, and
+    // adds the scopes it walked in full, which the journal alone would not report again
+    pub async fn retain(&self, execution: &Execution, retry: Vec<PathBuf>) -> Result<(), Error> {
+        let Ok(mut saved) = self.read_cursor(execution).await else {
+            return Ok(());
+        };
+        for directory in retry {
+            if !saved.retry.contains(&directory) {
+                saved.retry.push(directory);
+            }
+        }
+        self.write_cursor(execution, &saved).await
+    }
+
+    async fn write_cursor(&self, execution: &Execution, saved: &SavedCursor) -> Result<(), Error> {
+        let saved_path = self.cursor_path(execution);
+
+        if let Some(parent) = saved_path.parent() {
+            self.io_manager.create_directory(parent).await?;
+        }
+
+        let config = bincode::config::standard();
+        let serialized =
+            bincode::serde::encode_to_vec(saved, config).map_err(MiscError::SerializeError)?;
+
+        fs::write(&saved_path, serialized)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(saved_path, err))?;
+
+        Ok(())
+    }
+
+    async fn read_cursor(&self, execution: &Execution) -> Result<SavedCursor, Error> {
+        let saved_path = self.cursor_path(execution);
+
+        let serialized = fs::read(&saved_path)
+            .await
+            .map_err(|err| IOError::ReadFileFailed(saved_path, err))?;
+
+        let config = bincode::config::standard();
+        let (saved, _) = bincode::serde::decode_from_slice(&serialized, config)
+            .map_err(MiscError::DeserializeError)?;
+
+        Ok(saved)
+    }
+
+    // One cursor per source, destination and owning schedule, a cursor advanced by one backup
+    // of a source would otherwise hide the changes from every other backup of it
+    fn cursor_path(&self, execution: &Execution) -> PathBuf {
+        let owner = execution.schedule_uuid.unwrap_or(execution.uuid);
+        let key = cursor_key(&execution.source_path, &execution.destination_path, owner);
+        self.journal_path.join(key)
+    }
+}
+
+fn cursor_key(source: &Path, destination: &Path, owner: Uuid) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(source.as_os_str().as_encoded_bytes());
+    hasher.update(&[0]);
+    hasher.update(destination.as_os_str().as_encoded_bytes());
+    hasher.update(&[0]);
+    hasher.update(owner.as_bytes());
+    hasher.finalize().to_hex().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn destinations_sharing_a_source_keep_separate_cursors() {
+        let source = Path::new("/data/source");
+        let owner = Uuid::new_v4();
+        let first = cursor_key(source, Path::new("/backup/first"), owner);
+        let second = cursor_key(source, Path::new("/backup/second"), owner);
+        assert_ne!(first, second);
+        assert_eq!(first, cursor_key(source, Path::new("/backup/first"), owner));
+    }
+
+    #[test]
+    fn schedules_sharing_source_and_destination_keep_separate_cursors() {
+        let source = Path::new("/data/source");
+        let destination = Path::new("/backup/first");
+        let first = cursor_key(source, destination, Uuid::new_v4());
+        let second = cursor_key(source, destination, Uuid::new_v4());
+        assert_ne!(first, second);
+    }
+}
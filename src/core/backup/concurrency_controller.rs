@@ -0,0 +1,191 @@
+use crate::model::core::backup::execution::WorkerLimit;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+pub const ADJUST_INTERVAL: Duration = Duration::from_secs(2);
+const THROUGHPUT_TOLERANCE: f64 = 0.05;
+const LATENCY_TOLERANCE: f64 = 2.0;
+// Weight of the newest window in the latency baseline, so it follows a workload that changed
+const LATENCY_SMOOTHING: f64 = 0.3;
+// Per-entry work that copies nothing (stat, compare, metadata), counted as this many bytes
+const ENTRY_COST_BYTES: f64 = 64.0 * 1024.0;
+
+pub struct ConcurrencyController {
+    min_concurrency: usize,
+    max_concurrency: usize,
+    active_limit: AtomicUsize,
+    // Zero while the limit is tuned automatically
+    fixed_limit: AtomicUsize,
+    completed: AtomicU64,
+    bytes: AtomicU64,
+    busy_micros: AtomicU64,
+    state: Mutex<ControllerState>,
+}
+
+struct ControllerState {
+    last_sample: Instant,
+    last_throughput: f64,
+    baseline_latency: f64,
+    direction: isize,
+}
+
+impl ConcurrencyController {
+    pub fn new(min_concurrency: usize, max_concurrency: usize) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        let min_concurrency = min_concurrency.clamp(1, max_concurrency);
+        Self {
+            min_concurrency,
+            max_concurrency,
+            active_limit: AtomicUsize::new(max_concurrency),
+            fixed_limit: AtomicUsize::new(0),
+            completed: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            busy_micros: AtomicU64::new(0),
+            state: Mutex::new(ControllerState {
+                last_sample: Instant::now(),
+                last_throughput: 0.0,
+                baseline_latency: 0.0,
+                direction: -1,
+            }),
+        }
+    }
+
+    pub fn is_active(&self, worker_index: usize) -> bool {
+        worker_index < self.active_limit.load(Ordering::Relaxed)
+    }
+
+    pub fn active_limit(&self) -> usize {
+        self.active_limit.load(Ordering::Relaxed)
+    }
+
+    // A fixed limit may go below min_concurrency, workers beyond max_concurrency do not exist
+    pub fn set_limit(&self, worker_limit: WorkerLimit) {
+        match worker_limit {
+            WorkerLimit::Auto => self.fixed_limit.store(0, Ordering::Relaxed),
+            WorkerLimit::Fixed(limit) => {
+                let limit = limit.clamp(1, self.max_concurrency);
+                self.fixed_limit.store(limit, Ordering::Relaxed);
+                self.active_limit.store(limit, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn record(&self, elapsed: Duration) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+        self.busy_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_batch(&self, elapsed: Duration, count: usize) {
+        self.completed.fetch_add(count as u64, Ordering::Relaxed);
+        self.busy_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn add_bytes(&self, bytes: u64) {
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    // Hill climbing on throughput, backing off when latency degrades
+    pub fn adjust(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let elapsed = state.last_sample.elapsed().as_secs_f64();
+        state.last_sample = Instant::now();
+        let completed = self.completed.swap(0, Ordering::Relaxed);
+        let bytes = self.bytes.swap(0, Ordering::Relaxed);
+        let busy_micros = self.busy_micros.swap(0, Ordering::Relaxed);
+        if self.fixed_limit.load(Ordering::Relaxed) != 0 {
+            state.last_throughput = 0.0;
+            return;
+        }
+        self.apply(&mut state, elapsed, completed, bytes, busy_micros);
+    }
+
+    // Both throughput and latency are measured per byte of work, so a switch between small and
+    // large files does not look like a change in how well the current limit performs
+    fn apply(
+        &self,
+        state: &mut ControllerState,
+        elapsed: f64,
+        completed: u64,
+        bytes: u64,
+        busy_micros: u64,
+    ) {
+        if completed == 0 || elapsed <= 0.0 {
+            return;
+        }
+
+        let work = bytes as f64 + completed as f64 * ENTRY_COST_BYTES;
+        let throughput = work / elapsed;
+        let latency = busy_micros as f64 / work;
+        let baseline_latency = state.baseline_latency;
+        state.baseline_latency = if baseline_latency == 0.0 {
+            latency
+        } else {
+            baseline_latency + (latency - baseline_latency) * LATENCY_SMOOTHING
+        };
+
+        if baseline_latency != 0.0 && latency > baseline_latency * LATENCY_TOLERANCE {
+            state.direction = -1;
+        } else if throughput < state.last_throughput * (1.0 - THROUGHPUT_TOLERANCE) {
+            state.direction = -state.direction;
+        } else if throughput < state.last_throughput * (1.0 + THROUGHPUT_TOLERANCE) {
+            state.last_throughput = throughput;
+            return;
+        }
+        state.last_throughput = throughput;
+
+        let active_limit = self.active_limit.load(Ordering::Relaxed);
+        let next_limit = active_limit
+            .saturating_add_signed(state.direction)
+            .clamp(self.min_concurrency, self.max_concurrency);
+        if next_limit == active_limit {
+            state.direction = -state.direction;
+        }
+        self.active_limit.store(next_limit, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * KIB;
+
+    const SATURATION: u64 = 8;
+
+    // Every window keeps all active workers busy for two seconds, the device saturates at eight
+    fn run_windows(controller: &ConcurrencyController, windows: usize, files: u64, size: u64) {
+        for _ in 0..windows {
+            let mut state = controller.state.lock().unwrap();
+            let active_limit = controller.active_limit() as u64;
+            let files = (files * active_limit.min(SATURATION) / SATURATION).max(1);
+            let busy_micros = active_limit * 2_000_000;
+            controller.apply(&mut state, 2.0, files, files * size, busy_micros);
+        }
+    }
+
+    #[test]
+    fn changing_file_sizes_do_not_collapse_the_limit() {
+        let controller = ConcurrencyController::new(1, 16);
+
+        run_windows(&controller, 20, 2000, 4 * KIB);
+        run_windows(&controller, 20, 8, 256 * MIB);
+        run_windows(&controller, 20, 2000, 4 * KIB);
+
+        assert!(controller.active_limit() > 4);
+    }
+
+    #[test]
+    fn latency_baseline_follows_a_slower_workload() {
+        let controller = ConcurrencyController::new(1, 16);
+
+        run_windows(&controller, 1, 100, 64 * MIB);
+        run_windows(&controller, 40, 100, 4 * MIB);
+
+        assert!(controller.active_limit() > 4);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::model::core::backup::directory_signature::DirectorySignature;
+use crossbeam_queue::SegQueue;
+use dashmap::{DashMap, DashSet};
+use std::path::{Path, PathBuf};
+
+// Files skipped as locked, retried once the whole tree has been walked. Their directories only
+// get a signature once every deferred file in them has been copied, until then they are dirty
+pub struct LockedFiles {
+    queue: SegQueue<PathBuf>,
+    // Deferred files until copied or given up on, whether queued or being retried
+    pending: DashSet<PathBuf>,
+    directories: DashMap<PathBuf, DeferredDirectory>,
+}
+
+#[derive(Default)]
+struct DeferredDirectory {
+    pending: usize,
+    signature: Option<DirectorySignature>,
+}
+
+impl LockedFiles {
+    pub fn new() -> Self {
+        Self {
+            queue: SegQueue::new(),
+            pending: DashSet::new(),
+            directories: DashMap::new(),
+        }
+    }
+
+    pub fn defer(&self, path: PathBuf) {
+        if let Some(directory) = path.parent() {
+            self.directories
+                .entry(directory.to_path_buf())
+                .or_default()
+                .pending += 1;
+        }
+        self.pending.insert(path.clone());
+        self.queue.push(path);
+    }
+
+    // Put back by a retry that was cut off, the file is still pending
+    pub fn requeue(&self, path: PathBuf) {
+        self.queue.push(path);
+    }
+
+    pub fn pop(&self) -> Option<PathBuf> {
+        self.queue.pop()
+    }
+
+    // Left to the resumed run when a checkpoint is taken
+    pub fn pending(&self) -> Vec<PathBuf> {
+        self.pending.iter().map(|path| path.clone()).collect()
+    }
+
+    // Reported as an error, the directory stays dirty
+    pub fn give_up(&self, path: &Path) {
+        self.pending.remove(path);
+    }
+
+    pub fn is_dirty(&self, directory: &Path) -> bool {
+        self.directories.contains_key(directory)
+    }
+
+    pub fn hold_signature(&self, directory: &Path, signature: DirectorySignature) {
+        if let Some(mut deferred) = self.directories.get_mut(directory) {
+            deferred.signature = Some(signature);
+        }
+    }
+
+    // The directory and its held signature once the last deferred file in it has been copied
+    pub fn copied(&self, path: &Path) -> Option<(PathBuf, DirectorySignature)> {
+        self.pending.remove(path);
+        let directory = path.parent()?;
+        let (directory, deferred) = self.directories.remove_if_mut(directory, |_, deferred| {
+            deferred.pending = deferred.pending.saturating_sub(1);
+            deferred.pending == 0
+        })?;
+        Some((directory, deferred.signature?))
+    }
+}
@@ -0,0 +1,217 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::core::infrastructure::io_manager::IOManager;
+use crate::interface::core::file_system::FileSystemTrait;
+use crate::model::core::backup::execution::HashType;
+use crate::model::core::backup::manifest::{MANIFEST_NAME, ManifestEntry, ManifestHeader};
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use crate::model::error::task::TaskError;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{self, File};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+pub struct ManifestService {
+    app_config: Arc<AppConfig>,
+    io_manager: Arc<IOManager>,
+}
+
+impl ManifestService {
+    pub fn new(app_config: Arc<AppConfig>, io_manager: Arc<IOManager>) -> Self {
+        Self {
+            app_config,
+            io_manager,
+        }
+    }
+
+    // Hashes what actually landed on the destination, files that cannot be read are left out
+    // and returned as errors
+    pub async fn write(
+        &self,
+        destination_root: &Path,
+        hash_type: HashType,
+    ) -> Result<Vec<Error>, Error> {
+        let manifest_path = destination_root.join(MANIFEST_NAME);
+        let temp_path = manifest_path.with_extension("tmp");
+        let file = File::create(&temp_path)
+            .await
+            .map_err(|err| IOError::CreateFileFailed(&temp_path, err))?;
+        let mut writer = BufWriter::new(file);
+        let header = ManifestHeader {
+            hash_type,
+            created_at: chrono::Utc::now().naive_utc(),
+        };
+        Self::write_line(&mut writer, &temp_path, &header).await?;
+
+        let mut errors = Vec::new();
+        let mut pending = vec![destination_root.to_path_buf()];
+        while let Some(directory) = pending.pop() {
+            let files = match Self::list_directory(&directory, &mut pending).await {
+                Ok(files) => files,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+            let files = files
+                .into_iter()
+                .filter(|(path, _)| *path != manifest_path && *path != temp_path);
+            let io_manager = &self.io_manager;
+            let mut hashes = stream::iter(files)
+                .map(|(path, size)| async move {
+                    let hash = io_manager.calculate_hash(&path, hash_type).await;
+                    (path, size, hash)
+                })
+                .buffer_unordered(self.app_config.max_data_operations.max(1));
+            while let Some((path, size, hash)) = hashes.next().await {
+                match hash {
+                    Ok(hash) => {
+                        let entry = ManifestEntry {
+                            path: Self::relative_name(destination_root, &path),
+                            size,
+                            hash: Self::hex(&hash),
+                        };
+                        Self::write_line(&mut writer, &temp_path, &entry).await?;
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&temp_path, err))?;
+        writer
+            .get_ref()
+            .sync_all()
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&temp_path, err))?;
+        drop(writer);
+        fs::rename(&temp_path, &manifest_path)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&manifest_path, err))?;
+        Ok(errors)
+    }
+
+    // Returns how many files still match, every missing or changed file comes back as an error
+    pub async fn verify(&self, destination_root: &Path) -> Result<(usize, Vec<Error>), Error> {
+        let manifest_path = destination_root.join(MANIFEST_NAME);
+        let file = File::open(&manifest_path)
+            .await
+            .map_err(|err| IOError::ReadFileFailed(&manifest_path, err))?;
+        let mut lines = BufReader::new(file).lines();
+        let mut next_line = async || {
+            lines
+                .next_line()
+                .await
+                .map_err(|err| IOError::ReadFileFailed(&manifest_path, err))
+        };
+        let invalid = || TaskError::InvalidManifest {
+            path: manifest_path.clone(),
+        };
+
+        let header = next_line().await?.ok_or_else(invalid)?;
+        let header: ManifestHeader = serde_json::from_str(&header).map_err(|_| invalid())?;
+        let mut entries = Vec::new();
+        while let Some(line) = next_line().await? {
+            let entry: ManifestEntry = serde_json::from_str(&line).map_err(|_| invalid())?;
+            entries.push(entry);
+        }
+
+        let mut checks = stream::iter(entries)
+            .map(|entry| self.check_entry(destination_root, entry, header.hash_type))
+            .buffer_unordered(self.app_config.max_data_operations.max(1));
+        let mut verified = 0;
+        let mut errors = Vec::new();
+        while let Some(result) = checks.next().await {
+            match result {
+                Ok(()) => verified += 1,
+                Err(e) => errors.push(e),
+            }
+        }
+        Ok((verified, errors))
+    }
+
+    async fn check_entry(
+        &self,
+        destination_root: &Path,
+        entry: ManifestEntry,
+        hash_type: HashType,
+    ) -> Result<(), Error> {
+        let path = destination_root.join(&entry.path);
+        let metadata = match fs::metadata(&path).await {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Err(TaskError::ManifestFileMissing { path: path.clone() })?
+            }
+            Err(err) => Err(IOError::GetMetadataFailed(&path, err))?,
+        };
+        if metadata.len() != entry.size {
+            Err(TaskError::ManifestFileCorrupted { path: path.clone() })?
+        }
+        let hash = self.io_manager.calculate_hash(&path, hash_type).await?;
+        if Self::hex(&hash) != entry.hash {
+            Err(TaskError::ManifestFileCorrupted { path })?
+        }
+        Ok(())
+    }
+
+    // Symlinks are not followed, whatever they point to is not part of this backup
+    async fn list_directory(
+        directory: &Path,
+        pending: &mut Vec<PathBuf>,
+    ) -> Result<Vec<(PathBuf, u64)>, Error> {
+        let mut entries = fs::read_dir(directory)
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(directory, err))?;
+        let mut files = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(directory, err))?
+        {
+            let path = entry.path();
+            let metadata = fs::symlink_metadata(&path)
+                .await
+                .map_err(|err| IOError::GetMetadataFailed(&path, err))?;
+            if metadata.is_dir() {
+                pending.push(path);
+            } else if metadata.is_file() {
+                files.push((path, metadata.len()));
+            }
+        }
+        Ok(files)
+    }
+
+    async fn write_line<T: Serialize>(
+        writer: &mut BufWriter<File>,
+        path: &Path,
+        value: &T,
+    ) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(value).map_err(MiscError::SerializeError)?;
+        line.push(b'\n');
+        writer
+            .write_all(&line)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(path, err))?;
+        Ok(())
+    }
+
+    fn relative_name(root: &Path, path: &Path) -> String {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        relative
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
@@ -1,3 +1,15 @@
+pub mod background_weight;
 pub mod backup_engine;
+pub mod catalog_search_service;
+pub mod change_detector;
+pub mod concurrency_controller;
+pub mod locked_files;
+pub mod manifest_service;
 pub mod progress_tracker;
+pub mod report_service;
+pub mod scan_service;
+pub mod share_reconnector;
+pub mod sleep_inhibitor;
+pub mod storage_usage_service;
+pub mod work_queue;
 pub mod backup_service;
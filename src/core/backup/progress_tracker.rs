@@ -1,41 +1,88 @@
 use crate::core::infrastructure::io_manager::IOManager;
 use crate::interface::core::file_system::FileSystemTrait;
+use crate::model::core::backup::execution::Execution;
 use crate::model::core::backup::progress_data::ProgressData;
 use crate::model::error::Error;
 use crate::model::error::io::IOError;
 use crate::model::error::misc::MiscError;
-use crate::platform::constants::PROGRESS_SAVE_PATH;
 use memmap2::MmapMut;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::fs::OpenOptions;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tracing::error;
 use uuid::Uuid;
 
+// Files written before the header existed lack the magic and read as version 0
+const PROGRESS_MAGIC: &[u8; 4] = b"MSPG";
+const PROGRESS_VERSION: u32 = 2;
+
 pub struct ProgressTracker {
     io_manager: Arc<IOManager>,
+    progress_path: PathBuf,
 }
 
 impl ProgressTracker {
-    pub fn new(io_manager: Arc<IOManager>) -> Self {
-        Self { io_manager }
+    pub fn new(io_manager: Arc<IOManager>, progress_path: &Path) -> Self {
+        Self {
+            io_manager,
+            progress_path: progress_path.to_path_buf(),
+        }
     }
 
     pub async fn save_execution(
         &self,
         execution_uuid: Uuid,
-        current_level: Vec<PathBuf>,
-        errors: Vec<Error>,
+        progress_data: ProgressData,
     ) -> Result<(), Error> {
-        let progress_data = ProgressData::new(current_level, errors);
-
         self.write_progress_file(execution_uuid, &progress_data)
             .await
     }
 
-    pub async fn resume_execution(&self, execution_uuid: Uuid) -> (Vec<PathBuf>, Vec<Error>) {
+    pub async fn resume_execution(&self, execution_uuid: Uuid) -> Option<ProgressData> {
         match self.read_progress_file(execution_uuid).await {
-            Ok(progress_data) => (progress_data.current_level, progress_data.errors),
-            Err(_) => (Vec::new(), Vec::new()),
+            Ok(progress_data) => Some(progress_data),
+            Err(err) => {
+                error!("{}", err);
+                None
+            }
+        }
+    }
+
+    // Checkpoints left by a process that ended mid-run, each holding the execution it belongs to
+    pub async fn saved_executions(&self) -> Vec<Execution> {
+        let Ok(mut entries) = fs::read_dir(&self.progress_path).await else {
+            return Vec::new();
+        };
+        let mut executions = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Some(execution_uuid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| Uuid::parse_str(name).ok())
+            else {
+                continue;
+            };
+            match self.read_progress_file(execution_uuid).await {
+                Ok(ProgressData {
+                    execution: Some(execution),
+                    ..
+                }) => executions.push(execution),
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        executions
+    }
+
+    pub async fn remove_execution(&self, execution_uuid: Uuid) -> Result<(), Error> {
+        let saved_path = self.progress_path.join(execution_uuid.to_string());
+        match fs::remove_file(&saved_path).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                Err(IOError::DeleteFileFailed(saved_path, err))?
+            }
+            _ => Ok(()),
         }
     }
 
@@ -44,7 +91,7 @@ impl ProgressTracker {
         execution_uuid: Uuid,
         data: &ProgressData,
     ) -> Result<(), Error> {
-        let saved_path = PathBuf::from(PROGRESS_SAVE_PATH).join(execution_uuid.to_string());
+        let saved_path = self.progress_path.join(execution_uuid.to_string());
 
         if let Some(parent) = saved_path.parent() {
             let instance = &self.io_manager;
@@ -53,35 +100,37 @@ impl ProgressTracker {
         }
 
         let config = bincode::config::standard();
-        let serialized = bincode::serde::encode_to_vec(data, config)
-            .map_err(MiscError::DeserializeError)?;
-        let data_len = serialized.len();
-
-        let file = OpenOptions::new()
+        let mut serialized = PROGRESS_MAGIC.to_vec();
+        serialized.extend(PROGRESS_VERSION.to_le_bytes());
+        bincode::serde::encode_into_std_write(data, &mut serialized, config)
+            .map_err(MiscError::SerializeError)?;
+
+        // Written aside and renamed over, a crash mid-write leaves the previous checkpoint intact
+        let temp_path = saved_path.with_extension("tmp");
+        let mut file = OpenOptions::new()
             .create(true)
             .write(true)
             .truncate(true)
-            .open(&saved_path)
+            .open(&temp_path)
             .await
-            .map_err(|err| IOError::CreateFileFailed(saved_path.clone(), err))?;
-
-        file.set_len(data_len as u64)
+            .map_err(|err| IOError::CreateFileFailed(temp_path.clone(), err))?;
+        file.write_all(&serialized)
             .await
-            .map_err(|err| IOError::WriteFileFailed(saved_path.clone(), err))?;
+            .map_err(|err| IOError::WriteFileFailed(temp_path.clone(), err))?;
+        file.sync_all()
+            .await
+            .map_err(|err| IOError::WriteFileFailed(temp_path.clone(), err))?;
+        drop(file);
 
-        let mut mmap = unsafe {
-            MmapMut::map_mut(&file)
-                .map_err(|err| IOError::WriteFileFailed(saved_path.clone(), err))?
-        };
-        mmap[..data_len].copy_from_slice(&serialized);
-        mmap.flush()
+        fs::rename(&temp_path, &saved_path)
+            .await
             .map_err(|err| IOError::WriteFileFailed(saved_path, err))?;
 
         Ok(())
     }
 
     async fn read_progress_file(&self, execution_uuid: Uuid) -> Result<ProgressData, Error> {
-        let saved_path = PathBuf::from(PROGRESS_SAVE_PATH).join(execution_uuid.to_string());
+        let saved_path = self.progress_path.join(execution_uuid.to_string());
 
         if !saved_path.exists() {
             Err(IOError::FileDoesNotExist {
@@ -97,8 +146,18 @@ impl ProgressTracker {
             MmapMut::map_mut(&file).map_err(|err| IOError::ReadFileFailed(saved_path, err))?
         };
 
+        let version = match mmap.split_first_chunk::<4>() {
+            Some((magic, rest)) if magic == PROGRESS_MAGIC => rest
+                .first_chunk::<4>()
+                .map_or(0, |version| u32::from_le_bytes(*version)),
+            _ => 0,
+        };
+        if version != PROGRESS_VERSION {
+            Err(MiscError::UnsupportedProgressVersion { version })?
+        }
+
         let config = bincode::config::standard();
-        let (progress_data, _) = bincode::serde::decode_from_slice(&mmap, config)
+        let (progress_data, _) = bincode::serde::decode_from_slice(&mmap[8..], config)
             .map_err(MiscError::DeserializeError)?;
 
         Ok(progress_data)
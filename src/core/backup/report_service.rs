@@ -0,0 +1,214 @@
+use crate::model::core::backup::execution_history::ExecutionHistory;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::record::ErrorRecord;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+const MEBIBYTE: f64 = 1048576.0;
+
+// Files directly in the destination root are summed under this row
+const ROOT_FILES: &str = "(files in root)";
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:4px 10px;text-align:left}\
+th{background:#eee}td.number{text-align:right}\
+.ERROR{color:#b00020}.WARN{color:#b36b00}";
+
+#[derive(Default)]
+struct DirectorySize {
+    files: usize,
+    bytes: u64,
+}
+
+#[derive(Default)]
+pub struct ReportService;
+
+impl ReportService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Directory sizes are read from the destination as it is when the report is written
+    pub async fn write(
+        &self,
+        history: &ExecutionHistory,
+        errors: &[ErrorRecord],
+        report_path: &Path,
+    ) -> Result<(), Error> {
+        let directories = Self::top_level_sizes(&history.destination_path).await?;
+        let html = Self::render(history, errors, &directories);
+        fs::write(report_path, html)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(report_path, err))?;
+        Ok(())
+    }
+
+    // Symlinks are not followed, unreadable subdirectories are left out of the sums
+    async fn top_level_sizes(
+        destination_root: &Path,
+    ) -> Result<Vec<(String, DirectorySize)>, Error> {
+        let mut sizes: BTreeMap<OsString, DirectorySize> = BTreeMap::new();
+        let mut pending: Vec<(PathBuf, OsString)> = Vec::new();
+
+        let mut entries = fs::read_dir(destination_root)
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(destination_root, err))?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if metadata.is_dir() {
+                sizes.entry(entry.file_name()).or_default();
+                pending.push((entry.path(), entry.file_name()));
+            } else if metadata.is_file() {
+                let size = sizes.entry(OsString::from(ROOT_FILES)).or_default();
+                size.files += 1;
+                size.bytes += metadata.len();
+            }
+        }
+
+        while let Some((directory, top_level)) = pending.pop() {
+            let Ok(mut entries) = fs::read_dir(&directory).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending.push((entry.path(), top_level.clone()));
+                } else if metadata.is_file() {
+                    let size = sizes.entry(top_level.clone()).or_default();
+                    size.files += 1;
+                    size.bytes += metadata.len();
+                }
+            }
+        }
+
+        let mut sizes: Vec<(String, DirectorySize)> = sizes
+            .into_iter()
+            .map(|(name, size)| (name.to_string_lossy().to_string(), size))
+            .collect();
+        sizes.sort_by_key(|(_, size)| Reverse(size.bytes));
+        Ok(sizes)
+    }
+
+    fn render(
+        history: &ExecutionHistory,
+        errors: &[ErrorRecord],
+        directories: &[(String, DirectorySize)],
+    ) -> String {
+        let started_at = history.started_at.format("%Y-%m-%d %H:%M:%S");
+        let duration = (history.finished_at - history.started_at).num_seconds();
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>MirrorSphere Report - {started_at}</title>\n<style>{STYLE}</style>\n\
+            </head>\n<body>\n<h1>MirrorSphere Run Report</h1>\n"
+        );
+
+        html.push_str("<h2>Summary</h2>\n<table>\n");
+        let summary = [
+            ("Source", Self::escape(&history.source_path.to_string_lossy())),
+            ("Destination", Self::escape(&history.destination_path.to_string_lossy())),
+            ("State", format!("{:?}", history.state)),
+            ("Started", started_at.to_string()),
+            ("Finished", history.finished_at.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ("Duration", format!("{duration}s")),
+            ("Files Scanned", history.files_scanned.to_string()),
+            ("Files Copied", history.files_copied.to_string()),
+            ("Entries Deleted", history.entries_deleted.to_string()),
+            ("Data Copied", Self::format_size(history.bytes_copied)),
+            ("Errors", history.error_count.to_string()),
+        ];
+        for (name, value) in summary {
+            let _ = writeln!(html, "<tr><th>{name}</th><td>{value}</td></tr>");
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Top-Level Directories</h2>\n<table>\n");
+        html.push_str("<tr><th>Name</th><th>Files</th><th>Size</th></tr>\n");
+        for (name, size) in directories {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                Self::escape(name),
+                size.files,
+                Self::format_size(size.bytes),
+            );
+        }
+        html.push_str("</table>\n");
+
+        if let Some(capabilities) = &history.capabilities {
+            html.push_str("<h2>Filesystem Capabilities</h2>\n<table>\n");
+            html.push_str("<tr><th>Feature</th><th>Source</th><th>Destination</th></tr>\n");
+            let mark = |supported: bool| if supported { "Yes" } else { "No" };
+            for (name, source, destination) in capabilities.rows() {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{name}</td><td>{}</td><td>{}</td></tr>",
+                    mark(source),
+                    mark(destination),
+                );
+            }
+            let _ = writeln!(
+                html,
+                "<tr><td>Timestamp Resolution</td><td>{}</td><td>{}</td></tr>",
+                capabilities.source.resolution_label(),
+                capabilities.destination.resolution_label(),
+            );
+            html.push_str("</table>\n");
+        }
+
+        // Only the errors kept under max_stored_errors are listed
+        let _ = writeln!(
+            html,
+            "<h2>Errors ({} of {} stored)</h2>",
+            errors.len(),
+            history.error_count
+        );
+        if !errors.is_empty() {
+            html.push_str("<table>\n<tr><th>Level</th><th>Code</th><th>Message</th></tr>\n");
+            for error in errors {
+                let _ = writeln!(
+                    html,
+                    "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    Self::escape(&error.level),
+                    Self::escape(&error.level),
+                    Self::escape(&error.code),
+                    Self::escape(&error.message),
+                );
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn format_size(bytes: u64) -> String {
+        format!("{:.1} MiB", bytes as f64 / MEBIBYTE)
+    }
+
+    fn escape(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for character in text.chars() {
+            match character {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                character => escaped.push(character),
+            }
+        }
+        escaped
+    }
+}
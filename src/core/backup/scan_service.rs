@@ -0,0 +1,67 @@
+use crate::core::infrastructure::io_manager::IOManager;
+use crate::interface::core::file_system::FileSystemTrait;
+use crate::model::core::backup::scan_totals::ScanTotals;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::task::JoinHandle;
+
+pub struct ScanService {
+    io_manager: Arc<IOManager>,
+}
+
+impl ScanService {
+    pub fn new(io_manager: Arc<IOManager>) -> Self {
+        Self { io_manager }
+    }
+
+    // Runs alongside the workers, totals grow until the walk finishes
+    pub fn start(&self, source_root: PathBuf) -> (Arc<ScanTotals>, JoinHandle<()>) {
+        let io_manager = self.io_manager.clone();
+        let scan_totals = Arc::new(ScanTotals::new());
+        let totals = scan_totals.clone();
+        let handle = tokio::spawn(async move {
+            let mut pending = vec![source_root];
+            while let Some(directory) = pending.pop() {
+                // Unreadable directories are reported by the workers, the scan just skips them
+                let _ = Self::scan_directory(&io_manager, &directory, &mut pending, &totals).await;
+            }
+            totals.finish();
+        });
+        (scan_totals, handle)
+    }
+
+    async fn scan_directory(
+        io_manager: &IOManager,
+        directory: &Path,
+        pending: &mut Vec<PathBuf>,
+        totals: &ScanTotals,
+    ) -> Result<(), Error> {
+        let semaphore = io_manager.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let mut entries = fs::read_dir(directory)
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(directory, err))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(directory, err))?
+        {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                totals.add_file(metadata.len());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crate::model::log::io::IOLog;
+use crate::platform::file_system::reconnect_share;
+use macros::log;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tokio::task::spawn_blocking;
+use tokio::time::sleep;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+pub enum ShareState {
+    Connected,
+    Reconnected,
+    Disconnected,
+}
+
+// Shared by the workers of an execution, the first one to notice a dropped share reconnects it
+// while the others wait on the lock and find it back afterwards
+pub struct ShareReconnector {
+    roots: [PathBuf; 2],
+    attempts: u32,
+    lock: Mutex<()>,
+    // The root that could not be reached again, every worker stops once it is set
+    disconnected: OnceLock<PathBuf>,
+}
+
+impl ShareReconnector {
+    pub fn new(source_root: PathBuf, destination_root: PathBuf, attempts: u32) -> Self {
+        Self {
+            roots: [source_root, destination_root],
+            attempts,
+            lock: Mutex::new(()),
+            disconnected: OnceLock::new(),
+        }
+    }
+
+    pub fn disconnected(&self) -> Option<&PathBuf> {
+        self.disconnected.get()
+    }
+
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.get().is_some()
+    }
+
+    // A root that no longer answers means the errors came from a dropped share, not the entries
+    pub async fn restore(&self) -> ShareState {
+        let _guard = self.lock.lock().await;
+        if self.is_disconnected() {
+            return ShareState::Disconnected;
+        }
+
+        let mut reconnected = false;
+        for root in &self.roots {
+            if fs::metadata(root).await.is_ok() {
+                continue;
+            }
+            if !self.reconnect(root).await {
+                let _ = self.disconnected.set(root.clone());
+                return ShareState::Disconnected;
+            }
+            reconnected = true;
+        }
+        match reconnected {
+            true => ShareState::Reconnected,
+            false => ShareState::Connected,
+        }
+    }
+
+    async fn reconnect(&self, root: &Path) -> bool {
+        for attempt in 1..=self.attempts {
+            let path = root.to_path_buf();
+            let connected = spawn_blocking(move || reconnect_share(&path))
+                .await
+                .unwrap_or(false);
+            if connected && fs::metadata(root).await.is_ok() {
+                log!(IOLog::ShareReconnected {
+                    path: root.display().to_string(),
+                    attempt,
+                });
+                return true;
+            }
+            sleep(RECONNECT_DELAY).await;
+        }
+        false
+    }
+}
@@ -0,0 +1,60 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::interface::core::power_request::PowerRequestTrait;
+use crate::platform::power_request::PowerRequest;
+use std::sync::{Arc, Mutex, PoisonError};
+use tracing::error;
+
+// One power request for all running executions, taken by the first and released after the last
+pub struct SleepInhibitor {
+    enabled: bool,
+    allow_display_sleep: bool,
+    state: Mutex<InhibitorState>,
+}
+
+#[derive(Default)]
+struct InhibitorState {
+    holders: usize,
+    request: Option<PowerRequest>,
+}
+
+impl SleepInhibitor {
+    pub fn new(app_config: &AppConfig) -> Self {
+        Self {
+            enabled: app_config.prevent_sleep,
+            allow_display_sleep: app_config.allow_display_sleep,
+            state: Mutex::new(InhibitorState::default()),
+        }
+    }
+
+    // A failed request only gets logged, the run goes on and the system may sleep
+    pub fn hold(self: &Arc<Self>) -> SleepHold {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.holders += 1;
+        if self.enabled && state.request.is_none() {
+            state.request = PowerRequest::acquire(self.allow_display_sleep)
+                .inspect_err(|err| error!("{}", err))
+                .ok();
+        }
+        SleepHold {
+            inhibitor: self.clone(),
+        }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.holders -= 1;
+        if state.holders == 0 {
+            state.request = None;
+        }
+    }
+}
+
+pub struct SleepHold {
+    inhibitor: Arc<SleepInhibitor>,
+}
+
+impl Drop for SleepHold {
+    fn drop(&mut self) {
+        self.inhibitor.release();
+    }
+}
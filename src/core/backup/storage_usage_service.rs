@@ -0,0 +1,152 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::interface::repository::execution_history::ExecutionHistoryRepository;
+use crate::interface::repository::storage_usage::StorageUsageRepository;
+use crate::model::core::backup::storage_usage::{DestinationUsage, StorageSample};
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::log::io::IOLog;
+use crate::platform::file_system::available_space;
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use macros::log;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+use tokio::task::spawn_blocking;
+
+// Growth is measured over the samples of this many days before a run
+const GROWTH_WINDOW_DAYS: i64 = 30;
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+pub struct StorageUsageService {
+    app_config: Arc<AppConfig>,
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl StorageUsageService {
+    pub fn new(app_config: Arc<AppConfig>, database_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            app_config,
+            database_manager,
+        }
+    }
+
+    // Warns once the footprint is projected to use up the free space within the warning period
+    pub async fn record(&self, destination_path: &Path) -> Result<(), Error> {
+        let footprint = Self::footprint(destination_path).await?;
+        let directory = destination_path.to_path_buf();
+        let available = spawn_blocking(move || available_space(&directory))
+            .await
+            .ok()
+            .flatten();
+        let now = Local::now().naive_local();
+        let sample = StorageSample {
+            destination_path: destination_path.to_path_buf(),
+            recorded_at: now,
+            footprint,
+            available,
+        };
+        self.database_manager.create_storage_sample(&sample).await?;
+
+        let warning_days = self.app_config.storage_warning_days;
+        if warning_days == 0 {
+            return Ok(());
+        }
+        let usage = self.usage(now - TimeDelta::days(GROWTH_WINDOW_DAYS)..now).await?;
+        let days_until_full = usage
+            .iter()
+            .find(|usage| usage.destination_path == destination_path)
+            .and_then(|usage| usage.days_until_full);
+        if let Some(days) = days_until_full
+            && days < warning_days as f64
+        {
+            log!(IOLog::DestinationFillingUp {
+                destination: destination_path.display().to_string(),
+                days: days.floor() as u64,
+            });
+        }
+        Ok(())
+    }
+
+    pub async fn usage(&self, range: Range<NaiveDateTime>) -> Result<Vec<DestinationUsage>, Error> {
+        let samples = self
+            .database_manager
+            .get_storage_samples_since(range.start)
+            .await?;
+        let mut bytes_written: BTreeMap<PathBuf, u64> = self
+            .database_manager
+            .get_bytes_copied_per_destination(range)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut per_destination: BTreeMap<PathBuf, Vec<StorageSample>> = BTreeMap::new();
+        for sample in samples {
+            per_destination
+                .entry(sample.destination_path.clone())
+                .or_default()
+                .push(sample);
+        }
+
+        let mut usage = Vec::new();
+        for (destination_path, samples) in per_destination {
+            let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+                continue;
+            };
+            let elapsed_days =
+                (last.recorded_at - first.recorded_at).num_seconds() as f64 / SECONDS_PER_DAY;
+            let growth_per_day = (elapsed_days >= 1.0)
+                .then(|| (last.footprint as f64 - first.footprint as f64) / elapsed_days);
+            let days_until_full = match (growth_per_day, last.available) {
+                (Some(growth), Some(available)) if growth > 0.0 => {
+                    Some(available as f64 / growth)
+                }
+                _ => None,
+            };
+            usage.push(DestinationUsage {
+                bytes_written: bytes_written.remove(&destination_path).unwrap_or(0),
+                footprint: last.footprint,
+                available: last.available,
+                growth_per_day,
+                days_until_full,
+                footprints: samples
+                    .iter()
+                    .map(|sample| (sample.recorded_at, sample.footprint))
+                    .collect(),
+                destination_path,
+            });
+        }
+        Ok(usage)
+    }
+
+    // Symlinks are not followed, unreadable subdirectories are left out of the sum
+    async fn footprint(destination_root: &Path) -> Result<u64, Error> {
+        let mut footprint = 0;
+        let mut entries = fs::read_dir(destination_root)
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(destination_root, err))?;
+        let mut pending = Vec::new();
+        loop {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                } else if metadata.is_file() {
+                    footprint += metadata.len();
+                }
+            }
+            let Some(directory) = pending.pop() else {
+                break;
+            };
+            let Ok(next) = fs::read_dir(&directory).await else {
+                continue;
+            };
+            entries = next;
+        }
+        Ok(footprint)
+    }
+}
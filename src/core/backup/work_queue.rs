@@ -0,0 +1,402 @@
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use crate::model::error::system::SystemError;
+use macros::log;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::task::spawn_blocking;
+use tracing::error;
+use uuid::Uuid;
+
+// The spill file is only touched on blocking threads. Whatever moves directories between it and
+// the queue holds the spill lock first and the state lock second, so a checkpoint taken under
+// both never sees a directory in neither
+pub struct WorkQueue {
+    max_queued_directories: usize,
+    state: Arc<Mutex<QueueState>>,
+    spill: Arc<Mutex<SpillFile>>,
+}
+
+struct QueueState {
+    queued: VecDeque<PathBuf>,
+    // Entries finished so far inside each directory being processed
+    in_progress: HashMap<PathBuf, HashSet<PathBuf>>,
+    // Finished entries from a checkpoint, waiting for their directory to be popped again
+    resumed: HashMap<PathBuf, HashSet<PathBuf>>,
+    // Handed to the spill file but not written yet
+    spilling: Vec<PathBuf>,
+    unread_spill: bool,
+}
+
+impl WorkQueue {
+    pub fn new(
+        execution_uuid: Uuid,
+        directories: Vec<PathBuf>,
+        completed_entries: Vec<PathBuf>,
+        spill_range: (u64, u64),
+        max_queued_directories: usize,
+        progress_path: &Path,
+    ) -> Self {
+        let mut resumed: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
+        for entry in completed_entries {
+            if let Some(parent) = entry.parent() {
+                resumed.entry(parent.to_path_buf()).or_default().insert(entry);
+            }
+        }
+        let spill = SpillFile::new(execution_uuid, spill_range, progress_path);
+        // Checkpointed directories were all held in memory when they were saved
+        Self {
+            max_queued_directories: max_queued_directories.max(1),
+            state: Arc::new(Mutex::new(QueueState {
+                queued: directories.into(),
+                in_progress: HashMap::new(),
+                resumed,
+                spilling: Vec::new(),
+                unread_spill: !spill.is_empty(),
+            })),
+            spill: Arc::new(Mutex::new(spill)),
+        }
+    }
+
+    // Directories beyond the in-memory limit are appended to a spill file next to the progress data
+    pub async fn push(&self, directory: PathBuf) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if state.queued.len() < self.max_queued_directories {
+                state.queued.push_back(directory);
+                return;
+            }
+            state.spilling.push(directory.clone());
+        }
+
+        let state = self.state.clone();
+        let spill = self.spill.clone();
+        let result = spawn_blocking(move || {
+            let mut spill = spill.lock().unwrap_or_else(PoisonError::into_inner);
+            let result = spill.write(&directory);
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(position) = state.spilling.iter().position(|path| *path == directory) {
+                state.spilling.swap_remove(position);
+            }
+            match result {
+                Ok(()) => state.unread_spill = true,
+                Err(_) => state.queued.push_back(directory),
+            }
+            result
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => error!("{}", err),
+            Err(err) => log!(SystemError::ThreadPanic(err)),
+        }
+    }
+
+    // Directories stay in progress until completed so a checkpoint can resume them
+    pub async fn pop(&self) -> Result<Option<PathBuf>, Error> {
+        let refill = {
+            let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.queued.is_empty() && state.unread_spill
+        };
+        if refill {
+            self.refill().await?;
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(directory) = state.queued.pop_front() else {
+            return Ok(None);
+        };
+        let completed = state.resumed.remove(&directory).unwrap_or_default();
+        state.in_progress.insert(directory.clone(), completed);
+        Ok(Some(directory))
+    }
+
+    // Records before a failed one are queued, the failed one stays unread for a resume to retry
+    async fn refill(&self) -> Result<(), Error> {
+        let refill_size = self.max_queued_directories.div_ceil(2);
+        let state = self.state.clone();
+        let spill = self.spill.clone();
+        spawn_blocking(move || {
+            let mut spill = spill.lock().unwrap_or_else(PoisonError::into_inner);
+            let mut directories = Vec::new();
+            let result = spill.read(refill_size, &mut directories);
+            let mut state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.queued.extend(directories);
+            state.unread_spill = !spill.is_empty();
+            result
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+    }
+
+    pub fn complete(&self, directory: &Path) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.in_progress.remove(directory);
+    }
+
+    // Entries finished before the directory was given up on are still skipped when it comes back
+    pub async fn retry(&self, directory: &Path) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(completed) = state.in_progress.remove(directory) {
+                state.resumed.insert(directory.to_path_buf(), completed);
+            }
+        }
+        self.push(directory.to_path_buf()).await;
+    }
+
+    pub fn complete_entry(&self, entry: &Path) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(parent) = entry.parent()
+            && let Some(completed) = state.in_progress.get_mut(parent)
+        {
+            completed.insert(entry.to_path_buf());
+        }
+    }
+
+    pub fn is_entry_completed(&self, entry: &Path) -> bool {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        entry
+            .parent()
+            .and_then(|parent| state.in_progress.get(parent))
+            .is_some_and(|completed| completed.contains(entry))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.queued.is_empty()
+            && state.in_progress.is_empty()
+            && state.spilling.is_empty()
+            && !state.unread_spill
+    }
+
+    pub fn in_progress(&self) -> Vec<PathBuf> {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.in_progress.keys().cloned().collect()
+    }
+
+    // Spilled directories stay on disk, the checkpoint only records the unread range
+    pub async fn checkpoint(&self) -> Result<(Vec<PathBuf>, Vec<PathBuf>, (u64, u64)), Error> {
+        let state = self.state.clone();
+        let spill = self.spill.clone();
+        spawn_blocking(move || {
+            let mut spill = spill.lock().unwrap_or_else(PoisonError::into_inner);
+            spill.flush()?;
+            let state = state.lock().unwrap_or_else(PoisonError::into_inner);
+            let pending_directories = state
+                .in_progress
+                .keys()
+                .chain(state.queued.iter())
+                .chain(state.spilling.iter())
+                .cloned()
+                .collect();
+            let completed_entries = state
+                .in_progress
+                .values()
+                .chain(state.resumed.values())
+                .flatten()
+                .cloned()
+                .collect();
+            Ok((pending_directories, completed_entries, spill.range()))
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+    }
+
+    pub async fn remove_spill_file(&self) {
+        let spill = self.spill.clone();
+        let result = spawn_blocking(move || {
+            spill
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove()
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => error!("{}", err),
+            Err(err) => log!(SystemError::ThreadPanic(err)),
+        }
+    }
+}
+
+struct SpillFile {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    reader: Option<BufReader<File>>,
+    read_offset: u64,
+    write_offset: u64,
+}
+
+impl SpillFile {
+    fn new(
+        execution_uuid: Uuid,
+        (read_offset, write_offset): (u64, u64),
+        progress_path: &Path,
+    ) -> Self {
+        Self {
+            path: progress_path.join(format!("{execution_uuid}.queue")),
+            writer: None,
+            reader: None,
+            read_offset,
+            write_offset,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.read_offset >= self.write_offset
+    }
+
+    fn range(&self) -> (u64, u64) {
+        (self.read_offset, self.write_offset)
+    }
+
+    fn write(&mut self, directory: &Path) -> Result<(), Error> {
+        let config = bincode::config::standard();
+        let record = bincode::serde::encode_to_vec(directory, config)
+            .map_err(MiscError::SerializeError)?;
+
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let file = self
+                    .open_writer()
+                    .map_err(|err| IOError::CreateFileFailed(&self.path, err))?;
+                self.writer.insert(BufWriter::new(file))
+            }
+        };
+        writer
+            .write_all(&(record.len() as u32).to_le_bytes())
+            .and_then(|_| writer.write_all(&record))
+            .map_err(|err| IOError::WriteFileFailed(&self.path, err))?;
+
+        self.write_offset += (size_of::<u32>() + record.len()) as u64;
+        Ok(())
+    }
+
+    // A resumed queue continues after the checkpointed range, later records were never committed
+    fn open_writer(&self) -> io::Result<File> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&self.path)?;
+        file.set_len(self.write_offset)?;
+        file.seek(SeekFrom::Start(self.write_offset))?;
+        Ok(file)
+    }
+
+    // The read offset only moves past complete records, a failed read reopens the file there
+    fn read(&mut self, count: usize, directories: &mut Vec<PathBuf>) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        self.flush()?;
+
+        let result = self.read_records(count, directories);
+        if result.is_err() {
+            self.reader = None;
+        }
+        result
+    }
+
+    fn read_records(&mut self, count: usize, directories: &mut Vec<PathBuf>) -> Result<(), Error> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => {
+                let mut file = File::open(&self.path)
+                    .map_err(|err| IOError::ReadFileFailed(&self.path, err))?;
+                file.seek(SeekFrom::Start(self.read_offset))
+                    .map_err(|err| IOError::ReadFileFailed(&self.path, err))?;
+                self.reader.insert(BufReader::new(file))
+            }
+        };
+
+        let config = bincode::config::standard();
+        let mut record = Vec::new();
+        let mut read = 0;
+        while read < count && self.read_offset < self.write_offset {
+            let mut length = [0_u8; size_of::<u32>()];
+            reader
+                .read_exact(&mut length)
+                .map_err(|err| IOError::ReadFileFailed(&self.path, err))?;
+            record.resize(u32::from_le_bytes(length) as usize, 0);
+            reader
+                .read_exact(&mut record)
+                .map_err(|err| IOError::ReadFileFailed(&self.path, err))?;
+
+            let (directory, _) = bincode::serde::decode_from_slice(&record, config)
+                .map_err(MiscError::DeserializeError)?;
+            directories.push(directory);
+            read += 1;
+            self.read_offset += (length.len() + record.len()) as u64;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        if let Some(writer) = &mut self.writer {
+            writer
+                .flush()
+                .map_err(|err| IOError::WriteFileFailed(&self.path, err))?;
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self) -> Result<(), Error> {
+        self.writer = None;
+        self.reader = None;
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                Err(IOError::DeleteFileFailed(&self.path, err))?
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_failed_spill_read_keeps_the_unread_range() {
+        let directory = std::env::temp_dir().join("mirrorsphere_a_failed_spill_read_keeps_range");
+        let _ = fs::remove_dir_all(&directory);
+        let execution_uuid = Uuid::new_v4();
+        let work_queue =
+            WorkQueue::new(execution_uuid, Vec::new(), Vec::new(), (0, 0), 1, &directory);
+        for path in ["/first", "/second", "/third"] {
+            work_queue.push(PathBuf::from(path)).await;
+        }
+        let (_, _, (_, write_offset)) = work_queue.checkpoint().await.unwrap();
+
+        // Cut the last record short
+        let spill_path = directory.join(format!("{execution_uuid}.queue"));
+        let mut length = [0_u8; size_of::<u32>()];
+        File::open(&spill_path).unwrap().read_exact(&mut length).unwrap();
+        let third_offset = (length.len() + u32::from_le_bytes(length) as usize) as u64;
+        File::options()
+            .write(true)
+            .open(&spill_path)
+            .unwrap()
+            .set_len(write_offset - 1)
+            .unwrap();
+
+        assert_eq!(work_queue.pop().await.unwrap(), Some(PathBuf::from("/first")));
+        assert_eq!(work_queue.pop().await.unwrap(), Some(PathBuf::from("/second")));
+        assert!(work_queue.pop().await.is_err());
+        let (_, _, spill_range) = work_queue.checkpoint().await.unwrap();
+        assert_eq!(spill_range, (third_offset, write_offset));
+        assert!(!work_queue.is_finished());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+use crate::model::core::backup::execution::HashType;
+use crate::model::core::benchmark::benchmark_result::BenchmarkResult;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::utils::file_hash::buffer_hash;
+use chrono::Utc;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Instant;
+use uuid::Uuid;
+
+const WRITE_SAMPLE_SIZE: usize = 33554432;
+const READ_SAMPLE_SIZE: u64 = 134217728;
+const READ_BUFFER_SIZE: usize = 1048576;
+const READ_SCAN_LIMIT: usize = 10000;
+const HASH_SAMPLE_SIZE: usize = 33554432;
+const SMALL_FILE_COUNT: usize = 512;
+const SMALL_FILE_SIZE: usize = 4096;
+const BUFFER_SIZES: [usize; 4] = [65536, 262144, 1048576, 4194304];
+const CONCURRENCY_LEVELS: [usize; 6] = [1, 2, 4, 8, 16, 32];
+const CONCURRENCY_GAIN: f64 = 1.1;
+const HASH_TYPES: [HashType; 6] = [
+    HashType::MD5,
+    HashType::SHA3,
+    HashType::SHA256,
+    HashType::BLAKE2B,
+    HashType::BLAKE2S,
+    HashType::BLAKE3,
+];
+
+pub struct BenchmarkRunner;
+
+impl BenchmarkRunner {
+    // Blocking, measurements run inside a scratch directory under the destination
+    pub fn run(source: &Path, destination: &Path) -> Result<BenchmarkResult, Error> {
+        let uuid = Uuid::new_v4();
+        let scratch = destination.join(format!(".mirrorsphere-benchmark-{uuid}"));
+        fs::create_dir_all(&scratch)
+            .map_err(|err| IOError::CreateDirectoryFailed(&scratch, err))?;
+
+        let result = Self::measure(uuid, source, destination, &scratch);
+
+        if let Err(err) = fs::remove_dir_all(&scratch) {
+            Err(IOError::DeleteDirectoryFailed(&scratch, err))?
+        }
+        result
+    }
+
+    fn measure(
+        uuid: Uuid,
+        source: &Path,
+        destination: &Path,
+        scratch: &Path,
+    ) -> Result<BenchmarkResult, Error> {
+        let (write_throughput, recommended_buffer_size) = Self::measure_write(scratch)?;
+        let read_throughput = Self::measure_read(source)?;
+        let hash_throughput = Self::measure_hashing();
+        let (small_file_ops, recommended_concurrency) = Self::measure_small_files(scratch)?;
+
+        Ok(BenchmarkResult {
+            uuid,
+            source_path: source.to_path_buf(),
+            destination_path: destination.to_path_buf(),
+            read_throughput,
+            write_throughput,
+            hash_throughput,
+            small_file_ops,
+            recommended_concurrency: recommended_concurrency.min(u8::MAX as usize) as u8,
+            recommended_buffer_size,
+            created_at: Utc::now().naive_utc(),
+        })
+    }
+
+    fn measure_write(scratch: &Path) -> Result<(f64, usize), Error> {
+        let mut best = (0.0, BUFFER_SIZES[0]);
+        for buffer_size in BUFFER_SIZES {
+            let path = scratch.join(format!("write-{buffer_size}"));
+            let buffer = vec![0xA5_u8; buffer_size];
+
+            let start = Instant::now();
+            let mut file =
+                File::create(&path).map_err(|err| IOError::CreateFileFailed(&path, err))?;
+            for _ in 0..WRITE_SAMPLE_SIZE / buffer_size {
+                file.write_all(&buffer)
+                    .map_err(|err| IOError::WriteFileFailed(&path, err))?;
+            }
+            file.sync_all()
+                .map_err(|err| IOError::WriteFileFailed(&path, err))?;
+            let throughput = WRITE_SAMPLE_SIZE as f64 / start.elapsed().as_secs_f64();
+
+            fs::remove_file(&path).map_err(|err| IOError::DeleteFileFailed(&path, err))?;
+            if throughput > best.0 {
+                best = (throughput, buffer_size);
+            }
+        }
+        Ok(best)
+    }
+
+    // Reads existing source files, the source is never written to
+    fn measure_read(source: &Path) -> Result<f64, Error> {
+        let mut files = Vec::new();
+        let mut sample_size = 0_u64;
+        let mut pending = vec![source.to_path_buf()];
+        let mut scanned = 0;
+        while let Some(directory) = pending.pop() {
+            let entries = fs::read_dir(&directory)
+                .map_err(|err| IOError::ReadDirectoryFailed(&directory, err))?;
+            for entry in entries.flatten() {
+                scanned += 1;
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                } else if metadata.is_file() && metadata.len() > 0 {
+                    sample_size += metadata.len();
+                    files.push(entry.path());
+                }
+            }
+            if sample_size >= READ_SAMPLE_SIZE || scanned >= READ_SCAN_LIMIT {
+                break;
+            }
+        }
+
+        let mut buffer = vec![0_u8; READ_BUFFER_SIZE];
+        let mut read_bytes = 0_u64;
+        let start = Instant::now();
+        for path in files {
+            let mut file = File::open(&path).map_err(|err| IOError::ReadFileFailed(&path, err))?;
+            loop {
+                let read = file
+                    .read(&mut buffer)
+                    .map_err(|err| IOError::ReadFileFailed(&path, err))?;
+                if read == 0 {
+                    break;
+                }
+                read_bytes += read as u64;
+            }
+            if read_bytes >= READ_SAMPLE_SIZE {
+                break;
+            }
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+        if read_bytes == 0 || elapsed <= 0.0 {
+            return Ok(0.0);
+        }
+        Ok(read_bytes as f64 / elapsed)
+    }
+
+    fn measure_hashing() -> Vec<(HashType, f64)> {
+        let buffer: Vec<u8> = (0..HASH_SAMPLE_SIZE).map(|index| index as u8).collect();
+        HASH_TYPES
+            .into_iter()
+            .map(|hash_type| {
+                let start = Instant::now();
+                buffer_hash(hash_type, &buffer);
+                (hash_type, HASH_SAMPLE_SIZE as f64 / start.elapsed().as_secs_f64())
+            })
+            .collect()
+    }
+
+    // Raises concurrency while small file throughput still improves noticeably
+    fn measure_small_files(scratch: &Path) -> Result<(f64, usize), Error> {
+        let mut best = (0.0, CONCURRENCY_LEVELS[0]);
+        for concurrency in CONCURRENCY_LEVELS {
+            let directory = scratch.join(format!("small-{concurrency}"));
+            fs::create_dir_all(&directory)
+                .map_err(|err| IOError::CreateDirectoryFailed(&directory, err))?;
+
+            let start = Instant::now();
+            thread::scope(|scope| {
+                let handles: Vec<_> = (0..concurrency)
+                    .map(|worker| {
+                        let directory = &directory;
+                        scope.spawn(move || {
+                            Self::small_file_worker(directory, worker, concurrency)
+                        })
+                    })
+                    .collect();
+                handles.into_iter().try_for_each(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|err| panic::resume_unwind(err))
+                })
+            })?;
+            let ops = (SMALL_FILE_COUNT * 2) as f64 / start.elapsed().as_secs_f64();
+
+            fs::remove_dir_all(&directory)
+                .map_err(|err| IOError::DeleteDirectoryFailed(&directory, err))?;
+            if ops < best.0 * CONCURRENCY_GAIN {
+                break;
+            }
+            best = (ops, concurrency);
+        }
+        Ok(best)
+    }
+
+    fn small_file_worker(directory: &Path, worker: usize, concurrency: usize) -> Result<(), Error> {
+        let buffer = vec![0x5A_u8; SMALL_FILE_SIZE];
+        let paths: Vec<PathBuf> = (worker..SMALL_FILE_COUNT)
+            .step_by(concurrency)
+            .map(|index| directory.join(format!("{index}.dat")))
+            .collect();
+        for path in paths.iter() {
+            let mut file = File::create(path).map_err(|err| IOError::CreateFileFailed(path, err))?;
+            file.write_all(&buffer)
+                .map_err(|err| IOError::WriteFileFailed(path, err))?;
+        }
+        for path in paths.iter() {
+            fs::remove_file(path).map_err(|err| IOError::DeleteFileFailed(path, err))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::core::benchmark::benchmark_runner::BenchmarkRunner;
+use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::interface::communication::command::CommandHandler;
+use crate::interface::communication::query::QueryHandler;
+use crate::interface::repository::benchmark::BenchmarkRepository;
+use crate::model::core::benchmark::benchmark_result::BenchmarkResult;
+use crate::model::core::benchmark::communication::*;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::Error;
+use crate::model::error::system::SystemError;
+use crate::model::error::task::TaskError;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::spawn_blocking;
+
+pub struct BenchmarkService {
+    database_manager: Arc<DatabaseManager>,
+    communication_manager: Arc<CommunicationManager>,
+    running: AtomicBool,
+}
+
+impl BenchmarkService {
+    pub fn new(
+        database_manager: Arc<DatabaseManager>,
+        communication_manager: Arc<CommunicationManager>,
+    ) -> Self {
+        Self {
+            database_manager,
+            communication_manager,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    pub async fn register_services(self: Arc<Self>) {
+        let communication_manager = self.communication_manager.clone();
+        communication_manager
+            .with_service(self)
+            .register_all::<(BenchmarkCommand, BenchmarkQuery)>()
+            .build();
+    }
+
+    async fn run_benchmark(&self, source: PathBuf, destination: PathBuf) -> Result<(), Error> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            Err(TaskError::BenchmarkAlreadyRunning)?
+        }
+        let result = spawn_blocking(move || BenchmarkRunner::run(&source, &destination))
+            .await
+            .map_err(SystemError::ThreadPanic);
+        self.running.store(false, Ordering::Release);
+
+        let benchmark_result = result??;
+        self.database_manager
+            .create_benchmark_result(&benchmark_result)
+            .await
+    }
+
+    async fn get_results(&self) -> Result<Vec<BenchmarkResult>, Error> {
+        self.database_manager.get_all_benchmark_results().await
+    }
+}
+
+#[async_trait]
+impl CommandHandler<BenchmarkCommand> for BenchmarkService {
+    async fn handle_command(&self, command: BenchmarkCommand) -> Result<(), Error> {
+        match command {
+            BenchmarkCommand::RunBenchmark(source, destination) => {
+                if self.database_manager.is_read_only() {
+                    Err(DatabaseError::DatabaseReadOnly)?
+                }
+                self.run_benchmark(source, destination).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QueryHandler<BenchmarkQuery> for BenchmarkService {
+    async fn handle_query(&self, query: BenchmarkQuery) -> Result<BenchmarkQueryResponse, Error> {
+        match query {
+            BenchmarkQuery::GetResults => {
+                let results = self.get_results().await?;
+                Ok(BenchmarkQueryResponse::GetResults(results))
+            }
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod benchmark_runner;
+pub mod benchmark_service;
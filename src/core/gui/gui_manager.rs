@@ -1,28 +1,43 @@
+use crate::core::gui::ui_bridge::UiBridge;
 use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::model::config::ConfigIssue;
 use crate::model::error::misc::MiscError;
 use crate::model::error::Error;
+use crate::ui::benchmark_page::BenchmarkPage;
+use crate::ui::config_problem_page::ConfigProblemPage;
+use crate::ui::event_monitor::EventMonitor;
 use crate::ui::execution_page::ExecutionPage;
 use crate::ui::main_page::MainPage;
 use crate::ui::schedule_page::SchedulePage;
+use crate::ui::search_page::SearchPage;
+use crate::ui::settings_page::SettingsPage;
 use crate::utils::assets::Assets;
+use crate::utils::database_lock::LockHolder;
 use crate::utils::font;
 use eframe::egui;
 use std::sync::Arc;
+use tokio::runtime::Handle;
 
 pub struct GuiManager {
     app_config: Arc<AppConfig>,
     communication_manager: Arc<CommunicationManager>,
+    read_only: bool,
+    lock_holder: Option<LockHolder>,
 }
 
 impl GuiManager {
     pub fn new(
         app_config: Arc<AppConfig>,
         communication_manager: Arc<CommunicationManager>,
+        read_only: bool,
+        lock_holder: Option<LockHolder>,
     ) -> Self {
         Self {
             app_config,
             communication_manager,
+            read_only,
+            lock_holder,
         }
     }
 
@@ -30,15 +45,49 @@ impl GuiManager {
         let app_config = self.app_config.clone();
         let communication_manager = self.communication_manager.clone();
 
-        let execution_page = ExecutionPage::new(app_config.clone(), communication_manager.clone())?;
-        let schedule_page = SchedulePage::new(app_config, communication_manager)?;
-        let main_page = MainPage::new(execution_page, schedule_page);
+        let ui_bridge = UiBridge::new(Handle::current(), communication_manager.clone());
 
+        let read_only = self.read_only;
+        let event_monitor = EventMonitor::new(communication_manager.clone());
+        let execution_page = ExecutionPage::new(
+            app_config.clone(),
+            communication_manager,
+            ui_bridge.clone(),
+            read_only,
+        )?;
+        let schedule_page = SchedulePage::new(app_config.clone(), ui_bridge.clone(), read_only)?;
+        let search_page = SearchPage::new(ui_bridge.clone())?;
+        let benchmark_page = BenchmarkPage::new(ui_bridge.clone(), read_only)?;
+        let title = match app_config.profile() {
+            Some(profile) => format!("MirrorSphere - {profile}"),
+            None => "MirrorSphere".to_string(),
+        };
+        let settings_page = SettingsPage::new(app_config, ui_bridge, read_only)?;
+        let main_page = MainPage::new(
+            execution_page,
+            schedule_page,
+            search_page,
+            benchmark_page,
+            settings_page,
+            event_monitor,
+            read_only,
+            self.lock_holder.clone(),
+        );
+
+        Self::run_native(main_page, &title)
+    }
+
+    // Runs in place of the main window when config.toml is unusable, nothing else is started
+    pub fn show_config_issues(issues: Vec<ConfigIssue>) -> Result<(), Error> {
+        Self::run_native(ConfigProblemPage::new(issues), "MirrorSphere")
+    }
+
+    fn run_native(page: impl eframe::App + 'static, title: &str) -> Result<(), Error> {
         let icon_data = Assets::load_app_icon()?;
         let options = eframe::NativeOptions {
             viewport: egui::ViewportBuilder::default()
                 .with_inner_size([960.0, 540.0])
-                .with_title("MirrorSphere")
+                .with_title(title)
                 .with_icon(icon_data),
             ..Default::default()
         };
@@ -48,7 +97,7 @@ impl GuiManager {
             options,
             Box::new(|cc| {
                 font::setup_system_fonts(&cc.egui_ctx);
-                Ok(Box::new(main_page))
+                Ok(Box::new(page))
             }),
         )
         .map_err(MiscError::UIPlatformError)?;
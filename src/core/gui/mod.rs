@@ -1 +1,2 @@
 pub mod gui_manager;
+pub mod ui_bridge;
@@ -0,0 +1,83 @@
+use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::interface::communication::command::Command;
+use crate::interface::communication::query::Query;
+use crate::model::error::Error;
+use crate::model::error::misc::MiscError;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::select;
+use tokio::sync::oneshot;
+use tokio::sync::oneshot::error::TryRecvError;
+
+// Runs commands and queries on the runtime so the frame loop never waits on the core
+#[derive(Clone)]
+pub struct UiBridge {
+    runtime: Handle,
+    communication_manager: Arc<CommunicationManager>,
+}
+
+impl UiBridge {
+    pub fn new(runtime: Handle, communication_manager: Arc<CommunicationManager>) -> Self {
+        Self {
+            runtime,
+            communication_manager,
+        }
+    }
+
+    pub fn send_command<C: Command + 'static>(&self, command: C) -> Pending<()> {
+        let communication_manager = self.communication_manager.clone();
+        self.spawn(async move { communication_manager.send_command(command).await })
+    }
+
+    pub fn send_commands<C: Command + 'static>(&self, commands: Vec<C>) -> Pending<()> {
+        let communication_manager = self.communication_manager.clone();
+        self.spawn(async move {
+            for command in commands {
+                communication_manager.send_command(command).await?;
+            }
+            Ok(())
+        })
+    }
+
+    pub fn send_query<Q: Query + 'static>(&self, query: Q) -> Pending<Q::Response> {
+        let communication_manager = self.communication_manager.clone();
+        self.spawn(async move { communication_manager.send_query(query).await })
+    }
+
+    // For periodic refreshes, a failed one leaves the slot free so the next refresh asks again
+    pub fn send_refresh_query<Q: Query + 'static>(&self, query: Q) -> Pending<Q::Response> {
+        let communication_manager = self.communication_manager.clone();
+        let limit = communication_manager.query_timeout();
+        self.spawn(async move { communication_manager.send_query_timeout(query, limit).await })
+    }
+
+    fn spawn<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = Result<T, Error>> + Send + 'static,
+    ) -> Pending<T> {
+        let (mut tx, rx) = oneshot::channel();
+        // A page dropping its Pending no longer wants the result, the call is cancelled
+        self.runtime.spawn(async move {
+            let result = select! {
+                result = future => result,
+                _ = tx.closed() => return,
+            };
+            let _ = tx.send(result);
+        });
+        Pending { receiver: rx }
+    }
+}
+
+pub struct Pending<T> {
+    receiver: oneshot::Receiver<Result<T, Error>>,
+}
+
+impl<T> Pending<T> {
+    pub fn poll(&mut self) -> Option<Result<T, Error>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Closed) => Some(Err(MiscError::ChannelClosed.into())),
+        }
+    }
+}
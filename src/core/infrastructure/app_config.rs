@@ -1,31 +1,333 @@
-use crate::model::config::{Config, ConfigTable};
-use crate::model::error::system::SystemError;
+use crate::model::config::{Config, ConfigIssue, ConfigTable};
 use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
 use crate::model::log::system::SystemLog;
+use crate::platform::constants::*;
+use crate::platform::data_directory::data_directory;
+use crate::utils::portable_name::{is_reserved_character, portable_name};
 use macros::log;
-use std::fs;
+use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+const MAX_PROFILE_NAME_LENGTH: usize = 64;
+const ARGUMENT_USAGE: &str = "--profile <name>, --database-path <file> or --progress-path <folder>";
+
+// Read before config.toml, the profile decides which one is loaded
+#[derive(Default)]
+struct Arguments {
+    profile: Option<String>,
+    database_path: Option<PathBuf>,
+    progress_path: Option<PathBuf>,
+}
 
 pub struct AppConfig {
     config: Config,
+    // None for the default profile
+    profile: Option<String>,
 }
 
 impl AppConfig {
-    pub fn new() -> Result<Self, Error> {
+    pub fn new() -> Result<Self, Vec<ConfigIssue>> {
         log!(SystemLog::Initializing);
-        let config = Self::load_config_file()?;
+        let arguments = Self::parse_arguments(env::args().skip(1))?;
+        let profile = arguments.profile;
+        let config_path = Self::config_path_of(profile.as_deref());
+        if profile.is_some() && !config_path.exists() {
+            // A failed copy surfaces as the missing config.toml below
+            let _ = Self::seed_profile(&config_path);
+        }
+        let mut config = Self::load_config_file(&config_path).map_err(|issue| vec![issue])?;
+        if let Some(database_path) = arguments.database_path {
+            config.database_path = database_path;
+        }
+        if let Some(progress_path) = arguments.progress_path {
+            config.progress_path = progress_path;
+        }
+        Self::resolve_locations(&mut config, profile.as_deref());
+        let issues = Self::validate(&config);
+        if !issues.is_empty() {
+            return Err(issues);
+        }
         log!(SystemLog::InitializeComplete);
-        Ok(Self { config })
+        Ok(Self { config, profile })
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        Self::config_path_of(self.profile())
+    }
+
+    pub fn locations_path(&self) -> PathBuf {
+        match self.profile() {
+            Some(profile) => PathBuf::from(PROFILES_PATH)
+                .join(profile)
+                .join(DATA_LOCATIONS_FILE_NAME),
+            None => PathBuf::from(DATA_LOCATIONS_PATH),
+        }
+    }
+
+    pub fn config_path_of(profile: Option<&str>) -> PathBuf {
+        match profile {
+            Some(profile) => PathBuf::from(PROFILES_PATH).join(profile).join(CONFIG_FILE_NAME),
+            None => PathBuf::from(CONFIG_PATH),
+        }
+    }
+
+    // Used as a folder name, so kept to characters every file system accepts
+    pub fn is_valid_profile_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= MAX_PROFILE_NAME_LENGTH
+            && name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || "-_".contains(character))
+    }
+
+    // A new profile starts from the default config.toml, with its locations cleared so it does
+    // not share the database of the default profile
+    pub fn seed_profile(config_path: &Path) -> Result<(), Error> {
+        let toml_string = fs::read_to_string(CONFIG_PATH)
+            .map_err(|err| IOError::ReadFileFailed(CONFIG_PATH, err))?;
+        let mut table: toml::Table =
+            toml::from_str(&toml_string).map_err(MiscError::DeserializeError)?;
+        if let Some(toml::Value::Table(config)) = table.get_mut("Config") {
+            config.remove("database_path");
+            config.remove("progress_path");
+            config.remove("journal_path");
+        }
+        let toml_string = toml::to_string(&table).map_err(MiscError::SerializeError)?;
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| IOError::CreateDirectoryFailed(parent, err))?;
+        }
+        fs::write(config_path, toml_string)
+            .map_err(|err| IOError::WriteFileFailed(config_path, err))?;
+        Ok(())
     }
 
-    fn load_config_file() -> Result<Config, Error> {
-        let toml_string =
-            fs::read_to_string("config.toml").map_err(SystemError::ConfigNotFound)?;
+    fn load_config_file(config_path: &Path) -> Result<Config, ConfigIssue> {
+        let toml_string = fs::read_to_string(config_path).map_err(|err| ConfigIssue {
+            key: config_path.display().to_string(),
+            problem: format!("cannot be read: {err}"),
+            accepted: "a readable config.toml".to_string(),
+        })?;
         let config = toml::from_str::<ConfigTable>(&toml_string)
-            .map_err(SystemError::InvalidConfig)?
+            .map_err(|err| {
+                // The key on the line the parser stopped at
+                let line = err
+                    .span()
+                    .map(|span| toml_string[..span.start].matches('\n').count())
+                    .and_then(|line| toml_string.lines().nth(line))
+                    .unwrap_or("Config");
+                let key = line.split('=').next().unwrap_or(line).trim();
+                ConfigIssue {
+                    key: key.to_string(),
+                    problem: err.message().to_string(),
+                    accepted: "keys of the [Config] table with a value of their type"
+                        .to_string(),
+                }
+            })?
             .config;
         Ok(config)
     }
+
+    // Path flags given on the command line take precedence over config.toml
+    fn parse_arguments(
+        mut arguments: impl Iterator<Item = String>,
+    ) -> Result<Arguments, Vec<ConfigIssue>> {
+        let mut parsed = Arguments::default();
+        let mut issues = Vec::new();
+        while let Some(argument) = arguments.next() {
+            let (flag, value) = match argument.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (argument, None),
+            };
+            if !["--profile", "--database-path", "--progress-path"].contains(&flag.as_str()) {
+                issues.push(ConfigIssue {
+                    key: flag,
+                    problem: "is not a known argument".to_string(),
+                    accepted: ARGUMENT_USAGE.to_string(),
+                });
+                continue;
+            }
+            let Some(value) = value.or_else(|| arguments.next()).filter(|value| !value.is_empty())
+            else {
+                issues.push(ConfigIssue {
+                    key: flag,
+                    problem: "has no value".to_string(),
+                    accepted: "the flag followed by a value".to_string(),
+                });
+                continue;
+            };
+            match flag.as_str() {
+                "--profile" if !Self::is_valid_profile_name(&value) => issues.push(ConfigIssue {
+                    key: flag,
+                    problem: format!("names \"{value}\""),
+                    accepted: "up to 64 letters, digits, - and _".to_string(),
+                }),
+                "--profile" => parsed.profile = Some(value),
+                "--database-path" => parsed.database_path = Some(PathBuf::from(value)),
+                _ => parsed.progress_path = Some(PathBuf::from(value)),
+            }
+        }
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+        Ok(parsed)
+    }
+
+    // Each named profile gets a folder of its own in the data directory
+    fn resolve_locations(config: &mut Config, profile: Option<&str>) {
+        let data_directory = match profile {
+            Some(profile) => data_directory().join(PROFILES_DIRECTORY_NAME).join(profile),
+            None => data_directory(),
+        };
+        if config.database_path.as_os_str().is_empty() {
+            config.database_path = data_directory.join(DATABASE_FILE_NAME);
+        }
+        if config.progress_path.as_os_str().is_empty() {
+            config.progress_path = data_directory.join(PROGRESS_DIRECTORY_NAME);
+        }
+        if config.journal_path.as_os_str().is_empty() {
+            config.journal_path = data_directory.join(JOURNAL_DIRECTORY_NAME);
+        }
+    }
+
+    fn validate(config: &Config) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let mut check = |valid: bool, key: &str, problem: String, accepted: &str| {
+            if !valid {
+                issues.push(ConfigIssue {
+                    key: key.to_string(),
+                    problem,
+                    accepted: accepted.to_string(),
+                });
+            }
+        };
+
+        // Zero periods panic in the timers and zero permits stall every worker
+        let value = config.internal_timestamp;
+        check(value > 0, "internal_timestamp", format!("is {value}"), "milliseconds above 0");
+        let value = config.ui_refresh_time;
+        check(value > 0, "ui_refresh_time", format!("is {value}"), "seconds above 0");
+        let value = config.progress_events_per_second;
+        check(value > 0, "progress_events_per_second", format!("is {value}"), "1 or more");
+        let value = config.default_wakeup_time;
+        check(value > 0, "default_wakeup_time", format!("is {value}"), "seconds above 0");
+        let value = config.checkpoint_interval;
+        check(value > 0, "checkpoint_interval", format!("is {value}"), "seconds above 0");
+        let value = config.max_concurrency;
+        check(value > 0, "max_concurrency", format!("is {value}"), "1 to 255");
+        // Only a min_concurrency set in config.toml can be above max_concurrency
+        if let Some(value) = config.min_concurrency {
+            check(value > 0, "min_concurrency", format!("is {value}"), "1 to 255");
+            check(
+                config.max_concurrency >= value,
+                "max_concurrency",
+                format!("is {}, below min_concurrency", config.max_concurrency),
+                "min_concurrency to 255",
+            );
+        }
+        let value = config.max_metadata_operations;
+        check(value > 0, "max_metadata_operations", format!("is {value}"), "1 or more");
+        let value = config.max_data_operations;
+        check(value > 0, "max_data_operations", format!("is {value}"), "1 or more");
+        let value = config.channel_capacity;
+        check(value > 0, "channel_capacity", format!("is {value}"), "1 or more");
+        let value = config.history_retention_days;
+        check(value >= 0, "history_retention_days", format!("is {value}"), "0 or more days");
+        let value = config.maintenance_interval;
+        check(value > 0, "maintenance_interval", format!("is {value}"), "hours above 0");
+        let value = config.database_encryption;
+        check(
+            !value || cfg!(feature = "sqlcipher"),
+            "database_encryption",
+            "needs a build with the sqlcipher feature".to_string(),
+            "false, or a build made with --features sqlcipher",
+        );
+        let value = &config.database_path;
+        check(
+            !value.is_dir(),
+            "database_path",
+            format!("{} is a folder", value.display()),
+            "a database file path, or empty for the platform data directory",
+        );
+        let value = &config.progress_path;
+        check(
+            !value.is_file(),
+            "progress_path",
+            format!("{} is a file", value.display()),
+            "a folder path, or empty for the platform data directory",
+        );
+        let value = &config.journal_path;
+        check(
+            !value.is_file(),
+            "journal_path",
+            format!("{} is a file", value.display()),
+            "a folder path, or empty for the platform data directory",
+        );
+        let value = config.max_queued_directories;
+        check(value > 0, "max_queued_directories", format!("is {value}"), "1 or more");
+        let value = config.max_error_rate;
+        check(
+            (0.0..=1.0).contains(&value),
+            "max_error_rate",
+            format!("is {value}"),
+            "0 to disable, or a fraction up to 1.0",
+        );
+        let value = config.mirror_max_delete_percentage;
+        check(value <= 100, "mirror_max_delete_percentage", format!("is {value}"), "0 to 100");
+        check(
+            cfg!(target_os = "linux") || !config.io_uring,
+            "io_uring",
+            "is only available on Linux".to_string(),
+            "false",
+        );
+        check(
+            cfg!(target_os = "linux") || !config.selinux_contexts,
+            "selinux_contexts",
+            "is only available on Linux".to_string(),
+            "false",
+        );
+
+        check(
+            config.sanitize_names || config.name_replacements.is_empty(),
+            "name_replacements",
+            "has no effect while sanitize_names is false".to_string(),
+            "sanitize_names = true, or no replacements",
+        );
+        let mut replacements = config.name_replacements.iter().collect::<Vec<_>>();
+        replacements.sort();
+        for (character, replacement) in replacements {
+            let key = format!("name_replacements.\"{character}\"");
+            let mut characters = character.chars();
+            let reserved = match (characters.next(), characters.next()) {
+                (Some(character), None) => is_reserved_character(character),
+                _ => false,
+            };
+            check(
+                reserved,
+                &key,
+                "is not a single reserved character".to_string(),
+                "one of < > : \" / \\ | ? * or a control character",
+            );
+            let portable = portable_name(OsStr::new(replacement), &HashMap::new()).is_none();
+            check(
+                portable,
+                &key,
+                format!("replaces it with \"{replacement}\", which is reserved itself"),
+                "text without reserved characters or a trailing dot or space",
+            );
+        }
+
+        issues
+    }
 }
 
 impl Deref for AppConfig {
@@ -2,29 +2,41 @@ use crate::core::infrastructure::app_config::AppConfig;
 use crate::interface::communication::command::*;
 use crate::interface::communication::event::Event;
 use crate::interface::communication::event::EventBroadcaster;
+use crate::interface::communication::message::ServiceMessages;
 use crate::interface::communication::query::*;
-use crate::model::core::infrastructure::event_broadcaster::TypedEventBroadcaster;
+use crate::model::core::infrastructure::event_broadcaster::{
+    EventReceiver, EventStatistics, TappedEvent, TypedEventBroadcaster,
+};
 use crate::model::error::misc::MiscError;
 use crate::model::error::Error;
+use chrono::Local;
 use dashmap::DashMap;
 use std::any::{Any, TypeId};
 use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::time::Duration;
+use tokio::time::timeout;
 
 pub struct CommunicationManager {
     app_config: Arc<AppConfig>,
     command_handlers: DashMap<TypeId, CommandHandlerFn>,
     query_handlers: DashMap<TypeId, QueryHandlerFn>,
-    event_broadcasters: DashMap<TypeId, Box<dyn EventBroadcaster>>,
+    event_broadcasters: DashMap<TypeId, Arc<dyn EventBroadcaster>>,
+    event_tap: TypedEventBroadcaster<TappedEvent>,
 }
 
 impl CommunicationManager {
     pub fn new(app_config: Arc<AppConfig>) -> Self {
         Self {
-            app_config,
             command_handlers: DashMap::new(),
             query_handlers: DashMap::new(),
             event_broadcasters: DashMap::new(),
+            event_tap: TypedEventBroadcaster::new(
+                short_type_name::<TappedEvent>(),
+                app_config.channel_capacity,
+                TappedEvent::OVERFLOW_POLICY,
+                TappedEvent::REPLAY_CAPACITY,
+            ),
+            app_config,
         }
     }
 
@@ -53,13 +65,23 @@ impl CommunicationManager {
         self.command_handlers.insert(type_id, boxed_handler);
     }
 
+    // Commands may run for long, such as maintenance or an archive import, and wait indefinitely
     pub async fn send_command<C: Command + 'static>(&self, command: C) -> Result<(), Error> {
+        self.send_command_timeout(command, None).await
+    }
+
+    pub async fn send_command_timeout<C: Command + 'static>(
+        &self,
+        command: C,
+        limit: Option<Duration>,
+    ) -> Result<(), Error> {
         let type_id = TypeId::of::<C>();
-        if let Some(handler) = self.command_handlers.get(&type_id) {
-            handler(Box::new(command)).await
-        } else {
-            Err(MiscError::HandlerNotFound)?
-        }
+        let future = self
+            .command_handlers
+            .get(&type_id)
+            .map(|handler| handler(Box::new(command)))
+            .ok_or(MiscError::HandlerNotFound)?;
+        Self::within::<C, _>(future, limit).await
     }
 
     pub fn register_query_handler<Q: Query + 'static>(
@@ -79,28 +101,72 @@ impl CommunicationManager {
         self.query_handlers.insert(type_id, boxed_handler);
     }
 
+    // Dropped at a timeout a handler stops midway, so only callers that can retry opt into one
     pub async fn send_query<Q: Query + 'static>(&self, query: Q) -> Result<Q::Response, Error> {
-        let type_id = TypeId::of::<Q>();
-        if let Some(handler) = self.query_handlers.get(&type_id) {
-            let response = handler(Box::new(query)).await?;
-            Ok(*response
-                .downcast::<Q::Response>()
-                .map_err(|_| MiscError::TypeMismatch)?)
-        } else {
-            Err(MiscError::HandlerNotFound)?
+        self.send_query_timeout(query, None).await
+    }
+
+    pub fn query_timeout(&self) -> Option<Duration> {
+        match self.app_config.query_timeout {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
         }
     }
 
+    pub async fn send_query_timeout<Q: Query + 'static>(
+        &self,
+        query: Q,
+        limit: Option<Duration>,
+    ) -> Result<Q::Response, Error> {
+        let type_id = TypeId::of::<Q>();
+        let future = self
+            .query_handlers
+            .get(&type_id)
+            .map(|handler| handler(Box::new(query)))
+            .ok_or(MiscError::HandlerNotFound)?;
+        let response = Self::within::<Q, _>(future, limit).await?;
+        Ok(*response
+            .downcast::<Q::Response>()
+            .map_err(|_| MiscError::TypeMismatch)?)
+    }
+
+    // Dropping the handler future on timeout cancels the handler at its current await point
+    async fn within<M, T>(
+        future: impl Future<Output = Result<T, Error>>,
+        limit: Option<Duration>,
+    ) -> Result<T, Error> {
+        let Some(limit) = limit else {
+            return future.await;
+        };
+        timeout(limit, future).await.map_err(|_| MiscError::QueryTimeout {
+            message: short_type_name::<M>().to_string(),
+            seconds: limit.as_secs(),
+        })?
+    }
+
     pub fn register_event_type<E: Event + 'static>(&self) {
         let channel_capacity = self.app_config.channel_capacity;
         let type_id = TypeId::of::<E>();
-        let (tx, _) = broadcast::channel::<E>(channel_capacity);
-        let broadcaster = TypedEventBroadcaster { sender: tx };
+        let event = short_type_name::<E>();
+        let policy = self
+            .app_config
+            .event_overflow
+            .get(event)
+            .copied()
+            .unwrap_or(E::OVERFLOW_POLICY);
+        let replay_capacity = self
+            .app_config
+            .event_replay
+            .get(event)
+            .copied()
+            .unwrap_or(E::REPLAY_CAPACITY);
+        let broadcaster =
+            TypedEventBroadcaster::<E>::new(event, channel_capacity, policy, replay_capacity);
         self.event_broadcasters
-            .insert(type_id, Box::new(broadcaster));
+            .insert(type_id, Arc::new(broadcaster));
     }
 
-    pub fn subscribe_event<E: Event + 'static>(&self) -> Result<broadcast::Receiver<E>, Error> {
+    pub fn subscribe_event<E: Event + 'static>(&self) -> Result<EventReceiver<E>, Error> {
         let type_id = TypeId::of::<E>();
         let broadcaster = self
             .event_broadcasters
@@ -108,20 +174,42 @@ impl CommunicationManager {
             .ok_or(MiscError::TypeNotRegistered)?;
         let receiver_box = broadcaster.subscribe_typed();
         let receiver = *receiver_box
-            .downcast::<broadcast::Receiver<E>>()
+            .downcast::<EventReceiver<E>>()
             .map_err(|_| MiscError::TypeMismatch)?;
         Ok(receiver)
     }
 
+    // Every event type at once, rendered only while someone is subscribed
+    pub fn subscribe_all_events(&self) -> EventReceiver<TappedEvent> {
+        self.event_tap.subscribe()
+    }
+
     pub async fn publish_event<E: Event + 'static>(&self, event: E) -> Result<(), Error> {
         let type_id = TypeId::of::<E>();
+        if self.event_tap.has_subscribers() {
+            let tapped = TappedEvent {
+                event: short_type_name::<E>(),
+                published_at: Local::now().naive_local(),
+                payload: format!("{event:#?}"),
+            };
+            self.event_tap.broadcast(tapped).await;
+        }
+        // Cloned out of the map, a blocking policy may wait here for receivers
         let broadcaster = self
             .event_broadcasters
             .get(&type_id)
+            .map(|broadcaster| broadcaster.clone())
             .ok_or(MiscError::TypeNotRegistered)?;
-        broadcaster.broadcast_event(Box::new(event))
+        broadcaster.broadcast_event(Box::new(event)).await
+    }
+
+    pub fn event_statistics(&self) -> Vec<EventStatistics> {
+        self.event_broadcasters
+            .iter()
+            .map(|broadcaster| broadcaster.statistics())
+            .collect()
     }
-    
+
     pub fn clear_handlers(&self) {
         self.command_handlers.clear();
         self.query_handlers.clear();
@@ -162,7 +250,19 @@ impl<S: Send + Sync + 'static> ServiceRegistrar<S> {
         self
     }
 
+    // Registers a tuple of #[derive(ServiceMessage)] types, each the way its kind requires
+    pub fn register_all<M: ServiceMessages<S>>(self) -> Self {
+        M::register(self)
+    }
+
     pub fn build(self) -> Arc<CommunicationManager> {
         self.comm
     }
 }
+
+fn short_type_name<T>() -> &'static str {
+    std::any::type_name::<T>()
+        .rsplit("::")
+        .next()
+        .unwrap_or_default()
+}
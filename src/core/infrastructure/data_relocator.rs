@@ -0,0 +1,162 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::core::infrastructure::database_cipher::KEY_ID_SUFFIX;
+use crate::core::infrastructure::database_manager::{DATABASE_FILE_SUFFIXES, DatabaseManager};
+use crate::model::core::infrastructure::data_locations::DataLocations;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use crate::model::log::system::SystemLog;
+use crate::platform::constants::*;
+use crate::utils::database_lock::DatabaseLock;
+use macros::log;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+pub struct DataRelocator;
+
+impl DataRelocator {
+    // Data left at the locations of the previous start is moved to the configured ones, a
+    // location already holding data is never overwritten
+    pub async fn relocate(app_config: &AppConfig) -> Result<(), Error> {
+        let locations_path = app_config.locations_path();
+        let recorded: Option<DataLocations> = fs::read(&locations_path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_slice(&content).ok());
+        let mut previous = recorded
+            .clone()
+            .unwrap_or_else(|| Self::unrecorded_locations(app_config));
+        if previous.journal_path.is_none() {
+            previous.journal_path = Self::unrecorded_locations(app_config).journal_path;
+        }
+        let mut current = previous.clone();
+        if previous.database_path != app_config.database_path
+            && Self::move_database(&previous.database_path, &app_config.database_path).await?
+        {
+            current.database_path = app_config.database_path.clone();
+        }
+        if previous.progress_path != app_config.progress_path
+            && Self::move_directory(&previous.progress_path, &app_config.progress_path).await?
+        {
+            current.progress_path = app_config.progress_path.clone();
+        }
+        if let Some(journal_path) = &previous.journal_path
+            && *journal_path != app_config.journal_path
+            && Self::move_directory(journal_path, &app_config.journal_path).await?
+        {
+            current.journal_path = Some(app_config.journal_path.clone());
+        }
+        if recorded.as_ref() != Some(&current) {
+            let content = serde_json::to_vec_pretty(&current).map_err(MiscError::SerializeError)?;
+            fs::write(&locations_path, content)
+                .await
+                .map_err(|err| IOError::WriteFileFailed(&locations_path, err))?;
+        }
+        Ok(())
+    }
+
+    // Installs from before the locations were configurable kept everything beside config.toml,
+    // a named profile without a record is new and has nothing to move
+    fn unrecorded_locations(app_config: &AppConfig) -> DataLocations {
+        match app_config.profile() {
+            Some(_) => DataLocations {
+                database_path: app_config.database_path.clone(),
+                progress_path: app_config.progress_path.clone(),
+                journal_path: Some(app_config.journal_path.clone()),
+            },
+            None => DataLocations {
+                database_path: PathBuf::from(LEGACY_DATABASE_PATH),
+                progress_path: PathBuf::from(LEGACY_PROGRESS_PATH),
+                journal_path: Some(PathBuf::from(LEGACY_JOURNAL_PATH)),
+            },
+        }
+    }
+
+    // False while another instance still holds the old database, it is tried again next start
+    async fn move_database(from: &Path, to: &Path) -> Result<bool, Error> {
+        if fs::metadata(from).await.is_err() {
+            return Ok(true);
+        }
+        if fs::metadata(to).await.is_ok() {
+            log!(SystemLog::DataLeftBehind {
+                path: from.display().to_string(),
+                location: to.display().to_string(),
+            });
+            return Ok(true);
+        }
+        if fs::metadata(DatabaseLock::lock_path(from)).await.is_ok() {
+            log!(SystemLog::DataRelocationPostponed {
+                path: from.display().to_string(),
+            });
+            return Ok(false);
+        }
+
+        Self::create_parent(to).await?;
+        for suffix in DATABASE_FILE_SUFFIXES.into_iter().chain([KEY_ID_SUFFIX]) {
+            let source = DatabaseManager::suffixed_path(from, suffix);
+            if fs::metadata(&source).await.is_ok() {
+                Self::move_file(&source, &DatabaseManager::suffixed_path(to, suffix)).await?;
+            }
+        }
+        log!(SystemLog::DataRelocated {
+            from: from.display().to_string(),
+            to: to.display().to_string(),
+        });
+        Ok(true)
+    }
+
+    async fn move_directory(from: &Path, to: &Path) -> Result<bool, Error> {
+        let Ok(mut entries) = fs::read_dir(from).await else {
+            return Ok(true);
+        };
+        if let Ok(mut existing) = fs::read_dir(to).await
+            && let Ok(Some(_)) = existing.next_entry().await
+        {
+            log!(SystemLog::DataLeftBehind {
+                path: from.display().to_string(),
+                location: to.display().to_string(),
+            });
+            return Ok(true);
+        }
+
+        fs::create_dir_all(to)
+            .await
+            .map_err(|err| IOError::CreateDirectoryFailed(to, err))?;
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(from, err))?
+        {
+            Self::move_file(&entry.path(), &to.join(entry.file_name())).await?;
+        }
+        let _ = fs::remove_dir(from).await;
+        log!(SystemLog::DataRelocated {
+            from: from.display().to_string(),
+            to: to.display().to_string(),
+        });
+        Ok(true)
+    }
+
+    async fn create_parent(path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|err| IOError::CreateDirectoryFailed(parent, err))?;
+        }
+        Ok(())
+    }
+
+    // Renaming fails across file systems, the file is copied and removed there instead
+    async fn move_file(from: &Path, to: &Path) -> Result<(), Error> {
+        if fs::rename(from, to).await.is_ok() {
+            return Ok(());
+        }
+        fs::copy(from, to)
+            .await
+            .map_err(|err| IOError::CopyFileFailed(from, to, err))?;
+        fs::remove_file(from)
+            .await
+            .map_err(|err| IOError::DeleteFileFailed(from, err))?;
+        Ok(())
+    }
+}
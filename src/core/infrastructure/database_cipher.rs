@@ -0,0 +1,244 @@
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use sqlx::sqlite::SqliteConnectOptions;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+#[cfg(feature = "sqlcipher")]
+use {
+    crate::core::infrastructure::database_manager::{DATABASE_FILE_SUFFIXES, DatabaseManager},
+    crate::model::error::io::IOError,
+    crate::model::error::system::SystemError,
+    crate::model::log::database::DatabaseLog,
+    macros::log,
+    sqlx::{ConnectOptions, Connection},
+    std::io,
+    tokio::fs,
+    tokio::task::spawn_blocking,
+    uuid::Uuid,
+};
+
+#[cfg(feature = "sqlcipher")]
+const KEYRING_SERVICE: &str = "MirrorSphere";
+// Beside the database, names its keyring entry and moves along with it
+pub const KEY_ID_SUFFIX: &str = ".keyid";
+// The first bytes of every SQLite database that is not encrypted
+const PLAIN_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+// Encryption is switched by database_encryption, the database is converted once on the next
+// start and opened with the key from the OS keyring from then on
+pub struct DatabaseCipher;
+
+impl DatabaseCipher {
+    // The key the database is opened with, None while it is not encrypted
+    #[cfg(feature = "sqlcipher")]
+    pub async fn prepare(database_path: &Path, encryption: bool) -> Result<Option<String>, Error> {
+        let encrypted = Self::is_encrypted(database_path).await;
+        if !encryption && !encrypted {
+            return Ok(None);
+        }
+        let key = Self::key(database_path, !encrypted).await?;
+        if encryption && !encrypted && Self::has_content(database_path).await {
+            Self::convert(database_path, &key, true).await?;
+        } else if !encryption && encrypted {
+            Self::convert(database_path, &key, false).await?;
+            return Ok(None);
+        }
+        Ok(Some(key))
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    pub async fn prepare(database_path: &Path, _encryption: bool) -> Result<Option<String>, Error> {
+        Self::current_key(database_path).await
+    }
+
+    // A read-only session follows whatever the instance holding the lock left on disk
+    #[cfg(feature = "sqlcipher")]
+    pub async fn current_key(database_path: &Path) -> Result<Option<String>, Error> {
+        match Self::is_encrypted(database_path).await {
+            true => Ok(Some(Self::key(database_path, false).await?)),
+            false => Ok(None),
+        }
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    pub async fn current_key(database_path: &Path) -> Result<Option<String>, Error> {
+        match Self::is_encrypted(database_path).await {
+            true => Err(DatabaseError::EncryptionUnsupported)?,
+            false => Ok(None),
+        }
+    }
+
+    // SQLCipher takes a raw key in this form without deriving it, and needs it before any read
+    pub fn apply_key(options: SqliteConnectOptions, key: Option<&str>) -> SqliteConnectOptions {
+        match key {
+            Some(key) => options.pragma("key", format!("\"x'{key}'\"")),
+            None => options,
+        }
+    }
+
+    // An empty file is a database about to be created and takes either form
+    pub async fn is_encrypted(database_path: &Path) -> bool {
+        let Ok(mut file) = File::open(database_path).await else {
+            return false;
+        };
+        let mut header = [0; PLAIN_HEADER.len()];
+        match file.read_exact(&mut header).await {
+            Ok(_) => &header != PLAIN_HEADER,
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    async fn has_content(database_path: &Path) -> bool {
+        fs::metadata(database_path)
+            .await
+            .is_ok_and(|metadata| metadata.len() > 0)
+    }
+
+    // One key per database, so profiles with their own database do not share it. The entry is
+    // named by the key id file rather than the path, which changes when the database is moved. A
+    // new key is only made for a database that is not encrypted yet
+    #[cfg(feature = "sqlcipher")]
+    async fn key(database_path: &Path, create: bool) -> Result<String, Error> {
+        let id_path = DatabaseManager::suffixed_path(database_path, KEY_ID_SUFFIX);
+        let user = match fs::read_to_string(&id_path).await {
+            Ok(id) => id.trim().to_string(),
+            Err(err) if create && err.kind() == io::ErrorKind::NotFound => {
+                let id = Uuid::new_v4().to_string();
+                fs::write(&id_path, &id)
+                    .await
+                    .map_err(|err| IOError::WriteFileFailed(&id_path, err))?;
+                id
+            }
+            Err(err) => Err(IOError::ReadFileFailed(&id_path, err))?,
+        };
+        spawn_blocking(move || {
+            let entry = keyring::Entry::new(KEYRING_SERVICE, &user)
+                .map_err(DatabaseError::DatabaseKeyUnavailable)?;
+            match entry.get_password() {
+                Ok(key) => Ok(key),
+                Err(keyring::Error::NoEntry) if create => {
+                    // Two random UUIDs give the 32 bytes of a raw key
+                    let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+                    entry
+                        .set_password(&key)
+                        .map_err(DatabaseError::DatabaseKeyUnavailable)?;
+                    Ok(key)
+                }
+                Err(err) => Err(DatabaseError::DatabaseKeyUnavailable(err))?,
+            }
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+    }
+
+    // Exported into a new file that replaces the database only once complete, the previous one
+    // is deleted so encrypting leaves no readable copy behind
+    #[cfg(feature = "sqlcipher")]
+    async fn convert(database_path: &Path, key: &str, encrypt: bool) -> Result<(), Error> {
+        let converted_path = DatabaseManager::suffixed_path(database_path, ".converted");
+        let _ = fs::remove_file(&converted_path).await;
+
+        // Attached databases are opened with the same flags, without create the export has no file
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+        let options = Self::apply_key(options, (!encrypt).then_some(key));
+        let mut connection = options
+            .connect()
+            .await
+            .map_err(DatabaseError::DatabaseConnectFailed)?;
+        let converted_key = match encrypt {
+            true => format!("x'{key}'"),
+            false => String::new(),
+        };
+        sqlx::query("ATTACH DATABASE ? AS converted KEY ?")
+            .bind(converted_path.to_string_lossy())
+            .bind(converted_key)
+            .execute(&mut connection)
+            .await
+            .map_err(DatabaseError::DatabaseEncryptionFailed)?;
+        sqlx::query("SELECT sqlcipher_export('converted')")
+            .execute(&mut connection)
+            .await
+            .map_err(DatabaseError::DatabaseEncryptionFailed)?;
+        sqlx::query("DETACH DATABASE converted")
+            .execute(&mut connection)
+            .await
+            .map_err(DatabaseError::DatabaseEncryptionFailed)?;
+        connection
+            .close()
+            .await
+            .map_err(DatabaseError::DatabaseEncryptionFailed)?;
+
+        let replaced_path = DatabaseManager::suffixed_path(database_path, ".replaced");
+        for suffix in DATABASE_FILE_SUFFIXES {
+            let path = DatabaseManager::suffixed_path(database_path, suffix);
+            let replaced = DatabaseManager::suffixed_path(&replaced_path, suffix);
+            let _ = fs::remove_file(&replaced).await;
+            if fs::metadata(&path).await.is_ok() {
+                fs::rename(&path, &replaced)
+                    .await
+                    .map_err(|err| IOError::WriteFileFailed(&replaced, err))?;
+            }
+        }
+        fs::rename(&converted_path, database_path)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(database_path, err))?;
+        for suffix in DATABASE_FILE_SUFFIXES {
+            let _ = fs::remove_file(DatabaseManager::suffixed_path(&replaced_path, suffix)).await;
+        }
+        match encrypt {
+            true => log!(DatabaseLog::DatabaseEncrypted),
+            false => log!(DatabaseLog::DatabaseDecrypted),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlcipher"))]
+mod tests {
+    use super::*;
+    use sqlx::Row;
+    use sqlx::sqlite::SqliteJournalMode;
+
+    async fn read_value(database_path: &Path, key: Option<&str>) -> Result<String, sqlx::Error> {
+        // Journal mode is set the way DatabaseManager opens it, after the key
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .journal_mode(SqliteJournalMode::Wal);
+        let mut connection = DatabaseCipher::apply_key(options, key).connect().await?;
+        let row = sqlx::query("SELECT value FROM Sample").fetch_one(&mut connection).await?;
+        connection.close().await?;
+        Ok(row.get(0))
+    }
+
+    #[tokio::test]
+    async fn converts_a_database_both_ways() {
+        let directory = std::env::temp_dir().join("mirrorsphere_converts_a_database_both_ways");
+        let _ = fs::remove_dir_all(&directory).await;
+        fs::create_dir_all(&directory).await.unwrap();
+        let database_path = directory.join("MirrorSphere.db");
+        let key = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+
+        let options = SqliteConnectOptions::new()
+            .filename(&database_path)
+            .create_if_missing(true);
+        let mut connection = options.connect().await.unwrap();
+        sqlx::query("CREATE TABLE Sample (value TEXT)").execute(&mut connection).await.unwrap();
+        sqlx::query("INSERT INTO Sample VALUES ('kept')").execute(&mut connection).await.unwrap();
+        connection.close().await.unwrap();
+
+        DatabaseCipher::convert(&database_path, key, true).await.unwrap();
+        assert!(DatabaseCipher::is_encrypted(&database_path).await);
+        assert!(read_value(&database_path, None).await.is_err());
+        assert_eq!(read_value(&database_path, Some(key)).await.unwrap(), "kept");
+
+        DatabaseCipher::convert(&database_path, key, false).await.unwrap();
+        assert!(!DatabaseCipher::is_encrypted(&database_path).await);
+        assert_eq!(read_value(&database_path, None).await.unwrap(), "kept");
+
+        fs::remove_dir_all(&directory).await.unwrap();
+    }
+}
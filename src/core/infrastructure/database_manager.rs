@@ -1,55 +1,259 @@
+use crate::core::infrastructure::database_cipher::DatabaseCipher;
+use crate::interface::repository::benchmark::BenchmarkRepository;
+use crate::interface::repository::comparison_cache::ComparisonCacheRepository;
+use crate::interface::repository::directory_signature::DirectorySignatureRepository;
+use crate::interface::repository::event_journal::EventJournalRepository;
+use crate::interface::repository::execution_history::ExecutionHistoryRepository;
+use crate::interface::repository::maintenance::MaintenanceRepository;
+use crate::interface::repository::renamed_entry::RenamedEntryRepository;
 use crate::interface::repository::schedule::ScheduleRepository;
+use crate::interface::repository::schedule_version::ScheduleVersionRepository;
+use crate::interface::repository::storage_usage::StorageUsageRepository;
+use crate::interface::repository::schema_version::{
+    BASELINE_VERSION, MIGRATIONS, SchemaVersionRepository,
+};
 use crate::model::error::database::DatabaseError;
+use crate::model::error::io::IOError;
 use crate::model::error::Error;
 use crate::model::log::database::DatabaseLog;
 use crate::model::log::system::SystemLog;
-use crate::platform::constants::*;
 use crate::utils::database_lock::DatabaseLock;
 use macros::log;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqlitePool};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 use tokio::fs::File;
 
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const STATEMENT_CACHE_CAPACITY: usize = 256;
+// The database with its write-ahead log and shared memory index, moved and replaced together
+pub const DATABASE_FILE_SUFFIXES: [&str; 3] = ["", "-wal", "-shm"];
+
 #[derive(Debug)]
 pub struct DatabaseManager {
     pool: SqlitePool,
-    _lock: DatabaseLock,
+    database_path: PathBuf,
+    // None for a read-only session opened while another instance holds the lock
+    lock: Option<DatabaseLock>,
+    encrypted: bool,
 }
 
 impl DatabaseManager {
-    pub async fn new() -> Result<Self, Error> {
+    pub async fn new(database_path: &Path, encryption: bool) -> Result<Self, Error> {
         log!(SystemLog::Initializing);
-        let lock = DatabaseLock::acquire().await?;
-        if !Self::exist_database().await {
-            Self::create_database().await?;
+        if let Some(parent) = database_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|err| IOError::CreateDirectoryFailed(parent, err))?;
+        }
+        let lock = DatabaseLock::acquire(database_path).await?;
+        Self::apply_pending_import(database_path).await?;
+        let key = DatabaseCipher::prepare(database_path, encryption).await?;
+        let fresh = !Self::exist_database(database_path).await;
+        if fresh {
+            Self::create_database(database_path).await?;
         }
-        let pool = SqlitePool::connect(DATABASE_URL)
+        // WAL lets readers proceed while a writer is active, the timeout covers writer contention
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT)
+            .statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        let options = DatabaseCipher::apply_key(options, key.as_deref());
+        let pool = SqlitePool::connect_with(options)
             .await
             .map_err(DatabaseError::DatabaseConnectFailed)?;
         log!(DatabaseLog::DatabaseConnectSuccess);
-        let database_manager = Self { pool, _lock: lock };
+        let database_manager = Self {
+            pool,
+            database_path: database_path.to_path_buf(),
+            lock: Some(lock),
+            encrypted: key.is_some(),
+        };
         if !database_manager.exist_table("BackupSchedules").await {
             database_manager.create_backup_schedule_table().await?;
         }
+        if !database_manager.exist_table("DirectorySignatures").await {
+            database_manager.create_directory_signature_table().await?;
+        }
+        if !database_manager.exist_table("ComparisonCache").await {
+            database_manager.create_comparison_cache_table().await?;
+        }
+        if !database_manager.exist_table("RenamedEntries").await {
+            database_manager.create_renamed_entry_table().await?;
+        }
+        if !database_manager.exist_table("BenchmarkResults").await {
+            database_manager.create_benchmark_result_table().await?;
+        }
+        if !database_manager.exist_table("ExecutionHistory").await {
+            database_manager.create_execution_history_table().await?;
+        }
+        if !database_manager.exist_table("ExecutionErrors").await {
+            database_manager.create_execution_error_table().await?;
+        }
+        if !database_manager.exist_table("ExecutionCatalogs").await {
+            database_manager.create_execution_catalog_table().await?;
+        }
+        if !database_manager.exist_table("MaintenanceRuns").await {
+            database_manager.create_maintenance_run_table().await?;
+        }
+        if !database_manager.exist_table("ScheduleVersions").await {
+            database_manager.create_schedule_version_table().await?;
+        }
+        if !database_manager.exist_table("StorageSamples").await {
+            database_manager.create_storage_sample_table().await?;
+        }
+        if !database_manager.exist_table("EventJournal").await {
+            database_manager.create_event_journal_table().await?;
+        }
+        database_manager.migrate(fresh).await?;
         log!(SystemLog::InitializeComplete);
         Ok(database_manager)
     }
 
+    // Leaves tables and migrations to the instance holding the lock
+    pub async fn open_read_only(database_path: &Path) -> Result<Self, Error> {
+        log!(SystemLog::Initializing);
+        let key = DatabaseCipher::current_key(database_path).await?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .read_only(true)
+            .busy_timeout(BUSY_TIMEOUT)
+            .statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+        let options = DatabaseCipher::apply_key(options, key.as_deref());
+        let pool = SqlitePool::connect_with(options)
+            .await
+            .map_err(DatabaseError::DatabaseConnectFailed)?;
+        log!(DatabaseLog::DatabaseConnectSuccess);
+        log!(DatabaseLog::ReadOnlySession);
+        log!(SystemLog::InitializeComplete);
+        Ok(Self {
+            pool,
+            database_path: database_path.to_path_buf(),
+            lock: None,
+            encrypted: key.is_some(),
+        })
+    }
+
+    // Swaps in a database staged by a state import, the replaced one is kept beside it
+    async fn apply_pending_import(database_path: &Path) -> Result<(), Error> {
+        let import_path = Self::import_path(database_path);
+        if fs::metadata(&import_path).await.is_err() {
+            return Ok(());
+        }
+        let backup_path = Self::suffixed_path(database_path, ".pre-import.bak");
+        for suffix in DATABASE_FILE_SUFFIXES {
+            let path = Self::suffixed_path(database_path, suffix);
+            let backup = Self::suffixed_path(&backup_path, suffix);
+            let _ = fs::remove_file(&backup).await;
+            if fs::metadata(&path).await.is_ok() {
+                fs::rename(&path, &backup)
+                    .await
+                    .map_err(|err| IOError::WriteFileFailed(&backup, err))?;
+            }
+        }
+        fs::rename(&import_path, database_path)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(database_path, err))?;
+        log!(DatabaseLog::StateImported {
+            backup_path: backup_path.display().to_string(),
+        });
+        Ok(())
+    }
+
+    pub fn database_path(&self) -> &Path {
+        &self.database_path
+    }
+
+    // Where a state import stages the database it swaps in on the next start
+    pub fn import_path(database_path: &Path) -> PathBuf {
+        Self::suffixed_path(database_path, ".import")
+    }
+
+    pub fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+        let mut path = path.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.lock.is_none()
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    // Databases from before versioning hold the baseline tables created above
+    async fn migrate(&self, fresh: bool) -> Result<(), Error> {
+        if !self.exist_table("SchemaVersion").await {
+            self.create_schema_version_table(BASELINE_VERSION).await?;
+        }
+        let version = self.get_schema_version().await?;
+        let mut pending = MIGRATIONS
+            .iter()
+            .filter(|migration| migration.version > version)
+            .peekable();
+        if pending.peek().is_none() {
+            return Ok(());
+        }
+
+        if !fresh {
+            let path = Self::suffixed_path(&self.database_path, &format!(".v{version}.bak"));
+            let _ = fs::remove_file(&path).await;
+            self.backup_database(&path.to_string_lossy()).await?;
+            log!(DatabaseLog::DatabaseBackedUp {
+                path: path.display().to_string(),
+            });
+        }
+        for migration in pending {
+            self.apply_migration(migration).await?;
+            log!(DatabaseLog::MigrationApplied {
+                version: migration.version,
+                description: migration.description.to_string(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn get_pool(&self) -> SqlitePool {
         self.pool.clone()
     }
 
+    // Statements run on the connection commit together, an error rolls all of them back
+    pub async fn transaction<T>(
+        &self,
+        operation: impl AsyncFnOnce(&mut SqliteConnection) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        let result = operation(&mut transaction).await?;
+        transaction
+            .commit()
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(result)
+    }
+
     pub async fn shutdown(&self) {
         self.pool.close().await;
-        self._lock.release();
+        if let Some(lock) = &self.lock {
+            lock.release();
+        }
     }
 
-    pub async fn exist_database() -> bool {
-        fs::metadata(DATABASE_PATH).await.is_ok()
+    pub async fn exist_database(database_path: &Path) -> bool {
+        fs::metadata(database_path).await.is_ok()
     }
 
-    pub async fn create_database() -> Result<(), Error> {
-        let _ = File::create(DATABASE_PATH)
+    pub async fn create_database(database_path: &Path) -> Result<(), Error> {
+        let _ = File::create(database_path)
             .await
             .map_err(DatabaseError::CreateDatabaseFailed)?;
         Ok(())
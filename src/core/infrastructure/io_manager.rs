@@ -11,15 +11,16 @@ pub struct IOManager {
 
 impl IOManager {
     pub fn new(config: Arc<AppConfig>) -> Self {
-        let max_file_operations = config.max_file_operations;
-        let semaphore = Arc::new(Semaphore::new(max_file_operations));
+        let metadata_semaphore = Arc::new(Semaphore::new(config.max_metadata_operations));
+        let data_semaphore = Arc::new(Semaphore::new(config.max_data_operations));
         Self {
-            file_system: FileSystem::new(semaphore),
+            file_system: FileSystem::new(config, metadata_semaphore, data_semaphore),
         }
     }
 
     pub fn shutdown(&self) {
-        self.file_system.semaphore().close();
+        self.file_system.metadata_semaphore().close();
+        self.file_system.data_semaphore().close();
     }
 }
 
@@ -1,4 +1,6 @@
 pub mod app_config;
 pub mod communication_manager;
+pub mod data_relocator;
+pub mod database_cipher;
 pub mod database_manager;
 pub mod io_manager;
@@ -0,0 +1,289 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::core::maintenance::profile_service::ProfileService;
+use crate::core::maintenance::state_archive_service::StateArchiveService;
+use crate::interface::communication::command::CommandHandler;
+use crate::interface::communication::query::QueryHandler;
+use crate::interface::core::runnable::Runnable;
+use crate::interface::repository::event_journal::EventJournalRepository;
+use crate::interface::repository::maintenance::MaintenanceRepository;
+use crate::interface::repository::storage_usage::StorageUsageRepository;
+use crate::model::core::backup::communication::{BackupQuery, BackupQueryResponse};
+use crate::model::core::backup::execution::BackupState;
+use crate::model::core::maintenance::communication::*;
+use crate::model::core::maintenance::database_status::{DatabaseStatus, MaintenanceRun};
+use crate::model::core::schedule::communication::*;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::task::TaskError;
+use crate::model::log::database::DatabaseLog;
+use crate::model::log::system::SystemLog;
+use crate::platform::elevate;
+use async_trait::async_trait;
+use chrono::{Local, TimeDelta};
+use macros::log;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::select;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+use tracing::error;
+
+pub struct MaintenanceService {
+    app_config: Arc<AppConfig>,
+    database_manager: Arc<DatabaseManager>,
+    communication_manager: Arc<CommunicationManager>,
+    state_archive_service: Arc<StateArchiveService>,
+    profile_service: Arc<ProfileService>,
+    running: AtomicBool,
+}
+
+impl MaintenanceService {
+    pub fn new(
+        app_config: Arc<AppConfig>,
+        database_manager: Arc<DatabaseManager>,
+        communication_manager: Arc<CommunicationManager>,
+        state_archive_service: Arc<StateArchiveService>,
+        profile_service: Arc<ProfileService>,
+    ) -> Self {
+        Self {
+            app_config,
+            database_manager,
+            communication_manager,
+            state_archive_service,
+            profile_service,
+            running: AtomicBool::new(false),
+        }
+    }
+
+    pub async fn register_services(self: Arc<Self>) {
+        let communication_manager = self.communication_manager.clone();
+        communication_manager
+            .with_service(self)
+            .register_all::<(MaintenanceCommand, MaintenanceQuery)>()
+            .build();
+    }
+
+    async fn run_maintenance(&self) -> Result<(), Error> {
+        if self.running.swap(true, Ordering::AcqRel) {
+            Err(TaskError::MaintenanceAlreadyRunning)?
+        }
+        let result = self.maintain().await;
+        self.running.store(false, Ordering::Release);
+        result
+    }
+
+    async fn maintain(&self) -> Result<(), Error> {
+        let database_manager = &self.database_manager;
+        let size_before = database_manager.get_database_size().await?;
+
+        let pruned_runs = self.prune_history().await?;
+        database_manager.vacuum().await?;
+        database_manager.analyze().await?;
+        let integrity_problems = database_manager.integrity_check().await?;
+        if !integrity_problems.is_empty() {
+            log!(DatabaseLog::IntegrityCheckFailed {
+                problems: integrity_problems.join("; "),
+            });
+        }
+        let size_after = database_manager.get_database_size().await?;
+
+        let run = MaintenanceRun {
+            finished_at: Local::now().naive_local(),
+            pruned_runs,
+            size_before,
+            size_after,
+            integrity_problems,
+        };
+        database_manager.create_maintenance_run(&run).await?;
+        log!(DatabaseLog::MaintenanceComplete {
+            runs: pruned_runs,
+            size_before,
+            size_after,
+        });
+        Ok(())
+    }
+
+    async fn prune_history(&self) -> Result<usize, Error> {
+        let database_manager = &self.database_manager;
+        let response = self
+            .communication_manager
+            .send_query(ScheduleManagerQuery::GetSchedules)
+            .await?;
+        let ScheduleManagerQueryResponse::GetSchedules(schedules) = response else {
+            return Ok(0);
+        };
+        let now = Local::now().naive_local();
+        let days_ago = |days: u32| now - TimeDelta::days(days as i64);
+
+        let mut pruned_runs = 0;
+        let retention_days = self.app_config.history_retention_days;
+        if retention_days > 0 {
+            let exempt_schedules: Vec<_> = schedules
+                .iter()
+                .filter(|schedule| schedule.retention.max_days.is_some())
+                .map(|schedule| schedule.uuid)
+                .collect();
+            let cutoff = now - TimeDelta::days(retention_days);
+            pruned_runs += database_manager
+                .delete_execution_history_before(cutoff, &exempt_schedules)
+                .await?;
+            database_manager.delete_storage_samples_before(cutoff).await?;
+            database_manager.delete_journal_entries_before(cutoff).await?;
+        }
+
+        for schedule in &schedules {
+            let retention = schedule.retention;
+            if retention.max_days.is_some() || retention.max_runs.is_some() {
+                pruned_runs += database_manager
+                    .delete_schedule_history(
+                        schedule.uuid,
+                        retention.max_days.map(days_ago),
+                        retention.max_runs,
+                    )
+                    .await?;
+            }
+            if let Some(detail_days) = retention.detail_days {
+                database_manager
+                    .delete_schedule_history_details(schedule.uuid, days_ago(detail_days))
+                    .await?;
+            }
+        }
+        Ok(pruned_runs)
+    }
+
+    // Counted from the last run, so restarts do not postpone it indefinitely
+    async fn time_until_due(&self) -> Result<TimeDelta, Error> {
+        let Some(last_run) = self.database_manager.get_last_maintenance_run().await? else {
+            return Ok(TimeDelta::zero());
+        };
+        let due = last_run.finished_at + TimeDelta::hours(self.app_config.maintenance_interval);
+        let remaining = due - Local::now().naive_local();
+        Ok(remaining.max(TimeDelta::zero()))
+    }
+
+    // VACUUM holds the write lock throughout, runs would fail to record their progress
+    async fn execution_running(&self) -> bool {
+        let response = self
+            .communication_manager
+            .send_query(BackupQuery::GetExecutions)
+            .await;
+        match response {
+            Ok(BackupQueryResponse::GetExecutions(executions)) => executions
+                .iter()
+                .any(|(_, execution)| execution.state == BackupState::Running),
+            Ok(_) => false,
+            Err(err) => {
+                error!("{}", err);
+                true
+            }
+        }
+    }
+
+    async fn get_database_status(&self) -> Result<DatabaseStatus, Error> {
+        let size = self.database_manager.get_database_size().await?;
+        let last_maintenance = self.database_manager.get_last_maintenance_run().await?;
+        Ok(DatabaseStatus {
+            size,
+            last_maintenance,
+        })
+    }
+}
+
+#[async_trait]
+impl Runnable for MaintenanceService {
+    async fn run_impl(self: Arc<Self>, mut shutdown_rx: oneshot::Receiver<()>) {
+        let wakeup_time = TimeDelta::seconds(self.app_config.default_wakeup_time);
+        let mut postponed = false;
+
+        loop {
+            let remaining = match self.time_until_due().await {
+                Ok(remaining) => remaining,
+                Err(err) => {
+                    error!("{}", err);
+                    wakeup_time
+                }
+            };
+            if remaining.is_zero() {
+                if self.execution_running().await {
+                    if !postponed {
+                        log!(DatabaseLog::MaintenancePostponed);
+                        postponed = true;
+                    }
+                } else {
+                    postponed = false;
+                    match self.run_maintenance().await {
+                        Ok(()) => continue,
+                        Err(err) => error!("{}", err),
+                    }
+                }
+            }
+
+            // Still due here means it was postponed or failed, checked again after a wake-up period
+            let sleep_time = if remaining.is_zero() {
+                wakeup_time
+            } else {
+                remaining.min(wakeup_time)
+            };
+            select! {
+                biased;
+                _ = &mut shutdown_rx => { break; }
+                _ = sleep(sleep_time.to_std().unwrap_or_default()) => {}
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler<MaintenanceCommand> for MaintenanceService {
+    async fn handle_command(&self, command: MaintenanceCommand) -> Result<(), Error> {
+        match command {
+            MaintenanceCommand::RunMaintenance => {
+                if self.database_manager.is_read_only() {
+                    Err(DatabaseError::DatabaseReadOnly)?
+                }
+                self.run_maintenance().await?;
+            }
+            MaintenanceCommand::ExportState(archive_path) => {
+                self.state_archive_service.export(&archive_path).await?;
+            }
+            MaintenanceCommand::ImportState(archive_path) => {
+                if self.database_manager.is_read_only() {
+                    Err(DatabaseError::DatabaseReadOnly)?
+                }
+                self.state_archive_service.import(&archive_path).await?;
+            }
+            MaintenanceCommand::CreateProfile(name) => {
+                self.profile_service.create(&name).await?;
+            }
+            MaintenanceCommand::SwitchProfile(profile) => {
+                self.profile_service.switch(profile.as_deref())?;
+            }
+            MaintenanceCommand::RestartElevated => {
+                log!(SystemLog::ReRunAsAdmin);
+                elevate::elevate()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QueryHandler<MaintenanceQuery> for MaintenanceService {
+    async fn handle_query(
+        &self,
+        query: MaintenanceQuery,
+    ) -> Result<MaintenanceQueryResponse, Error> {
+        match query {
+            MaintenanceQuery::GetDatabaseStatus => {
+                let status = self.get_database_status().await?;
+                Ok(MaintenanceQueryResponse::GetDatabaseStatus(status))
+            }
+            MaintenanceQuery::GetProfiles => {
+                let profiles = self.profile_service.list().await?;
+                Ok(MaintenanceQueryResponse::GetProfiles(profiles))
+            }
+        }
+    }
+}
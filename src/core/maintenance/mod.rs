@@ -0,0 +1,3 @@
+pub mod maintenance_service;
+pub mod profile_service;
+pub mod state_archive_service;
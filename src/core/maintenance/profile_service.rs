@@ -0,0 +1,68 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use crate::platform::constants::{CONFIG_FILE_NAME, PROFILES_PATH};
+use std::path::Path;
+use std::{env, process};
+use tokio::fs;
+
+#[derive(Default)]
+pub struct ProfileService;
+
+impl ProfileService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Named profiles, the folders holding a config.toml, sorted by name
+    pub async fn list(&self) -> Result<Vec<String>, Error> {
+        let profiles_path = Path::new(PROFILES_PATH);
+        let Ok(mut entries) = fs::read_dir(profiles_path).await else {
+            return Ok(Vec::new());
+        };
+        let mut profiles = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(profiles_path, err))?
+        {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if AppConfig::is_valid_profile_name(&name)
+                && fs::try_exists(entry.path().join(CONFIG_FILE_NAME))
+                    .await
+                    .unwrap_or(false)
+            {
+                profiles.push(name);
+            }
+        }
+        profiles.sort();
+        Ok(profiles)
+    }
+
+    pub async fn create(&self, name: &str) -> Result<(), Error> {
+        if !AppConfig::is_valid_profile_name(name) {
+            Err(MiscError::InvalidProfileName {
+                name: name.to_string(),
+            })?
+        }
+        let config_path = AppConfig::config_path_of(Some(name));
+        if fs::try_exists(&config_path).await.unwrap_or(false) {
+            Err(IOError::FileAlreadyExists {
+                path: config_path.clone(),
+            })?
+        }
+        AppConfig::seed_profile(&config_path)
+    }
+
+    // Starts another instance on the profile, closing this one is left to the caller
+    pub fn switch(&self, profile: Option<&str>) -> Result<(), Error> {
+        let executable = env::current_exe().map_err(MiscError::StartProcessFailed)?;
+        let mut command = process::Command::new(executable);
+        if let Some(profile) = profile {
+            command.arg("--profile").arg(profile);
+        }
+        command.spawn().map_err(MiscError::StartProcessFailed)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::interface::repository::schema_version::SchemaVersionRepository;
+use crate::model::config::ConfigTable;
+use crate::model::core::maintenance::state_archive::StateArchive;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::misc::MiscError;
+use chrono::Local;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::fs;
+
+const ARCHIVE_MAGIC: &[u8; 4] = b"MSST";
+const ARCHIVE_VERSION: u32 = 1;
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+pub struct StateArchiveService {
+    app_config: Arc<AppConfig>,
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl StateArchiveService {
+    pub fn new(app_config: Arc<AppConfig>, database_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            app_config,
+            database_manager,
+        }
+    }
+
+    pub async fn export(&self, archive_path: &Path) -> Result<(), Error> {
+        // A consistent copy while the database stays in use, without the encryption of this
+        // machine so the archive imports anywhere
+        let database_path = self.database_manager.database_path();
+        let snapshot_path = DatabaseManager::suffixed_path(database_path, ".export");
+        let _ = fs::remove_file(&snapshot_path).await;
+        self.database_manager
+            .export_database(&snapshot_path.to_string_lossy())
+            .await?;
+        let database = fs::read(&snapshot_path)
+            .await
+            .map_err(|err| IOError::ReadFileFailed(&snapshot_path, err));
+        let _ = fs::remove_file(&snapshot_path).await;
+        let config_path = self.app_config.config_path();
+        let config = fs::read_to_string(&config_path)
+            .await
+            .map_err(|err| IOError::ReadFileFailed(&config_path, err))?;
+
+        let archive = StateArchive {
+            created_at: Local::now().naive_local(),
+            config,
+            database: database?,
+        };
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(&archive, config)
+            .map_err(MiscError::SerializeError)?;
+        let mut encoder = GzEncoder::new(ARCHIVE_MAGIC.to_vec(), Compression::default());
+        encoder.get_mut().extend(ARCHIVE_VERSION.to_le_bytes());
+        encoder
+            .write_all(&serialized)
+            .map_err(MiscError::SerializeError)?;
+        let content = encoder.finish().map_err(MiscError::SerializeError)?;
+
+        fs::write(archive_path, content)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(archive_path, err))?;
+        Ok(())
+    }
+
+    // The running instance keeps its database open, so the imported one is staged and swapped in
+    // on the next start, the config is replaced right away and read then as well
+    pub async fn import(&self, archive_path: &Path) -> Result<(), Error> {
+        let content = fs::read(archive_path)
+            .await
+            .map_err(|err| IOError::ReadFileFailed(archive_path, err))?;
+        let invalid = || MiscError::InvalidStateArchive {
+            path: archive_path.to_path_buf(),
+        };
+
+        let Some((magic, rest)) = content.split_first_chunk::<4>() else {
+            Err(invalid())?
+        };
+        let Some((version, payload)) = rest.split_first_chunk::<4>() else {
+            Err(invalid())?
+        };
+        if magic != ARCHIVE_MAGIC {
+            Err(invalid())?
+        }
+        let version = u32::from_le_bytes(*version);
+        if version != ARCHIVE_VERSION {
+            Err(MiscError::UnsupportedArchiveVersion { version })?
+        }
+
+        let mut serialized = Vec::new();
+        GzDecoder::new(payload)
+            .read_to_end(&mut serialized)
+            .map_err(|_| invalid())?;
+        let config = bincode::config::standard();
+        let (archive, _): (StateArchive, _) =
+            bincode::serde::decode_from_slice(&serialized, config).map_err(|_| invalid())?;
+        if toml::from_str::<ConfigTable>(&archive.config).is_err()
+            || !archive.database.starts_with(SQLITE_HEADER)
+        {
+            Err(invalid())?
+        }
+
+        let import_path = DatabaseManager::import_path(self.database_manager.database_path());
+        fs::write(&import_path, &archive.database)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&import_path, err))?;
+        let config_path = self.app_config.config_path();
+        let config_backup = DatabaseManager::suffixed_path(&config_path, ".bak");
+        fs::copy(&config_path, &config_backup)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&config_backup, err))?;
+        fs::write(&config_path, &archive.config)
+            .await
+            .map_err(|err| IOError::WriteFileFailed(&config_path, err))?;
+        Ok(())
+    }
+}
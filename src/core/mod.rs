@@ -1,5 +1,7 @@
 pub mod backup;
+pub mod benchmark;
 pub mod gui;
 pub mod infrastructure;
+pub mod maintenance;
 pub mod schedule;
 pub mod system;
@@ -2,21 +2,41 @@ use crate::core::infrastructure::communication_manager::CommunicationManager;
 use crate::core::infrastructure::database_manager::DatabaseManager;
 use crate::interface::communication::command::CommandHandler;
 use crate::interface::communication::query::QueryHandler;
+use crate::interface::core::runnable::Runnable;
+use crate::interface::repository::event_journal::EventJournalRepository;
 use crate::interface::repository::schedule::ScheduleRepository;
+use crate::interface::repository::schedule_version::ScheduleVersionRepository;
 use crate::model::core::backup::communication::BackupCommand;
+use crate::model::core::infrastructure::event_journal::{JournalEntry, JournalEvent};
 use crate::model::core::schedule::communication::*;
 use crate::model::core::schedule::schedule::*;
+use crate::model::core::schedule::schedule_version::ScheduleVersion;
+use crate::model::error::database::DatabaseError;
 use crate::model::error::Error;
 use async_trait::async_trait;
-use chrono::{Duration, Months, Utc};
+use chrono::{
+    Days, Duration, Local, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, Offset,
+    TimeZone, Utc,
+};
 use dashmap::DashMap;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::mem;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::select;
+use tokio::sync::{Notify, oneshot};
+use tokio::time::sleep;
+use tracing::error;
 use uuid::Uuid;
 
+const WRITE_DEBOUNCE: std::time::Duration = std::time::Duration::from_secs(1);
+const DST_GAP_SEARCH_MINUTES: i64 = 180;
+
 pub struct ScheduleManager {
     database_manager: Arc<DatabaseManager>,
     communication_manager: Arc<CommunicationManager>,
     schedules: DashMap<Uuid, Schedule>,
+    pending_writes: Mutex<HashSet<Uuid>>,
+    write_notify: Notify,
 }
 
 impl ScheduleManager {
@@ -33,6 +53,8 @@ impl ScheduleManager {
             database_manager,
             communication_manager,
             schedules,
+            pending_writes: Mutex::new(HashSet::new()),
+            write_notify: Notify::new(),
         };
         Ok(schedule_manager)
     }
@@ -41,12 +63,18 @@ impl ScheduleManager {
         let communication_manager = self.communication_manager.clone();
         communication_manager
             .with_service(self)
-            .command::<ScheduleManagerCommand>()
-            .query::<ScheduleManagerQuery>()
+            .register_all::<(ScheduleManagerCommand, ScheduleManagerQuery)>()
             .build();
     }
 
     pub async fn get_all_schedules(&self) -> Vec<Schedule> {
+        // Read back each time so changes made by the instance holding the lock show up
+        if self.database_manager.is_read_only() {
+            match self.database_manager.get_all_backup_schedules().await {
+                Ok(schedules) => return schedules,
+                Err(err) => error!("{}", err),
+            }
+        }
         self.schedules.iter().map(|x| x.value().clone()).collect()
     }
 
@@ -61,117 +89,296 @@ impl ScheduleManager {
         Ok(())
     }
 
+    // The settings being replaced are kept as a version the schedule can be reverted to
     pub async fn modify_schedule(&self, schedule: Schedule) -> Result<(), Error> {
-        self.database_manager
-            .modify_backup_schedule(&schedule)
-            .await?;
-        self.schedules.insert(schedule.uuid, schedule);
+        let uuid = schedule.uuid;
+        let previous = self.schedules.get(&uuid).map(|previous| previous.clone());
+        if let Some(previous) = previous
+            && !previous.changed_settings(&schedule).is_empty()
+        {
+            self.database_manager
+                .create_schedule_version(&previous, Utc::now().naive_utc())
+                .await?;
+        }
+        self.schedules.insert(uuid, schedule);
+        self.queue_write(uuid);
         self.communication_manager
             .send_command(ScheduleTimerCommand::RefreshTimer)
             .await?;
+        self.communication_manager
+            .send_command(BackupCommand::ReleaseChangeJournals)
+            .await?;
         Ok(())
     }
 
+    pub async fn revert_schedule(&self, uuid: Uuid, version: u32) -> Result<(), Error> {
+        let Some(mut schedule) = self.schedules.get(&uuid).map(|schedule| schedule.clone()) else {
+            return Ok(());
+        };
+        let Some(saved) = self.database_manager.get_schedule_version(uuid, version).await? else {
+            Err(DatabaseError::ScheduleVersionNotFound { version })?
+        };
+        schedule.apply_settings(&saved.schedule);
+        schedule.updated_at = Utc::now().naive_utc();
+        self.modify_schedule(schedule).await
+    }
+
+    pub async fn get_schedule_versions(&self, uuid: Uuid) -> Result<Vec<ScheduleVersion>, Error> {
+        self.database_manager.get_schedule_versions(uuid).await
+    }
+
     pub async fn remove_schedule(&self, uuid: Uuid) -> Result<(), Error> {
         self.database_manager.remove_backup_schedule(uuid).await?;
+        self.database_manager.remove_schedule_versions(uuid).await?;
         self.schedules.remove(&uuid);
         self.communication_manager
             .send_command(ScheduleTimerCommand::RefreshTimer)
             .await?;
+        self.communication_manager
+            .send_command(BackupCommand::ReleaseChangeJournals)
+            .await?;
         Ok(())
     }
 
     pub async fn active_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        if let Some(mut schedule) = self.database_manager.get_backup_schedule(uuid).await? {
-            schedule.state = ScheduleState::Active;
-            self.database_manager
-                .modify_backup_schedule(&schedule)
-                .await?;
-            self.schedules.insert(schedule.uuid, schedule);
-            self.communication_manager
-                .send_command(ScheduleTimerCommand::RefreshTimer)
-                .await?;
-        }
-        Ok(())
+        self.change_state(uuid, ScheduleState::Active).await
     }
 
     pub async fn pause_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        if let Some(mut schedule) = self.database_manager.get_backup_schedule(uuid).await? {
-            schedule.state = ScheduleState::Paused;
-            self.database_manager
-                .modify_backup_schedule(&schedule)
-                .await?;
-            self.schedules.insert(schedule.uuid, schedule);
-            self.communication_manager
-                .send_command(ScheduleTimerCommand::RefreshTimer)
-                .await?;
-        }
-        Ok(())
+        self.change_state(uuid, ScheduleState::Paused).await
     }
 
     pub async fn disable_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        if let Some(mut schedule) = self.database_manager.get_backup_schedule(uuid).await? {
-            schedule.state = ScheduleState::Disabled;
-            self.database_manager
-                .modify_backup_schedule(&schedule)
-                .await?;
-            self.schedules.insert(schedule.uuid, schedule);
-            self.communication_manager
-                .send_command(ScheduleTimerCommand::RefreshTimer)
-                .await?;
+        self.change_state(uuid, ScheduleState::Disabled).await
+    }
+
+    async fn change_state(&self, uuid: Uuid, state: ScheduleState) -> Result<(), Error> {
+        match self.schedules.get_mut(&uuid) {
+            Some(mut schedule) => schedule.state = state,
+            None => return Ok(()),
         }
+        self.queue_write(uuid);
+        self.communication_manager
+            .send_command(ScheduleTimerCommand::RefreshTimer)
+            .await?;
         Ok(())
     }
 
+    // Schedules that fire together are written back in one transaction right away
     pub async fn execute_ready_schedule(&self) -> Result<(), Error> {
-        let database_manager = self.database_manager.clone();
-
         let now = Utc::now().naive_utc();
-        let mut schedules = self.get_all_schedules().await;
+        let ready: Vec<Schedule> = self
+            .schedules
+            .iter()
+            .filter(|schedule| schedule.state == ScheduleState::Active)
+            .filter(|schedule| matches!(schedule.next_run_time, Some(time) if time < now))
+            .map(|schedule| schedule.value().clone())
+            .collect();
+
+        let mut fired = Vec::new();
+        for mut schedule in ready {
+            let execution = schedule.to_execution();
+            let execution_uuid = execution.uuid;
+            let mut detail = execution.source_path.display().to_string();
+            let command = BackupCommand::AddExecution(execution);
+            // A rejected execution still advances, otherwise it would fire again on every tick
+            if let Err(err) = self.communication_manager.send_command(command).await {
+                error!("{}", err);
+                detail = format!("Rejected: {err}");
+            }
+            let entry = JournalEntry::new(
+                JournalEvent::ScheduleTriggered,
+                Some(execution_uuid),
+                Some(schedule.uuid),
+                detail,
+            );
+            if let Err(err) = self.database_manager.append_journal_entry(&entry).await {
+                error!("{}", err);
+            }
+            self.update_next_run_time(&mut schedule);
+            self.schedules.insert(schedule.uuid, schedule.clone());
+            fired.push(schedule);
+        }
+
+        self.write_schedules(fired).await
+    }
 
-        for schedule in schedules.iter_mut() {
-            if schedule.state != ScheduleState::Active {
+    async fn write_schedules(&self, schedules: Vec<Schedule>) -> Result<(), Error> {
+        if schedules.is_empty() {
+            return Ok(());
+        }
+        self.database_manager
+            .modify_backup_schedules(&schedules)
+            .await
+    }
+
+    fn queue_write(&self, uuid: Uuid) {
+        self.pending_writes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(uuid);
+        self.write_notify.notify_one();
+    }
+
+    pub async fn flush_pending_writes(&self) -> Result<(), Error> {
+        let uuids = mem::take(
+            &mut *self
+                .pending_writes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        );
+        let schedules: Vec<Schedule> = uuids
+            .iter()
+            .filter_map(|uuid| self.schedules.get(uuid).map(|schedule| schedule.clone()))
+            .collect();
+        if let Err(err) = self.write_schedules(schedules).await {
+            self.pending_writes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend(uuids);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    // After the clock moved back, runs more than one interval away are pulled back onto the
+    // cadence they had instead of waiting out the difference
+    pub async fn recalculate_next_run_times(&self) -> Result<(), Error> {
+        let now = Utc::now().naive_utc();
+        let mut changed = Vec::new();
+        for mut schedule in self.schedules.iter_mut() {
+            if schedule.last_run_time.is_none() {
                 continue;
             }
-            if let Some(next_run_time) = schedule.next_run_time {
-                if next_run_time >= now {
-                    continue;
-                }
-                let execution = schedule.to_execution();
-                let command = BackupCommand::AddExecution(execution);
-                self.communication_manager.send_command(command).await?;
-                self.update_next_run_time(schedule);
-                database_manager.modify_backup_schedule(schedule).await?;
+            let Some(old_next_run_time) = schedule.next_run_time else {
+                continue;
+            };
+            let run_time = Self::run_time(schedule.value_mut(), old_next_run_time);
+            let mut next_run_time = old_next_run_time;
+            while let Some(previous) =
+                Self::previous_interval(schedule.interval, next_run_time, run_time)
+                && previous > now
+            {
+                next_run_time = previous;
+            }
+            if next_run_time != old_next_run_time {
+                schedule.next_run_time = Some(next_run_time);
+                changed.push(schedule.clone());
             }
         }
 
+        self.write_schedules(changed).await?;
+        self.communication_manager
+            .send_command(ScheduleTimerCommand::RefreshTimer)
+            .await?;
         Ok(())
     }
 
     fn update_next_run_time(&self, schedule: &mut Schedule) {
-        if schedule.next_run_time.is_none() {
+        let Some(old_next_run_time) = schedule.next_run_time else {
             return;
-        }
-        let now = Utc::now().naive_utc();
-        let old_next_run_time = schedule.next_run_time.unwrap();
-        let new_next_run_time = match schedule.interval {
-            ScheduleInterval::Once => None,
-            ScheduleInterval::Daily => Some(old_next_run_time + Duration::days(1)),
-            ScheduleInterval::Weekly => Some(old_next_run_time + Duration::days(7)),
-            ScheduleInterval::Monthly => Some(
-                old_next_run_time
-                    .checked_add_months(Months::new(1))
-                    .unwrap_or(old_next_run_time + Duration::days(30)),
-            ),
         };
+        let now = Utc::now().naive_utc();
+        let run_time = Self::run_time(schedule, old_next_run_time);
+        let mut next_run_time = Self::next_interval(schedule.interval, old_next_run_time, run_time);
+        // Runs missed while the clock jumped ahead or the app was closed fire once, not once each
+        while let Some(time) = next_run_time
+            && time <= now
+        {
+            next_run_time = Self::next_interval(schedule.interval, time, run_time);
+        }
         schedule.last_run_time = Some(now);
-        schedule.next_run_time = new_next_run_time;
+        schedule.next_run_time = next_run_time;
+    }
+
+    // Schedules from before the local run time was kept take it from their next run
+    fn run_time(schedule: &mut Schedule, next_run_time: NaiveDateTime) -> NaiveTime {
+        *schedule
+            .run_time
+            .get_or_insert_with(|| Local.from_utc_datetime(&next_run_time).time())
+    }
+
+    // Occurrences step through the local calendar, so a run keeps its wall clock time across
+    // daylight saving changes instead of shifting by the change
+    fn next_interval(
+        interval: ScheduleInterval,
+        time: NaiveDateTime,
+        run_time: NaiveTime,
+    ) -> Option<NaiveDateTime> {
+        let date = Local.from_utc_datetime(&time).date_naive();
+        let date = match interval {
+            ScheduleInterval::Once => return None,
+            ScheduleInterval::Daily => date.checked_add_days(Days::new(1))?,
+            ScheduleInterval::Weekly => date.checked_add_days(Days::new(7))?,
+            ScheduleInterval::Monthly => date.checked_add_months(Months::new(1))?,
+        };
+        Some(Self::resolve_local(date, run_time))
+    }
+
+    fn previous_interval(
+        interval: ScheduleInterval,
+        time: NaiveDateTime,
+        run_time: NaiveTime,
+    ) -> Option<NaiveDateTime> {
+        let date = Local.from_utc_datetime(&time).date_naive();
+        let date = match interval {
+            ScheduleInterval::Once => return None,
+            ScheduleInterval::Daily => date.checked_sub_days(Days::new(1))?,
+            ScheduleInterval::Weekly => date.checked_sub_days(Days::new(7))?,
+            ScheduleInterval::Monthly => date.checked_sub_months(Months::new(1))?,
+        };
+        Some(Self::resolve_local(date, run_time))
+    }
+
+    // A time repeated when the clock falls back runs at its first occurrence, a time skipped
+    // when it springs forward runs as soon as the clock is past the gap
+    fn resolve_local(date: NaiveDate, run_time: NaiveTime) -> NaiveDateTime {
+        let local = date.and_time(run_time);
+        match Local.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.naive_utc(),
+            LocalResult::None => (1..=DST_GAP_SEARCH_MINUTES)
+                .find_map(|minutes| {
+                    Local
+                        .from_local_datetime(&(local + Duration::minutes(minutes)))
+                        .earliest()
+                })
+                .map(|time| time.naive_utc())
+                .unwrap_or_else(|| {
+                    let offset = Local.offset_from_utc_datetime(&local).fix();
+                    local - Duration::seconds(offset.local_minus_utc() as i64)
+                }),
+        }
+    }
+}
+
+#[async_trait]
+impl Runnable for ScheduleManager {
+    // Edits arriving within the debounce window are coalesced into one transaction
+    async fn run_impl(self: Arc<Self>, mut shutdown_rx: oneshot::Receiver<()>) {
+        loop {
+            select! {
+                biased;
+                _ = &mut shutdown_rx => break,
+                _ = self.write_notify.notified() => {}
+            }
+            select! {
+                biased;
+                _ = &mut shutdown_rx => break,
+                _ = sleep(WRITE_DEBOUNCE) => {}
+            }
+            if let Err(err) = self.flush_pending_writes().await {
+                error!("{}", err);
+            }
+        }
     }
 }
 
 #[async_trait]
 impl CommandHandler<ScheduleManagerCommand> for ScheduleManager {
     async fn handle_command(&self, command: ScheduleManagerCommand) -> Result<(), Error> {
+        if self.database_manager.is_read_only() {
+            Err(DatabaseError::DatabaseReadOnly)?
+        }
         match command {
             ScheduleManagerCommand::AddSchedule(schedule) => {
                 self.create_schedule(schedule).await?;
@@ -191,9 +398,15 @@ impl CommandHandler<ScheduleManagerCommand> for ScheduleManager {
             ScheduleManagerCommand::DisableSchedule(uuid) => {
                 self.disable_schedule(uuid).await?;
             }
+            ScheduleManagerCommand::RevertSchedule(uuid, version) => {
+                self.revert_schedule(uuid, version).await?;
+            }
             ScheduleManagerCommand::ExecuteReadySchedules => {
                 self.execute_ready_schedule().await?;
             }
+            ScheduleManagerCommand::RecalculateNextRunTimes => {
+                self.recalculate_next_run_times().await?;
+            }
         }
         Ok(())
     }
@@ -210,6 +423,10 @@ impl QueryHandler<ScheduleManagerQuery> for ScheduleManager {
                 let executions = self.get_all_schedules().await;
                 Ok(ScheduleManagerQueryResponse::GetSchedules(executions))
             }
+            ScheduleManagerQuery::GetScheduleVersions(uuid) => {
+                let versions = self.get_schedule_versions(uuid).await?;
+                Ok(ScheduleManagerQueryResponse::GetScheduleVersions(versions))
+            }
         }
     }
 }
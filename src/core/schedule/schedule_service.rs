@@ -8,6 +8,7 @@ use crate::model::error::Error;
 use async_trait::async_trait;
 use std::sync::Arc;
 use tokio::sync::oneshot::Receiver;
+use tracing::error;
 
 pub struct ScheduleService {
     schedule_manager: Arc<ScheduleManager>,
@@ -36,14 +37,23 @@ impl ScheduleService {
         schedule_manager.register_services().await;
         schedule_timer.register_services().await;
     }
+
+    pub async fn shutdown(&self) {
+        if let Err(err) = self.schedule_manager.flush_pending_writes().await {
+            error!("{}", err);
+        }
+    }
 }
 
 #[async_trait]
 impl Runnable for ScheduleService {
     async fn run_impl(self: Arc<Self>, shutdown_rx: Receiver<()>) {
+        let schedule_manager = self.schedule_manager.clone();
         let schedule_timer = self.schedule_timer.clone();
+        let manager_shutdown = schedule_manager.run().await;
         let timer_shutdown = schedule_timer.run().await;
         let _ = shutdown_rx.await;
         let _ = timer_shutdown.send(());
+        let _ = manager_shutdown.send(());
     }
 }
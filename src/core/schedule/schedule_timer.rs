@@ -5,15 +5,19 @@ use crate::interface::core::runnable::Runnable;
 use crate::model::core::schedule::communication::*;
 use crate::model::core::schedule::schedule::ScheduleState;
 use crate::model::error::Error;
+use crate::model::log::system::SystemLog;
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
+use macros::log;
 use std::sync::Arc;
 use tokio::select;
 use tokio::sync::Notify;
 use tokio::sync::oneshot;
-use tokio::time::sleep;
+use tokio::time::{Instant, sleep};
 use tracing::error;
 
+const CLOCK_CHANGE_TOLERANCE: Duration = Duration::seconds(60);
+
 pub struct ScheduleTimer {
     app_config: Arc<AppConfig>,
     communication_manager: Arc<CommunicationManager>,
@@ -36,7 +40,7 @@ impl ScheduleTimer {
         let communication_manager = self.communication_manager.clone();
         communication_manager
             .with_service(self)
-            .command::<ScheduleTimerCommand>()
+            .register_all::<(ScheduleTimerCommand,)>()
             .build();
     }
 
@@ -46,7 +50,9 @@ impl ScheduleTimer {
         let response = communication_manager
             .send_query(ScheduleManagerQuery::GetSchedules)
             .await?;
-        let ScheduleManagerQueryResponse::GetSchedules(schedules) = response;
+        let ScheduleManagerQueryResponse::GetSchedules(schedules) = response else {
+            return Ok(None);
+        };
         for schedule in schedules {
             if schedule.state != ScheduleState::Active {
                 continue;
@@ -70,6 +76,15 @@ impl ScheduleTimer {
             Ok(None)
         }
     }
+
+    // Sleeps follow the monotonic clock, so a wall clock change shows up as a gap between them.
+    // The monotonic clock stops during suspend, which makes every wake look like a jump forward,
+    // only a jump back is acted on as runs a jump forward skipped are caught up as missed runs
+    fn clock_change(wall_start: DateTime<Utc>, monotonic_start: Instant) -> Option<Duration> {
+        let monotonic_elapsed = Duration::from_std(monotonic_start.elapsed()).ok()?;
+        let change = Utc::now().signed_duration_since(wall_start) - monotonic_elapsed;
+        (change < -CLOCK_CHANGE_TOLERANCE).then_some(change)
+    }
 }
 
 #[async_trait]
@@ -89,11 +104,29 @@ impl Runnable for ScheduleTimer {
             if sleep_time < Duration::seconds(0) {
                 sleep_time = Duration::seconds(0);
             }
-            select! {
+            // Capped so a clock change is noticed within one wake-up period
+            sleep_time = sleep_time.min(Duration::seconds(self.app_config.default_wakeup_time));
+            let wall_start = Utc::now();
+            let monotonic_start = Instant::now();
+            let refreshed = select! {
                 biased;
                 _ = &mut shutdown_rx => { break; }
-                _ = self.refresh_notify.notified() => { continue; }
-                _ = sleep(sleep_time.to_std().unwrap()) => {}
+                _ = self.refresh_notify.notified() => true,
+                _ = sleep(sleep_time.to_std().unwrap()) => false,
+            };
+            if let Some(change) = Self::clock_change(wall_start, monotonic_start) {
+                log!(SystemLog::ClockChanged {
+                    seconds: change.num_seconds(),
+                });
+                if let Err(err) = communication_manager
+                    .send_command(ScheduleManagerCommand::RecalculateNextRunTimes)
+                    .await
+                {
+                    error!("{}", err);
+                }
+            }
+            if refreshed {
+                continue;
             }
             if let Err(err) = communication_manager
                 .send_command(ScheduleManagerCommand::ExecuteReadySchedules)
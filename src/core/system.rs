@@ -1,22 +1,28 @@
 use crate::core::backup::backup_service::BackupService;
+use crate::core::benchmark::benchmark_service::BenchmarkService;
 use crate::core::gui::gui_manager::GuiManager;
 use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::core::infrastructure::data_relocator::DataRelocator;
 use crate::core::infrastructure::database_manager::DatabaseManager;
 use crate::core::infrastructure::io_manager::IOManager;
+use crate::core::maintenance::maintenance_service::MaintenanceService;
+use crate::core::maintenance::profile_service::ProfileService;
+use crate::core::maintenance::state_archive_service::StateArchiveService;
 use crate::core::schedule::schedule_service::ScheduleService;
 use crate::interface::core::runnable::Runnable;
+use crate::model::error::database::DatabaseError;
 use crate::model::error::Error;
+use crate::model::log::event::EventLog;
 use crate::model::log::system::SystemLog;
-#[cfg(any(target_os = "windows", not(debug_assertions)))]
+#[cfg(target_os = "windows")]
 use crate::platform::elevate;
+use crate::utils::database_lock::DatabaseLock;
 use crate::utils::logging::Logging;
+use crate::utils::operation_metrics::operation_statistics;
 use crossbeam_queue::SegQueue;
 use macros::log;
-#[cfg(not(debug_assertions))]
 use privilege::user::privileged;
-#[cfg(not(debug_assertions))]
-use std::process;
 use std::sync::Arc;
 use tokio::sync::oneshot;
 
@@ -26,20 +32,35 @@ pub struct System {
     communication_manager: Arc<CommunicationManager>,
     backup_service: Arc<BackupService>,
     schedule_service: Arc<ScheduleService>,
+    benchmark_service: Arc<BenchmarkService>,
+    maintenance_service: Arc<MaintenanceService>,
     gui_manager: Arc<GuiManager>,
     shutdowns: SegQueue<oneshot::Sender<()>>,
 }
 
 impl System {
-    pub async fn new() -> Result<Self, Error> {
-        let app_config = Arc::new(AppConfig::new()?);
+    pub async fn new(app_config: AppConfig) -> Result<Self, Error> {
+        let app_config = Arc::new(app_config);
         let io_manager = Arc::new(IOManager::new(app_config.clone()));
-        let database_manager = Arc::new(DatabaseManager::new().await?);
+        DataRelocator::relocate(&app_config).await?;
+        let database_path = &app_config.database_path;
+        let encryption = app_config.database_encryption;
+        let opened = DatabaseManager::new(database_path, encryption).await;
+        let (database_manager, lock_holder) = match opened {
+            Ok(database_manager) => (database_manager, None),
+            Err(Error::Database(DatabaseError::DatabaseInUse)) => {
+                let lock_holder = DatabaseLock::holder(database_path).await;
+                (DatabaseManager::open_read_only(database_path).await?, lock_holder)
+            }
+            Err(err) => Err(err)?,
+        };
+        let database_manager = Arc::new(database_manager);
         let communication_manager = Arc::new(CommunicationManager::new(app_config.clone()));
         let backup_service = Arc::new(
             BackupService::new(
                 app_config.clone(),
                 io_manager.clone(),
+                database_manager.clone(),
                 communication_manager.clone(),
             )
             .await,
@@ -52,10 +73,28 @@ impl System {
             )
             .await?,
         );
+        let benchmark_service = Arc::new(BenchmarkService::new(
+            database_manager.clone(),
+            communication_manager.clone(),
+        ));
+        let state_archive_service = Arc::new(StateArchiveService::new(
+            app_config.clone(),
+            database_manager.clone(),
+        ));
+        let profile_service = Arc::new(ProfileService::new());
+        let maintenance_service = Arc::new(MaintenanceService::new(
+            app_config.clone(),
+            database_manager.clone(),
+            communication_manager.clone(),
+            state_archive_service,
+            profile_service,
+        ));
         let gui_manager = Arc::new(
             GuiManager::new(
                 app_config,
-                communication_manager.clone()
+                communication_manager.clone(),
+                database_manager.is_read_only(),
+                lock_holder,
             )
         );
         let system = Self {
@@ -64,6 +103,8 @@ impl System {
             communication_manager,
             backup_service,
             schedule_service,
+            benchmark_service,
+            maintenance_service,
             gui_manager,
             shutdowns: SegQueue::new(),
         };
@@ -76,11 +117,22 @@ impl System {
         Self::elevate_privileges()?;
         let backup_service = self.backup_service.clone();
         let schedule_service = self.schedule_service.clone();
+        let benchmark_service = self.benchmark_service.clone();
+        let maintenance_service = self.maintenance_service.clone();
         let gui_manager = self.gui_manager.clone();
         backup_service.register_services().await;
         schedule_service.register_services().await;
-        let schedule_service_shutdown = schedule_service.run().await;
-        self.shutdowns.push(schedule_service_shutdown);
+        benchmark_service.register_services().await;
+        maintenance_service.clone().register_services().await;
+        // The instance holding the lock owns the schedules and maintenance, running them here would
+        // duplicate runs
+        if !self.database_manager.is_read_only() {
+            backup_service.restore_executions().await;
+            let schedule_service_shutdown = schedule_service.run().await;
+            self.shutdowns.push(schedule_service_shutdown);
+            let maintenance_service_shutdown = maintenance_service.run().await;
+            self.shutdowns.push(maintenance_service_shutdown);
+        }
         log!(SystemLog::InitializeComplete);
         gui_manager.start().await
     }
@@ -90,17 +142,43 @@ impl System {
             let _ = shutdown.send(());
         }
         self.backup_service.shutdown().await;
+        self.schedule_service.shutdown().await;
+        self.report_dropped_events();
+        Self::report_operation_metrics();
         self.communication_manager.clear_handlers();
         self.database_manager.shutdown().await;
         self.io_manager.shutdown();
     }
 
+    fn report_dropped_events(&self) {
+        for statistics in self.communication_manager.event_statistics() {
+            if statistics.dropped > 0 {
+                log!(EventLog::EventsDropped {
+                    event: statistics.event.to_string(),
+                    policy: format!("{:?}", statistics.policy),
+                    dropped: statistics.dropped,
+                });
+            }
+        }
+    }
+
+    fn report_operation_metrics() {
+        for statistics in operation_statistics() {
+            log!(SystemLog::OperationMetrics {
+                operation: format!("{}::{}", statistics.module, statistics.operation),
+                calls: statistics.calls,
+                failed: statistics.failed,
+                average_us: statistics.total.as_micros() as u64 / statistics.calls.max(1),
+                max_us: statistics.max.as_micros() as u64,
+            });
+        }
+    }
+
+    // Unelevated runs go on as they are, executions needing the rights ask for a restart instead
     fn elevate_privileges() -> Result<(), Error> {
-        #[cfg(not(debug_assertions))]
         if !privileged() {
-            log!(SystemLog::ReRunAsAdmin);
-            elevate::elevate()?;
-            process::exit(0);
+            log!(SystemLog::RunningUnelevated);
+            return Ok(());
         }
         #[cfg(target_os = "windows")]
         elevate::adjust_token_privileges()?;
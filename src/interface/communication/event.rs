@@ -1,9 +1,23 @@
+use crate::model::core::infrastructure::event_broadcaster::{EventStatistics, OverflowPolicy};
 use crate::model::error::Error;
+use async_trait::async_trait;
 use std::any::Any;
+use std::fmt::Debug;
+use uuid::Uuid;
 
-pub trait Event: Send + Clone + 'static {}
+pub trait Event: Send + Clone + Debug + 'static {
+    const OVERFLOW_POLICY: OverflowPolicy = OverflowPolicy::DropOldest;
+    // Most recent events a new subscriber receives first, 0 disables the replay
+    const REPLAY_CAPACITY: usize = 0;
 
+    fn coalesce_key(&self) -> Option<Uuid> {
+        None
+    }
+}
+
+#[async_trait]
 pub trait EventBroadcaster: Send + Sync {
     fn subscribe_typed(&self) -> Box<dyn Any + Send>;
-    fn broadcast_event(&self, event: Box<dyn Any + Send>) -> Result<(), Error>;
+    async fn broadcast_event(&self, event: Box<dyn Any + Send>) -> Result<(), Error>;
+    fn statistics(&self) -> EventStatistics;
 }
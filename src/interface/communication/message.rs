@@ -1,3 +1,39 @@
+use crate::core::infrastructure::communication_manager::ServiceRegistrar;
+
 pub trait Message: Send + 'static {
     type Response: Send + 'static;
 }
+
+// Implemented by #[derive(ServiceMessage)], registers the type with the service handling it
+pub trait ServiceMessage<S> {
+    fn register(registrar: ServiceRegistrar<S>) -> ServiceRegistrar<S>;
+}
+
+// Tuples of messages, registered together through ServiceRegistrar::register_all
+pub trait ServiceMessages<S> {
+    fn register(registrar: ServiceRegistrar<S>) -> ServiceRegistrar<S>;
+}
+
+macro_rules! impl_service_messages {
+    ($($message:ident),+) => {
+        impl<S, $($message: ServiceMessage<S>),+> ServiceMessages<S> for ($($message,)+) {
+            fn register(registrar: ServiceRegistrar<S>) -> ServiceRegistrar<S> {
+                $(let registrar = $message::register(registrar);)+
+                registrar
+            }
+        }
+    };
+}
+
+impl_service_messages!(A);
+impl_service_messages!(A, B);
+impl_service_messages!(A, B, C);
+impl_service_messages!(A, B, C, D);
+impl_service_messages!(A, B, C, D, E);
+impl_service_messages!(A, B, C, D, E, F);
+impl_service_messages!(A, B, C, D, E, F, G);
+impl_service_messages!(A, B, C, D, E, F, G, H);
+impl_service_messages!(A, B, C, D, E, F, G, H, I);
+impl_service_messages!(A, B, C, D, E, F, G, H, I, J);
+impl_service_messages!(A, B, C, D, E, F, G, H, I, J, K);
+impl_service_messages!(A, B, C, D, E, F, G, H, I, J, K, L);
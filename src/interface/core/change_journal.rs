@@ -0,0 +1,21 @@
+use crate::model::error::Error;
+use crate::platform::change_journal::ChangeCursor;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[async_trait]
+pub trait ChangeJournalTrait {
+    fn new() -> Self;
+
+    async fn current_cursor(&self, root: &Path) -> Result<ChangeCursor, Error>;
+
+    async fn changed_directories(
+        &self,
+        root: &Path,
+        cursor: &ChangeCursor,
+    ) -> Result<HashSet<PathBuf>, Error>;
+
+    // Stops watching every root outside in_use, journals kept by the system hold nothing to stop
+    fn release_unused(&self, _in_use: &HashSet<PathBuf>) {}
+}
@@ -1,12 +1,24 @@
+use crate::core::infrastructure::app_config::AppConfig;
 use crate::model::error::io::IOError;
 use crate::model::error::system::SystemError;
 use crate::model::error::Error;
-use crate::model::core::backup::execution::HashType;
+use crate::model::core::backup::comparison_cache::ComparisonRecord;
+use crate::model::core::backup::comparison_stats::{ComparisonOutcome, ComparisonStage};
+use crate::model::core::backup::copy_progress::CopyProgress;
+use crate::model::core::backup::directory_signature::{DirectorySignature, SignatureEntry};
+use crate::model::core::backup::execution::{ComparisonMode, HashType};
+use crate::interface::core::io_priority::{IOPriorityTrait, background_io};
 use crate::platform::attributes::*;
+use crate::platform::io_priority::IOPriority;
+use crate::utils::directory_stream::DirectoryStream;
 use crate::utils::file_hash::*;
+use crate::utils::mapped_compare::mapped_compare;
 use async_trait::async_trait;
+use macros::timed;
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::fs;
 use tokio::sync::Semaphore;
 use tokio::task::spawn_blocking;
@@ -15,12 +27,24 @@ use tokio_stream::StreamExt;
 
 #[async_trait]
 pub trait FileSystemTrait {
-    fn new(semaphore: Arc<Semaphore>) -> Self;
+    fn new(
+        app_config: Arc<AppConfig>,
+        metadata_semaphore: Arc<Semaphore>,
+        data_semaphore: Arc<Semaphore>,
+    ) -> Self;
 
-    fn semaphore(&self) -> Arc<Semaphore>;
+    // Directory listings and stat calls are limited separately so large copies cannot starve them
+    fn metadata_semaphore(&self) -> Arc<Semaphore>;
 
+    fn data_semaphore(&self) -> Arc<Semaphore>;
+
+    fn mapped_compare_threshold(&self) -> u64;
+
+    fn mtime_tolerance(&self) -> Duration;
+
+    #[timed]
     async fn is_symlink(&self, path: &Path) -> Result<bool, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -39,8 +63,9 @@ pub trait FileSystemTrait {
         destination_link: &Path,
     ) -> Result<(), Error>;
 
+    #[timed]
     async fn list_directory(&self, path: &Path) -> Result<Vec<PathBuf>, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -60,8 +85,115 @@ pub trait FileSystemTrait {
         Ok(result)
     }
 
+    #[timed]
+    async fn stream_directory(&self, path: &Path) -> Result<DirectoryStream, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let reader = fs::read_dir(path)
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(path, err))?;
+        Ok(DirectoryStream::new(path, reader, semaphore))
+    }
+
+    #[timed]
+    async fn entry_exists(&self, path: &Path) -> Result<bool, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        match fs::symlink_metadata(path).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(IOError::GetMetadataFailed(path, err))?,
+        }
+    }
+
+    #[timed]
+    async fn directory_signature(&self, path: &Path) -> Result<DirectorySignature, Error> {
+        let modified_nanos = |metadata: &std::fs::Metadata, path: &Path| {
+            metadata
+                .modified()
+                .map_err(|err| IOError::GetMetadataFailed(path, err))
+                .map(|modified| {
+                    modified
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_nanos() as i64)
+                        .unwrap_or_default()
+                })
+        };
+        let directory_metadata = fs::symlink_metadata(path)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+        let directory_modified = modified_nanos(&directory_metadata, path)?;
+
+        let mut signature_entries = Vec::new();
+        let mut entries = self.stream_directory(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let semaphore = self.metadata_semaphore();
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .map_err(IOError::SemaphoreClosed)?;
+
+            let metadata = fs::symlink_metadata(&entry)
+                .await
+                .map_err(|err| IOError::GetMetadataFailed(&entry, err))?;
+            signature_entries.push(SignatureEntry {
+                name: entry.file_name().unwrap_or_default().to_os_string(),
+                size: metadata.len(),
+                modified: modified_nanos(&metadata, &entry)?,
+            });
+        }
+        Ok(DirectorySignature::new(directory_modified, signature_entries))
+    }
+
+    #[timed]
+    async fn regular_file_size(&self, path: &Path) -> Result<Option<u64>, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let metadata = fs::symlink_metadata(path)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+        Ok(metadata.is_file().then_some(metadata.len()))
+    }
+
+    #[timed]
+    async fn comparison_record(&self, path: &Path) -> Result<ComparisonRecord, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let metadata = fs::metadata(path)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+        let modified = metadata
+            .modified()
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as i64)
+            .unwrap_or_default();
+        Ok(ComparisonRecord {
+            size: metadata.len() as i64,
+            modified,
+        })
+    }
+
+    #[timed]
     async fn create_directory(&self, path: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -73,8 +205,9 @@ pub trait FileSystemTrait {
         Ok(())
     }
 
+    #[timed]
     async fn delete_directory(&self, path: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -86,8 +219,9 @@ pub trait FileSystemTrait {
         Ok(())
     }
 
+    #[timed]
     async fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.data_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -99,8 +233,55 @@ pub trait FileSystemTrait {
         Ok(())
     }
 
+    // One permit and one blocking task for the whole batch, results follow the input order
+    #[timed]
+    async fn copy_small_files(
+        &self,
+        files: Vec<(PathBuf, PathBuf)>,
+    ) -> Result<Vec<Result<(), Error>>, Error> {
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+        let semaphore = self.data_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let background = background_io();
+        let results = spawn_blocking(move || {
+            IOPriority::run(background, || {
+                files
+                    .iter()
+                    .map(|(source, destination)| {
+                        std::fs::copy(source, destination)
+                            .map_err(|err| IOError::CopyFileFailed(source, destination, err))?;
+                        Ok(())
+                    })
+                    .collect()
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?;
+        Ok(results)
+    }
+
+    #[timed]
+    async fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        progress: Arc<CopyProgress>,
+    ) -> Result<(), Error> {
+        if progress.is_cancelled() {
+            Err(IOError::CopyFileCancelled(source))?
+        }
+        self.copy_file(source, destination).await
+    }
+
+    #[timed]
     async fn delete_file(&self, path: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -112,16 +293,24 @@ pub trait FileSystemTrait {
         Ok(())
     }
 
+    // Removes the link itself, never what it points to
+    #[timed]
+    async fn delete_link(&self, path: &Path) -> Result<(), Error> {
+        self.delete_file(path).await
+    }
+
     async fn get_attributes(&self, path: &Path) -> Result<Attributes, Error>;
 
     async fn set_attributes(&self, path: &Path, attributes: Attributes) -> Result<(), Error>;
 
+    #[timed]
     async fn copy_attributes(&self, source: &Path, destination: &Path) -> Result<(), Error> {
         let source_attributes = self.get_attributes(source).await?;
         self.set_attributes(destination, source_attributes).await?;
         Ok(())
     }
 
+    #[timed]
     async fn compare_attributes(
         &self,
         source: &Path,
@@ -136,41 +325,65 @@ pub trait FileSystemTrait {
 
     async fn set_permission(&self, path: &Path, permissions: Permissions) -> Result<(), Error>;
 
+    #[timed]
     async fn copy_permission(&self, source: &Path, destination: &Path) -> Result<(), Error> {
         let source_permissions = self.get_permission(source).await?;
         self.set_permission(destination, source_permissions).await?;
         Ok(())
     }
 
+    #[timed]
     async fn calculate_hash(&self, path: &Path, hash_type: HashType) -> Result<Vec<u8>, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.data_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
             .map_err(IOError::SemaphoreClosed)?;
 
-        let path = path.to_path_buf();
-        let hash = spawn_blocking(move || {
-            match hash_type {
-                HashType::MD5 => md5(path),
-                HashType::SHA3 => sha3(path),
-                HashType::SHA256 => sha256(path),
-                HashType::BLAKE2B => blake2b(path),
-                HashType::BLAKE2S => blake2s(path),
-                HashType::BLAKE3 => blake3(path),
-            }
-        })
-        .await
-        .map_err(SystemError::ThreadPanic)??;
+        // Hashing is mostly CPU, background runs keep it off the async workers at low priority
+        if background_io() {
+            let path = path.to_path_buf();
+            let cancellation = HashCancellation::default();
+            let cancelled = cancellation.flag();
+            return spawn_blocking(move || {
+                IOPriority::run(true, || blocking_file_hash(&path, hash_type, &cancelled))
+            })
+            .await
+            .map_err(SystemError::ThreadPanic)?;
+        }
+        let hash = match hash_type {
+            HashType::MD5 => md5(path).await,
+            HashType::SHA3 => sha3(path).await,
+            HashType::SHA256 => sha256(path).await,
+            HashType::BLAKE2B => blake2b(path).await,
+            HashType::BLAKE2S => blake2s(path).await,
+            HashType::BLAKE3 => blake3(path).await,
+        }?;
         Ok(hash)
     }
 
+    #[timed]
     async fn standard_compare(
         &self,
         source: &Path,
         destination: &Path,
+        mtime_resolution: Duration,
     ) -> Result<bool, Error> {
-        let semaphore = self.semaphore();
+        Ok(self
+            .metadata_mismatch(source, destination, mtime_resolution)
+            .await?
+            .is_none())
+    }
+
+    // Filesystems keeping whole seconds only widen the tolerance to their resolution
+    #[timed]
+    async fn metadata_mismatch(
+        &self,
+        source: &Path,
+        destination: &Path,
+        mtime_resolution: Duration,
+    ) -> Result<Option<ComparisonStage>, Error> {
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -186,7 +399,7 @@ pub trait FileSystemTrait {
                 .map_err(|err| IOError::GetMetadataFailed(destination, err))?;
 
         if source_metadata.len() != destination_metadata.len() {
-            return Ok(false);
+            return Ok(Some(ComparisonStage::Size));
         }
         let source_modified =
             source_metadata
@@ -196,40 +409,97 @@ pub trait FileSystemTrait {
             destination_metadata
                 .modified()
                 .map_err(|err| IOError::GetMetadataFailed(destination, err))?;
-        if source_modified != destination_modified {
-            return Ok(false);
+        let tolerance = match mtime_resolution >= Duration::from_secs(1) {
+            true => self.mtime_tolerance().max(mtime_resolution),
+            false => self.mtime_tolerance(),
+        };
+        if !same_mtime(source_modified, destination_modified, tolerance) {
+            return Ok(Some(ComparisonStage::Modified));
         }
-        Ok(true)
+        Ok(None)
     }
 
-    async fn advance_compare(
+    // Large local files are compared directly, stopping at the first differing window
+    #[timed]
+    async fn mapped_compare(
         &self,
         source: &Path,
         destination: &Path,
-    ) -> Result<bool, Error> {
-        if !self.standard_compare(source, destination).await? {
-            return Ok(false);
-        }
-
-        if !self.compare_attributes(source, destination).await? {
-            return Ok(false);
+    ) -> Result<Option<bool>, Error> {
+        let threshold = self.mapped_compare_threshold();
+        if threshold == 0 {
+            return Ok(None);
         }
+        let semaphore = self.data_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
 
-        Ok(true)
+        let background = background_io();
+        let source = source.to_path_buf();
+        let destination = destination.to_path_buf();
+        spawn_blocking(move || {
+            IOPriority::run(background, || mapped_compare(&source, &destination, threshold))
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
     }
 
-    async fn thorough_compare(
+    // Cheapest checks first, each stage only runs when every earlier one matched
+    #[timed]
+    async fn compare_files(
         &self,
         source: &Path,
         destination: &Path,
-        hash_type: HashType,
-    ) -> Result<bool, Error> {
-        if !self.advance_compare(source, destination).await? {
-            return Ok(false);
+        comparison_mode: ComparisonMode,
+        attributes: bool,
+        mtime_resolution: Duration,
+    ) -> Result<ComparisonOutcome, Error> {
+        if let Some(stage) = self
+            .metadata_mismatch(source, destination, mtime_resolution)
+            .await?
+        {
+            return Ok(ComparisonOutcome::differs(stage));
+        }
+        if comparison_mode == ComparisonMode::Standard {
+            return Ok(ComparisonOutcome::identical(ComparisonStage::Modified));
         }
-        let source_file_hash = self.calculate_hash(source, hash_type).await?;
-        let destination_file_hash = self.calculate_hash(destination, hash_type).await?;
 
-        Ok(source_file_hash == destination_file_hash)
+        // Skipped on destinations that cannot store attributes, they would never match
+        if attributes && !self.compare_attributes(source, destination).await? {
+            return Ok(ComparisonOutcome::differs(ComparisonStage::Attributes));
+        }
+        let ComparisonMode::Thorough(hash_type) = comparison_mode else {
+            return Ok(ComparisonOutcome::identical(ComparisonStage::Attributes));
+        };
+
+        if let Some(identical) = self.mapped_compare(source, destination).await? {
+            return Ok(ComparisonOutcome {
+                identical,
+                stage: ComparisonStage::Hash,
+            });
+        }
+        let (source_file_hash, destination_file_hash) = tokio::try_join!(
+            self.calculate_hash(source, hash_type),
+            self.calculate_hash(destination, hash_type),
+        )?;
+        Ok(ComparisonOutcome {
+            identical: source_file_hash == destination_file_hash,
+            stage: ComparisonStage::Hash,
+        })
+    }
+}
+
+// FAT keeps mtimes in 2 second steps and some shares drop the sub-second part, so with a
+// tolerance both sides are truncated to whole seconds before their distance is measured
+fn same_mtime(source: SystemTime, destination: SystemTime, tolerance: Duration) -> bool {
+    if tolerance.is_zero() {
+        return source == destination;
     }
+    let seconds = |time: SystemTime| match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i128,
+        Err(err) => -(err.duration().as_secs_f64().ceil() as i128),
+    };
+    (seconds(source) - seconds(destination)).unsigned_abs() <= tolerance.as_secs() as u128
 }
@@ -0,0 +1,34 @@
+use crate::model::error::Error;
+use tracing::error;
+
+tokio::task_local! {
+    // Set around the workers of an execution that runs with background IO priority
+    pub static BACKGROUND_IO: bool;
+}
+
+pub fn background_io() -> bool {
+    BACKGROUND_IO.try_with(|background| *background).unwrap_or(false)
+}
+
+pub trait ProcessWeightTrait: Sized {
+    // Lowers the scheduling weight of the whole process until the guard is dropped, None where
+    // the platform or the process setup offers no such weight
+    fn lower() -> Option<Self>;
+}
+
+pub trait IOPriorityTrait: Sized {
+    // Lowers the IO priority of the calling thread until the guard is dropped
+    fn enter_background() -> Result<Self, Error>;
+
+    // Meant for blocking sections, the priority is best effort and failures only get logged
+    fn run<T>(background: bool, operation: impl FnOnce() -> T) -> T {
+        let _guard = if background {
+            Self::enter_background()
+                .inspect_err(|err| error!("{}", err))
+                .ok()
+        } else {
+            None
+        };
+        operation()
+    }
+}
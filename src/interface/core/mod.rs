@@ -1,2 +1,5 @@
+pub mod change_journal;
 pub mod file_system;
+pub mod io_priority;
+pub mod power_request;
 pub mod runnable;
@@ -0,0 +1,7 @@
+use crate::model::error::Error;
+
+pub trait PowerRequestTrait: Sized {
+    // Keeps the system from sleeping until the guard is dropped, and the display as well unless
+    // it is allowed to turn off
+    fn acquire(allow_display_sleep: bool) -> Result<Self, Error>;
+}
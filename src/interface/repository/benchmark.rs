@@ -0,0 +1,129 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::benchmark::benchmark_result::BenchmarkResult;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::misc::MiscError;
+use macros::timed;
+use sqlx::Row;
+use uuid::Uuid;
+
+pub trait BenchmarkRepository {
+    async fn create_benchmark_result_table(&self) -> Result<(), Error>;
+    async fn create_benchmark_result(&self, result: &BenchmarkResult) -> Result<(), Error>;
+    async fn get_all_benchmark_results(&self) -> Result<Vec<BenchmarkResult>, Error>;
+}
+
+impl BenchmarkRepository for DatabaseManager {
+    #[timed]
+    async fn create_benchmark_result_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE BenchmarkResults (
+                uuid BLOB PRIMARY KEY,
+                source_path TEXT NOT NULL,
+                destination_path TEXT NOT NULL,
+                read_throughput REAL NOT NULL,
+                write_throughput REAL NOT NULL,
+                hash_throughput TEXT NOT NULL,
+                small_file_ops REAL NOT NULL,
+                recommended_concurrency INTEGER NOT NULL,
+                recommended_buffer_size INTEGER NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn create_benchmark_result(&self, result: &BenchmarkResult) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO BenchmarkResults (
+                uuid,
+                source_path,
+                destination_path,
+                read_throughput,
+                write_throughput,
+                hash_throughput,
+                small_file_ops,
+                recommended_concurrency,
+                recommended_buffer_size,
+                created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+            .bind(result.uuid.as_bytes().as_slice())
+            .bind(result.source_path.to_string_lossy().to_string())
+            .bind(result.destination_path.to_string_lossy().to_string())
+            .bind(result.read_throughput)
+            .bind(result.write_throughput)
+            .bind(
+                serde_json::to_string(&result.hash_throughput)
+                    .map_err(MiscError::SerializeError)?,
+            )
+            .bind(result.small_file_ops)
+            .bind(result.recommended_concurrency)
+            .bind(result.recommended_buffer_size as i64)
+            .bind(result.created_at)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_all_benchmark_results(&self) -> Result<Vec<BenchmarkResult>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                uuid,
+                source_path,
+                destination_path,
+                read_throughput,
+                write_throughput,
+                hash_throughput,
+                small_file_ops,
+                recommended_concurrency,
+                recommended_buffer_size,
+                created_at
+            FROM BenchmarkResults
+            ORDER BY created_at DESC
+            "#,
+        )
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let uuid_bytes: Vec<u8> = row.get("uuid");
+            let uuid = Uuid::from_slice(&uuid_bytes).map_err(|_| DatabaseError::DataCorrupted)?;
+
+            let hash_throughput_str: String = row.get("hash_throughput");
+            let hash_throughput = serde_json::from_str(&hash_throughput_str)
+                .map_err(MiscError::DeserializeError)?;
+
+            results.push(BenchmarkResult {
+                uuid,
+                source_path: row.get::<String, _>("source_path").into(),
+                destination_path: row.get::<String, _>("destination_path").into(),
+                read_throughput: row.get("read_throughput"),
+                write_throughput: row.get("write_throughput"),
+                hash_throughput,
+                small_file_ops: row.get("small_file_ops"),
+                recommended_concurrency: row.get("recommended_concurrency"),
+                recommended_buffer_size: row.get::<i64, _>("recommended_buffer_size") as usize,
+                created_at: row.get("created_at"),
+            });
+        }
+        Ok(results)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::backup::comparison_cache::ComparisonRecord;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use macros::timed;
+use sqlx::Row;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub trait ComparisonCacheRepository {
+    async fn create_comparison_cache_table(&self) -> Result<(), Error>;
+    async fn get_comparison_records(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        directory: &Path,
+        options: &str,
+    ) -> Result<HashMap<PathBuf, ComparisonRecord>, Error>;
+    async fn save_comparison_records(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        directory: &Path,
+        options: &str,
+        records: Vec<(PathBuf, ComparisonRecord)>,
+    ) -> Result<(), Error>;
+}
+
+impl ComparisonCacheRepository for DatabaseManager {
+    #[timed]
+    async fn create_comparison_cache_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE ComparisonCache (
+                source_root TEXT NOT NULL,
+                destination_root TEXT NOT NULL,
+                directory TEXT NOT NULL,
+                path TEXT NOT NULL,
+                options TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                modified INTEGER NOT NULL,
+                PRIMARY KEY (source_root, destination_root, directory, path)
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_comparison_records(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        directory: &Path,
+        options: &str,
+    ) -> Result<HashMap<PathBuf, ComparisonRecord>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                path,
+                size,
+                modified
+            FROM ComparisonCache
+            WHERE source_root = ? AND destination_root = ? AND directory = ? AND options = ?
+            "#,
+        )
+            .bind(source_root.to_string_lossy().to_string())
+            .bind(destination_root.to_string_lossy().to_string())
+            .bind(directory.to_string_lossy().to_string())
+            .bind(options)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let path = PathBuf::from(row.get::<String, _>("path"));
+                let record = ComparisonRecord {
+                    size: row.get("size"),
+                    modified: row.get("modified"),
+                };
+                (path, record)
+            })
+            .collect())
+    }
+
+    // Replaces the whole directory so entries of deleted files do not linger
+    #[timed]
+    async fn save_comparison_records(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        directory: &Path,
+        options: &str,
+        records: Vec<(PathBuf, ComparisonRecord)>,
+    ) -> Result<(), Error> {
+        let source_root = source_root.to_string_lossy().to_string();
+        let destination_root = destination_root.to_string_lossy().to_string();
+        let directory = directory.to_string_lossy().to_string();
+
+        self.transaction(async |connection| {
+            sqlx::query(
+                r#"
+                DELETE FROM ComparisonCache
+                WHERE source_root = ? AND destination_root = ? AND directory = ?
+                "#,
+            )
+                .bind(&source_root)
+                .bind(&destination_root)
+                .bind(&directory)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            for (path, record) in records {
+                sqlx::query(
+                    r#"
+                    INSERT INTO ComparisonCache (
+                        source_root,
+                        destination_root,
+                        directory,
+                        path,
+                        options,
+                        size,
+                        modified
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                    .bind(&source_root)
+                    .bind(&destination_root)
+                    .bind(&directory)
+                    .bind(path.to_string_lossy().to_string())
+                    .bind(options)
+                    .bind(record.size)
+                    .bind(record.modified)
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(DatabaseError::StatementExecutionFailed)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
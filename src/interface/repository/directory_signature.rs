@@ -0,0 +1,127 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::backup::directory_signature::DirectorySignature;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use macros::timed;
+use sqlx::Row;
+use std::path::Path;
+
+pub trait DirectorySignatureRepository {
+    async fn create_directory_signature_table(&self) -> Result<(), Error>;
+    async fn get_directory_signature(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+    ) -> Result<Option<DirectorySignature>, Error>;
+    async fn save_directory_signature(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+        signature: &DirectorySignature,
+    ) -> Result<(), Error>;
+}
+
+impl DirectorySignatureRepository for DatabaseManager {
+    #[timed]
+    async fn create_directory_signature_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE DirectorySignatures (
+                source_root TEXT NOT NULL,
+                destination_root TEXT NOT NULL,
+                path TEXT NOT NULL,
+                entry_count INTEGER NOT NULL,
+                max_modified INTEGER NOT NULL,
+                total_size INTEGER NOT NULL,
+                PRIMARY KEY (source_root, destination_root, path)
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_directory_signature(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+    ) -> Result<Option<DirectorySignature>, Error> {
+        let pool = self.get_pool();
+        let row = sqlx::query(
+            r#"
+            SELECT
+                entry_count,
+                max_modified,
+                total_size,
+                entries_hash
+            FROM DirectorySignatures
+            WHERE source_root = ? AND destination_root = ? AND path = ?
+            "#,
+        )
+            .bind(source_root.to_string_lossy().to_string())
+            .bind(destination_root.to_string_lossy().to_string())
+            .bind(path.to_string_lossy().to_string())
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        // Rows saved before the entries hash was kept count as missing, the directory is walked
+        Ok(row.and_then(|row| {
+            let entries_hash: Option<Vec<u8>> = row.get("entries_hash");
+            Some(DirectorySignature {
+                entry_count: row.get("entry_count"),
+                max_modified: row.get("max_modified"),
+                total_size: row.get("total_size"),
+                entries_hash: entries_hash?.try_into().ok()?,
+            })
+        }))
+    }
+
+    #[timed]
+    async fn save_directory_signature(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+        signature: &DirectorySignature,
+    ) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO DirectorySignatures (
+                source_root,
+                destination_root,
+                path,
+                entry_count,
+                max_modified,
+                total_size,
+                entries_hash
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (source_root, destination_root, path) DO UPDATE SET
+                entry_count = excluded.entry_count,
+                max_modified = excluded.max_modified,
+                total_size = excluded.total_size,
+                entries_hash = excluded.entries_hash
+            "#,
+        )
+            .bind(source_root.to_string_lossy().to_string())
+            .bind(destination_root.to_string_lossy().to_string())
+            .bind(path.to_string_lossy().to_string())
+            .bind(signature.entry_count)
+            .bind(signature.max_modified)
+            .bind(signature.total_size)
+            .bind(signature.entries_hash.to_vec())
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+}
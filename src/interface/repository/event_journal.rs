@@ -0,0 +1,120 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::infrastructure::event_journal::JournalEntry;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::misc::MiscError;
+use chrono::NaiveDateTime;
+use macros::timed;
+use sqlx::Row;
+use std::ops::Range;
+use uuid::Uuid;
+
+pub trait EventJournalRepository {
+    async fn create_event_journal_table(&self) -> Result<(), Error>;
+    async fn append_journal_entry(&self, entry: &JournalEntry) -> Result<(), Error>;
+    async fn get_journal_entries(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<JournalEntry>, Error>;
+    async fn delete_journal_entries_before(&self, cutoff: NaiveDateTime) -> Result<(), Error>;
+}
+
+impl EventJournalRepository for DatabaseManager {
+    // Append only, rows are never updated and only pruned by maintenance
+    #[timed]
+    async fn create_event_journal_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE EventJournal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                recorded_at TEXT NOT NULL,
+                event TEXT NOT NULL,
+                execution_uuid BLOB,
+                schedule_uuid BLOB,
+                detail TEXT NOT NULL
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn append_journal_entry(&self, entry: &JournalEntry) -> Result<(), Error> {
+        let event = serde_json::to_string(&entry.event).map_err(MiscError::SerializeError)?;
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO EventJournal (recorded_at, event, execution_uuid, schedule_uuid, detail)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+            .bind(entry.recorded_at)
+            .bind(event)
+            .bind(entry.execution_uuid.map(|uuid| uuid.as_bytes().to_vec()))
+            .bind(entry.schedule_uuid.map(|uuid| uuid.as_bytes().to_vec()))
+            .bind(&entry.detail)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // Newest first, in the order they were written
+    #[timed]
+    async fn get_journal_entries(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<JournalEntry>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT recorded_at, event, execution_uuid, schedule_uuid, detail
+            FROM EventJournal
+            WHERE recorded_at >= ? AND recorded_at < ?
+            ORDER BY id DESC
+            "#,
+        )
+            .bind(range.start)
+            .bind(range.end)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let optional_uuid = |bytes: Option<Vec<u8>>| -> Result<Option<Uuid>, Error> {
+            match bytes {
+                Some(bytes) => {
+                    Ok(Some(Uuid::from_slice(&bytes).map_err(|_| DatabaseError::DataCorrupted)?))
+                }
+                None => Ok(None),
+            }
+        };
+        let mut entries = Vec::new();
+        for row in rows {
+            let event_str: String = row.get("event");
+            let event = serde_json::from_str(&event_str).map_err(MiscError::DeserializeError)?;
+            entries.push(JournalEntry {
+                recorded_at: row.get("recorded_at"),
+                event,
+                execution_uuid: optional_uuid(row.get("execution_uuid"))?,
+                schedule_uuid: optional_uuid(row.get("schedule_uuid"))?,
+                detail: row.get("detail"),
+            });
+        }
+        Ok(entries)
+    }
+
+    #[timed]
+    async fn delete_journal_entries_before(&self, cutoff: NaiveDateTime) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("DELETE FROM EventJournal WHERE recorded_at < ?")
+            .bind(cutoff)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,512 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::backup::catalog::CatalogEntry;
+use crate::model::core::backup::execution::BackupState;
+use crate::model::core::backup::execution_history::ExecutionHistory;
+use crate::model::core::backup::history_statistics::{DailyBytesCopied, ScheduleErrorCount};
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::misc::MiscError;
+use crate::model::error::record::ErrorRecord;
+use chrono::NaiveDateTime;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use macros::timed;
+use sqlx::Row;
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+pub trait ExecutionHistoryRepository {
+    async fn create_execution_history_table(&self) -> Result<(), Error>;
+    async fn create_execution_error_table(&self) -> Result<(), Error>;
+    async fn create_execution_history(
+        &self,
+        history: &ExecutionHistory,
+        errors: Vec<(usize, ErrorRecord)>,
+    ) -> Result<(), Error>;
+    async fn get_all_execution_history(&self) -> Result<Vec<ExecutionHistory>, Error>;
+    async fn get_execution_errors(&self, history_uuid: Uuid) -> Result<Vec<ErrorRecord>, Error>;
+    async fn create_execution_catalog_table(&self) -> Result<(), Error>;
+    async fn create_execution_catalog(
+        &self,
+        history_uuid: Uuid,
+        entries: &[CatalogEntry],
+    ) -> Result<(), Error>;
+    async fn get_execution_catalog(&self, history_uuid: Uuid) -> Result<Vec<CatalogEntry>, Error>;
+    async fn get_bytes_copied_per_day(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<DailyBytesCopied>, Error>;
+    async fn get_error_counts_per_schedule(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<ScheduleErrorCount>, Error>;
+    async fn get_average_duration(&self, schedule_uuid: Uuid) -> Result<Option<Duration>, Error>;
+    async fn get_bytes_copied_per_destination(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<(PathBuf, u64)>, Error>;
+}
+
+impl ExecutionHistoryRepository for DatabaseManager {
+    #[timed]
+    async fn create_execution_history_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE ExecutionHistory (
+                uuid BLOB PRIMARY KEY,
+                execution_uuid BLOB NOT NULL,
+                schedule_uuid BLOB,
+                source_path TEXT NOT NULL,
+                destination_path TEXT NOT NULL,
+                state TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                finished_at TEXT NOT NULL,
+                files_scanned INTEGER NOT NULL,
+                files_copied INTEGER NOT NULL,
+                entries_deleted INTEGER NOT NULL,
+                bytes_copied INTEGER NOT NULL,
+                error_count INTEGER NOT NULL
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn create_execution_error_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE ExecutionErrors (
+                history_uuid BLOB NOT NULL,
+                position INTEGER NOT NULL,
+                code TEXT NOT NULL,
+                level TEXT NOT NULL,
+                message TEXT NOT NULL,
+                context TEXT NOT NULL,
+                PRIMARY KEY (history_uuid, position)
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // Errors are keyed by their position among all errors of the run, gaps show truncation
+    #[timed]
+    async fn create_execution_history(
+        &self,
+        history: &ExecutionHistory,
+        errors: Vec<(usize, ErrorRecord)>,
+    ) -> Result<(), Error> {
+        let state = serde_json::to_string(&history.state).map_err(MiscError::SerializeError)?;
+        let capabilities = match &history.capabilities {
+            Some(capabilities) => {
+                Some(serde_json::to_string(capabilities).map_err(MiscError::SerializeError)?)
+            }
+            None => None,
+        };
+        let mut contexts = Vec::with_capacity(errors.len());
+        for (_, record) in &errors {
+            let context =
+                serde_json::to_string(&record.context).map_err(MiscError::SerializeError)?;
+            contexts.push(context);
+        }
+
+        self.transaction(async |connection| {
+            sqlx::query(
+                r#"
+                INSERT INTO ExecutionHistory (
+                    uuid,
+                    execution_uuid,
+                    schedule_uuid,
+                    source_path,
+                    destination_path,
+                    state,
+                    started_at,
+                    finished_at,
+                    files_scanned,
+                    files_copied,
+                    entries_deleted,
+                    bytes_copied,
+                    error_count,
+                    capabilities
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+                .bind(history.uuid.as_bytes().as_slice())
+                .bind(history.execution_uuid.as_bytes().as_slice())
+                .bind(history.schedule_uuid.as_ref().map(|uuid| uuid.as_bytes().to_vec()))
+                .bind(history.source_path.to_string_lossy().to_string())
+                .bind(history.destination_path.to_string_lossy().to_string())
+                .bind(&state)
+                .bind(history.started_at)
+                .bind(history.finished_at)
+                .bind(history.files_scanned as i64)
+                .bind(history.files_copied as i64)
+                .bind(history.entries_deleted as i64)
+                .bind(history.bytes_copied as i64)
+                .bind(history.error_count as i64)
+                .bind(capabilities)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            for ((position, record), context) in errors.into_iter().zip(contexts) {
+                sqlx::query(
+                    r#"
+                    INSERT INTO ExecutionErrors (
+                        history_uuid,
+                        position,
+                        code,
+                        level,
+                        message,
+                        context
+                    )
+                    VALUES (?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                    .bind(history.uuid.as_bytes().as_slice())
+                    .bind(position as i64)
+                    .bind(record.code)
+                    .bind(record.level)
+                    .bind(record.message)
+                    .bind(context)
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(DatabaseError::StatementExecutionFailed)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    #[timed]
+    async fn get_all_execution_history(&self) -> Result<Vec<ExecutionHistory>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                uuid,
+                execution_uuid,
+                schedule_uuid,
+                source_path,
+                destination_path,
+                state,
+                started_at,
+                finished_at,
+                files_scanned,
+                files_copied,
+                entries_deleted,
+                bytes_copied,
+                error_count,
+                capabilities
+            FROM ExecutionHistory
+            ORDER BY started_at DESC
+            "#,
+        )
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            let uuid_bytes: Vec<u8> = row.get("uuid");
+            let uuid = Uuid::from_slice(&uuid_bytes).map_err(|_| DatabaseError::DataCorrupted)?;
+
+            let execution_uuid_bytes: Vec<u8> = row.get("execution_uuid");
+            let execution_uuid = Uuid::from_slice(&execution_uuid_bytes)
+                .map_err(|_| DatabaseError::DataCorrupted)?;
+
+            let schedule_uuid = match row.get::<Option<Vec<u8>>, _>("schedule_uuid") {
+                Some(bytes) => {
+                    Some(Uuid::from_slice(&bytes).map_err(|_| DatabaseError::DataCorrupted)?)
+                }
+                None => None,
+            };
+
+            let state_str: String = row.get("state");
+            let state = serde_json::from_str(&state_str)
+                .map_err(MiscError::DeserializeError)?;
+
+            let capabilities = match row.get::<Option<String>, _>("capabilities") {
+                Some(capabilities_str) => Some(
+                    serde_json::from_str(&capabilities_str)
+                        .map_err(MiscError::DeserializeError)?,
+                ),
+                None => None,
+            };
+
+            history.push(ExecutionHistory {
+                uuid,
+                execution_uuid,
+                schedule_uuid,
+                source_path: row.get::<String, _>("source_path").into(),
+                destination_path: row.get::<String, _>("destination_path").into(),
+                state,
+                started_at: row.get("started_at"),
+                finished_at: row.get("finished_at"),
+                files_scanned: row.get::<i64, _>("files_scanned") as usize,
+                files_copied: row.get::<i64, _>("files_copied") as usize,
+                entries_deleted: row.get::<i64, _>("entries_deleted") as usize,
+                bytes_copied: row.get::<i64, _>("bytes_copied") as u64,
+                error_count: row.get::<i64, _>("error_count") as usize,
+                capabilities,
+            });
+        }
+        Ok(history)
+    }
+
+    #[timed]
+    async fn get_execution_errors(&self, history_uuid: Uuid) -> Result<Vec<ErrorRecord>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                code,
+                level,
+                message,
+                context
+            FROM ExecutionErrors
+            WHERE history_uuid = ?
+            ORDER BY position
+            "#,
+        )
+            .bind(history_uuid.as_bytes().as_slice())
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let mut errors = Vec::new();
+        for row in rows {
+            let context_str: String = row.get("context");
+            let context = serde_json::from_str(&context_str)
+                .map_err(MiscError::DeserializeError)?;
+
+            errors.push(ErrorRecord {
+                code: row.get("code"),
+                level: row.get("level"),
+                message: row.get("message"),
+                context,
+            });
+        }
+        Ok(errors)
+    }
+
+    #[timed]
+    async fn create_execution_catalog_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE ExecutionCatalogs (
+                history_uuid BLOB PRIMARY KEY,
+                entry_count INTEGER NOT NULL,
+                entries BLOB NOT NULL
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // A run can touch millions of files, so the list is stored as one compressed blob
+    #[timed]
+    async fn create_execution_catalog(
+        &self,
+        history_uuid: Uuid,
+        entries: &[CatalogEntry],
+    ) -> Result<(), Error> {
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(entries, config)
+            .map_err(MiscError::SerializeError)?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&serialized)
+            .map_err(MiscError::SerializeError)?;
+        let compressed = encoder.finish().map_err(MiscError::SerializeError)?;
+
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO ExecutionCatalogs (
+                history_uuid,
+                entry_count,
+                entries
+            )
+            VALUES (?, ?, ?)
+            "#,
+        )
+            .bind(history_uuid.as_bytes().as_slice())
+            .bind(entries.len() as i64)
+            .bind(compressed)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_execution_catalog(&self, history_uuid: Uuid) -> Result<Vec<CatalogEntry>, Error> {
+        let pool = self.get_pool();
+        let row = sqlx::query("SELECT entries FROM ExecutionCatalogs WHERE history_uuid = ?")
+            .bind(history_uuid.as_bytes().as_slice())
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        let Some(row) = row else {
+            return Ok(Vec::new());
+        };
+
+        let compressed: Vec<u8> = row.get("entries");
+        let mut serialized = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut serialized)
+            .map_err(MiscError::DeserializeError)?;
+        let config = bincode::config::standard();
+        let (entries, _) = bincode::serde::decode_from_slice(&serialized, config)
+            .map_err(MiscError::DeserializeError)?;
+        Ok(entries)
+    }
+
+    // Runs are counted on the day they started
+    #[timed]
+    async fn get_bytes_copied_per_day(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<DailyBytesCopied>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                date(started_at) AS day,
+                SUM(bytes_copied) AS bytes_copied
+            FROM ExecutionHistory
+            WHERE started_at >= ? AND started_at < ?
+            GROUP BY day
+            ORDER BY day
+            "#,
+        )
+            .bind(range.start)
+            .bind(range.end)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let mut days = Vec::new();
+        for row in rows {
+            let day: String = row.get("day");
+            let day = day.parse().map_err(|_| DatabaseError::DataCorrupted)?;
+            days.push(DailyBytesCopied {
+                day,
+                bytes_copied: row.get::<i64, _>("bytes_copied") as u64,
+            });
+        }
+        Ok(days)
+    }
+
+    // Runs started by hand have no schedule and are left out
+    #[timed]
+    async fn get_error_counts_per_schedule(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<ScheduleErrorCount>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                schedule_uuid,
+                COUNT(*) AS runs,
+                SUM(error_count) AS error_count
+            FROM ExecutionHistory
+            WHERE schedule_uuid IS NOT NULL AND started_at >= ? AND started_at < ?
+            GROUP BY schedule_uuid
+            ORDER BY error_count DESC
+            "#,
+        )
+            .bind(range.start)
+            .bind(range.end)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let mut schedules = Vec::new();
+        for row in rows {
+            let schedule_uuid_bytes: Vec<u8> = row.get("schedule_uuid");
+            let schedule_uuid = Uuid::from_slice(&schedule_uuid_bytes)
+                .map_err(|_| DatabaseError::DataCorrupted)?;
+            schedules.push(ScheduleErrorCount {
+                schedule_uuid,
+                runs: row.get::<i64, _>("runs") as usize,
+                error_count: row.get::<i64, _>("error_count") as usize,
+            });
+        }
+        Ok(schedules)
+    }
+
+    // Only completed runs, a failed or canceled run stops early and would pull the average down
+    #[timed]
+    async fn get_average_duration(&self, schedule_uuid: Uuid) -> Result<Option<Duration>, Error> {
+        let state = serde_json::to_string(&BackupState::Completed)
+            .map_err(MiscError::SerializeError)?;
+        let pool = self.get_pool();
+        let row = sqlx::query(
+            r#"
+            SELECT
+                AVG((julianday(finished_at) - julianday(started_at)) * 86400.0) AS seconds
+            FROM ExecutionHistory
+            WHERE schedule_uuid = ? AND state = ?
+            "#,
+        )
+            .bind(schedule_uuid.as_bytes().as_slice())
+            .bind(state)
+            .fetch_one(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+
+        let seconds: Option<f64> = row.get("seconds");
+        Ok(seconds.map(|seconds| Duration::from_secs_f64(seconds.max(0.0))))
+    }
+
+    #[timed]
+    async fn get_bytes_copied_per_destination(
+        &self,
+        range: Range<NaiveDateTime>,
+    ) -> Result<Vec<(PathBuf, u64)>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                destination_path,
+                SUM(bytes_copied) AS bytes_copied
+            FROM ExecutionHistory
+            WHERE started_at >= ? AND started_at < ?
+            GROUP BY destination_path
+            "#,
+        )
+            .bind(range.start)
+            .bind(range.end)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let destination_path: String = row.get("destination_path");
+                let bytes_copied: i64 = row.get("bytes_copied");
+                (PathBuf::from(destination_path), bytes_copied as u64)
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,275 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::maintenance::database_status::MaintenanceRun;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::misc::MiscError;
+use chrono::NaiveDateTime;
+use macros::timed;
+use sqlx::{Row, SqliteConnection};
+use uuid::Uuid;
+
+pub trait MaintenanceRepository {
+    async fn create_maintenance_run_table(&self) -> Result<(), Error>;
+    async fn create_maintenance_run(&self, run: &MaintenanceRun) -> Result<(), Error>;
+    async fn get_last_maintenance_run(&self) -> Result<Option<MaintenanceRun>, Error>;
+    async fn delete_execution_history_before(
+        &self,
+        cutoff: NaiveDateTime,
+        exempt_schedules: &[Uuid],
+    ) -> Result<usize, Error>;
+    async fn delete_schedule_history(
+        &self,
+        schedule_uuid: Uuid,
+        cutoff: Option<NaiveDateTime>,
+        keep_runs: Option<usize>,
+    ) -> Result<usize, Error>;
+    async fn delete_schedule_history_details(
+        &self,
+        schedule_uuid: Uuid,
+        cutoff: NaiveDateTime,
+    ) -> Result<(), Error>;
+    async fn get_database_size(&self) -> Result<u64, Error>;
+    async fn vacuum(&self) -> Result<(), Error>;
+    async fn analyze(&self) -> Result<(), Error>;
+    async fn integrity_check(&self) -> Result<Vec<String>, Error>;
+}
+
+impl MaintenanceRepository for DatabaseManager {
+    #[timed]
+    async fn create_maintenance_run_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE MaintenanceRuns (
+                finished_at TEXT PRIMARY KEY,
+                pruned_runs INTEGER NOT NULL,
+                size_before INTEGER NOT NULL,
+                size_after INTEGER NOT NULL,
+                integrity_problems TEXT NOT NULL
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn create_maintenance_run(&self, run: &MaintenanceRun) -> Result<(), Error> {
+        let integrity_problems =
+            serde_json::to_string(&run.integrity_problems).map_err(MiscError::SerializeError)?;
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO MaintenanceRuns (
+                finished_at,
+                pruned_runs,
+                size_before,
+                size_after,
+                integrity_problems
+            )
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+            .bind(run.finished_at)
+            .bind(run.pruned_runs as i64)
+            .bind(run.size_before as i64)
+            .bind(run.size_after as i64)
+            .bind(integrity_problems)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_last_maintenance_run(&self) -> Result<Option<MaintenanceRun>, Error> {
+        let pool = self.get_pool();
+        let row = sqlx::query(
+            r#"
+            SELECT
+                finished_at,
+                pruned_runs,
+                size_before,
+                size_after,
+                integrity_problems
+            FROM MaintenanceRuns
+            ORDER BY finished_at DESC
+            LIMIT 1
+            "#,
+        )
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let integrity_problems_str: String = row.get("integrity_problems");
+        let integrity_problems = serde_json::from_str(&integrity_problems_str)
+            .map_err(MiscError::DeserializeError)?;
+        Ok(Some(MaintenanceRun {
+            finished_at: row.get("finished_at"),
+            pruned_runs: row.get::<i64, _>("pruned_runs") as usize,
+            size_before: row.get::<i64, _>("size_before") as u64,
+            size_after: row.get::<i64, _>("size_after") as u64,
+            integrity_problems,
+        }))
+    }
+
+    // Schedules with a day limit of their own are left to delete_schedule_history
+    #[timed]
+    async fn delete_execution_history_before(
+        &self,
+        cutoff: NaiveDateTime,
+        exempt_schedules: &[Uuid],
+    ) -> Result<usize, Error> {
+        self.transaction(async |connection| {
+            let rows = sqlx::query(
+                "SELECT uuid, schedule_uuid FROM ExecutionHistory WHERE finished_at < ?",
+            )
+                .bind(cutoff)
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            let history_uuids: Vec<Vec<u8>> = rows
+                .iter()
+                .filter(|row| {
+                    let schedule_uuid: Option<Vec<u8>> = row.get("schedule_uuid");
+                    schedule_uuid.is_none_or(|uuid| {
+                        !exempt_schedules.iter().any(|exempt| exempt.as_bytes() == &uuid[..])
+                    })
+                })
+                .map(|row| row.get("uuid"))
+                .collect();
+            delete_history_entries(connection, &history_uuids, false).await?;
+            Ok(history_uuids.len())
+        })
+        .await
+    }
+
+    // Drops the runs finished before the cutoff and all but the newest keep_runs
+    #[timed]
+    async fn delete_schedule_history(
+        &self,
+        schedule_uuid: Uuid,
+        cutoff: Option<NaiveDateTime>,
+        keep_runs: Option<usize>,
+    ) -> Result<usize, Error> {
+        let schedule_uuid = schedule_uuid.as_bytes().to_vec();
+        // A negative limit has SQLite keep every run
+        let keep_runs = keep_runs.map_or(-1, |keep_runs| keep_runs as i64);
+        self.transaction(async |connection| {
+            let history_uuids: Vec<Vec<u8>> = sqlx::query_scalar(
+                r#"
+                SELECT uuid FROM ExecutionHistory
+                WHERE schedule_uuid = ? AND (finished_at < ? OR uuid NOT IN (
+                    SELECT uuid FROM ExecutionHistory
+                    WHERE schedule_uuid = ?
+                    ORDER BY started_at DESC
+                    LIMIT ?
+                ))
+                "#,
+            )
+                .bind(&schedule_uuid)
+                .bind(cutoff)
+                .bind(&schedule_uuid)
+                .bind(keep_runs)
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            delete_history_entries(connection, &history_uuids, false).await?;
+            Ok(history_uuids.len())
+        })
+        .await
+    }
+
+    #[timed]
+    async fn delete_schedule_history_details(
+        &self,
+        schedule_uuid: Uuid,
+        cutoff: NaiveDateTime,
+    ) -> Result<(), Error> {
+        self.transaction(async |connection| {
+            let history_uuids: Vec<Vec<u8>> = sqlx::query_scalar(
+                "SELECT uuid FROM ExecutionHistory WHERE schedule_uuid = ? AND finished_at < ?",
+            )
+                .bind(schedule_uuid.as_bytes().to_vec())
+                .bind(cutoff)
+                .fetch_all(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            delete_history_entries(connection, &history_uuids, true).await
+        })
+        .await
+    }
+
+    #[timed]
+    async fn get_database_size(&self) -> Result<u64, Error> {
+        let pool = self.get_pool();
+        let size: i64 = sqlx::query_scalar(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        )
+            .fetch_one(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(size as u64)
+    }
+
+    #[timed]
+    async fn vacuum(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("VACUUM")
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn analyze(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("ANALYZE")
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // A healthy database reports a single "ok" row
+    #[timed]
+    async fn integrity_check(&self) -> Result<Vec<String>, Error> {
+        let pool = self.get_pool();
+        let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(rows.into_iter().filter(|row| row != "ok").collect())
+    }
+}
+
+// The errors and catalogs of a run go with it, details_only keeps the run itself
+async fn delete_history_entries(
+    connection: &mut SqliteConnection,
+    history_uuids: &[Vec<u8>],
+    details_only: bool,
+) -> Result<(), Error> {
+    for history_uuid in history_uuids {
+        for table in ["ExecutionErrors", "ExecutionCatalogs"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE history_uuid = ?"))
+                .bind(history_uuid)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+        }
+        if !details_only {
+            sqlx::query("DELETE FROM ExecutionHistory WHERE uuid = ?")
+                .bind(history_uuid)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+        }
+    }
+    Ok(())
+}
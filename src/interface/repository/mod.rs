@@ -1 +1,11 @@
-pub mod schedule;
\ No newline at end of file
+pub mod benchmark;
+pub mod comparison_cache;
+pub mod directory_signature;
+pub mod event_journal;
+pub mod execution_history;
+pub mod maintenance;
+pub mod renamed_entry;
+pub mod schedule;
+pub mod schedule_version;
+pub mod schema_version;
+pub mod storage_usage;
@@ -0,0 +1,101 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use macros::timed;
+use std::path::Path;
+
+// Source names the destination could not store, keyed by the sanitized destination path
+pub trait RenamedEntryRepository {
+    async fn create_renamed_entry_table(&self) -> Result<(), Error>;
+    async fn get_renamed_entry(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+    ) -> Result<Option<String>, Error>;
+    async fn save_renamed_entry(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+        original_name: &str,
+    ) -> Result<(), Error>;
+}
+
+impl RenamedEntryRepository for DatabaseManager {
+    #[timed]
+    async fn create_renamed_entry_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE RenamedEntries (
+                source_root TEXT NOT NULL,
+                destination_root TEXT NOT NULL,
+                path TEXT NOT NULL,
+                original_name TEXT NOT NULL,
+                PRIMARY KEY (source_root, destination_root, path)
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn get_renamed_entry(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+    ) -> Result<Option<String>, Error> {
+        let pool = self.get_pool();
+        let original_name = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT original_name
+            FROM RenamedEntries
+            WHERE source_root = ? AND destination_root = ? AND path = ?
+            "#,
+        )
+            .bind(source_root.to_string_lossy().to_string())
+            .bind(destination_root.to_string_lossy().to_string())
+            .bind(path.to_string_lossy().to_string())
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(original_name)
+    }
+
+    #[timed]
+    async fn save_renamed_entry(
+        &self,
+        source_root: &Path,
+        destination_root: &Path,
+        path: &Path,
+        original_name: &str,
+    ) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO RenamedEntries (
+                source_root,
+                destination_root,
+                path,
+                original_name
+            )
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (source_root, destination_root, path) DO UPDATE SET
+                original_name = excluded.original_name
+            "#,
+        )
+            .bind(source_root.to_string_lossy().to_string())
+            .bind(destination_root.to_string_lossy().to_string())
+            .bind(path.to_string_lossy().to_string())
+            .bind(original_name)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+}
@@ -1,21 +1,24 @@
 use crate::core::infrastructure::database_manager::DatabaseManager;
-use crate::model::core::schedule::schedule::Schedule;
+use crate::model::core::schedule::schedule::{HistoryRetention, Schedule};
 use crate::model::error::Error;
 use crate::model::error::database::DatabaseError;
 use crate::model::error::misc::MiscError;
-use sqlx::Row;
+use macros::timed;
+use sqlx::query::Query;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Row, Sqlite};
 use uuid::Uuid;
 
 pub trait ScheduleRepository {
     async fn create_backup_schedule_table(&self) -> Result<(), Error>;
     async fn create_backup_schedule(&self, backup_schedule: &Schedule) -> Result<(), Error>;
-    async fn modify_backup_schedule(&self, backup_schedule: &Schedule) -> Result<(), Error>;
+    async fn modify_backup_schedules(&self, backup_schedules: &[Schedule]) -> Result<(), Error>;
     async fn remove_backup_schedule(&self, uuid: Uuid) -> Result<(), Error>;
-    async fn get_backup_schedule(&self, uuid: Uuid) -> Result<Option<Schedule>, Error>;
     async fn get_all_backup_schedules(&self) -> Result<Vec<Schedule>, Error>;
 }
 
 impl ScheduleRepository for DatabaseManager {
+    #[timed]
     async fn create_backup_schedule_table(&self) -> Result<(), Error> {
         let pool = self.get_pool();
         sqlx::query(
@@ -43,6 +46,7 @@ impl ScheduleRepository for DatabaseManager {
         Ok(())
     }
 
+    #[timed]
     async fn create_backup_schedule(&self, backup_schedule: &Schedule) -> Result<(), Error> {
         let pool = self.get_pool();
         sqlx::query(
@@ -59,10 +63,12 @@ impl ScheduleRepository for DatabaseManager {
                 interval,
                 last_run_time,
                 next_run_time,
+                run_time,
+                retention,
                 created_at,
                 updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
             .bind(backup_schedule.uuid.as_bytes().as_slice())
@@ -91,61 +97,11 @@ impl ScheduleRepository for DatabaseManager {
             )
             .bind(backup_schedule.last_run_time)
             .bind(backup_schedule.next_run_time)
-            .bind(backup_schedule.created_at)
-            .bind(backup_schedule.updated_at)
-            .execute(&pool)
-            .await
-            .map_err(DatabaseError::StatementExecutionFailed)?;
-        Ok(())
-    }
-
-    async fn modify_backup_schedule(&self, backup_schedule: &Schedule) -> Result<(), Error> {
-        let pool = self.get_pool();
-        sqlx::query(
-            r#"
-            UPDATE BackupSchedules
-            SET
-                name = ?,
-                state = ?,
-                source_path = ?,
-                destination_path = ?,
-                backup_type = ?,
-                comparison_mode = ?,
-                options = ?,
-                interval = ?,
-                last_run_time = ?,
-                next_run_time = ?,
-                created_at = ?,
-                updated_at  = ?
-            WHERE uuid = ?
-            "#,
-        )
-            .bind(backup_schedule.uuid.as_bytes().as_slice())
-            .bind(&backup_schedule.name)
-            .bind(
-                serde_json::to_string(&backup_schedule.state)
-                    .map_err(MiscError::SerializeError)?,
-            )
-            .bind(backup_schedule.source_path.to_string_lossy().to_string())
-            .bind(backup_schedule.destination_path.to_string_lossy().to_string())
+            .bind(backup_schedule.run_time)
             .bind(
-                serde_json::to_string(&backup_schedule.backup_type)
+                serde_json::to_string(&backup_schedule.retention)
                     .map_err(MiscError::SerializeError)?,
             )
-            .bind(
-                serde_json::to_string(&backup_schedule.comparison_mode)
-                    .map_err(MiscError::SerializeError)?,
-            )
-            .bind(
-                serde_json::to_string(&backup_schedule.options)
-                    .map_err(MiscError::SerializeError)?,
-            )
-            .bind(
-                serde_json::to_string(&backup_schedule.interval)
-                    .map_err(MiscError::SerializeError)?,
-            )
-            .bind(backup_schedule.last_run_time)
-            .bind(backup_schedule.next_run_time)
             .bind(backup_schedule.created_at)
             .bind(backup_schedule.updated_at)
             .execute(&pool)
@@ -154,6 +110,21 @@ impl ScheduleRepository for DatabaseManager {
         Ok(())
     }
 
+    #[timed]
+    async fn modify_backup_schedules(&self, backup_schedules: &[Schedule]) -> Result<(), Error> {
+        self.transaction(async |connection| {
+            for backup_schedule in backup_schedules {
+                update_schedule_query(backup_schedule)?
+                    .execute(&mut *connection)
+                    .await
+                    .map_err(DatabaseError::StatementExecutionFailed)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    #[timed]
     async fn remove_backup_schedule(&self, uuid: Uuid) -> Result<(), Error> {
         let pool = self.get_pool();
         sqlx::query("DELETE FROM BackupSchedules WHERE uuid = ?")
@@ -164,77 +135,7 @@ impl ScheduleRepository for DatabaseManager {
         Ok(())
     }
 
-    async fn get_backup_schedule(&self, uuid: Uuid) -> Result<Option<Schedule>, Error> {
-        let pool = self.get_pool();
-        let row = sqlx::query(
-            r#"
-            SELECT
-                uuid,
-                name,
-                state,
-                source_path,
-                destination_path,
-                backup_type,
-                comparison_mode,
-                options,
-                "interval",
-                last_run_time,
-                next_run_time,
-                created_at,
-                updated_at
-            FROM BackupSchedules
-            WHERE uuid = ?
-            "#,
-        )
-            .bind(uuid.as_bytes().as_slice())
-            .fetch_optional(&pool)
-            .await
-            .map_err(DatabaseError::StatementExecutionFailed)?;
-
-        if let Some(row) = row {
-            let uuid_bytes: Vec<u8> = row.get("uuid");
-            let uuid = Uuid::from_slice(&uuid_bytes).map_err(|_| DatabaseError::DataCorrupted)?;
-
-            let state_str: String = row.get("state");
-            let state = serde_json::from_str(&state_str)
-                .map_err(MiscError::DeserializeError)?;
-
-            let backup_type_str: String = row.get("backup_type");
-            let backup_type = serde_json::from_str(&backup_type_str)
-                .map_err(MiscError::DeserializeError)?;
-
-            let comparison_mode_str: String = row.get("comparison_mode");
-            let comparison_mode = serde_json::from_str(&comparison_mode_str)
-                .map_err(MiscError::DeserializeError)?;
-
-            let options_str: String = row.get("options");
-            let options = serde_json::from_str(&options_str)
-                .map_err(MiscError::DeserializeError)?;
-
-            let interval_str: String = row.get("interval");
-            let interval = serde_json::from_str(&interval_str)
-                .map_err(MiscError::DeserializeError)?;
-
-            Ok(Some(Schedule {
-                uuid,
-                name: row.get("name"),
-                state,
-                source_path: row.get::<String, _>("source_path").into(),
-                destination_path: row.get::<String, _>("destination_path").into(),
-                backup_type,
-                comparison_mode,
-                options,
-                interval,
-                last_run_time: row.get("last_run_time"),
-                next_run_time: row.get("next_run_time"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            }))
-        } else {
-            Ok(None)
-        }
-    }
-
+    #[timed]
     async fn get_all_backup_schedules(&self) -> Result<Vec<Schedule>, Error> {
         let pool = self.get_pool();
         let rows = sqlx::query(
@@ -251,6 +152,8 @@ impl ScheduleRepository for DatabaseManager {
                 "interval",
                 last_run_time,
                 next_run_time,
+                run_time,
+                retention,
                 created_at,
                 updated_at
             FROM BackupSchedules
@@ -285,6 +188,13 @@ impl ScheduleRepository for DatabaseManager {
             let interval = serde_json::from_str(&interval_str)
                 .map_err(MiscError::DeserializeError)?;
 
+            // Schedules from before the column was added have none
+            let retention = match row.get::<Option<String>, _>("retention") {
+                Some(retention_str) => serde_json::from_str(&retention_str)
+                    .map_err(MiscError::DeserializeError)?,
+                None => HistoryRetention::default(),
+            };
+
             schedules.push(Schedule {
                 uuid,
                 name: row.get("name"),
@@ -297,6 +207,8 @@ impl ScheduleRepository for DatabaseManager {
                 interval,
                 last_run_time: row.get("last_run_time"),
                 next_run_time: row.get("next_run_time"),
+                run_time: row.get("run_time"),
+                retention,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
             });
@@ -305,3 +217,63 @@ impl ScheduleRepository for DatabaseManager {
         Ok(schedules)
     }
 }
+
+fn update_schedule_query(
+    backup_schedule: &Schedule,
+) -> Result<Query<'_, Sqlite, SqliteArguments<'_>>, Error> {
+    let query = sqlx::query(
+        r#"
+        UPDATE BackupSchedules
+        SET
+            name = ?,
+            state = ?,
+            source_path = ?,
+            destination_path = ?,
+            backup_type = ?,
+            comparison_mode = ?,
+            options = ?,
+            interval = ?,
+            last_run_time = ?,
+            next_run_time = ?,
+            run_time = ?,
+            retention = ?,
+            created_at = ?,
+            updated_at  = ?
+        WHERE uuid = ?
+        "#,
+    )
+        .bind(&backup_schedule.name)
+        .bind(
+            serde_json::to_string(&backup_schedule.state)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(backup_schedule.source_path.to_string_lossy().to_string())
+        .bind(backup_schedule.destination_path.to_string_lossy().to_string())
+        .bind(
+            serde_json::to_string(&backup_schedule.backup_type)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(
+            serde_json::to_string(&backup_schedule.comparison_mode)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(
+            serde_json::to_string(&backup_schedule.options)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(
+            serde_json::to_string(&backup_schedule.interval)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(backup_schedule.last_run_time)
+        .bind(backup_schedule.next_run_time)
+        .bind(backup_schedule.run_time)
+        .bind(
+            serde_json::to_string(&backup_schedule.retention)
+                .map_err(MiscError::SerializeError)?,
+        )
+        .bind(backup_schedule.created_at)
+        .bind(backup_schedule.updated_at)
+        .bind(backup_schedule.uuid.as_bytes().as_slice());
+    Ok(query)
+}
@@ -0,0 +1,162 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::schedule::schedule::Schedule;
+use crate::model::core::schedule::schedule_version::{MAX_SCHEDULE_VERSIONS, ScheduleVersion};
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::misc::MiscError;
+use chrono::NaiveDateTime;
+use macros::timed;
+use sqlx::Row;
+use sqlx::sqlite::SqliteRow;
+use uuid::Uuid;
+
+pub trait ScheduleVersionRepository {
+    async fn create_schedule_version_table(&self) -> Result<(), Error>;
+    async fn create_schedule_version(
+        &self,
+        schedule: &Schedule,
+        saved_at: NaiveDateTime,
+    ) -> Result<(), Error>;
+    async fn get_schedule_versions(
+        &self,
+        schedule_uuid: Uuid,
+    ) -> Result<Vec<ScheduleVersion>, Error>;
+    async fn get_schedule_version(
+        &self,
+        schedule_uuid: Uuid,
+        version: u32,
+    ) -> Result<Option<ScheduleVersion>, Error>;
+    async fn remove_schedule_versions(&self, schedule_uuid: Uuid) -> Result<(), Error>;
+}
+
+impl ScheduleVersionRepository for DatabaseManager {
+    #[timed]
+    async fn create_schedule_version_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE ScheduleVersions (
+                schedule_uuid BLOB NOT NULL,
+                version INTEGER NOT NULL,
+                saved_at TEXT NOT NULL,
+                schedule TEXT NOT NULL,
+                PRIMARY KEY (schedule_uuid, version)
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // Numbered after the newest version, only the last MAX_SCHEDULE_VERSIONS are kept
+    #[timed]
+    async fn create_schedule_version(
+        &self,
+        schedule: &Schedule,
+        saved_at: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let schedule_uuid = schedule.uuid.as_bytes().to_vec();
+        let serialized = serde_json::to_string(schedule).map_err(MiscError::SerializeError)?;
+        self.transaction(async |connection| {
+            let version: i64 = sqlx::query_scalar(
+                r#"
+                SELECT COALESCE(MAX(version), 0) + 1
+                FROM ScheduleVersions
+                WHERE schedule_uuid = ?
+                "#,
+            )
+                .bind(&schedule_uuid)
+                .fetch_one(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            sqlx::query(
+                r#"
+                INSERT INTO ScheduleVersions (schedule_uuid, version, saved_at, schedule)
+                VALUES (?, ?, ?, ?)
+                "#,
+            )
+                .bind(&schedule_uuid)
+                .bind(version)
+                .bind(saved_at)
+                .bind(&serialized)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            sqlx::query("DELETE FROM ScheduleVersions WHERE schedule_uuid = ? AND version <= ?")
+                .bind(&schedule_uuid)
+                .bind(version - MAX_SCHEDULE_VERSIONS as i64)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            Ok(())
+        })
+        .await
+    }
+
+    // Newest first
+    #[timed]
+    async fn get_schedule_versions(
+        &self,
+        schedule_uuid: Uuid,
+    ) -> Result<Vec<ScheduleVersion>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT version, saved_at, schedule
+            FROM ScheduleVersions
+            WHERE schedule_uuid = ?
+            ORDER BY version DESC
+            "#,
+        )
+            .bind(schedule_uuid.as_bytes().as_slice())
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        rows.iter().map(schedule_version_from_row).collect()
+    }
+
+    #[timed]
+    async fn get_schedule_version(
+        &self,
+        schedule_uuid: Uuid,
+        version: u32,
+    ) -> Result<Option<ScheduleVersion>, Error> {
+        let pool = self.get_pool();
+        let row = sqlx::query(
+            r#"
+            SELECT version, saved_at, schedule
+            FROM ScheduleVersions
+            WHERE schedule_uuid = ? AND version = ?
+            "#,
+        )
+            .bind(schedule_uuid.as_bytes().as_slice())
+            .bind(version as i64)
+            .fetch_optional(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        row.as_ref().map(schedule_version_from_row).transpose()
+    }
+
+    #[timed]
+    async fn remove_schedule_versions(&self, schedule_uuid: Uuid) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("DELETE FROM ScheduleVersions WHERE schedule_uuid = ?")
+            .bind(schedule_uuid.as_bytes().as_slice())
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+}
+
+fn schedule_version_from_row(row: &SqliteRow) -> Result<ScheduleVersion, Error> {
+    let schedule_str: String = row.get("schedule");
+    let schedule = serde_json::from_str(&schedule_str).map_err(MiscError::DeserializeError)?;
+    Ok(ScheduleVersion {
+        version: row.get::<i64, _>("version") as u32,
+        saved_at: row.get("saved_at"),
+        schedule,
+    })
+}
@@ -0,0 +1,144 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use crate::model::error::io::IOError;
+use macros::timed;
+use tokio::fs;
+
+// Schema the create table statements of the repositories produce, they stay at it for good
+pub const BASELINE_VERSION: i64 = 1;
+
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub script: &'static str,
+}
+
+// Ordered by version, a schema change is a new entry here and never an edit of an older one
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 2,
+        description: "Keep the local run time of schedules",
+        script: "ALTER TABLE BackupSchedules ADD COLUMN run_time TEXT",
+    },
+    Migration {
+        version: 3,
+        description: "Keep the history retention of schedules",
+        script: "ALTER TABLE BackupSchedules ADD COLUMN retention TEXT",
+    },
+    Migration {
+        version: 4,
+        description: "Keep the probed filesystem capabilities of runs",
+        script: "ALTER TABLE ExecutionHistory ADD COLUMN capabilities TEXT",
+    },
+    Migration {
+        version: 5,
+        description: "Keep a hash of the entries in directory signatures",
+        script: "ALTER TABLE DirectorySignatures ADD COLUMN entries_hash BLOB",
+    },
+];
+
+pub trait SchemaVersionRepository {
+    async fn create_schema_version_table(&self, version: i64) -> Result<(), Error>;
+    async fn get_schema_version(&self) -> Result<i64, Error>;
+    async fn apply_migration(&self, migration: &Migration) -> Result<(), Error>;
+    async fn backup_database(&self, path: &str) -> Result<(), Error>;
+    async fn export_database(&self, path: &str) -> Result<(), Error>;
+}
+
+impl SchemaVersionRepository for DatabaseManager {
+    #[timed]
+    async fn create_schema_version_table(&self, version: i64) -> Result<(), Error> {
+        self.transaction(async |connection| {
+            sqlx::query(
+                r#"
+                CREATE TABLE SchemaVersion (
+                    version INTEGER NOT NULL
+                )
+                "#,
+            )
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            sqlx::query("INSERT INTO SchemaVersion (version) VALUES (?)")
+                .bind(version)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            Ok(())
+        })
+        .await
+    }
+
+    #[timed]
+    async fn get_schema_version(&self) -> Result<i64, Error> {
+        let pool = self.get_pool();
+        let version = sqlx::query_scalar::<_, i64>("SELECT version FROM SchemaVersion")
+            .fetch_one(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(version)
+    }
+
+    // The script and the version bump commit together, a failed migration leaves no trace
+    #[timed]
+    async fn apply_migration(&self, migration: &Migration) -> Result<(), Error> {
+        self.transaction(async |connection| {
+            sqlx::raw_sql(migration.script)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            sqlx::query("UPDATE SchemaVersion SET version = ?")
+                .bind(migration.version)
+                .execute(&mut *connection)
+                .await
+                .map_err(DatabaseError::StatementExecutionFailed)?;
+            Ok(())
+        })
+        .await
+    }
+
+    // A consistent copy even in WAL mode, unlike copying the database file
+    #[timed]
+    async fn backup_database(&self, path: &str) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("VACUUM INTO ?")
+            .bind(path)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // A copy that opens without the key, VACUUM INTO would keep the encryption. The attached
+    // database is opened without create, an empty file is already a valid one
+    #[timed]
+    async fn export_database(&self, path: &str) -> Result<(), Error> {
+        if !self.is_encrypted() {
+            return self.backup_database(path).await;
+        }
+        fs::write(path, [])
+            .await
+            .map_err(|err| IOError::WriteFileFailed(path, err))?;
+        let pool = self.get_pool();
+        let mut connection = pool
+            .acquire()
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        sqlx::query("ATTACH DATABASE ? AS export KEY ''")
+            .bind(path)
+            .execute(&mut *connection)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        let exported = sqlx::query("SELECT sqlcipher_export('export')")
+            .execute(&mut *connection)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed);
+        sqlx::query("DETACH DATABASE export")
+            .execute(&mut *connection)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        exported?;
+        Ok(())
+    }
+}
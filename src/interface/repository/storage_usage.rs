@@ -0,0 +1,100 @@
+use crate::core::infrastructure::database_manager::DatabaseManager;
+use crate::model::core::backup::storage_usage::StorageSample;
+use crate::model::error::Error;
+use crate::model::error::database::DatabaseError;
+use chrono::NaiveDateTime;
+use macros::timed;
+use sqlx::Row;
+
+pub trait StorageUsageRepository {
+    async fn create_storage_sample_table(&self) -> Result<(), Error>;
+    async fn create_storage_sample(&self, sample: &StorageSample) -> Result<(), Error>;
+    async fn get_storage_samples_since(
+        &self,
+        since: NaiveDateTime,
+    ) -> Result<Vec<StorageSample>, Error>;
+    async fn delete_storage_samples_before(&self, cutoff: NaiveDateTime) -> Result<(), Error>;
+}
+
+impl StorageUsageRepository for DatabaseManager {
+    #[timed]
+    async fn create_storage_sample_table(&self) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            CREATE TABLE StorageSamples (
+                destination_path TEXT NOT NULL,
+                recorded_at TEXT NOT NULL,
+                footprint INTEGER NOT NULL,
+                available INTEGER
+            )
+            "#,
+        )
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn create_storage_sample(&self, sample: &StorageSample) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query(
+            r#"
+            INSERT INTO StorageSamples (destination_path, recorded_at, footprint, available)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+            .bind(sample.destination_path.to_string_lossy().to_string())
+            .bind(sample.recorded_at)
+            .bind(sample.footprint as i64)
+            .bind(sample.available.map(|available| available as i64))
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+
+    // Oldest first
+    #[timed]
+    async fn get_storage_samples_since(
+        &self,
+        since: NaiveDateTime,
+    ) -> Result<Vec<StorageSample>, Error> {
+        let pool = self.get_pool();
+        let rows = sqlx::query(
+            r#"
+            SELECT destination_path, recorded_at, footprint, available
+            FROM StorageSamples
+            WHERE recorded_at >= ?
+            ORDER BY recorded_at
+            "#,
+        )
+            .bind(since)
+            .fetch_all(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(rows
+            .iter()
+            .map(|row| StorageSample {
+                destination_path: row.get::<String, _>("destination_path").into(),
+                recorded_at: row.get("recorded_at"),
+                footprint: row.get::<i64, _>("footprint") as u64,
+                available: row
+                    .get::<Option<i64>, _>("available")
+                    .map(|available| available as u64),
+            })
+            .collect())
+    }
+
+    #[timed]
+    async fn delete_storage_samples_before(&self, cutoff: NaiveDateTime) -> Result<(), Error> {
+        let pool = self.get_pool();
+        sqlx::query("DELETE FROM StorageSamples WHERE recorded_at < ?")
+            .bind(cutoff)
+            .execute(&pool)
+            .await
+            .map_err(DatabaseError::StatementExecutionFailed)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use crate::core::gui::gui_manager::GuiManager;
+use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::system::System;
 
 mod core;
@@ -11,7 +13,11 @@ mod utils;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let system = System::new().await?;
+    let app_config = match AppConfig::new() {
+        Ok(app_config) => app_config,
+        Err(issues) => return Ok(GuiManager::show_config_issues(issues)?),
+    };
+    let system = System::new(app_config).await?;
     system.run().await?;
     system.shutdown().await;
     Ok(())
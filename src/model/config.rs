@@ -1,4 +1,18 @@
+use crate::model::core::backup::execution_history::StoredErrorPolicy;
+use crate::model::core::infrastructure::event_broadcaster::OverflowPolicy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const DEFAULT_MIN_CONCURRENCY: u8 = 2;
+
+// One rejected key, with the values it would accept, shown before anything else starts
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub key: String,
+    pub problem: String,
+    pub accepted: String,
+}
 
 #[derive(Debug, Deserialize)]
 pub struct ConfigTable {
@@ -6,12 +20,142 @@ pub struct ConfigTable {
     pub config: Config,
 }
 
+// Keys missing from an older config.toml take the values of the shipped one
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct Config {
     pub internal_timestamp: i64,    // mini second
     pub ui_refresh_time: i64,       // second
+    pub progress_events_per_second: u32, // number
     pub default_wakeup_time: i64,   // second
+    pub min_concurrency: Option<u8>, // number, absent takes 2 or max_concurrency if lower
     pub max_concurrency: u8,        // number
-    pub max_file_operations: usize, // number
+    #[serde(alias = "max_file_operations")]
+    pub max_metadata_operations: usize, // number
+    pub max_data_operations: usize, // number
     pub channel_capacity: usize,
+    pub query_timeout: u64,         // second, periodic UI refreshes only, 0 waits indefinitely
+    pub event_overflow: HashMap<String, OverflowPolicy>, // event type name
+    pub event_replay: HashMap<String, usize>, // event type name
+    pub checkpoint_interval: i64,   // second
+    pub checkpoint_files: usize,    // number
+    pub shutdown_grace_period: u64, // second
+    pub max_errors: usize,          // number
+    pub max_error_rate: f64,        // fraction of processed entries
+    pub max_stored_errors: usize,   // number per run
+    pub stored_error_policy: StoredErrorPolicy,
+    pub history_retention_days: i64, // day, 0 keeps every run
+    pub database_encryption: bool,  // needs the sqlcipher build feature
+    pub maintenance_interval: i64,  // hour
+    pub storage_warning_days: u32,  // day, 0 disables the warning
+    pub database_path: PathBuf,     // empty uses the platform data directory
+    pub progress_path: PathBuf,     // empty uses the platform data directory
+    pub journal_path: PathBuf,      // empty uses the platform data directory
+    pub max_queued_directories: usize, // number
+    pub pre_scan: bool,
+    pub io_uring: bool,             // linux only
+    pub selinux_contexts: bool,     // linux only
+    pub reflink: bool,
+    pub copy_buffer_size: usize,    // byte
+    pub unbuffered_copy_threshold: u64, // byte
+    pub small_file_threshold: u64, // byte
+    pub mapped_compare_threshold: u64, // byte
+    pub mtime_tolerance: u64,       // millisecond
+    pub mirror_max_delete_percentage: usize, // percent of destination root entries
+    pub share_reconnect_attempts: u32, // number, 0 suspends the run right away
+    pub prevent_sleep: bool,
+    pub allow_display_sleep: bool,
+    pub sanitize_names: bool,
+    pub name_replacements: HashMap<String, String>, // reserved character to replacement
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            internal_timestamp: 20,
+            ui_refresh_time: 2,
+            progress_events_per_second: 4,
+            default_wakeup_time: 10,
+            min_concurrency: None,
+            max_concurrency: 16,
+            max_metadata_operations: 100,
+            max_data_operations: 16,
+            channel_capacity: 100,
+            query_timeout: 30,
+            event_overflow: HashMap::new(),
+            event_replay: HashMap::new(),
+            checkpoint_interval: 10,
+            checkpoint_files: 10000,
+            shutdown_grace_period: 30,
+            max_errors: 10000,
+            max_error_rate: 0.5,
+            max_stored_errors: 1000,
+            stored_error_policy: StoredErrorPolicy::KeepFirst,
+            history_retention_days: 365,
+            database_encryption: false,
+            maintenance_interval: 24,
+            storage_warning_days: 30,
+            database_path: PathBuf::new(),
+            progress_path: PathBuf::new(),
+            journal_path: PathBuf::new(),
+            max_queued_directories: 100000,
+            pre_scan: true,
+            io_uring: false,
+            selinux_contexts: true,
+            reflink: true,
+            copy_buffer_size: 0,
+            unbuffered_copy_threshold: 0,
+            small_file_threshold: 65536,
+            mapped_compare_threshold: 1073741824,
+            mtime_tolerance: 2000,
+            mirror_max_delete_percentage: 50,
+            share_reconnect_attempts: 3,
+            prevent_sleep: true,
+            allow_display_sleep: true,
+            sanitize_names: true,
+            name_replacements: HashMap::from([(":".to_string(), "-".to_string())]),
+        }
+    }
+}
+
+impl Config {
+    // A config.toml from before min_concurrency may hold a lower max_concurrency
+    pub fn min_concurrency(&self) -> u8 {
+        self.min_concurrency
+            .unwrap_or(DEFAULT_MIN_CONCURRENCY.min(self.max_concurrency))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_before_the_split_limits_still_loads() {
+        let toml_string = "[Config]
+internal_timestamp = 20
+ui_refresh_time = 2
+default_wakeup_time = 10
+max_concurrency = 8
+max_file_operations = 50
+channel_capacity = 100
+";
+        let config = toml::from_str::<ConfigTable>(toml_string).unwrap().config;
+
+        assert_eq!(config.max_concurrency, 8);
+        assert_eq!(config.max_metadata_operations, 50);
+        assert_eq!(config.max_data_operations, 16);
+        assert_eq!(config.stored_error_policy, StoredErrorPolicy::KeepFirst);
+    }
+
+    #[test]
+    fn absent_min_concurrency_follows_a_lower_max_concurrency() {
+        let toml_string = "[Config]
+max_concurrency = 1
+";
+        let config = toml::from_str::<ConfigTable>(toml_string).unwrap().config;
+
+        assert_eq!(config.min_concurrency, None);
+        assert_eq!(config.min_concurrency(), 1);
+    }
 }
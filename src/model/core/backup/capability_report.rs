@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// What a volume reports it can store, read without writing to it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VolumeFeatures {
+    pub symlinks: bool,
+    pub hardlinks: bool,
+    pub xattrs: bool,
+    pub permissions: bool,
+    pub sparse: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointCapabilities {
+    pub features: VolumeFeatures,
+    // Smallest step between modification times the filesystem keeps
+    pub timestamp_resolution: Duration,
+}
+
+impl EndpointCapabilities {
+    pub fn resolution_label(&self) -> String {
+        let resolution = self.timestamp_resolution;
+        if resolution >= Duration::from_secs(1) {
+            format!("{}s", resolution.as_secs())
+        } else if resolution >= Duration::from_millis(1) {
+            format!("{}ms", resolution.as_millis())
+        } else if resolution >= Duration::from_micros(1) {
+            format!("{}µs", resolution.as_micros())
+        } else {
+            format!("{}ns", resolution.as_nanos())
+        }
+    }
+}
+
+// Both endpoints as probed at the start of a run, kept with its history entry
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapabilityReport {
+    pub source: EndpointCapabilities,
+    pub destination: EndpointCapabilities,
+}
+
+impl CapabilityReport {
+    // Feature name with its support on the source and on the destination
+    pub fn rows(&self) -> [(&'static str, bool, bool); 5] {
+        let source = self.source.features;
+        let destination = self.destination.features;
+        [
+            ("Symbolic Links", source.symlinks, destination.symlinks),
+            ("Hard Links", source.hardlinks, destination.hardlinks),
+            ("Extended Attributes", source.xattrs, destination.xattrs),
+            ("Permissions", source.permissions, destination.permissions),
+            ("Sparse Files", source.sparse, destination.sparse),
+        ]
+    }
+
+    pub fn missing_features(&self) -> Vec<&'static str> {
+        let mut missing: Vec<_> = self
+            .rows()
+            .into_iter()
+            .filter(|(_, source, destination)| *source && !destination)
+            .map(|(name, _, _)| name)
+            .collect();
+        if self.destination.timestamp_resolution > self.source.timestamp_resolution {
+            missing.push("Timestamp Precision");
+        }
+        missing
+    }
+
+    // The coarser of both, modification times closer than this cannot be told apart
+    pub fn timestamp_resolution(&self) -> Duration {
+        self.source
+            .timestamp_resolution
+            .max(self.destination.timestamp_resolution)
+    }
+}
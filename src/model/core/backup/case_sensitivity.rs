@@ -0,0 +1,6 @@
+// Case rules of both roots probed at the start of a run, directories may still override them
+#[derive(Debug, Clone, Copy)]
+pub struct CaseSensitivity {
+    pub source_insensitive: bool,
+    pub destination_insensitive: bool,
+}
@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Searches stop collecting files past this many
+pub const MAX_CATALOG_MATCHES: usize = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogAction {
+    Added,
+    Updated,
+    Deleted,
+}
+
+// Paths are relative to the destination root
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CatalogEntry {
+    pub action: CatalogAction,
+    pub path: PathBuf,
+}
+
+// A file found in the catalogs, with the newest run that touched it in one destination
+#[derive(Debug, Clone)]
+pub struct CatalogMatch {
+    pub destination_path: PathBuf,
+    pub path: PathBuf,
+    pub action: CatalogAction,
+    pub finished_at: NaiveDateTime,
+    // Runs that added or updated it
+    pub versions: usize,
+    // Whether the destination still holds it
+    pub present: bool,
+    // Backed up most recently among the destinations holding the same path
+    pub newest: bool,
+}
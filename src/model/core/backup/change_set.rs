@@ -0,0 +1,60 @@
+use dashmap::DashSet;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirectoryScope {
+    // Directory content changed, process every entry
+    Full,
+    // Directory leads to changed directories, only descend into them
+    Descend,
+    // Nothing changed below this directory
+    Skip,
+}
+
+#[derive(Debug)]
+pub struct ChangeSet {
+    directories: HashSet<PathBuf>,
+    ancestors: HashSet<PathBuf>,
+    forced: DashSet<PathBuf>,
+}
+
+impl ChangeSet {
+    pub fn new(root: &Path, directories: HashSet<PathBuf>, retry: Vec<PathBuf>) -> Self {
+        let mut ancestors = HashSet::new();
+        for directory in directories.iter().chain(retry.iter()) {
+            for ancestor in directory.ancestors().skip(1) {
+                if !ancestor.starts_with(root) || !ancestors.insert(ancestor.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+        Self {
+            directories,
+            ancestors,
+            forced: retry.into_iter().collect(),
+        }
+    }
+
+    pub fn scope(&self, directory: &Path) -> DirectoryScope {
+        if self.directories.contains(directory)
+            || directory
+                .ancestors()
+                .any(|ancestor| self.forced.contains(ancestor))
+        {
+            DirectoryScope::Full
+        } else if self.ancestors.contains(directory) {
+            DirectoryScope::Descend
+        } else {
+            DirectoryScope::Skip
+        }
+    }
+
+    pub fn force(&self, directory: &Path) {
+        self.forced.insert(directory.to_path_buf());
+    }
+
+    pub fn forced(&self) -> Vec<PathBuf> {
+        self.forced.iter().map(|directory| directory.clone()).collect()
+    }
+}
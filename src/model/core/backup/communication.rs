@@ -1,33 +1,68 @@
-use crate::interface::communication::command::Command;
-use crate::interface::communication::message::Message;
-use crate::interface::communication::query::Query;
-use crate::model::core::backup::execution::Execution;
+use crate::model::core::backup::catalog::{CatalogEntry, CatalogMatch};
+use crate::model::core::backup::execution::{Execution, WorkerLimit};
+use crate::model::core::backup::execution_history::ExecutionHistory;
+use crate::model::core::backup::history_statistics::{DailyBytesCopied, ScheduleErrorCount};
+use crate::model::core::backup::storage_usage::DestinationUsage;
+use crate::model::core::infrastructure::event_journal::JournalEntry;
+use crate::model::error::record::ErrorRecord;
+use chrono::NaiveDateTime;
+use macros::ServiceMessage;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
 use uuid::Uuid;
 
+#[derive(ServiceMessage)]
+#[message(command)]
 pub enum BackupCommand {
     AddExecution(Execution),
     RemoveExecution(Uuid),
     StartExecution(Uuid),
     SuspendExecution(Uuid),
     ResumeExecution(Uuid),
+    // Whether destinations of copies cut off by the cancel are deleted
+    CancelExecution(Uuid, bool),
+    // Re-hashes the destination against the manifest of the last completed run
+    VerifyExecution(Uuid),
+    SetWorkerLimit(Uuid, WorkerLimit),
+    // Writes an HTML report of a history entry to the given file
+    ExportReport(ExecutionHistory, PathBuf),
+    // Copies a file found by a catalog search out of its destination into the given folder
+    RestoreFile(CatalogMatch, PathBuf),
+    // Stops watching sources that no schedule or unscheduled execution backs up anymore
+    ReleaseChangeJournals,
 }
 
-impl Message for BackupCommand {
-    type Response = ();
-}
-
-impl Command for BackupCommand {}
-
+#[derive(ServiceMessage)]
+#[message(query = BackupQueryResponse)]
+#[allow(clippy::enum_variant_names)]
 pub enum BackupQuery {
+    #[response(Vec<(Uuid, Execution)>)]
     GetExecutions,
-}
-
-impl Message for BackupQuery {
-    type Response = BackupQueryResponse;
-}
-
-impl Query for BackupQuery {}
-
-pub enum BackupQueryResponse {
-    GetExecutions(Vec<(Uuid, Execution)>),
+    // Past runs, newest first
+    #[response(Vec<ExecutionHistory>)]
+    GetHistory,
+    // Errors stored for one history entry
+    #[response(Vec<ErrorRecord>)]
+    GetHistoryErrors(Uuid),
+    // Files a history entry added, updated or deleted, empty when it kept no catalog
+    #[response(Vec<CatalogEntry>)]
+    GetCatalog(Uuid),
+    // Trend data over the runs started within the range
+    #[response(Vec<DailyBytesCopied>)]
+    GetBytesCopiedPerDay(Range<NaiveDateTime>),
+    #[response(Vec<ScheduleErrorCount>)]
+    GetErrorCountsPerSchedule(Range<NaiveDateTime>),
+    // Over the completed runs of a schedule, None before its first one
+    #[response(Option<Duration>)]
+    GetAverageDuration(Uuid),
+    // Footprint and free space of each destination sampled within the range
+    #[response(Vec<DestinationUsage>)]
+    GetStorageUsage(Range<NaiveDateTime>),
+    // Lifecycle, error and schedule entries recorded within the range, newest first
+    #[response(Vec<JournalEntry>)]
+    GetJournal(Range<NaiveDateTime>),
+    // Files whose name or path matches the pattern across the catalogs of all runs
+    #[response(Vec<CatalogMatch>)]
+    SearchCatalogs(String),
 }
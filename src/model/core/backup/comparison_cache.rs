@@ -0,0 +1,52 @@
+use crate::model::core::backup::execution::Execution;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComparisonRecord {
+    pub size: i64,
+    pub modified: i64,
+}
+
+// Files of one directory that matched the destination in the previous run
+#[derive(Debug, Default)]
+pub struct ComparisonCache {
+    previous: HashMap<PathBuf, ComparisonRecord>,
+    current: Vec<(PathBuf, ComparisonRecord)>,
+}
+
+impl ComparisonCache {
+    pub fn new(previous: HashMap<PathBuf, ComparisonRecord>) -> Self {
+        Self {
+            previous,
+            current: Vec::new(),
+        }
+    }
+
+    // Cached outcomes only hold for the options they were produced with
+    pub fn options_key(execution: &Execution) -> String {
+        format!(
+            "{:?}:{}",
+            execution.comparison_mode, execution.options.backup_permission
+        )
+    }
+
+    pub fn is_unchanged(&self, path: &Path, record: &ComparisonRecord) -> bool {
+        self.previous.get(path) == Some(record)
+    }
+
+    pub fn record(&mut self, path: &Path, record: ComparisonRecord) {
+        self.current.push((path.to_path_buf(), record));
+    }
+
+    // Keeps the previous outcome of an entry a resumed run does not compare again
+    pub fn carry_over(&mut self, path: &Path) {
+        if let Some(record) = self.previous.get(path) {
+            self.current.push((path.to_path_buf(), *record));
+        }
+    }
+
+    pub fn into_records(self) -> Vec<(PathBuf, ComparisonRecord)> {
+        self.current
+    }
+}
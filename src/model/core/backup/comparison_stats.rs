@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonStage {
+    Size,
+    Modified,
+    Attributes,
+    Hash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComparisonOutcome {
+    pub identical: bool,
+    // The last stage that ran, a mismatch there ended the comparison
+    pub stage: ComparisonStage,
+}
+
+impl ComparisonOutcome {
+    pub fn identical(stage: ComparisonStage) -> Self {
+        Self {
+            identical: true,
+            stage,
+        }
+    }
+
+    pub fn differs(stage: ComparisonStage) -> Self {
+        Self {
+            identical: false,
+            stage,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ComparisonCounters {
+    size: AtomicUsize,
+    modified: AtomicUsize,
+    attributes: AtomicUsize,
+    hash: AtomicUsize,
+}
+
+impl ComparisonCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, outcome: ComparisonOutcome) {
+        let counter = match outcome.stage {
+            ComparisonStage::Size => &self.size,
+            ComparisonStage::Modified => &self.modified,
+            ComparisonStage::Attributes => &self.attributes,
+            ComparisonStage::Hash => &self.hash,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ComparisonStats {
+        ComparisonStats {
+            size: self.size.load(Ordering::Relaxed),
+            modified: self.modified.load(Ordering::Relaxed),
+            attributes: self.attributes.load(Ordering::Relaxed),
+            hash: self.hash.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Number of files decided at each stage
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ComparisonStats {
+    pub size: usize,
+    pub modified: usize,
+    pub attributes: usize,
+    pub hash: usize,
+}
+
+impl ComparisonStats {
+    pub fn total(&self) -> usize {
+        self.size + self.modified + self.attributes + self.hash
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug, Default)]
+pub struct CopyProgress {
+    cancelled: AtomicBool,
+    copied_bytes: AtomicU64,
+    total_bytes: AtomicU64,
+    file: Mutex<Option<PathBuf>>,
+    destination: Mutex<Option<PathBuf>>,
+    // Destination left partially written by a cancelled copy
+    interrupted: Mutex<Option<PathBuf>>,
+}
+
+impl CopyProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn begin(&self, file: &Path, destination: &Path) {
+        self.copied_bytes.store(0, Ordering::Relaxed);
+        self.total_bytes.store(0, Ordering::Relaxed);
+        *self.file.lock().unwrap_or_else(PoisonError::into_inner) = Some(file.to_path_buf());
+        *self
+            .destination
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(destination.to_path_buf());
+    }
+
+    pub fn update(&self, copied_bytes: u64, total_bytes: u64) {
+        self.copied_bytes.store(copied_bytes, Ordering::Relaxed);
+        self.total_bytes.store(total_bytes, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        *self.file.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        *self
+            .destination
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    // Destination being written right now, partial if the process ends before it is done
+    pub fn in_flight(&self) -> Option<PathBuf> {
+        self.destination
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn interrupt(&self, destination: &Path) {
+        *self
+            .interrupted
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(destination.to_path_buf());
+    }
+
+    pub fn take_interrupted(&self) -> Option<PathBuf> {
+        self.interrupted
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+
+    pub fn snapshot(&self) -> Option<(PathBuf, u64, u64)> {
+        let file = self
+            .file
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()?;
+        let copied_bytes = self.copied_bytes.load(Ordering::Relaxed);
+        let total_bytes = self.total_bytes.load(Ordering::Relaxed);
+        Some((file, copied_bytes, total_bytes))
+    }
+}
@@ -0,0 +1,12 @@
+use std::time::Duration;
+
+// Metadata the destination accepted when probed at the start of a run
+#[derive(Debug, Clone, Copy)]
+pub struct DestinationCapabilities {
+    pub attributes: bool,
+    pub permissions: bool,
+    // False on Windows and FAT style volumes, which refuse reserved characters in names
+    pub any_name: bool,
+    // The coarser timestamp resolution of source and destination
+    pub mtime_resolution: Duration,
+}
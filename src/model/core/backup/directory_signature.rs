@@ -0,0 +1,86 @@
+use std::ffi::OsString;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirectorySignature {
+    pub entry_count: i64,
+    pub max_modified: i64,
+    pub total_size: i64,
+    // Over the directory's own mtime and the name, size and mtime of each entry in name order,
+    // so a rename or an equally sized replacement with an older mtime changes it as well
+    pub entries_hash: [u8; 32],
+}
+
+pub struct SignatureEntry {
+    pub name: OsString,
+    pub size: u64,
+    pub modified: i64,
+}
+
+impl DirectorySignature {
+    pub fn new(directory_modified: i64, mut entries: Vec<SignatureEntry>) -> Self {
+        entries.sort_by(|first, second| first.name.cmp(&second.name));
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&directory_modified.to_le_bytes());
+        let mut signature = DirectorySignature {
+            entry_count: 0,
+            max_modified: 0,
+            total_size: 0,
+            entries_hash: [0; 32],
+        };
+        for entry in &entries {
+            let name = entry.name.as_encoded_bytes();
+            hasher.update(&(name.len() as u64).to_le_bytes());
+            hasher.update(name);
+            hasher.update(&entry.size.to_le_bytes());
+            hasher.update(&entry.modified.to_le_bytes());
+            signature.entry_count += 1;
+            signature.max_modified = signature.max_modified.max(entry.modified);
+            signature.total_size = signature.total_size.wrapping_add(entry.size as i64);
+        }
+        signature.entries_hash = *hasher.finalize().as_bytes();
+        signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, size: u64, modified: i64) -> SignatureEntry {
+        SignatureEntry {
+            name: OsString::from(name),
+            size,
+            modified,
+        }
+    }
+
+    #[test]
+    fn rename_changes_signature() {
+        let before =
+            DirectorySignature::new(100, vec![entry("a.txt", 10, 50), entry("b.txt", 20, 60)]);
+        let after =
+            DirectorySignature::new(100, vec![entry("c.txt", 10, 50), entry("b.txt", 20, 60)]);
+        assert_eq!(before.entry_count, after.entry_count);
+        assert_eq!(before.max_modified, after.max_modified);
+        assert_eq!(before.total_size, after.total_size);
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn older_replacement_of_equal_size_changes_signature() {
+        let before =
+            DirectorySignature::new(100, vec![entry("a.txt", 10, 50), entry("b.txt", 20, 60)]);
+        let after =
+            DirectorySignature::new(100, vec![entry("a.txt", 10, 40), entry("b.txt", 20, 60)]);
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn listing_order_does_not_change_signature() {
+        let before =
+            DirectorySignature::new(100, vec![entry("a.txt", 10, 50), entry("b.txt", 20, 60)]);
+        let after =
+            DirectorySignature::new(100, vec![entry("b.txt", 20, 60), entry("a.txt", 10, 50)]);
+        assert_eq!(before, after);
+    }
+}
@@ -38,14 +38,53 @@ pub enum ComparisonMode {
     Thorough(HashType),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorkerLimit {
+    #[default]
+    Auto,
+    Fixed(usize),
+}
+
+// Skipped files are set aside and tried once more after the rest of the tree is done
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockedFilePolicy {
+    #[default]
+    Report,
+    Retry(u32),
+    Skip,
+}
+
+impl LockedFilePolicy {
+    pub fn retries(&self) -> u32 {
+        match self {
+            LockedFilePolicy::Retry(retries) => *retries,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct BackupOptions {
     pub mirror: bool,
     pub backup_permission: bool,
     pub follow_symlinks: bool,
+    #[serde(default)]
+    pub background_io: bool,
+    #[serde(default)]
+    pub locked_files: LockedFilePolicy,
+    #[serde(default)]
+    pub manifest: bool,
+    #[serde(default)]
+    pub strict: bool,
+    // Keep the list of files each run added, updated or deleted
+    #[serde(default)]
+    pub catalog: bool,
+    // Refused to start unless the process runs elevated
+    #[serde(default)]
+    pub elevated: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Execution {
     pub uuid: Uuid,
     pub state: BackupState,
@@ -54,4 +93,6 @@ pub struct Execution {
     pub backup_type: BackupType,
     pub comparison_mode: Option<ComparisonMode>,
     pub options: BackupOptions,
+    // Set for runs started by a schedule, kept with their history entries
+    pub schedule_uuid: Option<Uuid>,
 }
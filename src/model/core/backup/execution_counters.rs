@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug, Default)]
+pub struct ExecutionCounters {
+    processed_files: AtomicUsize,
+    current_folder: Mutex<Option<PathBuf>>,
+}
+
+impl ExecutionCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn enter_folder(&self, folder: &Path) {
+        *self
+            .current_folder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(folder.to_path_buf());
+    }
+
+    pub fn add_processed(&self, count: usize) {
+        self.processed_files.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn processed_files(&self) -> usize {
+        self.processed_files.load(Ordering::Relaxed)
+    }
+
+    // Only the latest folder since the previous report is kept
+    pub fn take_current_folder(&self) -> Option<PathBuf> {
+        self.current_folder
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
@@ -0,0 +1,34 @@
+use crate::model::core::backup::capability_report::CapabilityReport;
+use crate::model::core::backup::execution::BackupState;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// Which errors of a run past max_stored_errors are kept, the first ones usually name the cause
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredErrorPolicy {
+    KeepFirst,
+    KeepLast,
+}
+
+// One run of an execution, a suspended execution adds another entry when it is resumed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExecutionHistory {
+    pub uuid: Uuid,
+    pub execution_uuid: Uuid,
+    pub schedule_uuid: Option<Uuid>,
+    pub source_path: PathBuf,
+    pub destination_path: PathBuf,
+    pub state: BackupState,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+    pub files_scanned: usize,
+    pub files_copied: usize,
+    pub entries_deleted: usize,
+    pub bytes_copied: u64,
+    pub error_count: usize,
+    // Missing on runs recorded before capabilities were probed
+    pub capabilities: Option<CapabilityReport>,
+}
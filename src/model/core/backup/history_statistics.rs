@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Days without a run are left out
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DailyBytesCopied {
+    pub day: NaiveDate,
+    pub bytes_copied: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleErrorCount {
+    pub schedule_uuid: Uuid,
+    pub runs: usize,
+    pub error_count: usize,
+}
@@ -0,0 +1,20 @@
+use crate::model::core::backup::execution::HashType;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Written to the destination root, one JSON header line followed by one line per file
+pub const MANIFEST_NAME: &str = ".mirrorsphere-manifest";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestHeader {
+    pub hash_type: HashType,
+    pub created_at: NaiveDateTime,
+}
+
+// Paths are relative to the destination root and separated by slashes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
@@ -1,3 +1,19 @@
+pub mod capability_report;
+pub mod case_sensitivity;
+pub mod catalog;
+pub mod change_set;
+pub mod comparison_cache;
+pub mod comparison_stats;
+pub mod copy_progress;
+pub mod destination_capabilities;
+pub mod directory_signature;
 pub mod execution;
+pub mod execution_counters;
+pub mod execution_history;
+pub mod history_statistics;
+pub mod manifest;
 pub mod progress_data;
+pub mod scan_totals;
+pub mod storage_usage;
+pub mod transfer_counters;
 pub mod communication;
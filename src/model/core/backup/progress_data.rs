@@ -1,18 +1,39 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use crate::model::error::Error;
+use crate::model::core::backup::execution::Execution;
+use crate::model::error::record::ErrorRecord;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ProgressData {
-    pub current_level: Vec<PathBuf>,
-    pub errors: Vec<Error>,
+    // The execution as entered, found again on the next start when the process ends mid-run
+    pub execution: Option<Execution>,
+    pub pending_directories: Vec<PathBuf>,
+    // Entries already finished inside the pending directories, skipped on resume
+    pub completed_entries: Vec<PathBuf>,
+    pub spill_range: (u64, u64),
+    pub errors: Vec<ErrorRecord>,
+    pub unverified_files: Vec<PathBuf>,
+    pub locked_files: Vec<PathBuf>,
 }
 
 impl ProgressData {
-    pub fn new(current_level: Vec<PathBuf>, errors: Vec<Error>) -> ProgressData {
+    pub fn new(
+        execution: Option<Execution>,
+        pending_directories: Vec<PathBuf>,
+        completed_entries: Vec<PathBuf>,
+        spill_range: (u64, u64),
+        errors: Vec<ErrorRecord>,
+        unverified_files: Vec<PathBuf>,
+        locked_files: Vec<PathBuf>,
+    ) -> ProgressData {
         ProgressData {
-            current_level,
-            errors
+            execution,
+            pending_directories,
+            completed_entries,
+            spill_range,
+            errors,
+            unverified_files,
+            locked_files,
         }
     }
 }
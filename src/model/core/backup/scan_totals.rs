@@ -0,0 +1,38 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+#[derive(Debug, Default)]
+pub struct ScanTotals {
+    files: AtomicUsize,
+    bytes: AtomicU64,
+    complete: AtomicBool,
+}
+
+impl ScanTotals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_file(&self, bytes: u64) {
+        self.files.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self) {
+        self.complete.store(true, Ordering::Release);
+    }
+
+    pub fn snapshot(&self) -> ScanProgress {
+        ScanProgress {
+            complete: self.complete.load(Ordering::Acquire),
+            total_files: self.files.load(Ordering::Relaxed),
+            total_bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanProgress {
+    pub total_files: usize,
+    pub total_bytes: u64,
+    pub complete: bool,
+}
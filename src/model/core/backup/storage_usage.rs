@@ -0,0 +1,29 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Taken after each completed run
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorageSample {
+    pub destination_path: PathBuf,
+    pub recorded_at: NaiveDateTime,
+    // Bytes of the files under the destination
+    pub footprint: u64,
+    // Free space left on its volume, None where it could not be read
+    pub available: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DestinationUsage {
+    pub destination_path: PathBuf,
+    pub footprint: u64,
+    pub available: Option<u64>,
+    // Copied by the runs started within the range
+    pub bytes_written: u64,
+    // None until the samples span a day
+    pub growth_per_day: Option<f64>,
+    // None while the footprint is not growing
+    pub days_until_full: Option<f64>,
+    // Footprint at each sample within the range, oldest first
+    pub footprints: Vec<(NaiveDateTime, u64)>,
+}
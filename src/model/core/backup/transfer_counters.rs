@@ -0,0 +1,69 @@
+use crate::model::core::backup::catalog::{CatalogAction, CatalogEntry};
+use crossbeam_queue::SegQueue;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+#[derive(Debug, Default)]
+pub struct TransferCounters {
+    copied_files: AtomicUsize,
+    copied_bytes: AtomicU64,
+    deleted_entries: AtomicUsize,
+    // Present when the execution records the files it changed
+    catalog: Option<SegQueue<CatalogEntry>>,
+}
+
+impl TransferCounters {
+    pub fn new(catalog: bool) -> Self {
+        Self {
+            catalog: catalog.then(SegQueue::new),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_cataloging(&self) -> bool {
+        self.catalog.is_some()
+    }
+
+    pub fn add_copied(&self, bytes: u64) {
+        self.copied_files.fetch_add(1, Ordering::Relaxed);
+        self.copied_bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn add_deleted(&self) {
+        self.deleted_entries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record(&self, action: CatalogAction, path: &Path) {
+        if let Some(catalog) = &self.catalog {
+            catalog.push(CatalogEntry {
+                action,
+                path: path.to_path_buf(),
+            });
+        }
+    }
+
+    pub fn take_catalog(&self) -> Vec<CatalogEntry> {
+        let mut entries = Vec::new();
+        if let Some(catalog) = &self.catalog {
+            while let Some(entry) = catalog.pop() {
+                entries.push(entry);
+            }
+        }
+        entries
+    }
+
+    pub fn snapshot(&self) -> TransferStats {
+        TransferStats {
+            copied_files: self.copied_files.load(Ordering::Relaxed),
+            copied_bytes: self.copied_bytes.load(Ordering::Relaxed),
+            deleted_entries: self.deleted_entries.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TransferStats {
+    pub copied_files: usize,
+    pub copied_bytes: u64,
+    pub deleted_entries: usize,
+}
@@ -0,0 +1,19 @@
+use crate::model::core::backup::execution::HashType;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BenchmarkResult {
+    pub uuid: Uuid,
+    pub source_path: PathBuf,
+    pub destination_path: PathBuf,
+    pub read_throughput: f64,  // byte per second
+    pub write_throughput: f64, // byte per second
+    pub hash_throughput: Vec<(HashType, f64)>,
+    pub small_file_ops: f64, // operation per second
+    pub recommended_concurrency: u8,
+    pub recommended_buffer_size: usize,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,16 @@
+use crate::model::core::benchmark::benchmark_result::BenchmarkResult;
+use macros::ServiceMessage;
+use std::path::PathBuf;
+
+#[derive(ServiceMessage)]
+#[message(command)]
+pub enum BenchmarkCommand {
+    RunBenchmark(PathBuf, PathBuf),
+}
+
+#[derive(ServiceMessage)]
+#[message(query = BenchmarkQueryResponse)]
+pub enum BenchmarkQuery {
+    #[response(Vec<BenchmarkResult>)]
+    GetResults,
+}
@@ -0,0 +1,2 @@
+pub mod benchmark_result;
+pub mod communication;
@@ -1,29 +1,64 @@
-use crate::interface::communication::event::Event;
+use crate::model::core::backup::comparison_stats::ComparisonStats;
+use crate::model::core::backup::scan_totals::ScanProgress;
 use crate::model::error::Error;
+use macros::ServiceMessage;
 use std::path::PathBuf;
 use uuid::Uuid;
 
-#[derive(Clone)]
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = Coalesce, coalesce = uuid)]
 pub struct FolderProcess {
     pub uuid: Uuid,
     pub folder: PathBuf,
 }
 
-impl Event for FolderProcess {}
-
-#[derive(Clone)]
+// Replayed so a page opened mid-run starts from the last known state of each execution
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = Coalesce, replay = 64, coalesce = uuid)]
 pub struct ExecutionProgress {
     pub uuid: Uuid,
     pub processed_files: usize,
     pub error_count: usize,
+    pub scan: Option<ScanProgress>,
+    pub comparison: ComparisonStats,
+    pub active_workers: usize,
 }
 
-impl Event for ExecutionProgress {}
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = Coalesce, coalesce = uuid)]
+pub struct FileProgress {
+    pub uuid: Uuid,
+    pub file: PathBuf,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+}
 
-#[derive(Clone)]
+// Errors are never dropped, the page drains them every frame
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = BlockProducer)]
 pub struct ExecutionErrors {
     pub uuid: Uuid,
     pub errors: Vec<Error>,
 }
 
-impl Event for ExecutionErrors {}
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = BlockProducer)]
+pub struct ExecutionFailed {
+    pub uuid: Uuid,
+    pub error: Error,
+}
+
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = BlockProducer)]
+pub struct DestinationFull {
+    pub uuid: Uuid,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event, policy = BlockProducer)]
+pub struct VerificationFinished {
+    pub uuid: Uuid,
+    pub verified: usize,
+    pub failed: usize,
+}
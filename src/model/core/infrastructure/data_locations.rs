@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+// Where the database, checkpoints and change cursors were kept on the last start
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DataLocations {
+    pub database_path: PathBuf,
+    pub progress_path: PathBuf,
+    // Not recorded before the change cursors moved with the rest of the data
+    #[serde(default)]
+    pub journal_path: Option<PathBuf>,
+}
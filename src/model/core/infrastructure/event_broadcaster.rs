@@ -2,21 +2,214 @@ use crate::interface::communication::event::Event;
 use crate::interface::communication::event::EventBroadcaster;
 use crate::model::error::misc::MiscError;
 use crate::model::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use macros::ServiceMessage;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
-use tokio::sync::broadcast;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use tokio::sync::Notify;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    DropOldest,
+    BlockProducer,
+    // Replaces a queued event with the same key, falls back to dropping the oldest
+    Coalesce,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventStatistics {
+    pub event: &'static str,
+    pub policy: OverflowPolicy,
+    pub dropped: u64,
+}
+
+// Every published event as its type name and debug rendering, for diagnostics only
+#[derive(Debug, Clone, ServiceMessage)]
+#[message(event)]
+pub struct TappedEvent {
+    pub event: &'static str,
+    pub published_at: NaiveDateTime,
+    pub payload: String,
+}
 
 pub struct TypedEventBroadcaster<E: Event> {
-    pub sender: broadcast::Sender<E>,
+    event: &'static str,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: AtomicU64,
+    subscribers: Mutex<Vec<Weak<Subscriber<E>>>>,
+    // Latest events handed to new subscribers, one per coalesce key where the event has one
+    replay_capacity: usize,
+    replay: Mutex<VecDeque<E>>,
+}
+
+struct Subscriber<E> {
+    queue: Mutex<VecDeque<E>>,
+    space: Notify,
+    closed: AtomicBool,
+}
+
+pub struct EventReceiver<E> {
+    subscriber: Arc<Subscriber<E>>,
 }
 
+impl<E: Event> TypedEventBroadcaster<E> {
+    pub fn new(
+        event: &'static str,
+        capacity: usize,
+        policy: OverflowPolicy,
+        replay_capacity: usize,
+    ) -> Self {
+        Self {
+            event,
+            capacity: capacity.max(1),
+            policy,
+            dropped: AtomicU64::new(0),
+            subscribers: Mutex::new(Vec::new()),
+            replay_capacity,
+            replay: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|subscriber| subscriber.strong_count() > 0)
+    }
+
+    // Registered under the replay lock, so an event is either replayed or delivered, never both
+    pub fn subscribe(&self) -> EventReceiver<E> {
+        let replay = self.lock_replay();
+        let skipped = replay.len().saturating_sub(self.capacity);
+        let subscriber = Arc::new(Subscriber {
+            queue: Mutex::new(replay.iter().skip(skipped).cloned().collect()),
+            space: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::downgrade(&subscriber));
+        EventReceiver { subscriber }
+    }
+
+    pub async fn broadcast(&self, event: E) {
+        let subscribers = {
+            let mut replay = self.lock_replay();
+            self.record(&mut replay, &event);
+            self.live_subscribers()
+        };
+        for subscriber in subscribers {
+            self.deliver(&subscriber, event.clone()).await;
+        }
+    }
+
+    fn lock_replay(&self) -> MutexGuard<'_, VecDeque<E>> {
+        self.replay.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, replay: &mut VecDeque<E>, event: &E) {
+        if self.replay_capacity == 0 {
+            return;
+        }
+        if let Some(key) = event.coalesce_key() {
+            replay.retain(|recorded| recorded.coalesce_key() != Some(key));
+        }
+        replay.push_back(event.clone());
+        if replay.len() > self.replay_capacity {
+            replay.pop_front();
+        }
+    }
+
+    fn live_subscribers(&self) -> Vec<Arc<Subscriber<E>>> {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+        subscribers.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    async fn deliver(&self, subscriber: &Subscriber<E>, event: E) {
+        if self.policy == OverflowPolicy::BlockProducer {
+            loop {
+                let space = subscriber.space.notified();
+                if subscriber.closed.load(Ordering::Acquire) {
+                    return;
+                }
+                {
+                    let mut queue = subscriber.lock();
+                    if queue.len() < self.capacity {
+                        queue.push_back(event);
+                        return;
+                    }
+                }
+                space.await;
+            }
+        }
+
+        let mut queue = subscriber.lock();
+        if queue.len() >= self.capacity {
+            let replaced = match (self.policy, event.coalesce_key()) {
+                (OverflowPolicy::Coalesce, Some(key)) => queue
+                    .iter()
+                    .position(|queued| queued.coalesce_key() == Some(key)),
+                _ => None,
+            };
+            queue.remove(replaced.unwrap_or(0));
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        queue.push_back(event);
+    }
+}
+
+#[async_trait]
 impl<E: Event + 'static> EventBroadcaster for TypedEventBroadcaster<E> {
     fn subscribe_typed(&self) -> Box<dyn Any + Send> {
-        Box::new(self.sender.subscribe())
+        Box::new(self.subscribe())
     }
 
-    fn broadcast_event(&self, event: Box<dyn Any + Send>) -> Result<(), Error> {
+    async fn broadcast_event(&self, event: Box<dyn Any + Send>) -> Result<(), Error> {
         let typed_event = *event.downcast::<E>().map_err(|_| MiscError::TypeMismatch)?;
-        let _ = self.sender.send(typed_event);
+        self.broadcast(typed_event).await;
         Ok(())
     }
+
+    fn statistics(&self) -> EventStatistics {
+        EventStatistics {
+            event: self.event,
+            policy: self.policy,
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl<E> Subscriber<E> {
+    fn lock(&self) -> MutexGuard<'_, VecDeque<E>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<E> EventReceiver<E> {
+    pub fn try_recv(&self) -> Option<E> {
+        let event = self.subscriber.lock().pop_front();
+        if event.is_some() {
+            self.subscriber.space.notify_waiters();
+        }
+        event
+    }
+}
+
+impl<E> Drop for EventReceiver<E> {
+    fn drop(&mut self) {
+        self.subscriber.closed.store(true, Ordering::Release);
+        self.subscriber.space.notify_waiters();
+    }
 }
@@ -0,0 +1,42 @@
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalEvent {
+    ExecutionStarted,
+    ExecutionResumed,
+    ExecutionSuspended,
+    ExecutionCompleted,
+    ExecutionCanceled,
+    ExecutionFailed,
+    ExecutionErrors,
+    ScheduleTriggered,
+}
+
+// Written straight to the database, unlike the events it is kept when a receiver falls behind
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub recorded_at: NaiveDateTime,
+    pub event: JournalEvent,
+    pub execution_uuid: Option<Uuid>,
+    pub schedule_uuid: Option<Uuid>,
+    pub detail: String,
+}
+
+impl JournalEntry {
+    pub fn new(
+        event: JournalEvent,
+        execution_uuid: Option<Uuid>,
+        schedule_uuid: Option<Uuid>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            recorded_at: Local::now().naive_local(),
+            event,
+            execution_uuid,
+            schedule_uuid,
+            detail: detail.into(),
+        }
+    }
+}
@@ -1 +1,3 @@
-pub mod event_broadcaster;
\ No newline at end of file
+pub mod data_locations;
+pub mod event_broadcaster;
+pub mod event_journal;
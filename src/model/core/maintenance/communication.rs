@@ -0,0 +1,30 @@
+use crate::model::core::maintenance::database_status::DatabaseStatus;
+use macros::ServiceMessage;
+use std::path::PathBuf;
+
+#[derive(ServiceMessage)]
+#[message(command)]
+pub enum MaintenanceCommand {
+    // Runs at once instead of waiting for maintenance_interval
+    RunMaintenance,
+    // Bundles config.toml and the database into one archive
+    ExportState(PathBuf),
+    // Takes effect on the next start
+    ImportState(PathBuf),
+    // Starts from a copy of the default config.toml
+    CreateProfile(String),
+    // Starts another instance on the profile, None being the default one
+    SwitchProfile(Option<String>),
+    // Starts an elevated instance on the same arguments
+    RestartElevated,
+}
+
+#[derive(ServiceMessage)]
+#[message(query = MaintenanceQueryResponse)]
+pub enum MaintenanceQuery {
+    #[response(DatabaseStatus)]
+    GetDatabaseStatus,
+    // Named profiles, the default one is not listed
+    #[response(Vec<String>)]
+    GetProfiles,
+}
@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaintenanceRun {
+    pub finished_at: NaiveDateTime,
+    pub pruned_runs: usize,
+    pub size_before: u64,
+    pub size_after: u64,
+    // Lines reported by the integrity check, empty when it passed
+    pub integrity_problems: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DatabaseStatus {
+    pub size: u64,
+    pub last_maintenance: Option<MaintenanceRun>,
+}
@@ -0,0 +1,3 @@
+pub mod communication;
+pub mod database_status;
+pub mod state_archive;
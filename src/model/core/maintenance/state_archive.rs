@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Everything needed to set MirrorSphere up again elsewhere, the database carries schedules,
+// history and the hash caches
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StateArchive {
+    pub created_at: NaiveDateTime,
+    pub config: String,
+    pub database: Vec<u8>,
+}
@@ -1,4 +1,6 @@
 pub mod backup;
+pub mod benchmark;
 pub mod gui;
 pub mod infrastructure;
+pub mod maintenance;
 pub mod schedule;
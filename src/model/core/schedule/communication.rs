@@ -1,9 +1,10 @@
-use uuid::Uuid;
-use crate::interface::communication::command::Command;
-use crate::interface::communication::message::Message;
-use crate::interface::communication::query::Query;
 use crate::model::core::schedule::schedule::Schedule;
+use crate::model::core::schedule::schedule_version::ScheduleVersion;
+use macros::ServiceMessage;
+use uuid::Uuid;
 
+#[derive(ServiceMessage)]
+#[message(command)]
 pub enum ScheduleManagerCommand {
     AddSchedule(Schedule),
     ModifySchedule(Schedule),
@@ -11,35 +12,22 @@ pub enum ScheduleManagerCommand {
     ActivateSchedule(Uuid),
     PauseSchedule(Uuid),
     DisableSchedule(Uuid),
+    RevertSchedule(Uuid, u32),
     ExecuteReadySchedules,
+    RecalculateNextRunTimes,
 }
 
-impl Message for ScheduleManagerCommand {
-    type Response = ();
-}
-
-impl Command for ScheduleManagerCommand {}
-
+#[derive(ServiceMessage)]
+#[message(query = ScheduleManagerQueryResponse)]
 pub enum ScheduleManagerQuery {
+    #[response(Vec<Schedule>)]
     GetSchedules,
+    #[response(Vec<ScheduleVersion>)]
+    GetScheduleVersions(Uuid),
 }
 
-impl Message for ScheduleManagerQuery {
-    type Response = ScheduleManagerQueryResponse;
-}
-
-impl Query for ScheduleManagerQuery {}
-
-pub enum ScheduleManagerQueryResponse {
-    GetSchedules(Vec<Schedule>),
-}
-
+#[derive(ServiceMessage)]
+#[message(command)]
 pub enum ScheduleTimerCommand {
     RefreshTimer
 }
-
-impl Message for ScheduleTimerCommand {
-    type Response = ();
-}
-
-impl Command for ScheduleTimerCommand {}
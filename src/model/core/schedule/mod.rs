@@ -1,2 +1,3 @@
 pub mod schedule;
 pub mod communication;
+pub mod schedule_version;
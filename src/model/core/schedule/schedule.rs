@@ -1,5 +1,5 @@
 use crate::model::core::backup::execution::*;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use uuid::Uuid;
@@ -19,7 +19,18 @@ pub enum ScheduleInterval {
     Monthly,
 }
 
-#[derive(Debug, Clone)]
+// Limits on the history of a schedule's runs, None falls back to the global settings
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HistoryRetention {
+    // Newest runs kept
+    pub max_runs: Option<usize>,
+    // Replaces history_retention_days for this schedule
+    pub max_days: Option<u32>,
+    // Runs older than this keep their summary but lose their stored errors and file catalog
+    pub detail_days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Schedule {
     pub uuid: Uuid,
     pub name: String,
@@ -32,11 +43,56 @@ pub struct Schedule {
     pub interval: ScheduleInterval,
     pub last_run_time: Option<NaiveDateTime>,
     pub next_run_time: Option<NaiveDateTime>,
+    // Local wall clock time runs are due at, next_run_time alone loses it to DST shifts
+    pub run_time: Option<NaiveTime>,
+    pub retention: HistoryRetention,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
 impl Schedule {
+    // Names of the user editable settings that differ from the other version
+    pub fn changed_settings(&self, other: &Schedule) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.name != other.name {
+            changed.push("Name");
+        }
+        if self.source_path != other.source_path {
+            changed.push("Source");
+        }
+        if self.destination_path != other.destination_path {
+            changed.push("Destination");
+        }
+        if self.backup_type != other.backup_type {
+            changed.push("Backup Type");
+        }
+        if self.comparison_mode != other.comparison_mode {
+            changed.push("Comparison Mode");
+        }
+        if self.options != other.options {
+            changed.push("Options");
+        }
+        if self.interval != other.interval {
+            changed.push("Interval");
+        }
+        if self.retention != other.retention {
+            changed.push("History Retention");
+        }
+        changed
+    }
+
+    // Takes the settings of another version, state and run times stay as they are
+    pub fn apply_settings(&mut self, other: &Schedule) {
+        self.name = other.name.clone();
+        self.source_path = other.source_path.clone();
+        self.destination_path = other.destination_path.clone();
+        self.backup_type = other.backup_type;
+        self.comparison_mode = other.comparison_mode;
+        self.options = other.options;
+        self.interval = other.interval;
+        self.retention = other.retention;
+    }
+
     pub fn to_execution(&self) -> Execution {
         Execution {
             uuid: Uuid::new_v4(),
@@ -50,6 +106,7 @@ impl Schedule {
             },
             comparison_mode: self.comparison_mode,
             options: self.options,
+            schedule_uuid: Some(self.uuid),
         }
     }
 }
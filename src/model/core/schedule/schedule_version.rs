@@ -0,0 +1,14 @@
+use crate::model::core::schedule::schedule::Schedule;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// Older versions of a schedule beyond this are dropped
+pub const MAX_SCHEDULE_VERSIONS: usize = 50;
+
+// The settings a schedule had before an edit replaced them
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleVersion {
+    pub version: u32,
+    pub saved_at: NaiveDateTime,
+    pub schedule: Schedule,
+}
@@ -11,6 +11,24 @@ traceable! {
         #[error("Failed to lock database")]
         LockDatabaseFailed => tracing::Level::ERROR,
 
+        #[no_source]
+        #[error("Database is in use by another instance")]
+        DatabaseInUse => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Database is open read-only while another instance holds it")]
+        DatabaseReadOnly => tracing::Level::WARN,
+
+        #[error("Failed to read the database key from the OS keyring")]
+        DatabaseKeyUnavailable => tracing::Level::ERROR,
+
+        #[error("Failed to convert the database encryption")]
+        DatabaseEncryptionFailed => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Database is encrypted, this build lacks the sqlcipher feature")]
+        EncryptionUnsupported => tracing::Level::ERROR,
+
         #[error("Failed to unlock database")]
         UnlockDatabaseFailed => tracing::Level::ERROR,
 
@@ -20,5 +38,9 @@ traceable! {
         #[no_source]
         #[error("Data is corrupted or in invalid format")]
         DataCorrupted => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Schedule version {version} no longer exists")]
+        ScheduleVersionNotFound { version: u32 } => tracing::Level::WARN,
     }
 }
@@ -51,5 +51,43 @@ traceable! {
         #[no_source]
         #[error("File does not exist: {path}")]
         FileDoesNotExist { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("File already exists: {path}")]
+        FileAlreadyExists { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("File copy cancelled: {path}")]
+        CopyFileCancelled { path: PathBuf } => tracing::Level::INFO,
+
+        #[no_source]
+        #[error("File hash cancelled: {path}")]
+        HashFileCancelled { path: PathBuf } => tracing::Level::INFO,
+
+        #[no_source]
+        #[error("Destination is out of space: {path}")]
+        DestinationFull { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination cannot store NTFS compressed files: {path}")]
+        CompressionUnsupported { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination cannot store EFS encrypted files: {path}")]
+        EncryptionUnsupported { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("File is locked by another process: {path}")]
+        FileLocked { path: PathBuf } => tracing::Level::ERROR,
+
+        #[error("Failed to read change journal: {path}")]
+        ReadChangeJournalFailed { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Change journal unavailable: {path}")]
+        ChangeJournalUnavailable { path: PathBuf } => tracing::Level::INFO,
+
+        #[error("Failed to change IO priority")]
+        SetIOPriorityFailed => tracing::Level::ERROR,
     }
 }
@@ -1,4 +1,5 @@
 use macros::traceable;
+use std::path::PathBuf;
 
 traceable! {
     MiscError {
@@ -22,6 +23,10 @@ traceable! {
         #[error("Handler not found")]
         HandlerNotFound => tracing::Level::ERROR,
 
+        #[no_source]
+        #[error("{message} got no response within {seconds} seconds")]
+        QueryTimeout { message: String, seconds: u64 } => tracing::Level::ERROR,
+
         #[no_source]
         #[error("Type mismatch")]
         TypeMismatch => tracing::Level::ERROR,
@@ -34,6 +39,25 @@ traceable! {
         #[error("Channel closed")]
         ChannelClosed => tracing::Level::ERROR,
 
+        #[no_source]
+        #[error("Unsupported progress file version: {version}")]
+        UnsupportedProgressVersion { version: u32 } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Unsupported state archive version: {version}")]
+        UnsupportedArchiveVersion { version: u32 } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Not a valid state archive: {path}")]
+        InvalidStateArchive { path: PathBuf } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Not a valid profile name: {name}")]
+        InvalidProfileName { name: String } => tracing::Level::WARN,
+
+        #[error("Failed to start another instance")]
+        StartProcessFailed => tracing::Level::ERROR,
+
         #[no_source]
         #[error("Channel empty")]
         ChannelEmpty => tracing::Level::INFO,
@@ -1,12 +1,14 @@
 pub mod database;
 pub mod io;
 pub mod misc;
+pub mod record;
 pub mod system;
 pub mod task;
 
 use crate::model::error::database::DatabaseError;
 use crate::model::error::io::IOError;
 use crate::model::error::misc::MiscError;
+use crate::model::error::record::ErrorRecord;
 use crate::model::error::system::SystemError;
 use crate::model::error::task::TaskError;
 use serde::{Deserialize, Serialize};
@@ -23,6 +25,22 @@ pub enum Error {
     System(SystemError),
     #[error("{0}")]
     Task(TaskError),
+    // Read back from a progress file, whatever the variant it was written from
+    #[error("{}", .0.message)]
+    Restored(ErrorRecord),
+}
+
+impl Error {
+    pub fn level(&self) -> tracing::Level {
+        match self {
+            Self::Database(error) => error.level(),
+            Self::IO(error) => error.level(),
+            Self::Misc(error) => error.level(),
+            Self::System(error) => error.level(),
+            Self::Task(error) => error.level(),
+            Self::Restored(record) => record.level.parse().unwrap_or(tracing::Level::ERROR),
+        }
+    }
 }
 
 impl From<DatabaseError> for Error {
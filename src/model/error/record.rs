@@ -0,0 +1,60 @@
+use crate::model::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+// Persisted in place of the error enums, so renaming or adding variants keeps old files readable
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorRecord {
+    // Category and variant, such as IO.CopyFileFailed
+    pub code: String,
+    pub message: String,
+    pub level: String,
+    pub context: BTreeMap<String, String>,
+}
+
+impl From<&Error> for ErrorRecord {
+    fn from(error: &Error) -> Self {
+        if let Error::Restored(record) = error {
+            return record.clone();
+        }
+
+        // Enums serialize externally tagged, the outer two names are the category and variant
+        let mut code = Vec::new();
+        let mut value = serde_json::to_value(error).unwrap_or_default();
+        while code.len() < 2 {
+            value = match value {
+                Value::String(name) => {
+                    code.push(name);
+                    Value::Null
+                }
+                Value::Object(map) if map.len() == 1 => {
+                    let (name, inner) = map.into_iter().next().unwrap_or_default();
+                    code.push(name);
+                    inner
+                }
+                other => {
+                    value = other;
+                    break;
+                }
+            };
+        }
+        let context = match value {
+            Value::Object(fields) => fields
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Value::String(value) => (name, value),
+                    value => (name, value.to_string()),
+                })
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+
+        Self {
+            code: code.join("."),
+            message: error.to_string(),
+            level: error.level().to_string(),
+            context,
+        }
+    }
+}
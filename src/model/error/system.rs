@@ -9,12 +9,6 @@ traceable! {
         #[error("Failed to adjust token privileges")]
         AdjustTokenPrivilegesFailed => tracing::Level::ERROR,
 
-        #[error("Invalid configuration")]
-        InvalidConfig => tracing::Level::ERROR,
-
-        #[error("Configuration not found")]
-        ConfigNotFound => tracing::Level::ERROR,
-
         #[error("Failed to terminate instance")]
         TerminateError => tracing::Level::ERROR,
 
@@ -25,6 +19,12 @@ traceable! {
         #[error("Unexcepted thread panic")]
         ThreadPanic => tracing::Level::ERROR,
 
+        #[error("Failed to keep the system awake")]
+        PowerRequestFailed => tracing::Level::ERROR,
+
+        #[error("Failed to spawn thread")]
+        ThreadSpawnFailed => tracing::Level::ERROR,
+
         #[error("Unexcepted error")]
         UnexpectError => tracing::Level::ERROR,
 
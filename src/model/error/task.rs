@@ -1,4 +1,5 @@
 use macros::traceable;
+use std::path::PathBuf;
 
 traceable! {
     TaskError {
@@ -9,5 +10,101 @@ traceable! {
         #[no_source]
         #[error("Task not found")]
         ExecutionNotFound => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Execution needs administrator rights, restart MirrorSphere elevated")]
+        ElevationRequired => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Source and destination are the same directory: {path}")]
+        IdenticalPaths { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination {dst} is inside source {src}")]
+        DestinationInsideSource { src: PathBuf, dst: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Source {src} is inside destination {dst}")]
+        SourceInsideDestination { src: PathBuf, dst: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("{path} collides with the destination name of {existing}")]
+        NameCollision { path: PathBuf, existing: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Name cannot be stored on the destination, enable sanitize_names: {path}")]
+        UnportableName { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Manifest is malformed: {path}")]
+        InvalidManifest { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("File listed in the manifest is missing: {path}")]
+        ManifestFileMissing { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("File no longer matches the manifest: {path}")]
+        ManifestFileCorrupted { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Network share could not be reconnected, the run was suspended: {path}")]
+        ShareDisconnected { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination volume is not attached: {path}")]
+        VolumeNotPresent { path: PathBuf } => tracing::Level::ERROR,
+
+        #[error("Destination is not writable: {path}")]
+        DestinationNotWritable { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination is on a read-only volume: {path}")]
+        DestinationReadOnly { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination cannot hold symbolic links, enable following them instead: {path}")]
+        SymlinksUnsupported { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Destination does not keep {features}, they are skipped for this run: {path}")]
+        CapabilitiesMissing { path: PathBuf, features: String } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Aborted after {errors} errors")]
+        ErrorLimitExceeded { errors: usize } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Aborted after {errors} errors in {entries} entries")]
+        ErrorRateExceeded { errors: usize, entries: usize } => tracing::Level::ERROR,
+
+        #[error("Source is not readable, mirroring it would empty the destination: {path}")]
+        SourceUnreadable { path: PathBuf } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Source is empty, mirroring it would empty the destination: {path}")]
+        SourceEmpty { path: PathBuf } => tracing::Level::WARN,
+
+        #[no_source]
+        #[error("Mirroring would delete {deleted} of {total} entries in the destination: {path}")]
+        MirrorDeleteLimitExceeded {
+            path: PathBuf,
+            deleted: usize,
+            total: usize,
+        } => tracing::Level::WARN,
+
+        #[error("Worker panicked processing {path}, retrying the directory")]
+        DirectoryPanicked { path: PathBuf } => tracing::Level::WARN,
+
+        #[error("Worker panicked again processing {path}, the backup is incomplete")]
+        DirectoryPanicRepeated { path: PathBuf } => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Benchmark already running")]
+        BenchmarkAlreadyRunning => tracing::Level::ERROR,
+
+        #[no_source]
+        #[error("Database maintenance already running")]
+        MaintenanceAlreadyRunning => tracing::Level::ERROR,
     }
 }
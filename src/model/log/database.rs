@@ -4,5 +4,33 @@ loggable! {
     DatabaseLog {
         #[error("Connected to database successfully")]
         DatabaseConnectSuccess => tracing::Level::INFO,
+
+        #[error("Opened database read-only, another instance holds the lock")]
+        ReadOnlySession => tracing::Level::WARN,
+
+        #[error("Backed up database to {path} before migrating")]
+        DatabaseBackedUp { path: String } => tracing::Level::INFO,
+
+        #[error("Migrated database to version {version}: {description}")]
+        MigrationApplied { version: i64, description: String } => tracing::Level::INFO,
+
+        #[error("Database maintenance pruned {runs} runs, {size_before} to {size_after} bytes")]
+        MaintenanceComplete { runs: usize, size_before: u64, size_after: u64 }
+            => tracing::Level::INFO,
+
+        #[error("Database integrity check failed: {problems}")]
+        IntegrityCheckFailed { problems: String } => tracing::Level::ERROR,
+
+        #[error("Applied the imported state, the previous database was kept as {backup_path}")]
+        StateImported { backup_path: String } => tracing::Level::INFO,
+
+        #[error("Encrypted the database, its key is kept in the OS keyring")]
+        DatabaseEncrypted => tracing::Level::INFO,
+
+        #[error("Decrypted the database")]
+        DatabaseDecrypted => tracing::Level::INFO,
+
+        #[error("Database maintenance postponed, an execution is running")]
+        MaintenancePostponed => tracing::Level::INFO,
     }
 }
@@ -4,5 +4,8 @@ loggable! {
     EventLog {
         #[error("Placeholder")]
         Placeholder => tracing::Level::INFO,
+
+        #[error("{dropped} {event} events dropped under the {policy} overflow policy")]
+        EventsDropped { event: String, policy: String, dropped: u64 } => tracing::Level::WARN,
     }
 }
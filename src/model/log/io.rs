@@ -4,5 +4,23 @@ loggable! {
     IOLog {
         #[error("Placeholder")]
         Placeholder => tracing::Level::INFO,
+
+        #[error("io_uring is not supported by the kernel, falling back to standard copy")]
+        IoUringUnavailable => tracing::Level::WARN,
+
+        #[error("Destination {destination} is projected to fill up in {days} days")]
+        DestinationFillingUp { destination: String, days: u64 } => tracing::Level::WARN,
+
+        #[error("Capabilities of {path} were not kept, the destination cannot hold them")]
+        CapabilityNotPreserved { path: String } => tracing::Level::WARN,
+
+        #[error("SELinux context of {path} was not kept, the destination cannot hold it")]
+        SelinuxContextNotPreserved { path: String } => tracing::Level::WARN,
+
+        #[error("Access to {path} was denied, copying it through the backup privilege")]
+        BackupSemanticsCopy { path: String } => tracing::Level::INFO,
+
+        #[error("Network share {path} reconnected after {attempt} attempts")]
+        ShareReconnected { path: String, attempt: u32 } => tracing::Level::WARN,
     }
 }
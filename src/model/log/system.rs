@@ -5,6 +5,9 @@ loggable! {
         #[error("Rerun the program as administrator")]
         ReRunAsAdmin => tracing::Level::WARN,
 
+        #[error("Running without administrator rights")]
+        RunningUnelevated => tracing::Level::INFO,
+
         #[error("Initializing")]
         Initializing => tracing::Level::INFO,
 
@@ -17,6 +20,27 @@ loggable! {
         #[error("Termination completed")]
         TerminateComplete => tracing::Level::INFO,
 
+        #[error("System clock moved by {seconds} seconds, recalculating schedules")]
+        ClockChanged { seconds: i64 } => tracing::Level::WARN,
+
+        #[error("Moved data from {from} to {to}")]
+        DataRelocated { from: String, to: String } => tracing::Level::INFO,
+
+        #[error("Left data at {path} in place, {location} already holds data")]
+        DataLeftBehind { path: String, location: String } => tracing::Level::WARN,
+
+        #[error("Data at {path} is in use by another instance, it is moved on a later start")]
+        DataRelocationPostponed { path: String } => tracing::Level::WARN,
+
+        #[error("{operation}: {calls} calls, {failed} failed, {average_us}/{max_us} us avg/max")]
+        OperationMetrics {
+            operation: String,
+            calls: u64,
+            failed: u64,
+            average_us: u64,
+            max_us: u64,
+        } => tracing::Level::INFO,
+
         #[error("Gui Exited")]
         GuiExited => tracing::Level::INFO,
     }
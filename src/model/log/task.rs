@@ -4,5 +4,8 @@ loggable! {
     TaskLog {
         #[error("Placeholder")]
         Placeholder => tracing::Level::INFO,
+
+        #[error("Strict mode stopped the run with these directories unfinished: {directories}")]
+        StrictModeStopped { directories: String } => tracing::Level::WARN,
     }
 }
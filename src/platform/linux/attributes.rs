@@ -25,4 +25,7 @@ pub struct Permissions {
     pub is_sticky: bool,
     pub is_setuid: bool,
     pub is_setgid: bool,
+    // Raw security.capability value of binaries given capabilities through setcap
+    pub capability: Option<Vec<u8>>,
+    pub selinux_context: Option<Vec<u8>>,
 }
@@ -0,0 +1,364 @@
+use crate::interface::core::change_journal::ChangeJournalTrait;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::system::SystemError;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CString, OsStr};
+use std::fs;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_ATTRIB
+    | libc::IN_CLOSE_WRITE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF
+    | libc::IN_ONLYDIR
+    | libc::IN_DONT_FOLLOW;
+const READ_BUFFER_SIZE: usize = 65536;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeCursor {
+    pub session: Uuid,
+    pub sequence: u64,
+}
+
+pub struct ChangeJournal {
+    watchers: DashMap<PathBuf, Arc<Watcher>>,
+}
+
+#[async_trait]
+impl ChangeJournalTrait for ChangeJournal {
+    fn new() -> Self {
+        Self {
+            watchers: DashMap::new(),
+        }
+    }
+
+    async fn current_cursor(&self, root: &Path) -> Result<ChangeCursor, Error> {
+        let watcher = match self.watchers.get(root) {
+            Some(watcher) if watcher.is_available() => watcher.clone(),
+            _ => {
+                let root_path = root.to_path_buf();
+                let watcher = spawn_blocking(move || Watcher::start(root_path))
+                    .await
+                    .map_err(SystemError::ThreadPanic)??;
+                self.watchers.insert(root.to_path_buf(), watcher.clone());
+                watcher
+            }
+        };
+        Ok(watcher.cursor())
+    }
+
+    async fn changed_directories(
+        &self,
+        root: &Path,
+        cursor: &ChangeCursor,
+    ) -> Result<HashSet<PathBuf>, Error> {
+        let watcher = self
+            .watchers
+            .get(root)
+            .map(|watcher| watcher.clone())
+            .ok_or(IOError::ChangeJournalUnavailable(root))?;
+        watcher
+            .changed_since(cursor)
+            .ok_or(IOError::ChangeJournalUnavailable(root).into())
+    }
+
+    fn release_unused(&self, in_use: &HashSet<PathBuf>) {
+        self.watchers.retain(|root, watcher| {
+            if in_use.contains(root) {
+                return true;
+            }
+            watcher.stop();
+            false
+        });
+    }
+}
+
+struct Watcher {
+    session: Uuid,
+    // Owned by the event thread, open for as long as the state is available
+    fd: RawFd,
+    state: Mutex<WatchState>,
+}
+
+struct WatchState {
+    available: bool,
+    sequence: u64,
+    valid_from: u64,
+    watches: HashMap<i32, PathBuf>,
+    changed: HashMap<PathBuf, u64>,
+}
+
+impl Watcher {
+    fn start(root: PathBuf) -> Result<Arc<Self>, Error> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            Err(IOError::ReadChangeJournalFailed(
+                &root,
+                io::Error::last_os_error(),
+            ))?
+        }
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut state = WatchState {
+            available: true,
+            sequence: 0,
+            valid_from: 0,
+            watches: HashMap::new(),
+            changed: HashMap::new(),
+        };
+        state
+            .watch_tree(&fd, &root, false)
+            .map_err(|err| IOError::ReadChangeJournalFailed(&root, err))?;
+
+        let watcher = Arc::new(Self {
+            session: Uuid::new_v4(),
+            fd: fd.as_raw_fd(),
+            state: Mutex::new(state),
+        });
+
+        let event_watcher = watcher.clone();
+        thread::Builder::new()
+            .name("change-journal".to_string())
+            .spawn(move || event_watcher.read_events(fd, root))
+            .map_err(SystemError::ThreadSpawnFailed)?;
+
+        Ok(watcher)
+    }
+
+    // Removing the watches queues an IN_IGNORED for each, which wakes the event thread to see the
+    // state unavailable and close the descriptor
+    fn stop(&self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if !state.available {
+            return;
+        }
+        state.available = false;
+        for (wd, _) in state.watches.drain() {
+            unsafe {
+                libc::inotify_rm_watch(self.fd, wd);
+            }
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.available)
+            .unwrap_or(false)
+    }
+
+    fn cursor(&self) -> ChangeCursor {
+        let sequence = self
+            .state
+            .lock()
+            .map(|state| state.sequence)
+            .unwrap_or_default();
+        ChangeCursor {
+            session: self.session,
+            sequence,
+        }
+    }
+
+    fn changed_since(&self, cursor: &ChangeCursor) -> Option<HashSet<PathBuf>> {
+        let state = self.state.lock().ok()?;
+        if !state.available || cursor.session != self.session || cursor.sequence < state.valid_from
+        {
+            return None;
+        }
+        let directories = state
+            .changed
+            .iter()
+            .filter(|(_, sequence)| **sequence > cursor.sequence)
+            .map(|(directory, _)| directory.clone())
+            .collect();
+        Some(directories)
+    }
+
+    fn read_events(&self, fd: OwnedFd, root: PathBuf) {
+        let mut buffer = vec![0_u8; READ_BUFFER_SIZE];
+        loop {
+            let length = unsafe {
+                libc::read(
+                    fd.as_raw_fd(),
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            if length < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                break;
+            }
+
+            let Ok(mut state) = self.state.lock() else {
+                break;
+            };
+
+            let length = length as usize;
+            let mut offset = 0;
+            while offset + size_of::<libc::inotify_event>() <= length {
+                let event = unsafe {
+                    std::ptr::read_unaligned(
+                        buffer.as_ptr().add(offset) as *const libc::inotify_event
+                    )
+                };
+                let name_start = offset + size_of::<libc::inotify_event>();
+                let name_end = name_start + event.len as usize;
+                let name = buffer[name_start..name_end.min(length)]
+                    .split(|&byte| byte == 0)
+                    .next()
+                    .unwrap_or_default();
+                state.handle_event(&fd, &root, &event, OsStr::from_bytes(name));
+                offset = name_end;
+            }
+
+            if !state.available {
+                break;
+            }
+        }
+
+        if let Ok(mut state) = self.state.lock() {
+            state.available = false;
+        }
+    }
+}
+
+impl WatchState {
+    fn handle_event(
+        &mut self,
+        fd: &OwnedFd,
+        root: &Path,
+        event: &libc::inotify_event,
+        name: &OsStr,
+    ) {
+        if event.mask & libc::IN_Q_OVERFLOW != 0 {
+            self.sequence += 1;
+            self.valid_from = self.sequence;
+            return;
+        }
+
+        let Some(directory) = self.watches.get(&event.wd).cloned() else {
+            return;
+        };
+
+        if event.mask & libc::IN_IGNORED != 0 {
+            self.watches.remove(&event.wd);
+            if directory == root {
+                self.available = false;
+            }
+            return;
+        }
+
+        if event.mask & (libc::IN_MOVE_SELF | libc::IN_DELETE_SELF) != 0 {
+            if directory == root {
+                self.available = false;
+            }
+            return;
+        }
+
+        self.mark_changed(directory.clone());
+
+        let is_directory = event.mask & libc::IN_ISDIR != 0 && !name.is_empty();
+        if is_directory && event.mask & libc::IN_MOVED_FROM != 0 {
+            self.unwatch_tree(fd, &directory.join(name));
+        }
+
+        let created = event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0;
+        if is_directory && created {
+            let _ = self.watch_tree(fd, &directory.join(name), true);
+        }
+    }
+
+    fn watch_tree(&mut self, fd: &OwnedFd, root: &Path, mark_changed: bool) -> io::Result<()> {
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(directory) = pending.pop() {
+            let c_path = CString::new(directory.as_os_str().as_bytes())?;
+            let wd =
+                unsafe { libc::inotify_add_watch(fd.as_raw_fd(), c_path.as_ptr(), WATCH_MASK) };
+            if wd < 0 {
+                let err = io::Error::last_os_error();
+                if directory == root || err.raw_os_error() == Some(libc::ENOSPC) {
+                    return Err(err);
+                }
+                continue;
+            }
+            self.watches.insert(wd, directory.clone());
+
+            if mark_changed {
+                self.mark_changed(directory.clone());
+            }
+
+            let Ok(entries) = fs::read_dir(&directory) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    pending.push(entry.path());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unwatch_tree(&mut self, fd: &OwnedFd, directory: &Path) {
+        let stale: Vec<i32> = self
+            .watches
+            .iter()
+            .filter(|(_, path)| path.starts_with(directory))
+            .map(|(wd, _)| *wd)
+            .collect();
+        for wd in stale {
+            self.watches.remove(&wd);
+            unsafe {
+                libc::inotify_rm_watch(fd.as_raw_fd(), wd);
+            }
+        }
+    }
+
+    fn mark_changed(&mut self, directory: PathBuf) {
+        self.sequence += 1;
+        self.changed.insert(directory, self.sequence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn stopped_watcher_ends_its_event_thread() {
+        let root = std::env::temp_dir().join("mirrorsphere_stopped_watcher_ends_its_event_thread");
+        fs::create_dir_all(root.join("nested")).unwrap();
+
+        let watcher = Watcher::start(root.clone()).unwrap();
+        watcher.stop();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Arc::strong_count(&watcher) > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(!watcher.is_available());
+        assert_eq!(Arc::strong_count(&watcher), 1);
+    }
+}
@@ -1,4 +1,14 @@
-pub const DATABASE_PATH: &str = "./mirrorSphere.db";
-pub const DATABASE_URL: &str = "sqlite://./mirrorSphere.db";
-pub const DATABASE_LOCK_PATH: &str = "./db.lock";
-pub const PROGRESS_SAVE_PATH: &str = "./progress";
+pub const CONFIG_PATH: &str = "./config.toml";
+// Where the database and checkpoints were last kept, beside config.toml
+pub const DATA_LOCATIONS_PATH: &str = "./locations.json";
+// Each named profile keeps its config.toml and locations.json in a folder of its own here
+pub const PROFILES_PATH: &str = "./profiles";
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+pub const DATA_LOCATIONS_FILE_NAME: &str = "locations.json";
+pub const LEGACY_DATABASE_PATH: &str = "./mirrorSphere.db";
+pub const LEGACY_PROGRESS_PATH: &str = "./progress";
+pub const LEGACY_JOURNAL_PATH: &str = "./journal";
+pub const DATABASE_FILE_NAME: &str = "mirrorSphere.db";
+pub const PROGRESS_DIRECTORY_NAME: &str = "progress";
+pub const JOURNAL_DIRECTORY_NAME: &str = "journal";
+pub const PROFILES_DIRECTORY_NAME: &str = "profiles";
@@ -3,7 +3,6 @@ use crate::model::error::system::SystemError;
 use std::process::Command;
 use std::env;
 
-#[allow(dead_code)]
 pub fn elevate() -> Result<(), Error> {
     let exe = env::current_exe()
         .map_err(|_| SystemError::RunAsAdminFailed)?;
@@ -13,7 +12,7 @@ pub fn elevate() -> Result<(), Error> {
         .arg("--")
         .arg(exe)
         .args(&args)
-        .status()
+        .spawn()
         .map_err(|_| SystemError::RunAsAdminFailed)?;
 
     Ok(())
@@ -1,34 +1,220 @@
+use crate::core::infrastructure::app_config::AppConfig;
 use crate::interface::core::file_system::FileSystemTrait;
+use crate::interface::core::io_priority::{IOPriorityTrait, background_io};
+use crate::model::core::backup::capability_report::VolumeFeatures;
+use crate::model::core::backup::copy_progress::CopyProgress;
 use crate::model::error::Error;
 use crate::model::error::io::IOError;
 use crate::model::error::system::SystemError;
+use crate::model::log::io::IOLog;
 use crate::platform::attributes::{Attributes, Permissions};
+use crate::platform::io_priority::IOPriority;
+use crate::utils::chunked_copy::{AlignedBuffer, copy_chunks};
 use async_trait::async_trait;
+use io_uring::{IoUring, Probe, opcode, types};
 use libc::mode_t;
-use std::ffi::CString;
-use std::os::unix::fs::MetadataExt;
-use std::path::Path;
+use macros::{log, timed};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::mem;
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::ptr;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::Semaphore;
 use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const IO_URING_QUEUE_DEPTH: usize = 8;
+const IO_URING_CHUNK_SIZE: usize = 131072;
+const DEFAULT_COPY_BUFFER_SIZE: usize = 1048576;
+const COPY_RANGE_CHUNK_SIZE: usize = 67108864;
+const CAPABILITY_XATTR: &CStr = c"security.capability";
+const SELINUX_XATTR: &CStr = c"security.selinux";
+const FS_CASEFOLD_FL: libc::c_int = 0x40000000;
+// FAT and exFAT, neither keeps links, modes or sparse files
+const FOREIGN_FILE_SYSTEMS: [u32; 2] = [0x4d44, 0x2011bab0];
+// NFS, SMB, CIFS, SMB2, FUSE, AFS, Ceph and 9P
+const NETWORK_FILE_SYSTEMS: [u32; 8] = [
+    0x6969, 0x517b, 0xff534d42, 0xfe534d42, 0x65735546, 0x5346414f, 0x00c36400, 0x01021997,
+];
+// fstab types of shares that can be mounted again
+const NETWORK_MOUNT_TYPES: [&str; 5] = ["cifs", "smb3", "nfs", "nfs4", "fuse.sshfs"];
+
+thread_local! {
+    static IO_URING: RefCell<Option<IoUringState>> = const { RefCell::new(None) };
+}
+
+// The buffers belong to the ring, so they live as long as any operation that may use them
+struct IoUringState {
+    ring: IoUring,
+    buffers: Vec<Vec<u8>>,
+    // Set when the ring failed with operations in flight, it is never used or freed again
+    poisoned: bool,
+}
 
 pub struct FileSystem {
-    semaphore: Arc<Semaphore>,
+    metadata_semaphore: Arc<Semaphore>,
+    data_semaphore: Arc<Semaphore>,
+    io_uring: bool,
+    selinux_contexts: bool,
+    reflink: bool,
+    copy_buffer_size: usize,
+    unbuffered_copy_threshold: u64,
+    mapped_compare_threshold: u64,
+    mtime_tolerance: Duration,
 }
 
 #[async_trait]
 impl FileSystemTrait for FileSystem {
-    fn new(semaphore: Arc<Semaphore>) -> Self {
-        Self { semaphore }
+    fn new(
+        app_config: Arc<AppConfig>,
+        metadata_semaphore: Arc<Semaphore>,
+        data_semaphore: Arc<Semaphore>,
+    ) -> Self {
+        let io_uring = app_config.io_uring && Self::io_uring_supported();
+        if app_config.io_uring && !io_uring {
+            log!(IOLog::IoUringUnavailable);
+        }
+        Self {
+            metadata_semaphore,
+            data_semaphore,
+            io_uring,
+            selinux_contexts: app_config.selinux_contexts,
+            reflink: app_config.reflink,
+            copy_buffer_size: app_config.copy_buffer_size,
+            unbuffered_copy_threshold: app_config.unbuffered_copy_threshold,
+            mapped_compare_threshold: app_config.mapped_compare_threshold,
+            mtime_tolerance: Duration::from_millis(app_config.mtime_tolerance),
+        }
+    }
+
+    fn metadata_semaphore(&self) -> Arc<Semaphore> {
+        self.metadata_semaphore.clone()
+    }
+
+    fn data_semaphore(&self) -> Arc<Semaphore> {
+        self.data_semaphore.clone()
     }
 
-    fn semaphore(&self) -> Arc<Semaphore> {
-        self.semaphore.clone()
+    fn mapped_compare_threshold(&self) -> u64 {
+        self.mapped_compare_threshold
     }
 
+    fn mtime_tolerance(&self) -> Duration {
+        self.mtime_tolerance
+    }
+
+    #[timed]
+    async fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error> {
+        self.copy_file_with_progress(source, destination, Arc::new(CopyProgress::new()))
+            .await
+    }
+
+    #[timed]
+    async fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        progress: Arc<CopyProgress>,
+    ) -> Result<(), Error> {
+        let semaphore = self.data_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        if progress.is_cancelled() {
+            Err(IOError::CopyFileCancelled(source))?
+        }
+
+        let length = tokio::fs::metadata(source)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(source, err))?
+            .len();
+        let unbuffered =
+            self.unbuffered_copy_threshold > 0 && length >= self.unbuffered_copy_threshold;
+        // io_uring, a buffer size or unbuffered IO asked for in config.toml take over from
+        // copy_file_range, only a clone that shares extents goes before them
+        let configured = unbuffered || self.io_uring || self.copy_buffer_size > 0;
+        let background = background_io();
+
+        if self.reflink {
+            let source_path = source.to_path_buf();
+            let destination_path = destination.to_path_buf();
+            let copy_progress = progress.clone();
+            let cloned = spawn_blocking(move || {
+                IOPriority::run(background, || {
+                    if configured {
+                        Self::clone_file(&source_path, &destination_path, &copy_progress)
+                    } else {
+                        Ok(Self::clone_file(&source_path, &destination_path, &copy_progress)?
+                            || Self::range_copy(&source_path, &destination_path, &copy_progress)?)
+                    }
+                })
+            })
+            .await
+            .map_err(SystemError::ThreadPanic)?
+            .map_err(|err| {
+                if progress.is_cancelled() {
+                    IOError::CopyFileCancelled(source)
+                } else {
+                    IOError::CopyFileFailed(source, destination, err)
+                }
+            })?;
+            if cloned {
+                return Ok(());
+            }
+        }
+
+        if !unbuffered && !self.io_uring && self.copy_buffer_size == 0 && !background {
+            tokio::fs::copy(source, destination)
+                .await
+                .map_err(|err| IOError::CopyFileFailed(source, destination, err))?;
+            return Ok(());
+        }
+
+        let source_path = source.to_path_buf();
+        let destination_path = destination.to_path_buf();
+        let copy_progress = progress.clone();
+        let io_uring = self.io_uring;
+        let buffer_size = self.copy_buffer_size;
+        spawn_blocking(move || {
+            IOPriority::run(background, || {
+                if io_uring && !unbuffered {
+                    Self::io_uring_copy(&source_path, &destination_path, &copy_progress)
+                } else {
+                    Self::chunked_copy(
+                        &source_path,
+                        &destination_path,
+                        buffer_size,
+                        unbuffered,
+                        &copy_progress,
+                    )
+                }
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+        .map_err(|err| {
+            if progress.is_cancelled() {
+                IOError::CopyFileCancelled(source)
+            } else {
+                IOError::CopyFileFailed(source, destination, err)
+            }
+        })?;
+        Ok(())
+    }
+
+    #[timed]
     async fn copy_symlink(&self, source_link: &Path, destination_link: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -41,8 +227,9 @@ impl FileSystemTrait for FileSystem {
         Ok(())
     }
 
+    #[timed]
     async fn get_attributes(&self, path: &Path) -> Result<Attributes, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -87,8 +274,9 @@ impl FileSystemTrait for FileSystem {
         Ok(attributes)
     }
 
+    #[timed]
     async fn set_attributes(&self, path: &Path, attributes: Attributes) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -121,19 +309,28 @@ impl FileSystemTrait for FileSystem {
         Ok(())
     }
 
+    #[timed]
     async fn get_permission(&self, path: &Path) -> Result<Permissions, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
             .map_err(IOError::SemaphoreClosed)?;
 
         let path_clone = path.to_path_buf();
+        let selinux_contexts = self.selinux_contexts;
 
         let permission = spawn_blocking(move || {
             let path = path_clone;
             let metadata = std::fs::metadata(&path)
                 .map_err(|err| IOError::GetMetadataFailed(path.clone(), err))?;
+            let capability = Self::get_xattr(&path, CAPABILITY_XATTR)
+                .map_err(|err| IOError::GetMetadataFailed(path.clone(), err))?;
+            let selinux_context = match selinux_contexts {
+                true => Self::get_xattr(&path, SELINUX_XATTR)
+                    .map_err(|err| IOError::GetMetadataFailed(path.clone(), err))?,
+                false => None,
+            };
 
             let uid = metadata.uid();
             let gid = metadata.gid();
@@ -146,6 +343,8 @@ impl FileSystemTrait for FileSystem {
                 is_sticky: (mode & libc::S_ISVTX) != 0,
                 is_setuid: (mode & libc::S_ISUID) != 0,
                 is_setgid: (mode & libc::S_ISGID) != 0,
+                capability,
+                selinux_context,
             })
         })
         .await
@@ -154,8 +353,9 @@ impl FileSystemTrait for FileSystem {
         Ok(permission)
     }
 
+    #[timed]
     async fn set_permission(&self, path: &Path, permissions: Permissions) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -189,6 +389,31 @@ impl FileSystemTrait for FileSystem {
                 }
             }
 
+            // Set last, changing the owner drops the capabilities of a file
+            if let Some(capability) = &permissions.capability
+                && let Err(err) = Self::set_xattr(&c_path, CAPABILITY_XATTR, capability)
+            {
+                match err.raw_os_error() {
+                    Some(libc::EOPNOTSUPP | libc::EPERM) => log!(IOLog::CapabilityNotPreserved {
+                        path: path.display().to_string(),
+                    }),
+                    _ => Err(IOError::SetMetadataFailed(path.clone(), err))?,
+                }
+            }
+            // Policies refusing the relabel answer with EACCES
+            if let Some(context) = &permissions.selinux_context
+                && let Err(err) = Self::set_xattr(&c_path, SELINUX_XATTR, context)
+            {
+                match err.raw_os_error() {
+                    Some(libc::EOPNOTSUPP | libc::EPERM | libc::EACCES) => {
+                        log!(IOLog::SelinuxContextNotPreserved {
+                            path: path.display().to_string(),
+                        })
+                    }
+                    _ => Err(IOError::SetMetadataFailed(path, err))?,
+                }
+            }
+
             Ok::<(), Error>(())
         })
         .await
@@ -199,6 +424,47 @@ impl FileSystemTrait for FileSystem {
 }
 
 impl FileSystem {
+    fn get_xattr(path: &Path, name: &CStr) -> io::Result<Option<Vec<u8>>> {
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let mut value = vec![0_u8; 64];
+        loop {
+            let size = unsafe {
+                libc::getxattr(
+                    c_path.as_ptr(),
+                    name.as_ptr(),
+                    value.as_mut_ptr() as *mut libc::c_void,
+                    value.len(),
+                )
+            };
+            if size >= 0 {
+                value.truncate(size as usize);
+                return Ok(Some(value));
+            }
+            let err = io::Error::last_os_error();
+            match err.raw_os_error() {
+                Some(libc::ENODATA | libc::EOPNOTSUPP) => return Ok(None),
+                Some(libc::ERANGE) => value.resize(value.len() * 2, 0),
+                _ => return Err(err),
+            }
+        }
+    }
+
+    fn set_xattr(c_path: &CString, name: &CStr, value: &[u8]) -> io::Result<()> {
+        let result = unsafe {
+            libc::setxattr(
+                c_path.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr() as *const libc::c_void,
+                value.len(),
+                0,
+            )
+        };
+        match result {
+            0 => Ok(()),
+            _ => Err(io::Error::last_os_error()),
+        }
+    }
+
     fn set_file_times(path: &Path, attributes: &Attributes) -> Result<(), Error> {
         let c_path = CString::new(path.to_string_lossy().as_bytes())
             .map_err(|err| IOError::SetMetadataFailed(path, err))?;
@@ -227,4 +493,399 @@ impl FileSystem {
             tv_nsec: duration.subsec_nanos() as libc::c_long,
         })
     }
+
+    // Shares extents on btrfs and XFS, false where the file system cannot clone, a real IO
+    // problem then shows up in the copy that follows
+    fn clone_file(source: &Path, destination: &Path, progress: &CopyProgress) -> io::Result<bool> {
+        let (source_file, destination_file, length) = Self::open_pair(source, destination)?;
+        let source_fd = source_file.as_raw_fd();
+        let destination_fd = destination_file.as_raw_fd();
+        if unsafe { libc::ioctl(destination_fd, libc::FICLONE, source_fd) } == 0 {
+            progress.update(length, length);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    // Keeps the copy in kernel, false where copy_file_range is not available between the two
+    fn range_copy(source: &Path, destination: &Path, progress: &CopyProgress) -> io::Result<bool> {
+        let (source_file, destination_file, length) = Self::open_pair(source, destination)?;
+        let source_fd = source_file.as_raw_fd();
+        let destination_fd = destination_file.as_raw_fd();
+        let mut copied = 0_u64;
+        while copied < length {
+            let chunk_size = COPY_RANGE_CHUNK_SIZE.min((length - copied) as usize);
+            let result = unsafe {
+                libc::copy_file_range(
+                    source_fd,
+                    ptr::null_mut(),
+                    destination_fd,
+                    ptr::null_mut(),
+                    chunk_size,
+                    0,
+                )
+            };
+            if result < 0 {
+                let err = io::Error::last_os_error();
+                return match err.raw_os_error() {
+                    Some(libc::EXDEV | libc::ENOSYS | libc::EOPNOTSUPP | libc::EINVAL)
+                        if copied == 0 =>
+                    {
+                        Ok(false)
+                    }
+                    _ => Err(err),
+                };
+            }
+            if result == 0 {
+                Err(io::Error::from(io::ErrorKind::UnexpectedEof))?
+            }
+
+            copied += result as u64;
+            progress.update(copied, length);
+            if progress.is_cancelled() {
+                Err(io::Error::from(io::ErrorKind::Interrupted))?
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn open_pair(source: &Path, destination: &Path) -> io::Result<(File, File, u64)> {
+        let source_file = File::open(source)?;
+        let metadata = source_file.metadata()?;
+        let destination_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(destination)?;
+        destination_file.set_permissions(metadata.permissions())?;
+        Ok((source_file, destination_file, metadata.len()))
+    }
+
+    fn chunked_copy(
+        source: &Path,
+        destination: &Path,
+        buffer_size: usize,
+        unbuffered: bool,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let mut source_file = File::open(source)?;
+        let metadata = source_file.metadata()?;
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        let mut destination_file = if unbuffered {
+            match options.clone().custom_flags(libc::O_DIRECT).open(destination) {
+                Err(err) if err.raw_os_error() == Some(libc::EINVAL) => options.open(destination)?,
+                result => result?,
+            }
+        } else {
+            options.open(destination)?
+        };
+        destination_file.set_permissions(metadata.permissions())?;
+
+        let buffer_size = match buffer_size {
+            0 => DEFAULT_COPY_BUFFER_SIZE,
+            buffer_size => buffer_size,
+        };
+        let mut buffer = AlignedBuffer::new(buffer_size);
+        copy_chunks(
+            &mut source_file,
+            &mut destination_file,
+            &mut buffer,
+            metadata.len(),
+            progress,
+            |file| match unbuffered {
+                true => Self::clear_direct_io(file),
+                false => Ok(()),
+            },
+        )
+    }
+
+    // O_DIRECT rejects the unaligned tail of a file, finish it through the page cache
+    fn clear_direct_io(file: &File) -> io::Result<()> {
+        let fd = file.as_raw_fd();
+        unsafe {
+            let flags = libc::fcntl(fd, libc::F_GETFL);
+            if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_DIRECT) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    fn io_uring_supported() -> bool {
+        let Ok(ring) = IoUring::new(IO_URING_QUEUE_DEPTH as u32) else {
+            return false;
+        };
+        let mut probe = Probe::new();
+        if ring.submitter().register_probe(&mut probe).is_err() {
+            return false;
+        }
+        probe.is_supported(opcode::Read::CODE) && probe.is_supported(opcode::Write::CODE)
+    }
+
+    fn io_uring_copy(
+        source: &Path,
+        destination: &Path,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let source_file = File::open(source)?;
+        let metadata = source_file.metadata()?;
+        let destination_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(destination)?;
+        destination_file.set_permissions(metadata.permissions())?;
+
+        IO_URING.with_borrow_mut(|state| {
+            let ring_state = match state {
+                Some(ring_state) => ring_state,
+                None => state.insert(IoUringState {
+                    ring: IoUring::new(IO_URING_QUEUE_DEPTH as u32)?,
+                    buffers: vec![vec![0_u8; IO_URING_CHUNK_SIZE]; IO_URING_QUEUE_DEPTH],
+                    poisoned: false,
+                }),
+            };
+            let result = Self::io_uring_transfer(
+                ring_state,
+                &source_file,
+                &destination_file,
+                metadata.len(),
+                progress,
+            );
+            // Operations may still be in flight, the kernel can write into the buffers at any
+            // time, so the ring and its buffers are leaked rather than freed
+            if ring_state.poisoned {
+                mem::forget(state.take());
+            }
+            result
+        })
+    }
+
+    // Reads a batch of chunks in parallel, then writes the batch back at the same offsets
+    fn io_uring_transfer(
+        state: &mut IoUringState,
+        source: &File,
+        destination: &File,
+        length: u64,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let source_fd = types::Fd(source.as_raw_fd());
+        let destination_fd = types::Fd(destination.as_raw_fd());
+
+        let mut offset = 0_u64;
+        while offset < length {
+            let chunk_lengths: Vec<usize> = (0..IO_URING_QUEUE_DEPTH)
+                .map(|index| offset + (index * IO_URING_CHUNK_SIZE) as u64)
+                .take_while(|chunk_offset| *chunk_offset < length)
+                .map(|chunk_offset| {
+                    (length - chunk_offset).min(IO_URING_CHUNK_SIZE as u64) as usize
+                })
+                .collect();
+            Self::io_uring_chunks(state, source_fd, offset, &chunk_lengths, false)?;
+            Self::io_uring_chunks(state, destination_fd, offset, &chunk_lengths, true)?;
+
+            offset += chunk_lengths.iter().sum::<usize>() as u64;
+            progress.update(offset, length);
+            if progress.is_cancelled() {
+                Err(io::Error::from(io::ErrorKind::Interrupted))?
+            }
+        }
+
+        Ok(())
+    }
+
+    // Chunks transferred short are submitted again for their remainder until they are complete
+    fn io_uring_chunks(
+        state: &mut IoUringState,
+        fd: types::Fd,
+        offset: u64,
+        chunk_lengths: &[usize],
+        write: bool,
+    ) -> io::Result<()> {
+        let mut transferred = vec![0_usize; chunk_lengths.len()];
+        let mut pending: Vec<usize> = (0..chunk_lengths.len()).collect();
+        while !pending.is_empty() {
+            let mut submitted = 0;
+            let mut failure = None;
+            for &index in &pending {
+                let done = transferred[index];
+                let remaining = (chunk_lengths[index] - done) as u32;
+                let position = offset + (index * IO_URING_CHUNK_SIZE + done) as u64;
+                let buffer = state.buffers[index][done..].as_mut_ptr();
+                let entry = match write {
+                    true => opcode::Write::new(fd, buffer, remaining)
+                        .offset(position)
+                        .build(),
+                    false => opcode::Read::new(fd, buffer, remaining)
+                        .offset(position)
+                        .build(),
+                };
+                let entry = entry.user_data(index as u64);
+                if let Err(err) = unsafe { state.ring.submission().push(&entry) } {
+                    failure = Some(io::Error::other(err));
+                    break;
+                }
+                submitted += 1;
+            }
+            // Whatever was queued completes before returning, the buffers must outlive it
+            let results = Self::io_uring_complete(state, submitted);
+            if let Some(err) = failure {
+                return Err(err);
+            }
+            for (index, result) in results? {
+                match result {
+                    0 if write => Err(io::Error::from(io::ErrorKind::WriteZero))?,
+                    0 => Err(io::Error::from(io::ErrorKind::UnexpectedEof))?,
+                    result => transferred[index] += result,
+                }
+            }
+            pending.retain(|&index| transferred[index] < chunk_lengths[index]);
+        }
+        Ok(())
+    }
+
+    // Waits for every submitted operation even after one failed, returning early would free
+    // buffers the kernel still writes into
+    fn io_uring_complete(
+        state: &mut IoUringState,
+        count: usize,
+    ) -> io::Result<Vec<(usize, usize)>> {
+        let mut results = Vec::with_capacity(count);
+        let mut failure = None;
+        while results.len() < count && !state.poisoned {
+            if state.ring.completion().is_empty() {
+                match state.ring.submit_and_wait(1) {
+                    Ok(_) => {}
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) if err.raw_os_error() == Some(libc::EBUSY) => continue,
+                    Err(err) => {
+                        state.poisoned = true;
+                        failure = Some(err);
+                    }
+                }
+            }
+            for entry in state.ring.completion() {
+                let result = entry.result();
+                if result < 0 {
+                    failure.get_or_insert(io::Error::from_raw_os_error(-result));
+                }
+                results.push((entry.user_data() as usize, result.max(0) as usize));
+            }
+        }
+
+        match failure {
+            Some(err) => Err(err),
+            None => Ok(results),
+        }
+    }
+}
+
+fn volume_stat(path: &Path) -> Option<libc::statvfs> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat)
+}
+
+// Shares listed in fstab are mounted again through mount(8), which takes their credentials
+// file and options from there
+pub fn reconnect_share(path: &Path) -> bool {
+    let Ok(fstab) = std::fs::read_to_string("/etc/fstab") else {
+        return false;
+    };
+    let mount_point = fstab
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let spec = fields.next()?;
+            let directory = fields.next()?;
+            let fs_type = fields.next()?;
+            let network = !spec.starts_with('#') && NETWORK_MOUNT_TYPES.contains(&fs_type);
+            network.then(|| PathBuf::from(directory.replace("\\040", " ")))
+        })
+        .filter(|directory| path.starts_with(directory))
+        .max_by_key(|directory| directory.components().count());
+    let Some(mount_point) = mount_point else {
+        return false;
+    };
+    // A dropped share lingers as a dead mount until it is detached
+    let _ = Command::new("umount").arg("-l").arg(&mount_point).status();
+    Command::new("mount")
+        .arg(&mount_point)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+// Relative paths resolve against the working directory, the same way for every caller
+pub fn normalize_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Mount points do not move between sessions here, destinations are used as given
+pub fn resolve_volume_path(path: &Path) -> Option<PathBuf> {
+    Some(path.to_path_buf())
+}
+
+// The statvfs field widths differ between 32 and 64 bit targets
+#[allow(clippy::unnecessary_cast)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    let stat = volume_stat(path)?;
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+pub fn is_read_only_volume(path: &Path) -> bool {
+    volume_stat(path).is_some_and(|stat| stat.f_flag & libc::ST_RDONLY != 0)
+}
+
+pub fn supports_symlinks(directory: &Path) -> bool {
+    let link = directory.join(format!(".mirrorsphere-link-{}", Uuid::new_v4().simple()));
+    let created = std::os::unix::fs::symlink(directory, &link).is_ok();
+    let _ = std::fs::remove_file(&link);
+    created
+}
+
+// Locks are advisory here and never keep a plain read from going through
+pub fn is_locked(_path: &Path) -> bool {
+    false
+}
+
+pub fn volume_features(path: &Path) -> VolumeFeatures {
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return VolumeFeatures::default();
+    };
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    let posix = unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } == 0
+        && !FOREIGN_FILE_SYSTEMS.contains(&(stat.f_type as u32));
+    // Listing succeeds wherever xattrs are kept, even when the entry has none
+    let xattrs = unsafe { libc::listxattr(c_path.as_ptr(), ptr::null_mut(), 0) } >= 0;
+    VolumeFeatures {
+        symlinks: posix,
+        hardlinks: posix,
+        xattrs,
+        permissions: posix,
+        sparse: posix,
+    }
+}
+
+// Directories with the casefold attribute fold names on an otherwise case-sensitive ext4 or f2fs
+pub fn directory_case_insensitive(directory: &Path) -> Option<bool> {
+    let directory = File::open(directory).ok()?;
+    let mut flags: libc::c_int = 0;
+    if unsafe { libc::ioctl(directory.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) } != 0 {
+        return None;
+    }
+    (flags & FS_CASEFOLD_FL != 0).then_some(true)
+}
+
+pub fn is_local_file(file: &File) -> bool {
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::fstatfs(file.as_raw_fd(), &mut stat) } != 0 {
+        return false;
+    }
+    !NETWORK_FILE_SYSTEMS.contains(&(stat.f_type as u32))
 }
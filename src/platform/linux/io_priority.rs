@@ -0,0 +1,103 @@
+use crate::interface::core::io_priority::{IOPriorityTrait, ProcessWeightTrait};
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+const BACKGROUND_NICE: libc::c_int = 19;
+const BACKGROUND_CGROUP_WEIGHT: u32 = 10;
+const CGROUP_WEIGHT_FILES: [&str; 2] = ["cpu.weight", "io.weight"];
+
+// ioprio_set with IOPRIO_WHO_PROCESS and 0 targets the calling thread only, as does
+// setpriority with the thread id
+pub struct IOPriority {
+    previous: libc::c_long,
+    previous_nice: libc::c_int,
+}
+
+impl IOPriorityTrait for IOPriority {
+    fn enter_background() -> Result<Self, Error> {
+        let previous = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) };
+        if previous < 0 {
+            Err(IOError::SetIOPriorityFailed(io::Error::last_os_error()))?
+        }
+        let idle = IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT;
+        if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, idle) } < 0 {
+            Err(IOError::SetIOPriorityFailed(io::Error::last_os_error()))?
+        }
+        // Pooled threads go on to run other work, so the nice value is only raised where it can
+        // be lowered back, as root or within RLIMIT_NICE
+        let thread_id = unsafe { libc::gettid() } as libc::id_t;
+        let previous_nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, thread_id) };
+        if can_renice(previous_nice) {
+            unsafe { libc::setpriority(libc::PRIO_PROCESS, thread_id, BACKGROUND_NICE) };
+        }
+        Ok(Self {
+            previous,
+            previous_nice,
+        })
+    }
+}
+
+impl Drop for IOPriority {
+    fn drop(&mut self) {
+        unsafe {
+            libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, self.previous);
+            let thread_id = libc::gettid() as libc::id_t;
+            libc::setpriority(libc::PRIO_PROCESS, thread_id, self.previous_nice);
+        }
+    }
+}
+
+fn can_renice(nice: libc::c_int) -> bool {
+    if unsafe { libc::geteuid() } == 0 {
+        return true;
+    }
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NICE, &mut limit) } < 0 {
+        return false;
+    }
+    // RLIMIT_NICE caps the nice value at 20 - rlim_cur
+    (20 - nice) as libc::rlim_t <= limit.rlim_cur
+}
+
+// cgroup v2 weights of the cgroup the process runs in, writable only once it was delegated,
+// as in a systemd unit with Delegate=yes
+pub struct ProcessWeight {
+    previous: Vec<(PathBuf, String)>,
+}
+
+impl ProcessWeightTrait for ProcessWeight {
+    fn lower() -> Option<Self> {
+        let cgroups = fs::read_to_string("/proc/self/cgroup").ok()?;
+        let relative = cgroups.lines().find_map(|line| line.strip_prefix("0::"))?;
+        let directory = Path::new("/sys/fs/cgroup").join(relative.trim_start_matches('/'));
+
+        let mut previous = Vec::new();
+        for file in CGROUP_WEIGHT_FILES {
+            let path = directory.join(file);
+            let Ok(value) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if fs::write(&path, BACKGROUND_CGROUP_WEIGHT.to_string()).is_ok() {
+                previous.push((path, value.trim().to_string()));
+            }
+        }
+        (!previous.is_empty()).then_some(Self { previous })
+    }
+}
+
+impl Drop for ProcessWeight {
+    fn drop(&mut self) {
+        for (path, value) in &self.previous {
+            let _ = fs::write(path, value);
+        }
+    }
+}
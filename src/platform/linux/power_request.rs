@@ -0,0 +1,35 @@
+use crate::interface::core::power_request::PowerRequestTrait;
+use crate::model::error::Error;
+use crate::model::error::system::SystemError;
+use std::process::{Child, Command, Stdio};
+
+// logind holds the inhibitor lock for as long as the systemd-inhibit child lives
+pub struct PowerRequest {
+    inhibitor: Child,
+}
+
+impl PowerRequestTrait for PowerRequest {
+    fn acquire(allow_display_sleep: bool) -> Result<Self, Error> {
+        let what = match allow_display_sleep {
+            true => "sleep",
+            false => "sleep:idle",
+        };
+        let inhibitor = Command::new("systemd-inhibit")
+            .arg(format!("--what={what}"))
+            .args(["--who=MirrorSphere", "--why=Backup running", "--mode=block"])
+            .args(["sleep", "infinity"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(SystemError::PowerRequestFailed)?;
+        Ok(Self { inhibitor })
+    }
+}
+
+impl Drop for PowerRequest {
+    fn drop(&mut self) {
+        let _ = self.inhibitor.kill();
+        let _ = self.inhibitor.wait();
+    }
+}
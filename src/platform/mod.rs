@@ -1,9 +1,13 @@
 #[cfg(target_os = "linux")]
 mod linux;
+#[cfg(all(unix, not(target_os = "linux")))]
+mod unix;
 #[cfg(target_os = "windows")]
 mod windows;
 
 #[cfg(target_os = "linux")]
 pub use linux::*;
+#[cfg(all(unix, not(target_os = "linux")))]
+pub use unix::*;
 #[cfg(target_os = "windows")]
 pub use windows::*;
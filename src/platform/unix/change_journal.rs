@@ -0,0 +1,36 @@
+use crate::interface::core::change_journal::ChangeJournalTrait;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeCursor {
+    pub session: Uuid,
+    pub sequence: u64,
+}
+
+// Without inotify or a journal every incremental run scans the whole source
+pub struct ChangeJournal;
+
+#[async_trait]
+impl ChangeJournalTrait for ChangeJournal {
+    fn new() -> Self {
+        Self
+    }
+
+    async fn current_cursor(&self, root: &Path) -> Result<ChangeCursor, Error> {
+        Err(IOError::ChangeJournalUnavailable(root))?
+    }
+
+    async fn changed_directories(
+        &self,
+        root: &Path,
+        _cursor: &ChangeCursor,
+    ) -> Result<HashSet<PathBuf>, Error> {
+        Err(IOError::ChangeJournalUnavailable(root))?
+    }
+}
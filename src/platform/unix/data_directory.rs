@@ -0,0 +1,12 @@
+use std::env;
+use std::path::PathBuf;
+
+// $XDG_DATA_HOME/MirrorSphere, ~/.local/share/MirrorSphere when it is unset
+pub fn data_directory() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|data_home| !data_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|data_home| data_home.join("MirrorSphere"))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
@@ -0,0 +1,467 @@
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::interface::core::file_system::FileSystemTrait;
+use crate::interface::core::io_priority::{IOPriorityTrait, background_io};
+use crate::model::core::backup::capability_report::VolumeFeatures;
+use crate::model::core::backup::copy_progress::CopyProgress;
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::model::error::system::SystemError;
+use crate::platform::attributes::{Attributes, Permissions};
+use crate::platform::io_priority::IOPriority;
+use crate::utils::chunked_copy::{AlignedBuffer, copy_chunks};
+use async_trait::async_trait;
+use libc::mode_t;
+use macros::timed;
+use std::ffi::CString;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Semaphore;
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const DEFAULT_COPY_BUFFER_SIZE: usize = 1048576;
+// fstab types of shares that can be mounted again
+const NETWORK_MOUNT_TYPES: [&str; 3] = ["nfs", "smbfs", "fusefs.sshfs"];
+
+pub struct FileSystem {
+    metadata_semaphore: Arc<Semaphore>,
+    data_semaphore: Arc<Semaphore>,
+    copy_buffer_size: usize,
+    unbuffered_copy_threshold: u64,
+    mapped_compare_threshold: u64,
+    mtime_tolerance: Duration,
+}
+
+#[async_trait]
+impl FileSystemTrait for FileSystem {
+    fn new(
+        app_config: Arc<AppConfig>,
+        metadata_semaphore: Arc<Semaphore>,
+        data_semaphore: Arc<Semaphore>,
+    ) -> Self {
+        Self {
+            metadata_semaphore,
+            data_semaphore,
+            copy_buffer_size: app_config.copy_buffer_size,
+            unbuffered_copy_threshold: app_config.unbuffered_copy_threshold,
+            mapped_compare_threshold: app_config.mapped_compare_threshold,
+            mtime_tolerance: Duration::from_millis(app_config.mtime_tolerance),
+        }
+    }
+
+    fn metadata_semaphore(&self) -> Arc<Semaphore> {
+        self.metadata_semaphore.clone()
+    }
+
+    fn data_semaphore(&self) -> Arc<Semaphore> {
+        self.data_semaphore.clone()
+    }
+
+    fn mapped_compare_threshold(&self) -> u64 {
+        self.mapped_compare_threshold
+    }
+
+    fn mtime_tolerance(&self) -> Duration {
+        self.mtime_tolerance
+    }
+
+    #[timed]
+    async fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error> {
+        self.copy_file_with_progress(source, destination, Arc::new(CopyProgress::new()))
+            .await
+    }
+
+    #[timed]
+    async fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        progress: Arc<CopyProgress>,
+    ) -> Result<(), Error> {
+        let semaphore = self.data_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        if progress.is_cancelled() {
+            Err(IOError::CopyFileCancelled(source))?
+        }
+
+        let length = tokio::fs::metadata(source)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(source, err))?
+            .len();
+        let unbuffered =
+            self.unbuffered_copy_threshold > 0 && length >= self.unbuffered_copy_threshold;
+        let background = background_io();
+
+        if !unbuffered && self.copy_buffer_size == 0 && !background {
+            tokio::fs::copy(source, destination)
+                .await
+                .map_err(|err| IOError::CopyFileFailed(source, destination, err))?;
+            return Ok(());
+        }
+
+        let source_path = source.to_path_buf();
+        let destination_path = destination.to_path_buf();
+        let copy_progress = progress.clone();
+        let buffer_size = self.copy_buffer_size;
+        spawn_blocking(move || {
+            IOPriority::run(background, || {
+                Self::chunked_copy(&source_path, &destination_path, buffer_size, &copy_progress)
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+        .map_err(|err| {
+            if progress.is_cancelled() {
+                IOError::CopyFileCancelled(source)
+            } else {
+                IOError::CopyFileFailed(source, destination, err)
+            }
+        })?;
+        Ok(())
+    }
+
+    #[timed]
+    async fn copy_symlink(&self, source_link: &Path, destination_link: &Path) -> Result<(), Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        tokio::fs::symlink(source_link, destination_link)
+            .await
+            .map_err(|err| IOError::CreateSymbolLinkFailed(source_link, destination_link, err))?;
+
+        Ok(())
+    }
+
+    // mode_t is 16 bits wide on FreeBSD and macOS
+    #[allow(clippy::unnecessary_cast)]
+    #[timed]
+    async fn get_attributes(&self, path: &Path) -> Result<Attributes, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+
+        let mode = metadata.mode();
+        let file_type = metadata.file_type();
+
+        let mut attributes = 0_u32;
+
+        if file_type.is_dir() {
+            attributes |= libc::S_IFDIR as u32;
+        } else if file_type.is_file() {
+            attributes |= libc::S_IFREG as u32;
+        } else if file_type.is_symlink() {
+            attributes |= libc::S_IFLNK as u32;
+        }
+
+        attributes |= mode & 0o777;
+
+        let creation_time = metadata
+            .created()
+            .unwrap_or_else(|_| metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+        let last_access_time = metadata
+            .accessed()
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+        let change_time = metadata
+            .modified()
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+
+        let attributes = Attributes {
+            attributes,
+            creation_time,
+            last_access_time,
+            change_time,
+        };
+
+        Ok(attributes)
+    }
+
+    #[timed]
+    async fn set_attributes(&self, path: &Path, attributes: Attributes) -> Result<(), Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let path_clone = path.to_path_buf();
+        let mode = attributes.attributes & 0o7777;
+
+        spawn_blocking(move || {
+            let path = path_clone;
+            let c_path = CString::new(path.to_string_lossy().as_bytes())
+                .map_err(|err| IOError::SetMetadataFailed(path.clone(), err))?;
+
+            unsafe {
+                if libc::chmod(c_path.as_ptr(), mode as mode_t) != 0 {
+                    Err(IOError::SetMetadataFailed(
+                        path.clone(),
+                        "libc chmod failed",
+                    ))?;
+                }
+            }
+
+            Self::set_file_times(&path, &attributes)?;
+
+            Ok::<(), Error>(())
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)??;
+
+        Ok(())
+    }
+
+    // mode_t is 16 bits wide on FreeBSD and macOS
+    #[allow(clippy::unnecessary_cast)]
+    #[timed]
+    async fn get_permission(&self, path: &Path) -> Result<Permissions, Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let path_clone = path.to_path_buf();
+
+        let permission = spawn_blocking(move || {
+            let path = path_clone;
+            let metadata = std::fs::metadata(&path)
+                .map_err(|err| IOError::GetMetadataFailed(path.clone(), err))?;
+
+            let uid = metadata.uid();
+            let gid = metadata.gid();
+            let mode = metadata.mode();
+
+            Ok::<Permissions, Error>(Permissions {
+                uid,
+                gid,
+                mode,
+                is_sticky: (mode & libc::S_ISVTX as u32) != 0,
+                is_setuid: (mode & libc::S_ISUID as u32) != 0,
+                is_setgid: (mode & libc::S_ISGID as u32) != 0,
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)??;
+
+        Ok(permission)
+    }
+
+    // mode_t is 16 bits wide on FreeBSD and macOS
+    #[allow(clippy::unnecessary_cast)]
+    #[timed]
+    async fn set_permission(&self, path: &Path, permissions: Permissions) -> Result<(), Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let path_clone = path.to_path_buf();
+
+        spawn_blocking(move || {
+            let path = path_clone;
+            let c_path: CString = CString::new(path.to_string_lossy().as_bytes())
+                .map_err(|err| Error::IO(IOError::SetMetadataFailed(path.clone(), err)))?;
+
+            unsafe {
+                if libc::chown(c_path.as_ptr(), permissions.uid, permissions.gid) != 0 {
+                    return Err(IOError::SetMetadataFailed(path, "libc chmod failed").into());
+                }
+
+                let mut mode = permissions.mode & 0o7777;
+                if permissions.is_sticky {
+                    mode |= libc::S_ISVTX as u32;
+                }
+                if permissions.is_setuid {
+                    mode |= libc::S_ISUID as u32;
+                }
+                if permissions.is_setgid {
+                    mode |= libc::S_ISGID as u32;
+                }
+
+                if libc::chmod(c_path.as_ptr(), mode as mode_t) != 0 {
+                    return Err(IOError::SetMetadataFailed(path, "libc chmod failed").into());
+                }
+            }
+
+            Ok::<(), Error>(())
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)??;
+
+        Ok(())
+    }
+}
+
+impl FileSystem {
+    fn set_file_times(path: &Path, attributes: &Attributes) -> Result<(), Error> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|err| IOError::SetMetadataFailed(path, err))?;
+
+        let access_time = Self::system_time_to_timespec(attributes.last_access_time)?;
+        let modify_time = Self::system_time_to_timespec(attributes.change_time)?;
+
+        let times = [access_time, modify_time];
+
+        unsafe {
+            if libc::utimensat(libc::AT_FDCWD, c_path.as_ptr(), times.as_ptr(), 0) != 0 {
+                Err(IOError::SetMetadataFailed(path, "libc utimensat failed"))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn system_time_to_timespec(system_time: SystemTime) -> Result<libc::timespec, Error> {
+        let duration = system_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(SystemError::UnexpectError)?;
+
+        Ok(libc::timespec {
+            tv_sec: duration.as_secs() as libc::time_t,
+            tv_nsec: duration.subsec_nanos() as libc::c_long,
+        })
+    }
+
+    // O_DIRECT is not available everywhere, so large files go through the page cache as well
+    fn chunked_copy(
+        source: &Path,
+        destination: &Path,
+        buffer_size: usize,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let mut source_file = File::open(source)?;
+        let metadata = source_file.metadata()?;
+        let mut destination_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(destination)?;
+        destination_file.set_permissions(metadata.permissions())?;
+
+        let buffer_size = match buffer_size {
+            0 => DEFAULT_COPY_BUFFER_SIZE,
+            buffer_size => buffer_size,
+        };
+        let mut buffer = AlignedBuffer::new(buffer_size);
+        copy_chunks(
+            &mut source_file,
+            &mut destination_file,
+            &mut buffer,
+            metadata.len(),
+            progress,
+            |_| Ok(()),
+        )
+    }
+}
+
+fn volume_stat(path: &Path) -> Option<libc::statvfs> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat)
+}
+
+// Shares listed in fstab are mounted again through mount(8), which takes their credentials
+// file and options from there
+pub fn reconnect_share(path: &Path) -> bool {
+    let Ok(fstab) = std::fs::read_to_string("/etc/fstab") else {
+        return false;
+    };
+    let mount_point = fstab
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let spec = fields.next()?;
+            let directory = fields.next()?;
+            let fs_type = fields.next()?;
+            let network = !spec.starts_with('#') && NETWORK_MOUNT_TYPES.contains(&fs_type);
+            network.then(|| PathBuf::from(directory.replace("\\040", " ")))
+        })
+        .filter(|directory| path.starts_with(directory))
+        .max_by_key(|directory| directory.components().count());
+    let Some(mount_point) = mount_point else {
+        return false;
+    };
+    // A dropped share lingers as a dead mount until it is detached
+    let _ = Command::new("umount").arg("-f").arg(&mount_point).status();
+    Command::new("mount")
+        .arg(&mount_point)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+// Relative paths resolve against the working directory, the same way for every caller
+pub fn normalize_path(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Mount points do not move between sessions here, destinations are used as given
+pub fn resolve_volume_path(path: &Path) -> Option<PathBuf> {
+    Some(path.to_path_buf())
+}
+
+// The statvfs field widths differ between 32 and 64 bit targets
+#[allow(clippy::unnecessary_cast)]
+pub fn available_space(path: &Path) -> Option<u64> {
+    let stat = volume_stat(path)?;
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+pub fn is_read_only_volume(path: &Path) -> bool {
+    volume_stat(path).is_some_and(|stat| stat.f_flag & libc::ST_RDONLY != 0)
+}
+
+pub fn supports_symlinks(directory: &Path) -> bool {
+    let link = directory.join(format!(".mirrorsphere-link-{}", Uuid::new_v4().simple()));
+    let created = std::os::unix::fs::symlink(directory, &link).is_ok();
+    let _ = std::fs::remove_file(&link);
+    created
+}
+
+// Locks are advisory here and never keep a plain read from going through
+pub fn is_locked(_path: &Path) -> bool {
+    false
+}
+
+// statfs differs too much between the BSDs to tell FAT mounts apart, a POSIX volume is assumed
+pub fn volume_features(_path: &Path) -> VolumeFeatures {
+    VolumeFeatures {
+        symlinks: true,
+        hardlinks: true,
+        xattrs: cfg!(any(target_os = "macos", target_os = "freebsd")),
+        permissions: true,
+        sparse: true,
+    }
+}
+
+// No per-directory case rule to read here, the whole volume follows one
+pub fn directory_case_insensitive(_directory: &Path) -> Option<bool> {
+    None
+}
+
+// statfs differs too much between the BSDs to tell network mounts apart, so no file is mapped
+pub fn is_local_file(_file: &File) -> bool {
+    false
+}
@@ -0,0 +1,20 @@
+use crate::interface::core::io_priority::{IOPriorityTrait, ProcessWeightTrait};
+use crate::model::error::Error;
+
+// POSIX has no per-thread IO priority, background runs copy at the normal priority here
+pub struct IOPriority;
+
+impl IOPriorityTrait for IOPriority {
+    fn enter_background() -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+// Without cgroups there is no process wide weight to lower
+pub struct ProcessWeight;
+
+impl ProcessWeightTrait for ProcessWeight {
+    fn lower() -> Option<Self> {
+        None
+    }
+}
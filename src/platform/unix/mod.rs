@@ -0,0 +1,8 @@
+pub mod attributes;
+pub mod change_journal;
+pub mod constants;
+pub mod data_directory;
+pub mod elevate;
+pub mod file_system;
+pub mod io_priority;
+pub mod power_request;
@@ -0,0 +1,11 @@
+use crate::interface::core::power_request::PowerRequestTrait;
+use crate::model::error::Error;
+
+// There is no common sleep inhibitor across the BSDs, the system may still sleep during a run
+pub struct PowerRequest;
+
+impl PowerRequestTrait for PowerRequest {
+    fn acquire(_allow_display_sleep: bool) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
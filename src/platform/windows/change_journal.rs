@@ -0,0 +1,248 @@
+use crate::interface::core::change_journal::ChangeJournalTrait;
+use crate::model::error::io::IOError;
+use crate::model::error::system::SystemError;
+use crate::model::error::Error;
+use crate::platform::raii_guard::HandleGuard;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::{OsString, c_void};
+use std::fs;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+use tokio::task::spawn_blocking;
+use windows::Win32::Foundation::GENERIC_READ;
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAGS_AND_ATTRIBUTES, FILE_ID_DESCRIPTOR,
+    FILE_ID_DESCRIPTOR_0, FILE_NAME_NORMALIZED, FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE,
+    FILE_SHARE_READ, FILE_SHARE_WRITE, FileIdType, GetFinalPathNameByHandleW,
+    GetVolumeNameForVolumeMountPointW, GetVolumePathNameW, OPEN_EXISTING, OpenFileById,
+};
+use windows::Win32::System::IO::DeviceIoControl;
+use windows::Win32::System::Ioctl::{
+    FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, READ_USN_JOURNAL_DATA_V0,
+    USN_JOURNAL_DATA_V0, USN_RECORD_V2,
+};
+use windows::core::PCWSTR;
+
+const READ_BUFFER_SIZE: usize = 65536;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeCursor {
+    pub journal_id: u64,
+    pub next_usn: i64,
+}
+
+pub struct ChangeJournal;
+
+#[async_trait]
+impl ChangeJournalTrait for ChangeJournal {
+    fn new() -> Self {
+        Self
+    }
+
+    async fn current_cursor(&self, root: &Path) -> Result<ChangeCursor, Error> {
+        let root = root.to_path_buf();
+        spawn_blocking(move || {
+            let volume = Self::open_volume(&root)?;
+            let journal_data = Self::query_journal(&volume, &root)?;
+            Ok(ChangeCursor {
+                journal_id: journal_data.UsnJournalID,
+                next_usn: journal_data.NextUsn,
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+    }
+
+    async fn changed_directories(
+        &self,
+        root: &Path,
+        cursor: &ChangeCursor,
+    ) -> Result<HashSet<PathBuf>, Error> {
+        let root = root.to_path_buf();
+        let cursor = cursor.clone();
+        spawn_blocking(move || Self::read_changed_directories(&root, &cursor))
+            .await
+            .map_err(SystemError::ThreadPanic)?
+    }
+}
+
+impl ChangeJournal {
+    fn read_changed_directories(
+        root: &Path,
+        cursor: &ChangeCursor,
+    ) -> Result<HashSet<PathBuf>, Error> {
+        let canonical_root =
+            fs::canonicalize(root).map_err(|err| IOError::ReadChangeJournalFailed(root, err))?;
+        let volume = Self::open_volume(root)?;
+        let journal_data = Self::query_journal(&volume, root)?;
+
+        if journal_data.UsnJournalID != cursor.journal_id
+            || cursor.next_usn < journal_data.FirstUsn
+        {
+            Err(IOError::ChangeJournalUnavailable(root))?
+        }
+
+        let parent_ids = Self::read_parent_ids(&volume, root, cursor, journal_data.NextUsn)?;
+
+        let mut directories = HashSet::new();
+        for parent_id in parent_ids {
+            let Some(parent_path) = Self::resolve_file_id(&volume, parent_id) else {
+                continue;
+            };
+            if let Ok(relative_path) = parent_path.strip_prefix(&canonical_root) {
+                directories.insert(root.join(relative_path));
+            }
+        }
+
+        Ok(directories)
+    }
+
+    fn read_parent_ids(
+        volume: &HandleGuard,
+        root: &Path,
+        cursor: &ChangeCursor,
+        end_usn: i64,
+    ) -> Result<HashSet<u64>, Error> {
+        let mut parent_ids = HashSet::new();
+        let mut read_data = READ_USN_JOURNAL_DATA_V0 {
+            StartUsn: cursor.next_usn,
+            ReasonMask: u32::MAX,
+            ReturnOnlyOnClose: 0,
+            Timeout: 0,
+            BytesToWaitFor: 0,
+            UsnJournalID: cursor.journal_id,
+        };
+        let mut buffer = vec![0_u64; READ_BUFFER_SIZE / size_of::<u64>()];
+
+        while read_data.StartUsn < end_usn {
+            let mut bytes_returned = 0_u32;
+            unsafe {
+                DeviceIoControl(
+                    **volume,
+                    FSCTL_READ_USN_JOURNAL,
+                    Some(&read_data as *const _ as *const c_void),
+                    size_of::<READ_USN_JOURNAL_DATA_V0>() as u32,
+                    Some(buffer.as_mut_ptr() as *mut c_void),
+                    READ_BUFFER_SIZE as u32,
+                    Some(&mut bytes_returned),
+                    None,
+                )
+                .map_err(|err| IOError::ReadChangeJournalFailed(root, err))?;
+            }
+
+            let bytes_returned = bytes_returned as usize;
+            if bytes_returned <= size_of::<i64>() {
+                break;
+            }
+
+            let bytes = buffer.as_ptr() as *const u8;
+            let mut offset = size_of::<i64>();
+            while offset < bytes_returned {
+                let record = unsafe { &*(bytes.add(offset) as *const USN_RECORD_V2) };
+                if record.RecordLength == 0 {
+                    break;
+                }
+                if record.MajorVersion == 2 {
+                    parent_ids.insert(record.ParentFileReferenceNumber);
+                }
+                offset += record.RecordLength as usize;
+            }
+
+            read_data.StartUsn = buffer[0] as i64;
+        }
+
+        Ok(parent_ids)
+    }
+
+    fn resolve_file_id(volume: &HandleGuard, file_id: u64) -> Option<PathBuf> {
+        let descriptor = FILE_ID_DESCRIPTOR {
+            dwSize: size_of::<FILE_ID_DESCRIPTOR>() as u32,
+            Type: FileIdType,
+            Anonymous: FILE_ID_DESCRIPTOR_0 {
+                FileId: file_id as i64,
+            },
+        };
+
+        unsafe {
+            let handle = OpenFileById(
+                **volume,
+                &descriptor,
+                FILE_READ_ATTRIBUTES.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                FILE_FLAG_BACKUP_SEMANTICS,
+            )
+            .ok()
+            .map(HandleGuard::new)?;
+
+            let mut path_buffer = vec![0_u16; 32768];
+            let length = GetFinalPathNameByHandleW(*handle, &mut path_buffer, FILE_NAME_NORMALIZED)
+                as usize;
+            if length == 0 || length > path_buffer.len() {
+                return None;
+            }
+            Some(PathBuf::from(OsString::from_wide(&path_buffer[..length])))
+        }
+    }
+
+    fn open_volume(root: &Path) -> Result<HandleGuard, Error> {
+        let root_wide: Vec<u16> = root.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut mount_point = vec![0_u16; 32768];
+        let mut volume_name = vec![0_u16; 64];
+
+        unsafe {
+            GetVolumePathNameW(PCWSTR(root_wide.as_ptr()), &mut mount_point)
+                .map_err(|err| IOError::ReadChangeJournalFailed(root, err))?;
+            GetVolumeNameForVolumeMountPointW(PCWSTR(mount_point.as_ptr()), &mut volume_name)
+                .map_err(|err| IOError::ReadChangeJournalFailed(root, err))?;
+        }
+
+        let length = volume_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(volume_name.len());
+        let mut device: Vec<u16> = volume_name[..length].to_vec();
+        if device.last() == Some(&(b'\\' as u16)) {
+            device.pop();
+        }
+        device.push(0);
+
+        let handle = unsafe {
+            CreateFileW(
+                PCWSTR(device.as_ptr()),
+                GENERIC_READ.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            )
+            .map_err(|err| IOError::ReadChangeJournalFailed(root, err))?
+        };
+
+        Ok(HandleGuard::new(handle))
+    }
+
+    fn query_journal(volume: &HandleGuard, root: &Path) -> Result<USN_JOURNAL_DATA_V0, Error> {
+        let mut journal_data = USN_JOURNAL_DATA_V0::default();
+        let mut bytes_returned = 0_u32;
+
+        unsafe {
+            DeviceIoControl(
+                **volume,
+                FSCTL_QUERY_USN_JOURNAL,
+                None,
+                0,
+                Some(&mut journal_data as *mut _ as *mut c_void),
+                size_of::<USN_JOURNAL_DATA_V0>() as u32,
+                Some(&mut bytes_returned),
+                None,
+            )
+            .map_err(|_| IOError::ChangeJournalUnavailable(root))?;
+        }
+
+        Ok(journal_data)
+    }
+}
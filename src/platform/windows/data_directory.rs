@@ -0,0 +1,10 @@
+use std::env;
+use std::path::PathBuf;
+
+// %LOCALAPPDATA%\MirrorSphere, machine local since the database is not meant to roam
+pub fn data_directory() -> PathBuf {
+    env::var_os("LOCALAPPDATA")
+        .filter(|local_app_data| !local_app_data.is_empty())
+        .map(|local_app_data| PathBuf::from(local_app_data).join("MirrorSphere"))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
@@ -18,7 +18,6 @@ use windows::Win32::UI::Shell::{SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW, Shel
 use windows::Win32::UI::WindowsAndMessaging::SW_NORMAL;
 use windows::core::PCWSTR;
 
-#[allow(dead_code)]
 pub fn elevate() -> Result<(), Error> {
     let exe = env::current_exe()
         .map_err(SystemError::UnexpectError)?;
@@ -38,7 +37,6 @@ pub fn elevate() -> Result<(), Error> {
     unsafe { win_runas(file, params) }
 }
 
-#[allow(dead_code)]
 unsafe fn win_runas(cmd: Vec<u16>, args: Vec<u16>) -> Result<(), Error> {
     unsafe {
         let mut sei: SHELLEXECUTEINFOW = mem::zeroed();
@@ -1,21 +1,42 @@
+use crate::core::infrastructure::app_config::AppConfig;
 use crate::interface::core::file_system::FileSystemTrait;
+use crate::interface::core::io_priority::{IOPriorityTrait, background_io};
+use crate::model::core::backup::capability_report::VolumeFeatures;
+use crate::model::core::backup::copy_progress::CopyProgress;
 use crate::model::error::Error;
 use crate::model::error::io::IOError;
 use crate::model::error::misc::MiscError;
 use crate::model::error::system::SystemError;
+use crate::model::log::io::IOLog;
 use crate::platform::attributes::{Attributes, Permissions};
+use crate::platform::io_priority::IOPriority;
 use crate::platform::raii_guard::SecurityDescriptorGuard;
+use crate::utils::chunked_copy::{AlignedBuffer, copy_chunks};
 use async_trait::async_trait;
 use chrono::{DateTime, Datelike, Timelike};
+use macros::{log, timed};
+use std::ffi::c_void;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
 use std::os::windows::ffi::OsStrExt;
-use std::os::windows::fs::MetadataExt;
-use std::path::Path;
+use std::os::windows::fs::{MetadataExt, OpenOptionsExt};
+use std::os::windows::io::AsRawHandle;
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::sync::Semaphore;
 use tokio::task::spawn_blocking;
-use windows::Win32::Foundation::{CloseHandle, FILETIME, GENERIC_WRITE, SYSTEMTIME};
+use uuid::Uuid;
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_ACCESS_DENIED, ERROR_ALREADY_ASSIGNED, ERROR_CONNECTION_UNAVAIL,
+    ERROR_DEVICE_ALREADY_REMEMBERED, ERROR_ENCRYPTION_FAILED, ERROR_INVALID_FUNCTION,
+    ERROR_LOCK_VIOLATION, ERROR_NOT_SUPPORTED, ERROR_SHARING_VIOLATION, FILETIME, GENERIC_READ,
+    GENERIC_WRITE, HANDLE, MAX_PATH, NO_ERROR, SYSTEMTIME,
+};
+use windows::Win32::NetworkManagement::WNet::{
+    CONNECT_TEMPORARY, NETRESOURCEW, RESOURCETYPE_DISK, WNetAddConnection2W, WNetGetConnectionW,
+};
 use windows::Win32::Security::Authorization::{
     GetNamedSecurityInfoW, SE_FILE_OBJECT, SetNamedSecurityInfoW,
 };
@@ -24,28 +45,185 @@ use windows::Win32::Security::{
     OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID, SACL_SECURITY_INFORMATION,
 };
 use windows::Win32::Storage::FileSystem::{
-    CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_WRITE,
-    OPEN_EXISTING, SetFileAttributesW, SetFileTime,
+    BackupRead, BackupWrite, COMPRESSION_FORMAT_DEFAULT, COPY_FILE_NO_BUFFERING, COPYFILE_FLAGS,
+    COPYPROGRESSROUTINE_PROGRESS, CopyFileExW, CreateFileW, FILE_ATTRIBUTE_COMPRESSED,
+    FILE_ATTRIBUTE_DIRECTORY, FILE_ATTRIBUTE_ENCRYPTED, FILE_ATTRIBUTE_SPARSE_FILE,
+    FILE_CASE_SENSITIVE_INFO, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_SEQUENTIAL_SCAN,
+    FILE_FLAGS_AND_ATTRIBUTES, FILE_REMOTE_PROTOCOL_INFO, FILE_SHARE_DELETE, FILE_SHARE_READ,
+    FILE_SHARE_WRITE, FileCaseSensitiveInfo, FileRemoteProtocolInfo,
+    FindFirstVolumeW, FindNextVolumeW, FindVolumeClose, GetDiskFreeSpaceExW,
+    GetFileInformationByHandleEx, GetVolumeInformationByHandleW, GetVolumeInformationW,
+    GetVolumePathNamesForVolumeNameW, LPPROGRESS_ROUTINE_CALLBACK_REASON, OPEN_EXISTING,
+    PROGRESS_CANCEL, PROGRESS_CONTINUE, SetFileAttributesW, SetFileTime, WRITE_DAC, WRITE_OWNER,
+};
+use windows::Win32::System::IO::DeviceIoControl;
+use windows::Win32::System::Ioctl::{
+    DUPLICATE_EXTENTS_DATA, FSCTL_DUPLICATE_EXTENTS_TO_FILE, FSCTL_GET_INTEGRITY_INFORMATION,
+    FSCTL_GET_INTEGRITY_INFORMATION_BUFFER, FSCTL_SET_COMPRESSION, FSCTL_SET_INTEGRITY_INFORMATION,
+    FSCTL_SET_INTEGRITY_INFORMATION_BUFFER, FSCTL_SET_SPARSE,
+};
+use windows::Win32::System::SystemServices::{
+    FILE_CS_FLAG_CASE_SENSITIVE_DIR, FILE_NAMED_STREAMS, FILE_PERSISTENT_ACLS,
+    FILE_READ_ONLY_VOLUME, FILE_SUPPORTS_BLOCK_REFCOUNTING, FILE_SUPPORTS_HARD_LINKS,
+    FILE_SUPPORTS_REPARSE_POINTS, FILE_SUPPORTS_SPARSE_FILES,
 };
 use windows::Win32::System::Time::SystemTimeToFileTime;
-use windows::core::PCWSTR;
+use windows::core::{PCWSTR, PWSTR};
+
+const CLONE_CHUNK_SIZE: u64 = 1073741824;
+const BACKUP_BUFFER_SIZE: usize = 1048576;
+const VOLUME_LABEL_PREFIX: &str = "label:";
+const VOLUME_GUID_PREFIX: &str = r"\\?\Volume{";
+const EXTENDED_PREFIX: &str = r"\\?\";
+const EXTENDED_UNC_PREFIX: &str = r"\\?\UNC\";
+const DEVICE_PREFIX: &str = r"\\.\";
 
 pub struct FileSystem {
-    semaphore: Arc<Semaphore>,
+    metadata_semaphore: Arc<Semaphore>,
+    data_semaphore: Arc<Semaphore>,
+    reflink: bool,
+    copy_buffer_size: usize,
+    unbuffered_copy_threshold: u64,
+    mapped_compare_threshold: u64,
+    mtime_tolerance: Duration,
 }
 
 #[async_trait]
 impl FileSystemTrait for FileSystem {
-    fn new(semaphore: Arc<Semaphore>) -> Self {
-        Self { semaphore }
+    fn new(
+        app_config: Arc<AppConfig>,
+        metadata_semaphore: Arc<Semaphore>,
+        data_semaphore: Arc<Semaphore>,
+    ) -> Self {
+        Self {
+            metadata_semaphore,
+            data_semaphore,
+            reflink: app_config.reflink,
+            copy_buffer_size: app_config.copy_buffer_size,
+            unbuffered_copy_threshold: app_config.unbuffered_copy_threshold,
+            mapped_compare_threshold: app_config.mapped_compare_threshold,
+            mtime_tolerance: Duration::from_millis(app_config.mtime_tolerance),
+        }
+    }
+
+    fn metadata_semaphore(&self) -> Arc<Semaphore> {
+        self.metadata_semaphore.clone()
+    }
+
+    fn data_semaphore(&self) -> Arc<Semaphore> {
+        self.data_semaphore.clone()
     }
 
-    fn semaphore(&self) -> Arc<Semaphore> {
-        self.semaphore.clone()
+    fn mapped_compare_threshold(&self) -> u64 {
+        self.mapped_compare_threshold
+    }
+
+    fn mtime_tolerance(&self) -> Duration {
+        self.mtime_tolerance
+    }
+
+    #[timed]
+    async fn copy_file(&self, source: &Path, destination: &Path) -> Result<(), Error> {
+        self.copy_file_with_progress(source, destination, Arc::new(CopyProgress::new()))
+            .await
+    }
+
+    #[timed]
+    async fn copy_file_with_progress(
+        &self,
+        source: &Path,
+        destination: &Path,
+        progress: Arc<CopyProgress>,
+    ) -> Result<(), Error> {
+        let semaphore = self.data_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        if progress.is_cancelled() {
+            Err(IOError::CopyFileCancelled(source))?
+        }
+
+        let metadata = tokio::fs::metadata(source)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(source, err))?;
+        let length = metadata.len();
+        let unbuffered =
+            self.unbuffered_copy_threshold > 0 && length >= self.unbuffered_copy_threshold;
+        // Only CopyFileEx carries EFS encryption over, reading the file yields its plaintext
+        let encrypted = metadata.file_attributes() & FILE_ATTRIBUTE_ENCRYPTED.0 != 0;
+        let compressed = metadata.file_attributes() & FILE_ATTRIBUTE_COMPRESSED.0 != 0;
+
+        let source_path = source.to_path_buf();
+        let destination_path = destination.to_path_buf();
+        let copy_progress = progress.clone();
+        let buffer_size = self.copy_buffer_size;
+        let reflink = self.reflink;
+        let background = background_io();
+        spawn_blocking(move || {
+            IOPriority::run(background, || {
+                let result = if encrypted {
+                    Self::copy_file_ex(&source_path, &destination_path, unbuffered, &copy_progress)
+                } else if reflink
+                    && Self::clone_file(&source_path, &destination_path, &copy_progress)?
+                {
+                    Ok(())
+                } else if buffer_size > 0 && !unbuffered {
+                    Self::chunked_copy(&source_path, &destination_path, buffer_size, &copy_progress)
+                } else {
+                    Self::copy_file_ex(&source_path, &destination_path, unbuffered, &copy_progress)
+                };
+                // BackupRead hands out EFS files as plaintext, they keep the CopyFileEx result
+                match result {
+                    Err(err)
+                        if !encrypted
+                            && err.raw_os_error() == Some(ERROR_ACCESS_DENIED.0 as i32) =>
+                    {
+                        log!(IOLog::BackupSemanticsCopy {
+                            path: source_path.display().to_string(),
+                        });
+                        Self::backup_copy(&source_path, &destination_path, &copy_progress)
+                    }
+                    result => result,
+                }
+            })
+        })
+        .await
+        .map_err(SystemError::ThreadPanic)?
+        .map_err(|err| {
+            if progress.is_cancelled() {
+                IOError::CopyFileCancelled(source)
+            } else if err.raw_os_error() == Some(ERROR_ENCRYPTION_FAILED.0 as i32) {
+                IOError::EncryptionUnsupported(destination)
+            } else {
+                IOError::CopyFileFailed(source, destination, err)
+            }
+        })?;
+
+        if compressed {
+            let destination_path = destination.to_path_buf();
+            spawn_blocking(move || Self::set_compression(&destination_path))
+                .await
+                .map_err(SystemError::ThreadPanic)?
+                .map_err(|err| {
+                    let unsupported = [ERROR_INVALID_FUNCTION, ERROR_NOT_SUPPORTED]
+                        .iter()
+                        .any(|code| err.raw_os_error() == Some(code.0 as i32));
+                    if unsupported {
+                        IOError::CompressionUnsupported(destination)
+                    } else {
+                        IOError::SetMetadataFailed(destination, err)
+                    }
+                })?;
+        }
+
+        Ok(())
     }
 
+    #[timed]
     async fn copy_symlink(&self, source_link: &Path, destination_link: &Path) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -65,8 +243,34 @@ impl FileSystemTrait for FileSystem {
         Ok(())
     }
 
+    // Junctions, mount points and directory symlinks go through RemoveDirectoryW, which drops
+    // the reparse point and leaves the target untouched
+    #[timed]
+    async fn delete_link(&self, path: &Path) -> Result<(), Error> {
+        let semaphore = self.metadata_semaphore();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let metadata = tokio::fs::symlink_metadata(path)
+            .await
+            .map_err(|err| IOError::GetMetadataFailed(path, err))?;
+        if metadata.file_attributes() & FILE_ATTRIBUTE_DIRECTORY.0 != 0 {
+            tokio::fs::remove_dir(path)
+                .await
+                .map_err(|err| IOError::DeleteDirectoryFailed(path, err))?;
+        } else {
+            tokio::fs::remove_file(path)
+                .await
+                .map_err(|err| IOError::DeleteFileFailed(path, err))?;
+        }
+        Ok(())
+    }
+
+    #[timed]
     async fn get_attributes(&self, path: &Path) -> Result<Attributes, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -98,8 +302,9 @@ impl FileSystemTrait for FileSystem {
         Ok(attributes)
     }
 
+    #[timed]
     async fn set_attributes(&self, path: &Path, attributes: Attributes) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -151,8 +356,9 @@ impl FileSystemTrait for FileSystem {
         Ok(())
     }
 
+    #[timed]
     async fn get_permission(&self, path: &Path) -> Result<Permissions, Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -204,8 +410,9 @@ impl FileSystemTrait for FileSystem {
         Ok(permission)
     }
 
+    #[timed]
     async fn set_permission(&self, path: &Path, permissions: Permissions) -> Result<(), Error> {
-        let semaphore = self.semaphore();
+        let semaphore = self.metadata_semaphore();
         let _permit = semaphore
             .acquire_owned()
             .await
@@ -246,6 +453,263 @@ impl FileSystemTrait for FileSystem {
 }
 
 impl FileSystem {
+    // ReFS block cloning, any refusal falls back to a regular copy over the same destination
+    fn clone_file(
+        source: &Path,
+        destination: &Path,
+        progress: &CopyProgress,
+    ) -> io::Result<bool> {
+        let source_file = File::open(source)?;
+        let metadata = source_file.metadata()?;
+        let length = metadata.len();
+        if length == 0 {
+            return Ok(false);
+        }
+        let destination_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(destination)?;
+
+        let source_handle = HANDLE(source_file.as_raw_handle());
+        let destination_handle = HANDLE(destination_file.as_raw_handle());
+
+        let mut volume_flags = 0_u32;
+        let supported = unsafe {
+            GetVolumeInformationByHandleW(
+                destination_handle,
+                None,
+                None,
+                None,
+                Some(&mut volume_flags),
+                None,
+            )
+        }
+        .is_ok_and(|_| volume_flags & FILE_SUPPORTS_BLOCK_REFCOUNTING != 0);
+        if !supported {
+            return Ok(false);
+        }
+
+        // Cloning requires matching integrity streams and yields the cluster size
+        let mut integrity = FSCTL_GET_INTEGRITY_INFORMATION_BUFFER::default();
+        let mut bytes_returned = 0_u32;
+        let read_integrity = unsafe {
+            DeviceIoControl(
+                source_handle,
+                FSCTL_GET_INTEGRITY_INFORMATION,
+                None,
+                0,
+                Some(&mut integrity as *mut _ as *mut c_void),
+                size_of::<FSCTL_GET_INTEGRITY_INFORMATION_BUFFER>() as u32,
+                Some(&mut bytes_returned),
+                None,
+            )
+        };
+        if read_integrity.is_err() || integrity.ClusterSizeInBytes == 0 {
+            return Ok(false);
+        }
+        let set_integrity = FSCTL_SET_INTEGRITY_INFORMATION_BUFFER {
+            ChecksumAlgorithm: integrity.ChecksumAlgorithm,
+            Reserved: 0,
+            Flags: integrity.Flags,
+        };
+        if Self::file_control(destination_handle, FSCTL_SET_INTEGRITY_INFORMATION, &set_integrity)
+            .is_err()
+        {
+            return Ok(false);
+        }
+
+        if metadata.file_attributes() & FILE_ATTRIBUTE_SPARSE_FILE.0 != 0
+            && Self::file_control(destination_handle, FSCTL_SET_SPARSE, &()).is_err()
+        {
+            return Ok(false);
+        }
+        destination_file.set_len(length)?;
+
+        let cluster_size = integrity.ClusterSizeInBytes as u64;
+        let mut offset = 0_u64;
+        while offset < length {
+            let byte_count = CLONE_CHUNK_SIZE
+                .min(length - offset)
+                .next_multiple_of(cluster_size);
+            let extents = DUPLICATE_EXTENTS_DATA {
+                FileHandle: source_handle,
+                SourceFileOffset: offset as i64,
+                TargetFileOffset: offset as i64,
+                ByteCount: byte_count as i64,
+            };
+            if Self::file_control(destination_handle, FSCTL_DUPLICATE_EXTENTS_TO_FILE, &extents)
+                .is_err()
+            {
+                return Ok(false);
+            }
+
+            offset += byte_count;
+            progress.update(offset.min(length), length);
+            if progress.is_cancelled() {
+                Err(io::Error::from(io::ErrorKind::Interrupted))?
+            }
+        }
+
+        Ok(true)
+    }
+
+    // SetFileAttributesW ignores the compressed flag, NTFS compression is switched on per file
+    fn set_compression(path: &Path) -> io::Result<()> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let format = COMPRESSION_FORMAT_DEFAULT.0;
+        Self::file_control(HANDLE(file.as_raw_handle()), FSCTL_SET_COMPRESSION, &format)
+            .map_err(io::Error::from)
+    }
+
+    fn file_control<T>(handle: HANDLE, control_code: u32, input: &T) -> windows::core::Result<()> {
+        let input_size = size_of::<T>() as u32;
+        let mut bytes_returned = 0_u32;
+        unsafe {
+            DeviceIoControl(
+                handle,
+                control_code,
+                (input_size > 0).then_some(input as *const T as *const c_void),
+                input_size,
+                None,
+                0,
+                Some(&mut bytes_returned),
+                None,
+            )
+        }
+    }
+
+    fn chunked_copy(
+        source: &Path,
+        destination: &Path,
+        buffer_size: usize,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let mut source_file = File::open(source)?;
+        let length = source_file.metadata()?.len();
+        let mut destination_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(destination)?;
+
+        let mut buffer = AlignedBuffer::new(buffer_size);
+        copy_chunks(
+            &mut source_file,
+            &mut destination_file,
+            &mut buffer,
+            length,
+            progress,
+            |_| Ok(()),
+        )
+    }
+
+    // Backup semantics handles skip the DACL checks under SeBackupPrivilege and SeRestorePrivilege,
+    // BackupRead streams the data, alternate streams and security descriptor in one pass
+    fn backup_copy(source: &Path, destination: &Path, progress: &CopyProgress) -> io::Result<()> {
+        let source_file = OpenOptions::new()
+            .access_mode(GENERIC_READ.0)
+            .share_mode((FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE).0)
+            .custom_flags((FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_SEQUENTIAL_SCAN).0)
+            .open(source)?;
+        let length = source_file.metadata()?.len();
+        let destination_file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .access_mode(GENERIC_WRITE.0 | WRITE_DAC.0 | WRITE_OWNER.0)
+            .custom_flags(FILE_FLAG_BACKUP_SEMANTICS.0)
+            .open(destination)?;
+        let source_handle = HANDLE(source_file.as_raw_handle());
+        let destination_handle = HANDLE(destination_file.as_raw_handle());
+
+        let mut buffer = vec![0_u8; BACKUP_BUFFER_SIZE];
+        let mut read_context = ptr::null_mut();
+        let mut write_context = ptr::null_mut();
+        let mut copy = || -> io::Result<()> {
+            let mut copied = 0_u64;
+            loop {
+                let mut read = 0_u32;
+                unsafe {
+                    BackupRead(
+                        source_handle,
+                        &mut buffer,
+                        &mut read,
+                        false,
+                        true,
+                        &mut read_context,
+                    )
+                }?;
+                if read == 0 {
+                    break;
+                }
+                let mut written = 0_u32;
+                let chunk = &buffer[..read as usize];
+                unsafe {
+                    BackupWrite(
+                        destination_handle,
+                        chunk,
+                        &mut written,
+                        false,
+                        true,
+                        &mut write_context,
+                    )
+                }?;
+
+                // Stream headers and security data count in too, the total stays the file size
+                copied += read as u64;
+                progress.update(copied.min(length), length);
+                if progress.is_cancelled() {
+                    Err(io::Error::from(io::ErrorKind::Interrupted))?
+                }
+            }
+            Ok(())
+        };
+        let result = copy();
+
+        // Aborting frees the contexts the calls allocated
+        let mut unused = 0_u32;
+        if !read_context.is_null() {
+            let _ = unsafe {
+                BackupRead(source_handle, &mut [], &mut unused, true, false, &mut read_context)
+            };
+        }
+        if !write_context.is_null() {
+            let _ = unsafe {
+                BackupWrite(destination_handle, &[], &mut unused, true, false, &mut write_context)
+            };
+        }
+        result
+    }
+
+    fn copy_file_ex(
+        source: &Path,
+        destination: &Path,
+        unbuffered: bool,
+        progress: &CopyProgress,
+    ) -> io::Result<()> {
+        let copy_flags = match unbuffered {
+            true => COPY_FILE_NO_BUFFERING,
+            false => COPYFILE_FLAGS(0),
+        };
+        let source_wide: Vec<u16> = source.as_os_str().encode_wide().chain(Some(0)).collect();
+        let destination_wide: Vec<u16> =
+            destination.as_os_str().encode_wide().chain(Some(0)).collect();
+
+        unsafe {
+            CopyFileExW(
+                PCWSTR(source_wide.as_ptr()),
+                PCWSTR(destination_wide.as_ptr()),
+                Some(copy_progress_routine),
+                Some(progress as *const CopyProgress as *const c_void),
+                None,
+                copy_flags,
+            )
+        }
+        .map_err(io::Error::from)
+    }
+
     fn system_time_to_file_time(system_time: SystemTime) -> Result<FILETIME, Error> {
         let duration = system_time
             .duration_since(SystemTime::UNIX_EPOCH)
@@ -273,3 +737,305 @@ impl FileSystem {
         }
     }
 }
+
+// Brings a path as entered into the extended-length form, so Win32 calls and tokio::fs see the
+// same path without the MAX_PATH limit. Separators are unified, relative paths made absolute and
+// trailing dots and spaces dropped from every component as Win32 would, which the extended form
+// otherwise keeps in the names it creates
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let Some(path_str) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    let path_str = path_str.trim().replace('/', "\\");
+    let verbatim = [EXTENDED_PREFIX, DEVICE_PREFIX, VOLUME_LABEL_PREFIX];
+    if verbatim.iter().any(|prefix| path_str.starts_with(prefix)) {
+        return PathBuf::from(path_str);
+    }
+    let Ok(absolute_path) = std::path::absolute(&path_str) else {
+        return PathBuf::from(path_str);
+    };
+    let Some(absolute) = absolute_path.to_str() else {
+        return absolute_path;
+    };
+
+    let (prefix, rest) = match absolute.strip_prefix(r"\\") {
+        Some(unc) => (EXTENDED_UNC_PREFIX, unc),
+        None => (EXTENDED_PREFIX, absolute),
+    };
+    let components: Vec<_> = rest
+        .split('\\')
+        .map(|component| component.trim_end_matches(['.', ' ']))
+        .filter(|component| !component.is_empty())
+        .collect();
+    let mut normalized = format!("{prefix}{}", components.join("\\"));
+    // \\?\C: names the volume itself, its root directory needs the separator
+    if prefix == EXTENDED_PREFIX && components.len() == 1 {
+        normalized.push('\\');
+    }
+    PathBuf::from(normalized)
+}
+
+// Volume GUID paths and label: prefixes name a volume wherever it is mounted this session,
+// None when that volume is not attached
+pub fn resolve_volume_path(path: &Path) -> Option<PathBuf> {
+    let Some(path_str) = path.to_str() else {
+        return Some(path.to_path_buf());
+    };
+    let (volume, relative) = if let Some(rest) = path_str.strip_prefix(VOLUME_LABEL_PREFIX) {
+        let (label, relative) = rest.split_once('\\').unwrap_or((rest, ""));
+        let volume = volume_names().into_iter().find(|volume| {
+            volume_label(volume).is_some_and(|found| found.eq_ignore_ascii_case(label))
+        })?;
+        (volume, relative)
+    } else if path_str.starts_with(VOLUME_GUID_PREFIX) {
+        let end = path_str.find('}')? + 1;
+        let volume = format!("{}\\", &path_str[..end]);
+        (volume, path_str[end..].trim_start_matches('\\'))
+    } else {
+        return Some(path.to_path_buf());
+    };
+    let mount_point = volume_mount_point(&volume)?;
+    Some(PathBuf::from(mount_point).join(relative))
+}
+
+fn volume_names() -> Vec<String> {
+    let mut volumes = Vec::new();
+    let mut buffer = [0_u16; MAX_PATH as usize];
+    let Ok(handle) = (unsafe { FindFirstVolumeW(&mut buffer) }) else {
+        return volumes;
+    };
+    loop {
+        volumes.push(wide_to_string(&buffer));
+        if unsafe { FindNextVolumeW(handle, &mut buffer) }.is_err() {
+            break;
+        }
+    }
+    let _ = unsafe { FindVolumeClose(handle) };
+    volumes
+}
+
+fn volume_label(volume: &str) -> Option<String> {
+    let volume_wide: Vec<u16> = volume.encode_utf16().chain(Some(0)).collect();
+    let mut label = [0_u16; MAX_PATH as usize + 1];
+    unsafe {
+        GetVolumeInformationW(
+            PCWSTR(volume_wide.as_ptr()),
+            Some(&mut label),
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+    .ok()?;
+    Some(wide_to_string(&label))
+}
+
+// A volume without a drive letter or mounted folder cannot be written to either
+fn volume_mount_point(volume: &str) -> Option<String> {
+    let volume_wide: Vec<u16> = volume.encode_utf16().chain(Some(0)).collect();
+    let mut mount_points = [0_u16; 1024];
+    let mut length = 0_u32;
+    unsafe {
+        GetVolumePathNamesForVolumeNameW(
+            PCWSTR(volume_wide.as_ptr()),
+            Some(&mut mount_points),
+            &mut length,
+        )
+    }
+    .ok()?;
+    let mount_point = wide_to_string(&mount_points);
+    (!mount_point.is_empty()).then_some(mount_point)
+}
+
+// Mapped drives are connected again to the share they remember, UNC paths directly, both with
+// the credentials Windows has stored for the server
+pub fn reconnect_share(path: &Path) -> bool {
+    let Some(path_str) = path.to_str() else {
+        return false;
+    };
+    let path_str = match path_str.strip_prefix(EXTENDED_UNC_PREFIX) {
+        Some(unc) => format!(r"\\{unc}"),
+        None => path_str.strip_prefix(EXTENDED_PREFIX).unwrap_or(path_str).to_string(),
+    };
+    let (local_name, remote_name) = if let Some(unc) = path_str.strip_prefix(r"\\") {
+        let mut components = unc.split('\\');
+        let (Some(server), Some(share)) = (components.next(), components.next()) else {
+            return false;
+        };
+        (None, format!(r"\\{server}\{share}"))
+    } else {
+        let Some(drive) = path_str.get(..2).filter(|drive| drive.ends_with(':')) else {
+            return false;
+        };
+        let Some(remote_name) = remembered_connection(drive) else {
+            return false;
+        };
+        (Some(drive), remote_name)
+    };
+
+    let connect = |local_name: Option<&str>| {
+        let mut local_wide: Option<Vec<u16>> =
+            local_name.map(|local_name| local_name.encode_utf16().chain(Some(0)).collect());
+        let mut remote_wide: Vec<u16> = remote_name.encode_utf16().chain(Some(0)).collect();
+        let resource = NETRESOURCEW {
+            dwType: RESOURCETYPE_DISK,
+            lpLocalName: local_wide
+                .as_mut()
+                .map_or(PWSTR::null(), |local_wide| PWSTR(local_wide.as_mut_ptr())),
+            lpRemoteName: PWSTR(remote_wide.as_mut_ptr()),
+            ..Default::default()
+        };
+        unsafe { WNetAddConnection2W(&resource, PCWSTR::null(), PCWSTR::null(), CONNECT_TEMPORARY) }
+    };
+    match connect(local_name) {
+        NO_ERROR => true,
+        // The letter is still assigned to the dead session, a connection without one revives it
+        ERROR_ALREADY_ASSIGNED | ERROR_DEVICE_ALREADY_REMEMBERED => connect(None) == NO_ERROR,
+        _ => false,
+    }
+}
+
+// Persistent mappings report their share even while disconnected
+fn remembered_connection(drive: &str) -> Option<String> {
+    let drive_wide: Vec<u16> = drive.encode_utf16().chain(Some(0)).collect();
+    let mut remote_name = [0_u16; 1024];
+    let mut length = remote_name.len() as u32;
+    let result = unsafe {
+        WNetGetConnectionW(
+            PCWSTR(drive_wide.as_ptr()),
+            Some(PWSTR(remote_name.as_mut_ptr())),
+            &mut length,
+        )
+    };
+    match result {
+        NO_ERROR | ERROR_CONNECTION_UNAVAIL => Some(wide_to_string(&remote_name)),
+        _ => None,
+    }
+}
+
+fn wide_to_string(buffer: &[u16]) -> String {
+    let length = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..length])
+}
+
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path_wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0_u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(path_wide.as_ptr()), Some(&mut available), None, None) }
+        .ok()?;
+    Some(available)
+}
+
+pub fn is_read_only_volume(path: &Path) -> bool {
+    volume_flags(path).is_some_and(|flags| flags & FILE_READ_ONLY_VOLUME != 0)
+}
+
+// Alternate data streams stand in for extended attributes here
+pub fn volume_features(path: &Path) -> VolumeFeatures {
+    let flags = volume_flags(path).unwrap_or_default();
+    VolumeFeatures {
+        symlinks: flags & FILE_SUPPORTS_REPARSE_POINTS != 0,
+        hardlinks: flags & FILE_SUPPORTS_HARD_LINKS != 0,
+        xattrs: flags & FILE_NAMED_STREAMS != 0,
+        permissions: flags & FILE_PERSISTENT_ACLS != 0,
+        sparse: flags & FILE_SUPPORTS_SPARSE_FILES != 0,
+    }
+}
+
+fn volume_flags(path: &Path) -> Option<u32> {
+    let directory = OpenOptions::new()
+        .read(true)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS.0)
+        .open(path)
+        .ok()?;
+    let mut volume_flags = 0_u32;
+    unsafe {
+        GetVolumeInformationByHandleW(
+            HANDLE(directory.as_raw_handle()),
+            None,
+            None,
+            None,
+            Some(&mut volume_flags),
+            None,
+        )
+    }
+    .ok()?;
+    Some(volume_flags)
+}
+
+// Creating links needs a privilege or developer mode, so the check is an actual link
+pub fn supports_symlinks(directory: &Path) -> bool {
+    let link = directory.join(format!(".mirrorsphere-link-{}", Uuid::new_v4().simple()));
+    let created = std::os::windows::fs::symlink_file(directory.join("missing"), &link).is_ok();
+    let _ = std::fs::remove_file(&link);
+    created
+}
+
+// Held open without read sharing, or a byte range lock over the start of the file
+pub fn is_locked(path: &Path) -> bool {
+    let locked = |err: io::Error| {
+        err.raw_os_error().is_some_and(|code| {
+            code == ERROR_SHARING_VIOLATION.0 as i32 || code == ERROR_LOCK_VIOLATION.0 as i32
+        })
+    };
+    match File::open(path) {
+        Ok(mut file) => file.read(&mut [0; 1]).err().is_some_and(locked),
+        Err(err) => locked(err),
+    }
+}
+
+// NTFS directories flagged case-sensitive, as WSL does, tell names apart on a case-insensitive
+// volume
+pub fn directory_case_insensitive(directory: &Path) -> Option<bool> {
+    let directory = OpenOptions::new()
+        .read(true)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS.0)
+        .open(directory)
+        .ok()?;
+    let mut case_info = FILE_CASE_SENSITIVE_INFO::default();
+    unsafe {
+        GetFileInformationByHandleEx(
+            HANDLE(directory.as_raw_handle()),
+            FileCaseSensitiveInfo,
+            &mut case_info as *mut _ as *mut c_void,
+            size_of::<FILE_CASE_SENSITIVE_INFO>() as u32,
+        )
+    }
+    .ok()?;
+    (case_info.Flags & FILE_CS_FLAG_CASE_SENSITIVE_DIR != 0).then_some(false)
+}
+
+// Only files behind a redirector carry remote protocol information
+pub fn is_local_file(file: &File) -> bool {
+    let mut protocol_info = FILE_REMOTE_PROTOCOL_INFO::default();
+    unsafe {
+        GetFileInformationByHandleEx(
+            HANDLE(file.as_raw_handle()),
+            FileRemoteProtocolInfo,
+            &mut protocol_info as *mut _ as *mut c_void,
+            size_of::<FILE_REMOTE_PROTOCOL_INFO>() as u32,
+        )
+    }
+    .is_err()
+}
+
+unsafe extern "system" fn copy_progress_routine(
+    total_file_size: i64,
+    total_bytes_transferred: i64,
+    _stream_size: i64,
+    _stream_bytes_transferred: i64,
+    _stream_number: u32,
+    _callback_reason: LPPROGRESS_ROUTINE_CALLBACK_REASON,
+    _source_file: HANDLE,
+    _destination_file: HANDLE,
+    data: *const c_void,
+) -> COPYPROGRESSROUTINE_PROGRESS {
+    let progress = unsafe { &*(data as *const CopyProgress) };
+    progress.update(total_bytes_transferred as u64, total_file_size as u64);
+    if progress.is_cancelled() {
+        PROGRESS_CANCEL
+    } else {
+        PROGRESS_CONTINUE
+    }
+}
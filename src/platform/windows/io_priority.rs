@@ -0,0 +1,36 @@
+use crate::interface::core::io_priority::{IOPriorityTrait, ProcessWeightTrait};
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use windows::Win32::System::Threading::{
+    GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN, THREAD_MODE_BACKGROUND_END,
+};
+
+// Background mode lowers both the CPU and the IO priority hint of the calling thread
+pub struct IOPriority {
+    _private: (),
+}
+
+impl IOPriorityTrait for IOPriority {
+    fn enter_background() -> Result<Self, Error> {
+        unsafe { SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN) }
+            .map_err(IOError::SetIOPriorityFailed)?;
+        Ok(Self { _private: () })
+    }
+}
+
+impl Drop for IOPriority {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_END);
+        }
+    }
+}
+
+// Process background mode would slow the window down as well, the thread mode is enough here
+pub struct ProcessWeight;
+
+impl ProcessWeightTrait for ProcessWeight {
+    fn lower() -> Option<Self> {
+        None
+    }
+}
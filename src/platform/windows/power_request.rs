@@ -0,0 +1,40 @@
+use crate::interface::core::power_request::PowerRequestTrait;
+use crate::model::error::Error;
+use crate::model::error::system::SystemError;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use windows::Win32::System::Power::{
+    ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED, SetThreadExecutionState,
+};
+
+// The execution state belongs to the thread that set it, so a thread of its own keeps it until
+// the guard drops the sender and wakes it up
+pub struct PowerRequest {
+    _release: Sender<()>,
+}
+
+impl PowerRequestTrait for PowerRequest {
+    fn acquire(allow_display_sleep: bool) -> Result<Self, Error> {
+        let mut state = ES_CONTINUOUS | ES_SYSTEM_REQUIRED;
+        if !allow_display_sleep {
+            state |= ES_DISPLAY_REQUIRED;
+        }
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        thread::Builder::new()
+            .name("power-request".to_string())
+            .spawn(move || {
+                let previous = unsafe { SetThreadExecutionState(state) };
+                let _ = ready_tx.send(previous.0 != 0);
+                let _ = release_rx.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })
+            .map_err(SystemError::ThreadSpawnFailed)?;
+        if !ready_rx.recv().unwrap_or(false) {
+            Err(SystemError::PowerRequestFailed("SetThreadExecutionState was refused"))?
+        }
+        Ok(Self {
+            _release: release_tx,
+        })
+    }
+}
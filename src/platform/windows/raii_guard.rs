@@ -1,5 +1,5 @@
 use std::ops::Deref;
-use windows::Win32::Foundation::{LocalFree, HLOCAL};
+use windows::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL};
 use windows::Win32::Security::PSECURITY_DESCRIPTOR;
 
 #[derive(Debug)]
@@ -30,3 +30,32 @@ impl Drop for SecurityDescriptorGuard {
         }
     }
 }
+
+#[derive(Debug)]
+pub struct HandleGuard {
+    handle: HANDLE,
+}
+
+impl HandleGuard {
+    pub fn new(handle: HANDLE) -> Self {
+        Self { handle }
+    }
+}
+
+impl Deref for HandleGuard {
+    type Target = HANDLE;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handle
+    }
+}
+
+impl Drop for HandleGuard {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.handle.is_invalid() {
+                let _ = CloseHandle(self.handle);
+            }
+        }
+    }
+}
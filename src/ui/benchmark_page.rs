@@ -0,0 +1,217 @@
+use crate::core::gui::ui_bridge::{Pending, UiBridge};
+use crate::model::core::benchmark::benchmark_result::BenchmarkResult;
+use crate::model::core::benchmark::communication::*;
+use crate::model::error::Error;
+use crate::ui::common::{FolderSelectionMode, MEBIBYTE};
+use eframe::egui;
+use egui_file_dialog::FileDialog;
+use std::path::PathBuf;
+use tracing::error;
+
+pub struct BenchmarkPage {
+    ui_bridge: UiBridge,
+    read_only: bool,
+
+    results: Vec<BenchmarkResult>,
+    results_query: Option<Pending<BenchmarkQueryResponse>>,
+    running_benchmark: Option<Pending<()>>,
+    last_error: Option<String>,
+
+    source: String,
+    destination: String,
+
+    file_dialog: FileDialog,
+    folder_selection_mode: Option<FolderSelectionMode>,
+    loaded: bool,
+}
+
+impl BenchmarkPage {
+    pub fn new(ui_bridge: UiBridge, read_only: bool) -> Result<Self, Error> {
+        let benchmark_page = Self {
+            ui_bridge,
+            read_only,
+            results: Vec::new(),
+            results_query: None,
+            running_benchmark: None,
+            last_error: None,
+            source: String::new(),
+            destination: String::new(),
+            file_dialog: FileDialog::new(),
+            folder_selection_mode: None,
+            loaded: false,
+        };
+        Ok(benchmark_page)
+    }
+
+    fn load_results(&mut self) {
+        if self.results_query.is_none() {
+            self.results_query = Some(self.ui_bridge.send_query(BenchmarkQuery::GetResults));
+        }
+        self.loaded = true;
+    }
+
+    fn poll_backend(&mut self) {
+        if let Some(result) = self.running_benchmark.as_mut().and_then(Pending::poll) {
+            self.running_benchmark = None;
+            if let Err(err) = result {
+                self.last_error = Some(err.to_string());
+                error!("{}", err);
+            }
+            self.load_results();
+        }
+
+        let Some(result) = self.results_query.as_mut().and_then(Pending::poll) else {
+            return;
+        };
+        self.results_query = None;
+        match result {
+            Ok(BenchmarkQueryResponse::GetResults(results)) => {
+                self.results = results;
+            }
+            Err(err) => {
+                error!("{}", err);
+            }
+        }
+    }
+
+    fn run_benchmark(&mut self) {
+        let source = PathBuf::from(self.source.trim());
+        let destination = PathBuf::from(self.destination.trim());
+        let command = BenchmarkCommand::RunBenchmark(source, destination);
+        self.last_error = None;
+        self.running_benchmark = Some(self.ui_bridge.send_command(command));
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context) {
+        self.poll_backend();
+        if !self.loaded {
+            self.load_results();
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Benchmark");
+
+            egui::Grid::new("benchmark_grid")
+                .num_columns(3)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Source Path:");
+                    ui.add_sized([300.0, 20.0], egui::TextEdit::singleline(&mut self.source));
+                    if ui.button("📁 Browse").clicked() {
+                        self.folder_selection_mode = Some(FolderSelectionMode::Source);
+                        self.file_dialog.pick_directory();
+                    }
+                    ui.end_row();
+
+                    ui.label("Destination Path:");
+                    ui.add_sized(
+                        [300.0, 20.0],
+                        egui::TextEdit::singleline(&mut self.destination),
+                    );
+                    if ui.button("📁 Browse").clicked() {
+                        self.folder_selection_mode = Some(FolderSelectionMode::Destination);
+                        self.file_dialog.pick_directory();
+                    }
+                    ui.end_row();
+                });
+
+            ui.horizontal(|ui| {
+                let running = self.running_benchmark.is_some();
+                let ready = !self.read_only
+                    && !self.source.trim().is_empty()
+                    && !self.destination.trim().is_empty();
+                if ui
+                    .add_enabled(!running && ready, egui::Button::new("▶ Run Benchmark"))
+                    .clicked()
+                {
+                    self.run_benchmark();
+                }
+                if ui.button("🔄 Refresh").clicked() {
+                    self.load_results();
+                }
+                if running {
+                    ui.spinner();
+                    ui.label("Benchmark running...");
+                }
+            });
+
+            if let Some(err) = &self.last_error {
+                ui.colored_label(egui::Color32::RED, format!("❌ {err}"));
+            }
+
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    for result in &self.results {
+                        Self::draw_result_item(ui, result);
+                        ui.separator();
+                    }
+
+                    if self.results.is_empty() {
+                        ui.vertical_centered(|ui| {
+                            ui.label("📊 No benchmark results");
+                        });
+                    }
+                });
+        });
+
+        self.handle_file_dialog(ctx);
+    }
+
+    fn draw_result_item(ui: &mut egui::Ui, result: &BenchmarkResult) {
+        egui::Frame::new()
+            .fill(ui.visuals().faint_bg_color)
+            .inner_margin(8.0)
+            .show(ui, |ui| {
+                ui.label(format!(
+                    "🕒 {}",
+                    result.created_at.format("%Y-%m-%d %H:%M:%S")
+                ));
+                ui.label(format!("🗂️ {}", result.source_path.display()));
+                ui.label(format!("📁 {}", result.destination_path.display()));
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Read: {:.1} MiB/s", result.read_throughput / MEBIBYTE));
+                    ui.separator();
+                    ui.label(format!("Write: {:.1} MiB/s", result.write_throughput / MEBIBYTE));
+                    ui.separator();
+                    ui.label(format!("Small files: {:.0} ops/s", result.small_file_ops));
+                });
+
+                ui.horizontal_wrapped(|ui| {
+                    for (hash_type, throughput) in &result.hash_throughput {
+                        ui.label(format!("{:?}: {:.1} MiB/s", hash_type, throughput / MEBIBYTE));
+                    }
+                });
+
+                ui.colored_label(
+                    egui::Color32::LIGHT_BLUE,
+                    format!(
+                        "💡 max_concurrency = {}, copy buffer = {} KiB",
+                        result.recommended_concurrency,
+                        result.recommended_buffer_size / 1024
+                    ),
+                );
+            });
+    }
+
+    fn handle_file_dialog(&mut self, ctx: &egui::Context) {
+        self.file_dialog.update(ctx);
+
+        if let Some(path) = self.file_dialog.take_picked() {
+            if let Some(mode) = &self.folder_selection_mode {
+                match mode {
+                    FolderSelectionMode::Source => {
+                        self.source = path.to_string_lossy().to_string();
+                    }
+                    FolderSelectionMode::Destination => {
+                        self.destination = path.to_string_lossy().to_string();
+                    }
+                }
+            }
+            self.folder_selection_mode = None;
+        }
+    }
+}
@@ -1,9 +1,29 @@
-use crate::model::core::backup::execution::Execution;
+use crate::model::core::backup::comparison_stats::ComparisonStats;
+use crate::model::core::backup::execution::{Execution, LockedFilePolicy, WorkerLimit};
+use crate::model::core::backup::scan_totals::ScanProgress;
+use crate::model::core::schedule::schedule::HistoryRetention;
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use eframe::egui;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+pub const MEBIBYTE: f64 = 1048576.0;
+
+// How far back the trend statistics reach
+pub const TREND_DAYS: i64 = 30;
+
+pub fn trend_range() -> Range<NaiveDateTime> {
+    let now = Local::now().naive_local();
+    now - TimeDelta::days(TREND_DAYS)..now
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PageType {
     Executions,
     Schedules,
+    Search,
+    Benchmark,
+    Settings,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -19,12 +39,44 @@ pub enum ComparisonModeSelection {
     Thorough,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum Verification {
+    Running,
+    Finished { verified: usize, failed: usize },
+}
+
 #[derive(Debug, Clone)]
 pub struct ExecutionDisplay {
     pub execution: Execution,
     pub current_folder: String,
     pub processed_files: usize,
     pub error_count: usize,
+    pub current_file: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub scan: Option<ScanProgress>,
+    pub comparison: ComparisonStats,
+    pub worker_limit: WorkerLimit,
+    pub active_workers: usize,
+    pub progress_started: Option<Instant>,
+    // Why the execution failed or was suspended on its own
+    pub alert: Option<String>,
+    pub verification: Option<Verification>,
+}
+
+impl ExecutionDisplay {
+    // Extrapolates the file rate so far, only once the pre-scan has the final total
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        let scan = self.scan.filter(|scan| scan.complete)?;
+        let started = self.progress_started?;
+        if self.processed_files == 0 {
+            return None;
+        }
+        let remaining_files = scan.total_files.saturating_sub(self.processed_files);
+        let elapsed = started.elapsed().as_secs_f64();
+        let remaining = elapsed * remaining_files as f64 / self.processed_files as f64;
+        Some(Duration::from_secs_f64(remaining))
+    }
 }
 
 impl From<Execution> for ExecutionDisplay {
@@ -34,6 +86,98 @@ impl From<Execution> for ExecutionDisplay {
             current_folder: String::new(),
             processed_files: 0,
             error_count: 0,
+            current_file: String::new(),
+            copied_bytes: 0,
+            total_bytes: 0,
+            scan: None,
+            comparison: ComparisonStats::default(),
+            worker_limit: WorkerLimit::Auto,
+            active_workers: 0,
+            progress_started: None,
+            alert: None,
+            verification: None,
         }
     }
 }
+
+const DEFAULT_LOCKED_RETRIES: u32 = 3;
+
+pub fn locked_file_policy_selector(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    policy: &mut LockedFilePolicy,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Locked Files:");
+        let selected_text = match policy {
+            LockedFilePolicy::Report => "Report",
+            LockedFilePolicy::Retry(_) => "Retry",
+            LockedFilePolicy::Skip => "Skip and Retry at End",
+        };
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(policy, LockedFilePolicy::Report, "Report");
+                let retrying = matches!(policy, LockedFilePolicy::Retry(_));
+                if ui.selectable_label(retrying, "Retry").clicked() && !retrying {
+                    *policy = LockedFilePolicy::Retry(DEFAULT_LOCKED_RETRIES);
+                }
+                ui.selectable_value(policy, LockedFilePolicy::Skip, "Skip and Retry at End");
+            });
+        if let LockedFilePolicy::Retry(retries) = policy {
+            ui.add(egui::DragValue::new(retries).range(1..=100).suffix(" times"));
+        }
+    });
+}
+
+const DEFAULT_RETAINED_RUNS: usize = 100;
+const DEFAULT_RETAINED_DAYS: u32 = 30;
+
+// Limits left unchecked fall back to the global retention
+pub fn history_retention_editor(ui: &mut egui::Ui, retention: &mut HistoryRetention) {
+    retention_limit(ui, "Keep Newest Runs", &mut retention.max_runs, DEFAULT_RETAINED_RUNS, "");
+    retention_limit(ui, "Keep Runs For", &mut retention.max_days, DEFAULT_RETAINED_DAYS, " days");
+    retention_limit(
+        ui,
+        "Keep Run Details For",
+        &mut retention.detail_days,
+        DEFAULT_RETAINED_DAYS,
+        " days",
+    );
+}
+
+fn retention_limit<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    label: &str,
+    limit: &mut Option<T>,
+    default: T,
+    suffix: &str,
+) {
+    ui.horizontal(|ui| {
+        let mut enabled = limit.is_some();
+        if ui.checkbox(&mut enabled, label).changed() {
+            *limit = enabled.then_some(default);
+        }
+        if let Some(value) = limit {
+            ui.add(egui::DragValue::new(value).range(1..=100000).suffix(suffix));
+        }
+    });
+}
+
+pub fn history_retention_text(retention: &HistoryRetention) -> String {
+    let mut limits = Vec::new();
+    if let Some(max_runs) = retention.max_runs {
+        limits.push(format!("newest {max_runs} runs"));
+    }
+    if let Some(max_days) = retention.max_days {
+        limits.push(format!("{max_days} days"));
+    }
+    if let Some(detail_days) = retention.detail_days {
+        limits.push(format!("details for {detail_days} days"));
+    }
+    if limits.is_empty() {
+        "Global setting".to_string()
+    } else {
+        limits.join(", ")
+    }
+}
@@ -0,0 +1,59 @@
+use crate::model::config::ConfigIssue;
+use crate::model::log::system::SystemLog;
+use eframe::egui;
+use eframe::{App, Frame};
+use macros::log;
+
+pub struct ConfigProblemPage {
+    issues: Vec<ConfigIssue>,
+}
+
+impl ConfigProblemPage {
+    pub fn new(issues: Vec<ConfigIssue>) -> Self {
+        Self { issues }
+    }
+}
+
+impl App for ConfigProblemPage {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("⚠ Configuration Problem");
+            ui.label(
+                "MirrorSphere cannot start with the current config.toml. \
+                Correct the keys below and start it again.",
+            );
+            ui.separator();
+
+            egui::ScrollArea::vertical()
+                .max_height(ui.available_height() - 40.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("config_issues_grid")
+                        .num_columns(3)
+                        .spacing([20.0, 8.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Key");
+                            ui.strong("Problem");
+                            ui.strong("Accepted Values");
+                            ui.end_row();
+
+                            for issue in &self.issues {
+                                ui.monospace(&issue.key);
+                                ui.colored_label(ui.visuals().error_fg_color, &issue.problem);
+                                ui.label(&issue.accepted);
+                                ui.end_row();
+                            }
+                        });
+                });
+
+            ui.separator();
+            if ui.button("Exit").clicked() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        });
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        log!(SystemLog::GuiExited)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::model::core::infrastructure::event_broadcaster::{EventReceiver, TappedEvent};
+use eframe::egui;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+const MONITOR_CAPACITY: usize = 500;
+
+// Hidden diagnostics window, toggled with F12 and subscribed only while it is open
+pub struct EventMonitor {
+    communication_manager: Arc<CommunicationManager>,
+    receiver: Option<EventReceiver<TappedEvent>>,
+    // Numbered on arrival, the number keeps an entry expanded while older ones are dropped
+    events: VecDeque<(u64, TappedEvent)>,
+    received: u64,
+    filter: String,
+    paused: bool,
+    open: bool,
+}
+
+impl EventMonitor {
+    pub fn new(communication_manager: Arc<CommunicationManager>) -> Self {
+        Self {
+            communication_manager,
+            receiver: None,
+            events: VecDeque::new(),
+            received: 0,
+            filter: String::new(),
+            paused: false,
+            open: false,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            self.receiver = None;
+            return;
+        }
+        self.receive_events();
+
+        let mut open = self.open;
+        egui::Window::new("Event Monitor")
+            .open(&mut open)
+            .resizable(true)
+            .default_width(600.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.filter);
+                    ui.checkbox(&mut self.paused, "Pause");
+                    if ui.button("Clear").clicked() {
+                        self.events.clear();
+                    }
+                });
+                ui.separator();
+                self.draw_events(ui);
+            });
+        self.open = open;
+    }
+
+    // Drained while paused as well, so the queue does not fill up with stale events
+    fn receive_events(&mut self) {
+        let receiver = self
+            .receiver
+            .get_or_insert_with(|| self.communication_manager.subscribe_all_events());
+        while let Some(event) = receiver.try_recv() {
+            if self.paused {
+                continue;
+            }
+            self.events.push_back((self.received, event));
+            self.received += 1;
+            if self.events.len() > MONITOR_CAPACITY {
+                self.events.pop_front();
+            }
+        }
+    }
+
+    fn draw_events(&self, ui: &mut egui::Ui) {
+        let filter = self.filter.to_lowercase();
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                let events = self.events.iter().filter(|(_, event)| {
+                    filter.is_empty()
+                        || event.event.to_lowercase().contains(&filter)
+                        || event.payload.to_lowercase().contains(&filter)
+                });
+                for (number, event) in events {
+                    let published_at = event.published_at.format("%H:%M:%S%.3f");
+                    egui::CollapsingHeader::new(format!("{published_at}  {}", event.event))
+                        .id_salt(("tapped_event", number))
+                        .show(ui, |ui| {
+                            ui.monospace(&event.payload);
+                        });
+                }
+            });
+    }
+}
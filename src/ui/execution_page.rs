@@ -1,38 +1,91 @@
+use crate::core::gui::ui_bridge::{Pending, UiBridge};
 use crate::core::infrastructure::app_config::AppConfig;
 use crate::core::infrastructure::communication_manager::CommunicationManager;
+use crate::model::core::backup::capability_report::CapabilityReport;
+use crate::model::core::backup::catalog::{CatalogAction, CatalogEntry};
 use crate::model::core::backup::communication::{BackupCommand, BackupQuery, BackupQueryResponse};
 use crate::model::core::backup::execution::*;
-use crate::model::core::gui::communication::{ExecutionErrors, ExecutionProgress, FolderProcess};
+use crate::model::core::backup::execution_history::ExecutionHistory;
+use crate::model::core::backup::history_statistics::DailyBytesCopied;
+use crate::model::core::backup::storage_usage::DestinationUsage;
+use crate::model::core::infrastructure::event_journal::JournalEntry;
+use crate::model::core::maintenance::communication::MaintenanceCommand;
+use crate::model::core::gui::communication::{
+    DestinationFull, ExecutionErrors, ExecutionFailed, ExecutionProgress, FileProgress,
+    FolderProcess, VerificationFinished,
+};
+use crate::model::core::infrastructure::event_broadcaster::EventReceiver;
 use crate::model::error::Error;
-use crate::ui::common::{ComparisonModeSelection, ExecutionDisplay, FolderSelectionMode};
+use crate::model::error::record::ErrorRecord;
+use crate::model::error::task::TaskError;
+use crate::ui::common::{
+    ComparisonModeSelection, ExecutionDisplay, FolderSelectionMode, MEBIBYTE, TREND_DAYS,
+    Verification, locked_file_policy_selector, trend_range,
+};
 use dashmap::DashMap;
 use eframe::egui;
 use egui_file_dialog::FileDialog;
-use futures::executor::block_on;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::broadcast;
 use tracing::error;
 use uuid::Uuid;
 
 pub struct ExecutionPage {
     app_config: Arc<AppConfig>,
-    communication_manager: Arc<CommunicationManager>,
-
-    folder_process: broadcast::Receiver<FolderProcess>,
-    execution_progress: broadcast::Receiver<ExecutionProgress>,
-    execution_errors: broadcast::Receiver<ExecutionErrors>,
+    ui_bridge: UiBridge,
+    read_only: bool,
+
+    folder_process: EventReceiver<FolderProcess>,
+    execution_progress: EventReceiver<ExecutionProgress>,
+    file_progress: EventReceiver<FileProgress>,
+    execution_errors: EventReceiver<ExecutionErrors>,
+    execution_failed: EventReceiver<ExecutionFailed>,
+    destination_full: EventReceiver<DestinationFull>,
+    verification_finished: EventReceiver<VerificationFinished>,
+
+    executions_query: Option<Pending<BackupQueryResponse>>,
+    history_query: Option<Pending<BackupQueryResponse>>,
+    history_errors_query: Option<Pending<BackupQueryResponse>>,
+    catalog_query: Option<Pending<BackupQueryResponse>>,
+    daily_bytes_query: Option<Pending<BackupQueryResponse>>,
+    storage_usage_query: Option<Pending<BackupQueryResponse>>,
+    journal_query: Option<Pending<BackupQueryResponse>>,
+    pending_commands: Vec<Pending<()>>,
+    // Set when a start was refused for lack of administrator rights
+    show_elevation_dialog: bool,
+    restarting_elevated: Option<Pending<()>>,
+    close_requested: bool,
 
     executions: DashMap<Uuid, ExecutionDisplay>,
     error_messages: DashMap<Uuid, Vec<Error>>,
+    history: Vec<ExecutionHistory>,
+    daily_bytes: Vec<DailyBytesCopied>,
+    storage_usage: Vec<DestinationUsage>,
+    journal: Vec<JournalEntry>,
+    show_history: bool,
+    // History entry whose stored errors are shown, with its total error count
+    viewing_history_errors: Option<(Uuid, usize)>,
+    history_errors: Vec<ErrorRecord>,
+    viewing_catalog: bool,
+    catalog: Vec<CatalogEntry>,
+    catalog_filter: String,
+    // History entry whose report is waiting for a file name
+    report_history: Option<ExecutionHistory>,
+    report_dialog: FileDialog,
 
     new_task_source: String,
     new_task_destination: String,
     new_task_mirror: bool,
     new_task_backup_permission: bool,
     new_task_follow_symlinks: bool,
+    new_task_background_io: bool,
+    new_task_manifest: bool,
+    new_task_strict: bool,
+    new_task_catalog: bool,
+    new_task_elevated: bool,
+    new_task_locked_files: LockedFilePolicy,
     new_task_comparison_mode: ComparisonModeSelection,
     new_task_hash_type: HashType,
     show_add_task_dialog: bool,
@@ -44,29 +97,72 @@ pub struct ExecutionPage {
     pub show_completed_tasks: bool,
     viewing_errors_for_task: Option<Uuid>,
     last_refresh: Option<Instant>,
+    // Events stay queued until the executions they refer to are known, replayed ones included
+    states_loaded: bool,
 }
 
 impl ExecutionPage {
     pub fn new(
         app_config: Arc<AppConfig>,
         communication_manager: Arc<CommunicationManager>,
+        ui_bridge: UiBridge,
+        read_only: bool,
     ) -> Result<Self, Error> {
         let folder_process = communication_manager.subscribe_event::<FolderProcess>()?;
         let execution_progress = communication_manager.subscribe_event::<ExecutionProgress>()?;
+        let file_progress = communication_manager.subscribe_event::<FileProgress>()?;
         let execution_errors = communication_manager.subscribe_event::<ExecutionErrors>()?;
+        let execution_failed = communication_manager.subscribe_event::<ExecutionFailed>()?;
+        let destination_full = communication_manager.subscribe_event::<DestinationFull>()?;
+        let verification_finished =
+            communication_manager.subscribe_event::<VerificationFinished>()?;
         let execution_page = Self {
             app_config,
-            communication_manager,
+            ui_bridge,
+            read_only,
             folder_process,
             execution_progress,
+            file_progress,
             execution_errors,
+            execution_failed,
+            destination_full,
+            verification_finished,
+            executions_query: None,
+            history_query: None,
+            history_errors_query: None,
+            catalog_query: None,
+            daily_bytes_query: None,
+            storage_usage_query: None,
+            journal_query: None,
+            pending_commands: Vec::new(),
+            show_elevation_dialog: false,
+            restarting_elevated: None,
+            close_requested: false,
             executions: DashMap::new(),
             error_messages: DashMap::new(),
+            history: Vec::new(),
+            daily_bytes: Vec::new(),
+            storage_usage: Vec::new(),
+            journal: Vec::new(),
+            show_history: false,
+            viewing_history_errors: None,
+            history_errors: Vec::new(),
+            viewing_catalog: false,
+            catalog: Vec::new(),
+            catalog_filter: String::new(),
+            report_history: None,
+            report_dialog: FileDialog::new().default_file_name("report.html"),
             new_task_source: String::new(),
             new_task_destination: String::new(),
             new_task_mirror: false,
             new_task_backup_permission: false,
             new_task_follow_symlinks: false,
+            new_task_background_io: false,
+            new_task_manifest: false,
+            new_task_strict: false,
+            new_task_catalog: false,
+            new_task_elevated: false,
+            new_task_locked_files: LockedFilePolicy::Report,
             new_task_comparison_mode: ComparisonModeSelection::Standard,
             new_task_hash_type: HashType::BLAKE3,
             show_add_task_dialog: false,
@@ -76,25 +172,52 @@ impl ExecutionPage {
             show_completed_tasks: true,
             viewing_errors_for_task: None,
             last_refresh: None,
+            states_loaded: false,
         };
         Ok(execution_page)
     }
 
     fn process_events(&mut self) {
-        while let Ok(event) = self.folder_process.try_recv() {
+        if !self.states_loaded {
+            return;
+        }
+        while let Some(event) = self.folder_process.try_recv() {
             let FolderProcess { uuid, folder } = event;
             if let Some(mut task_display) = self.executions.get_mut(&uuid) {
                 task_display.current_folder = folder.to_string_lossy().to_string();
             }
         }
-        while let Ok(event) = self.execution_progress.try_recv() {
-            let ExecutionProgress { uuid, processed_files, error_count } = event;
+        while let Some(event) = self.execution_progress.try_recv() {
+            let ExecutionProgress {
+                uuid,
+                processed_files,
+                error_count,
+                scan,
+                comparison,
+                active_workers,
+            } = event;
             if let Some(mut task_display) = self.executions.get_mut(&uuid) {
+                if task_display.progress_started.is_none()
+                    || processed_files < task_display.processed_files
+                {
+                    task_display.progress_started = Some(Instant::now());
+                }
                 task_display.processed_files = processed_files;
+                task_display.scan = scan;
+                task_display.comparison = comparison;
+                task_display.active_workers = active_workers;
                 task_display.error_count = error_count;
             }
         }
-        while let Ok(event) = self.execution_errors.try_recv() {
+        while let Some(event) = self.file_progress.try_recv() {
+            let FileProgress { uuid, file, copied_bytes, total_bytes } = event;
+            if let Some(mut task_display) = self.executions.get_mut(&uuid) {
+                task_display.current_file = file.to_string_lossy().to_string();
+                task_display.copied_bytes = copied_bytes;
+                task_display.total_bytes = total_bytes;
+            }
+        }
+        while let Some(event) = self.execution_errors.try_recv() {
             let ExecutionErrors { uuid, errors } = event;
             match self.error_messages.get_mut(&uuid) {
                 Some(mut errors_ref) => errors_ref.extend(errors),
@@ -103,78 +226,183 @@ impl ExecutionPage {
                 }
             }
         }
+        while let Some(event) = self.execution_failed.try_recv() {
+            let ExecutionFailed { uuid, error } = event;
+            if let Some(mut task_display) = self.executions.get_mut(&uuid) {
+                task_display.alert = Some(error.to_string());
+            }
+        }
+        while let Some(event) = self.destination_full.try_recv() {
+            let DestinationFull { uuid, path } = event;
+            if let Some(mut task_display) = self.executions.get_mut(&uuid) {
+                task_display.alert = Some(format!(
+                    "Destination {} is full, free some space and resume",
+                    path.display()
+                ));
+            }
+        }
+        while let Some(event) = self.verification_finished.try_recv() {
+            let VerificationFinished {
+                uuid,
+                verified,
+                failed,
+            } = event;
+            if let Some(mut task_display) = self.executions.get_mut(&uuid) {
+                task_display.verification = Some(Verification::Finished { verified, failed });
+            }
+        }
     }
 
-    fn sync_all_execution_states(&mut self) {
-        if let Ok(response) = block_on(async {
-            self.communication_manager
-                .send_query(BackupQuery::GetExecutions)
-                .await
-        }) {
-            let BackupQueryResponse::GetExecutions(latest_executions) = response;
-            let latest_ids: HashSet<Uuid> = latest_executions.iter().map(|(id, _)| *id).collect();
-
-            for (task_id, latest_execution) in latest_executions {
-                let execution_display = ExecutionDisplay::from(latest_execution);
-                self.executions.insert(task_id, execution_display);
+    fn request_execution_states(&mut self) {
+        if self.executions_query.is_none() {
+            let query = BackupQuery::GetExecutions;
+            self.executions_query = Some(self.ui_bridge.send_refresh_query(query));
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    fn poll_backend(&mut self) {
+        let mut refresh = false;
+        let mut elevation_required = false;
+        self.pending_commands.retain_mut(|pending| match pending.poll() {
+            Some(result) => {
+                if let Err(err) = result {
+                    elevation_required |=
+                        matches!(err, Error::Task(TaskError::ElevationRequired));
+                    error!("{}", err);
+                }
+                refresh = true;
+                false
             }
+            None => true,
+        });
+        self.show_elevation_dialog |= elevation_required;
+        if let Some(result) = self.restarting_elevated.as_mut().and_then(Pending::poll) {
+            self.restarting_elevated = None;
+            match result {
+                Ok(()) => self.close_requested = true,
+                Err(err) => error!("{}", err),
+            }
+        }
+        if refresh {
+            self.last_refresh = None;
+        }
 
-            self.executions
-                .retain(|task_id, _| latest_ids.contains(task_id));
-            self.error_messages
-                .retain(|task_id, _| latest_ids.contains(task_id));
+        if let Some(result) = self.history_query.as_mut().and_then(Pending::poll) {
+            self.history_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetHistory(history)) => self.history = history,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.history_errors_query.as_mut().and_then(Pending::poll) {
+            self.history_errors_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetHistoryErrors(errors)) => self.history_errors = errors,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.catalog_query.as_mut().and_then(Pending::poll) {
+            self.catalog_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetCatalog(entries)) => self.catalog = entries,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.daily_bytes_query.as_mut().and_then(Pending::poll) {
+            self.daily_bytes_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetBytesCopiedPerDay(days)) => self.daily_bytes = days,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.storage_usage_query.as_mut().and_then(Pending::poll) {
+            self.storage_usage_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetStorageUsage(usage)) => self.storage_usage = usage,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.journal_query.as_mut().and_then(Pending::poll) {
+            self.journal_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetJournal(entries)) => self.journal = entries,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
 
-            if let Some(viewing_id) = self.viewing_errors_for_task {
-                if !latest_ids.contains(&viewing_id) {
-                    self.viewing_errors_for_task = None;
-                }
+        let Some(result) = self.executions_query.as_mut().and_then(Pending::poll) else {
+            return;
+        };
+        self.executions_query = None;
+        match result {
+            Ok(BackupQueryResponse::GetExecutions(latest_executions)) => {
+                self.apply_execution_states(latest_executions)
             }
+            Ok(_) => {}
+            Err(err) => error!("{}", err),
         }
     }
 
-    fn handle_add_execution(&mut self, execution: Execution) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(BackupCommand::AddExecution(execution))
-                .await?;
-            Ok(())
-        })
+    fn request_history(&mut self) {
+        if self.history_query.is_none() {
+            self.history_query = Some(self.ui_bridge.send_query(BackupQuery::GetHistory));
+        }
+        if self.daily_bytes_query.is_none() {
+            let query = BackupQuery::GetBytesCopiedPerDay(trend_range());
+            self.daily_bytes_query = Some(self.ui_bridge.send_query(query));
+        }
+        if self.storage_usage_query.is_none() {
+            let query = BackupQuery::GetStorageUsage(trend_range());
+            self.storage_usage_query = Some(self.ui_bridge.send_query(query));
+        }
+        if self.journal_query.is_none() {
+            let query = BackupQuery::GetJournal(trend_range());
+            self.journal_query = Some(self.ui_bridge.send_query(query));
+        }
     }
 
-    fn handle_start_execution(&mut self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(BackupCommand::StartExecution(uuid))
-                .await?;
-            Ok(())
-        })
-    }
+    fn apply_execution_states(&mut self, latest_executions: Vec<(Uuid, Execution)>) {
+        self.states_loaded = true;
+        let latest_ids: HashSet<Uuid> = latest_executions.iter().map(|(id, _)| *id).collect();
 
-    fn handle_suspend_execution(&mut self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(BackupCommand::SuspendExecution(uuid))
-                .await?;
-            Ok(())
-        })
-    }
+        for (task_id, latest_execution) in latest_executions {
+            match self.executions.get_mut(&task_id) {
+                Some(mut execution_display) => {
+                    if latest_execution.state == BackupState::Running {
+                        execution_display.alert = None;
+                        execution_display.verification = None;
+                    }
+                    execution_display.execution = latest_execution;
+                }
+                None => {
+                    let execution_display = ExecutionDisplay::from(latest_execution);
+                    self.executions.insert(task_id, execution_display);
+                }
+            }
+        }
+
+        self.executions
+            .retain(|task_id, _| latest_ids.contains(task_id));
+        self.error_messages
+            .retain(|task_id, _| latest_ids.contains(task_id));
 
-    fn handle_resume_execution(&mut self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(BackupCommand::ResumeExecution(uuid))
-                .await?;
-            Ok(())
-        })
+        if let Some(viewing_id) = self.viewing_errors_for_task {
+            if !latest_ids.contains(&viewing_id) {
+                self.viewing_errors_for_task = None;
+            }
+        }
     }
 
-    fn handle_remove_execution(&mut self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(BackupCommand::RemoveExecution(uuid))
-                .await?;
-            Ok(())
-        })
+    fn send_command(&mut self, command: BackupCommand) {
+        let pending = self.ui_bridge.send_command(command);
+        self.pending_commands.push(pending);
     }
 
     pub fn update(&mut self, ctx: &egui::Context) {
@@ -187,9 +415,12 @@ impl ExecutionPage {
             }
         };
 
+        self.poll_backend();
+        if self.close_requested {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
         if should_refresh {
-            self.sync_all_execution_states();
-            self.last_refresh = Some(Instant::now());
+            self.request_execution_states();
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -197,11 +428,16 @@ impl ExecutionPage {
 
             ui.horizontal(|ui| {
                 if ui.button("🔄 Refresh").clicked() {
-                    self.sync_all_execution_states();
-                    self.last_refresh = Some(Instant::now());
+                    self.request_execution_states();
+                }
+
+                if ui.button("📜 History").clicked() {
+                    self.show_history = true;
+                    self.request_history();
                 }
 
-                if ui.button("➕ Add Execution").clicked() {
+                let add_button = egui::Button::new("➕ Add Execution");
+                if ui.add_enabled(!self.read_only, add_button).clicked() {
                     self.show_add_task_dialog = true;
                 }
 
@@ -268,7 +504,9 @@ impl ExecutionPage {
         });
 
         self.draw_add_execution_dialog(ctx);
+        self.draw_elevation_dialog(ctx);
         self.draw_execution_errors_window(ctx);
+        self.draw_history_window(ctx);
     }
 
     fn draw_execution_item(
@@ -322,12 +560,94 @@ impl ExecutionPage {
 
                         ui.horizontal(|ui| {
                             if task_display.processed_files > 0 || task_display.error_count > 0 {
+                                let processed_files = task_display.processed_files;
+                                let processed = match task_display.scan {
+                                    Some(scan) if scan.complete => {
+                                        format!("{processed_files} of {}", scan.total_files)
+                                    }
+                                    Some(scan) => {
+                                        format!("{processed_files} of {}+", scan.total_files)
+                                    }
+                                    None => processed_files.to_string(),
+                                };
                                 ui.label(format!(
                                     "📊 Processed: {} | Errors: {}",
-                                    task_display.processed_files, task_display.error_count
+                                    processed, task_display.error_count
+                                ));
+                            }
+                            if task_display.execution.state == BackupState::Running
+                                && let Some(remaining) = task_display.estimated_remaining()
+                            {
+                                let seconds = remaining.as_secs();
+                                ui.separator();
+                                ui.label(format!(
+                                    "⏳ ETA: {:02}:{:02}:{:02}",
+                                    seconds / 3600,
+                                    seconds % 3600 / 60,
+                                    seconds % 60
                                 ));
                             }
                         });
+
+                        let state = task_display.execution.state;
+                        if matches!(state, BackupState::Failed | BackupState::Suspended)
+                            && let Some(alert) = &task_display.alert
+                        {
+                            ui.colored_label(egui::Color32::RED, format!("⚠ {alert}"));
+                        }
+
+                        match task_display.verification {
+                            Some(Verification::Running) => {
+                                ui.label("🔍 Verifying against the manifest...");
+                            }
+                            Some(Verification::Finished { verified, failed: 0 }) => {
+                                ui.colored_label(
+                                    egui::Color32::GREEN,
+                                    format!("✅ {verified} files match the manifest"),
+                                );
+                            }
+                            Some(Verification::Finished { verified, failed }) => {
+                                ui.colored_label(
+                                    egui::Color32::RED,
+                                    format!("⚠ {failed} failed verification, {verified} match"),
+                                );
+                            }
+                            None => {}
+                        }
+
+                        let comparison = task_display.comparison;
+                        if comparison.total() > 0 {
+                            ui.label(format!(
+                                "🔍 Decided by size: {} | mtime: {} | attributes: {} | hash: {}",
+                                comparison.size,
+                                comparison.modified,
+                                comparison.attributes,
+                                comparison.hash
+                            ));
+                        }
+
+                        if task_display.execution.state == BackupState::Running
+                            && !task_display.current_file.is_empty()
+                            && task_display.total_bytes > 0
+                        {
+                            ui.horizontal(|ui| {
+                                ui.label(format!(
+                                    "📄 {}",
+                                    task_display
+                                        .current_file
+                                        .chars()
+                                        .take(50)
+                                        .collect::<String>()
+                                ));
+                                let copied_bytes = task_display.copied_bytes as f32;
+                                let fraction = copied_bytes / task_display.total_bytes as f32;
+                                ui.add(
+                                    egui::ProgressBar::new(fraction)
+                                        .desired_width(150.0)
+                                        .show_percentage(),
+                                );
+                            });
+                        }
                     });
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -343,38 +663,88 @@ impl ExecutionPage {
                         match task_display.execution.state {
                             BackupState::Pending => {
                                 if ui.button("▶ Start").clicked() {
-                                    if let Err(err) = self.handle_start_execution(uuid) {
-                                        error!("{}", err);
-                                    }
+                                    self.send_command(BackupCommand::StartExecution(uuid));
                                 }
                             }
                             BackupState::Suspended => {
+                                self.draw_cancel(ui, uuid);
                                 if ui.button("▶ Resume").clicked() {
-                                    if let Err(err) = self.handle_resume_execution(uuid) {
-                                        error!("{}", err);
-                                    }
+                                    self.send_command(BackupCommand::ResumeExecution(uuid));
                                 }
                             }
                             BackupState::Running => {
+                                self.draw_cancel(ui, uuid);
                                 if ui.button("⏸ Pause").clicked() {
-                                    if let Err(err) = self.handle_suspend_execution(uuid) {
-                                        error!("{}", err);
+                                    self.send_command(BackupCommand::SuspendExecution(uuid));
+                                }
+                                self.draw_worker_limit(ui, uuid, task_display);
+                            }
+                            BackupState::Completed => {
+                                let verification = task_display.verification;
+                                let verifying = matches!(verification, Some(Verification::Running));
+                                if task_display.execution.options.manifest
+                                    && !verifying
+                                    && ui.button("🔍 Verify").clicked()
+                                {
+                                    if let Some(mut display) = self.executions.get_mut(&uuid) {
+                                        display.verification = Some(Verification::Running);
                                     }
+                                    self.send_command(BackupCommand::VerifyExecution(uuid));
                                 }
                             }
                             _ => {}
                         }
 
                         if ui.button("🗑").clicked() {
-                            if let Err(err) = self.handle_remove_execution(uuid) {
-                                error!("{}", err);
-                            }
+                            self.send_command(BackupCommand::RemoveExecution(uuid));
                         }
                     });
                 });
             });
     }
 
+    fn draw_cancel(&mut self, ui: &mut egui::Ui, uuid: Uuid) {
+        ui.menu_button("⏹ Cancel", |ui| {
+            if ui.button("Keep Partial Copies").clicked() {
+                self.send_command(BackupCommand::CancelExecution(uuid, false));
+            }
+            if ui.button("Delete Partial Copies").clicked() {
+                self.send_command(BackupCommand::CancelExecution(uuid, true));
+            }
+        });
+    }
+
+    fn draw_worker_limit(
+        &mut self,
+        ui: &mut egui::Ui,
+        uuid: Uuid,
+        task_display: &ExecutionDisplay,
+    ) {
+        let mut worker_limit = task_display.worker_limit;
+        let selected = match worker_limit {
+            WorkerLimit::Auto => format!("Auto ({})", task_display.active_workers),
+            WorkerLimit::Fixed(limit) => limit.to_string(),
+        };
+        egui::ComboBox::from_id_salt(("worker_limit", uuid))
+            .selected_text(selected)
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut worker_limit, WorkerLimit::Auto, "Auto");
+                for limit in 1..=self.app_config.max_concurrency as usize {
+                    let label = limit.to_string();
+                    ui.selectable_value(&mut worker_limit, WorkerLimit::Fixed(limit), label);
+                }
+            });
+        ui.label("Workers:");
+
+        if worker_limit != task_display.worker_limit {
+            if let Some(mut display) = self.executions.get_mut(&uuid) {
+                display.worker_limit = worker_limit;
+            }
+            self.send_command(BackupCommand::SetWorkerLimit(uuid, worker_limit));
+        }
+    }
+
     fn draw_add_execution_dialog(&mut self, ctx: &egui::Context) {
         if self.show_add_task_dialog {
             egui::Window::new("Add Backup Execution")
@@ -481,6 +851,16 @@ impl ExecutionPage {
                         &mut self.new_task_backup_permission,
                         "Backup File Permissions",
                     );
+                    ui.checkbox(&mut self.new_task_background_io, "Background IO Priority");
+                    ui.checkbox(&mut self.new_task_manifest, "Write Checksum Manifest");
+                    ui.checkbox(&mut self.new_task_strict, "Stop at First Error");
+                    ui.checkbox(&mut self.new_task_catalog, "Record Changed Files");
+                    ui.checkbox(&mut self.new_task_elevated, "Run as Administrator");
+                    locked_file_policy_selector(
+                        ui,
+                        "new_task_locked_files",
+                        &mut self.new_task_locked_files,
+                    );
 
                     ui.separator();
 
@@ -508,20 +888,20 @@ impl ExecutionPage {
                                     mirror: self.new_task_mirror,
                                     backup_permission: self.new_task_backup_permission,
                                     follow_symlinks: self.new_task_follow_symlinks,
+                                    background_io: self.new_task_background_io,
+                                    locked_files: self.new_task_locked_files,
+                                    manifest: self.new_task_manifest,
+                                    strict: self.new_task_strict,
+                                    catalog: self.new_task_catalog,
+                                    elevated: self.new_task_elevated,
                                 },
+                                schedule_uuid: None,
                             };
 
-                            match self.handle_add_execution(execution.clone()) {
-                                Ok(_) => {
-                                    let execution_display =
-                                        ExecutionDisplay::from(execution.clone());
-                                    self.executions.insert(execution.uuid, execution_display);
-                                    self.reset_form();
-                                }
-                                Err(err) => {
-                                    error!("{}", err);
-                                }
-                            }
+                            let execution_display = ExecutionDisplay::from(execution.clone());
+                            self.executions.insert(execution.uuid, execution_display);
+                            self.send_command(BackupCommand::AddExecution(execution));
+                            self.reset_form();
                         }
 
                         if ui.button("Cancel").clicked() {
@@ -548,6 +928,45 @@ impl ExecutionPage {
         }
     }
 
+    fn draw_elevation_dialog(&mut self, ctx: &egui::Context) {
+        if !self.show_elevation_dialog {
+            return;
+        }
+        let running = self
+            .executions
+            .iter()
+            .any(|entry| entry.value().execution.state == BackupState::Running);
+        egui::Window::new("Administrator Rights Required")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("This execution runs as administrator, MirrorSphere is not elevated.");
+                ui.label(
+                    "Restarting elevated closes this window, executions not created by a \
+                    schedule have to be added again.",
+                );
+                if running {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "Wait for the running executions to finish before restarting.",
+                    );
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let idle = !running && self.restarting_elevated.is_none();
+                    let restart = egui::Button::new("🛡 Restart as Administrator");
+                    if ui.add_enabled(idle, restart).clicked() {
+                        let command = MaintenanceCommand::RestartElevated;
+                        self.restarting_elevated = Some(self.ui_bridge.send_command(command));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.show_elevation_dialog = false;
+                    }
+                });
+            });
+    }
+
     fn draw_execution_errors_window(&mut self, ctx: &egui::Context) {
         if let Some(task_id) = self.viewing_errors_for_task {
             let mut show_window = true;
@@ -629,12 +1048,412 @@ impl ExecutionPage {
         }
     }
 
+    fn draw_history_window(&mut self, ctx: &egui::Context) {
+        if !self.show_history {
+            return;
+        }
+        let mut show_window = true;
+        let mut history_errors = None;
+        let mut catalog = None;
+        let mut report = None;
+
+        egui::Window::new("Execution History")
+            .open(&mut show_window)
+            .resizable(true)
+            .default_width(800.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(format!("Past Runs ({} items)", self.history.len()));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("🔄 Refresh").clicked() {
+                            self.request_history();
+                        }
+                    });
+                });
+
+                ui.separator();
+
+                egui::CollapsingHeader::new(format!("📈 Data Copied, Last {TREND_DAYS} Days"))
+                    .show(ui, |ui| self.draw_daily_bytes(ui));
+                egui::CollapsingHeader::new(format!("💾 Storage Usage, Last {TREND_DAYS} Days"))
+                    .show(ui, |ui| self.draw_storage_usage(ui));
+                egui::CollapsingHeader::new(format!("📓 Event Journal, Last {TREND_DAYS} Days"))
+                    .show(ui, |ui| self.draw_journal(ui));
+
+                ui.separator();
+
+                egui::ScrollArea::both().show(ui, |ui| {
+                    egui::Grid::new("execution_history_grid")
+                        .num_columns(12)
+                        .spacing([12.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in [
+                                "Started", "Duration", "Source", "State", "Scanned", "Copied",
+                                "Deleted", "Size", "Errors", "Capabilities", "Files", "Report",
+                            ] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+
+                            for entry in &self.history {
+                                let duration = entry.finished_at - entry.started_at;
+                                let source = if entry.schedule_uuid.is_some() {
+                                    format!("⏰ {}", entry.source_path.display())
+                                } else {
+                                    entry.source_path.display().to_string()
+                                };
+                                ui.label(entry.started_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                                ui.label(format!("{}s", duration.num_seconds()));
+                                ui.label(source)
+                                    .on_hover_text(entry.destination_path.display().to_string());
+                                ui.label(format!("{:?}", entry.state));
+                                ui.label(entry.files_scanned.to_string());
+                                ui.label(entry.files_copied.to_string());
+                                ui.label(entry.entries_deleted.to_string());
+                                let size = entry.bytes_copied as f64 / MEBIBYTE;
+                                ui.label(format!("{size:.1} MiB"));
+                                if entry.error_count == 0 {
+                                    ui.label("0");
+                                } else if ui.small_button(entry.error_count.to_string()).clicked() {
+                                    history_errors = Some((entry.uuid, entry.error_count));
+                                }
+                                Self::draw_capabilities(ui, entry.capabilities.as_ref());
+                                if ui.small_button("📂").clicked() {
+                                    catalog = Some(entry.uuid);
+                                }
+                                let export = ui.small_button("📄").on_hover_text("Export report");
+                                if export.clicked() {
+                                    report = Some(entry.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+
+                    if self.history.is_empty() {
+                        ui.vertical_centered(|ui| {
+                            ui.label("No runs recorded yet");
+                        });
+                    }
+                });
+            });
+
+        if let Some((history_uuid, error_count)) = history_errors {
+            self.viewing_history_errors = Some((history_uuid, error_count));
+            self.history_errors.clear();
+            let query = BackupQuery::GetHistoryErrors(history_uuid);
+            self.history_errors_query = Some(self.ui_bridge.send_query(query));
+        }
+        if let Some(history_uuid) = catalog {
+            self.viewing_catalog = true;
+            self.catalog.clear();
+            self.catalog_filter.clear();
+            let query = BackupQuery::GetCatalog(history_uuid);
+            self.catalog_query = Some(self.ui_bridge.send_query(query));
+        }
+        if report.is_some() {
+            self.report_history = report;
+            self.report_dialog.save_file();
+        }
+        self.report_dialog.update(ctx);
+        if let Some(path) = self.report_dialog.take_picked()
+            && let Some(history) = self.report_history.take()
+        {
+            self.send_command(BackupCommand::ExportReport(history, path));
+        }
+        if !show_window {
+            self.show_history = false;
+        }
+        self.draw_history_errors_window(ctx);
+        self.draw_catalog_window(ctx);
+    }
+
+    // Features the destination lacks stand out, the whole matrix shows on hover
+    fn draw_capabilities(ui: &mut egui::Ui, capabilities: Option<&CapabilityReport>) {
+        let Some(capabilities) = capabilities else {
+            ui.label("-");
+            return;
+        };
+        let missing = capabilities.missing_features();
+        let label = if missing.is_empty() {
+            ui.label("✅")
+        } else {
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", missing.len()))
+        };
+        label.on_hover_ui(|ui| {
+            egui::Grid::new("capability_grid")
+                .num_columns(3)
+                .spacing([12.0, 2.0])
+                .show(ui, |ui| {
+                    let mark = |supported: bool| if supported { "✅" } else { "❌" };
+                    ui.strong("Feature");
+                    ui.strong("Source");
+                    ui.strong("Destination");
+                    ui.end_row();
+                    for (name, source, destination) in capabilities.rows() {
+                        ui.label(name);
+                        ui.label(mark(source));
+                        ui.label(mark(destination));
+                        ui.end_row();
+                    }
+                    ui.label("Timestamp Resolution");
+                    ui.label(capabilities.source.resolution_label());
+                    ui.label(capabilities.destination.resolution_label());
+                    ui.end_row();
+                });
+        });
+    }
+
+    fn draw_daily_bytes(&self, ui: &mut egui::Ui) {
+        if self.daily_bytes.is_empty() {
+            ui.label("No runs in this period");
+            return;
+        }
+        let most = self.daily_bytes.iter().map(|day| day.bytes_copied).max().unwrap_or(0);
+        egui::Grid::new("daily_bytes_grid")
+            .num_columns(2)
+            .spacing([12.0, 2.0])
+            .show(ui, |ui| {
+                for day in &self.daily_bytes {
+                    let size = day.bytes_copied as f64 / MEBIBYTE;
+                    let fraction = day.bytes_copied as f32 / most.max(1) as f32;
+                    ui.label(day.day.format("%Y-%m-%d").to_string());
+                    ui.add(
+                        egui::ProgressBar::new(fraction)
+                            .desired_width(300.0)
+                            .text(format!("{size:.1} MiB")),
+                    );
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn draw_journal(&self, ui: &mut egui::Ui) {
+        if self.journal.is_empty() {
+            ui.label("Nothing was recorded in this period");
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .id_salt("event_journal_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                egui::Grid::new("event_journal_grid")
+                    .num_columns(3)
+                    .spacing([12.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for entry in &self.journal {
+                            ui.label(entry.recorded_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                            let event = format!("{:?}", entry.event);
+                            let label = match entry.schedule_uuid {
+                                Some(_) => ui.label(format!("⏰ {event}")),
+                                None => ui.label(event),
+                            };
+                            if let Some(execution_uuid) = entry.execution_uuid {
+                                label.on_hover_text(format!("Execution {execution_uuid}"));
+                            }
+                            // Error batches span several lines, the first one stands for them
+                            let mut lines = entry.detail.lines();
+                            let first = lines.next().unwrap_or_default();
+                            let detail = ui.label(first);
+                            if lines.next().is_some() {
+                                detail.on_hover_text(&entry.detail);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+
+    // Destinations projected to fill within storage_warning_days are shown in red
+    fn draw_storage_usage(&self, ui: &mut egui::Ui) {
+        if self.storage_usage.is_empty() {
+            ui.label("No completed runs in this period");
+            return;
+        }
+        let warning_days = self.app_config.storage_warning_days as f64;
+        egui::Grid::new("storage_usage_grid")
+            .num_columns(6)
+            .spacing([12.0, 2.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Destination", "Size", "Free", "Written", "Growth", "Full In"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+
+                for usage in &self.storage_usage {
+                    ui.label(usage.destination_path.display().to_string());
+                    let trend = usage
+                        .footprints
+                        .iter()
+                        .map(|(recorded_at, footprint)| {
+                            let size = *footprint as f64 / MEBIBYTE;
+                            format!("{}  {size:.1} MiB", recorded_at.format("%Y-%m-%d %H:%M"))
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.label(format!("{:.1} MiB", usage.footprint as f64 / MEBIBYTE))
+                        .on_hover_text(trend);
+                    ui.label(match usage.available {
+                        Some(available) => format!("{:.1} MiB", available as f64 / MEBIBYTE),
+                        None => "Unknown".to_string(),
+                    });
+                    ui.label(format!("{:.1} MiB", usage.bytes_written as f64 / MEBIBYTE));
+                    ui.label(match usage.growth_per_day {
+                        Some(growth) => format!("{:+.1} MiB/day", growth / MEBIBYTE),
+                        None => "-".to_string(),
+                    });
+                    match usage.days_until_full {
+                        Some(days) if warning_days > 0.0 && days < warning_days => {
+                            let text = format!("⚠ {days:.0} days");
+                            ui.colored_label(ui.visuals().error_fg_color, text);
+                        }
+                        Some(days) => {
+                            ui.label(format!("{days:.0} days"));
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn draw_catalog_window(&mut self, ctx: &egui::Context) {
+        if !self.viewing_catalog {
+            return;
+        }
+        let mut show_window = true;
+
+        egui::Window::new("Changed Files")
+            .open(&mut show_window)
+            .resizable(true)
+            .default_width(600.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                if self.catalog_query.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if self.catalog.is_empty() {
+                    ui.vertical_centered(|ui| {
+                        ui.label("No changed files were recorded for this run");
+                    });
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.catalog_filter);
+                });
+                ui.separator();
+
+                let filter = self.catalog_filter.to_lowercase();
+                let entries: Vec<&CatalogEntry> = self
+                    .catalog
+                    .iter()
+                    .filter(|entry| {
+                        filter.is_empty()
+                            || entry.path.to_string_lossy().to_lowercase().contains(&filter)
+                    })
+                    .collect();
+                ui.label(format!("{} of {} files", entries.len(), self.catalog.len()));
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                egui::ScrollArea::vertical().show_rows(ui, row_height, entries.len(), |ui, rows| {
+                    for entry in &entries[rows] {
+                        ui.horizontal(|ui| {
+                            let (icon, color) = match entry.action {
+                                CatalogAction::Added => ("➕", egui::Color32::LIGHT_GREEN),
+                                CatalogAction::Updated => ("✏", egui::Color32::LIGHT_BLUE),
+                                CatalogAction::Deleted => ("🗑", egui::Color32::LIGHT_RED),
+                            };
+                            ui.colored_label(color, icon);
+                            ui.label(entry.path.display().to_string());
+                        });
+                    }
+                });
+            });
+
+        if !show_window {
+            self.viewing_catalog = false;
+        }
+    }
+
+    fn draw_history_errors_window(&mut self, ctx: &egui::Context) {
+        let Some((_, error_count)) = self.viewing_history_errors else {
+            return;
+        };
+        let mut show_window = true;
+
+        egui::Window::new("Run Errors")
+            .open(&mut show_window)
+            .resizable(true)
+            .default_width(600.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                if self.history_errors_query.is_some() {
+                    ui.spinner();
+                    return;
+                }
+                if self.history_errors.len() < error_count {
+                    ui.label(format!(
+                        "Showing {} of {} errors, the rest exceeded max_stored_errors",
+                        self.history_errors.len(),
+                        error_count
+                    ));
+                    ui.separator();
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, error) in self.history_errors.iter().enumerate() {
+                        egui::Frame::new()
+                            .fill(if i % 2 == 0 {
+                                ui.visuals().faint_bg_color
+                            } else {
+                                egui::Color32::TRANSPARENT
+                            })
+                            .inner_margin(4.0)
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{}.", i + 1));
+                                    ui.colored_label(
+                                        egui::Color32::LIGHT_RED,
+                                        &error.message,
+                                    )
+                                    .on_hover_text(&error.code);
+                                });
+                            });
+                    }
+
+                    if self.history_errors.is_empty() {
+                        ui.vertical_centered(|ui| {
+                            ui.label("⚠ No errors were stored for this run");
+                        });
+                    }
+                });
+            });
+
+        if !show_window {
+            self.viewing_history_errors = None;
+        }
+    }
+
     fn reset_form(&mut self) {
         self.new_task_source.clear();
         self.new_task_destination.clear();
         self.new_task_mirror = false;
         self.new_task_backup_permission = false;
         self.new_task_follow_symlinks = false;
+        self.new_task_background_io = false;
+        self.new_task_manifest = false;
+        self.new_task_strict = false;
+        self.new_task_catalog = false;
+        self.new_task_elevated = false;
+        self.new_task_locked_files = LockedFilePolicy::Report;
         self.new_task_comparison_mode = ComparisonModeSelection::Standard;
         self.new_task_hash_type = HashType::BLAKE3;
         self.show_add_task_dialog = false;
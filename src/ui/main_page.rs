@@ -1,7 +1,12 @@
 use crate::model::log::system::SystemLog;
+use crate::ui::benchmark_page::BenchmarkPage;
 use crate::ui::common::PageType;
+use crate::ui::event_monitor::EventMonitor;
 use crate::ui::execution_page::ExecutionPage;
 use crate::ui::schedule_page::SchedulePage;
+use crate::ui::search_page::SearchPage;
+use crate::ui::settings_page::SettingsPage;
+use crate::utils::database_lock::LockHolder;
 use eframe::egui;
 use eframe::{App, Frame};
 use macros::log;
@@ -10,14 +15,38 @@ pub struct MainPage {
     current_page: PageType,
     execution_page: ExecutionPage,
     schedule_page: SchedulePage,
+    search_page: SearchPage,
+    benchmark_page: BenchmarkPage,
+    settings_page: SettingsPage,
+    event_monitor: EventMonitor,
+    read_only: bool,
+    lock_holder: Option<LockHolder>,
+    show_lock_dialog: bool,
 }
 
 impl MainPage {
-    pub fn new(execution_page: ExecutionPage, schedule_page: SchedulePage) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        execution_page: ExecutionPage,
+        schedule_page: SchedulePage,
+        search_page: SearchPage,
+        benchmark_page: BenchmarkPage,
+        settings_page: SettingsPage,
+        event_monitor: EventMonitor,
+        read_only: bool,
+        lock_holder: Option<LockHolder>,
+    ) -> Self {
         Self {
             current_page: PageType::Executions,
             execution_page,
             schedule_page,
+            search_page,
+            benchmark_page,
+            settings_page,
+            event_monitor,
+            read_only,
+            lock_holder,
+            show_lock_dialog: read_only,
         }
     }
 
@@ -46,6 +75,7 @@ impl MainPage {
                             "Show Disabled Schedules",
                         );
                     }
+                    PageType::Search | PageType::Benchmark | PageType::Settings => {}
                 });
             });
         });
@@ -60,9 +90,59 @@ impl MainPage {
                     "📋 Executions",
                 );
                 ui.selectable_value(&mut self.current_page, PageType::Schedules, "⏰ Schedules");
+                ui.selectable_value(&mut self.current_page, PageType::Search, "🔍 Find");
+                ui.selectable_value(&mut self.current_page, PageType::Benchmark, "📊 Benchmark");
+                ui.selectable_value(&mut self.current_page, PageType::Settings, "⚙ Settings");
             });
         });
     }
+
+    fn draw_read_only_banner(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::top("read_only_panel").show(ctx, |ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "🔒 Read-only monitoring, another instance holds the database",
+            );
+        });
+    }
+
+    fn draw_lock_dialog(&mut self, ctx: &egui::Context) {
+        egui::Window::new("Database In Use")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("Another MirrorSphere instance is using the database.");
+                match &self.lock_holder {
+                    Some(holder) => {
+                        let since = holder.since.format("%Y-%m-%d %H:%M:%S");
+                        let process = if holder.user.is_empty() {
+                            format!("process {}", holder.pid)
+                        } else {
+                            format!("process {} ({})", holder.pid, holder.user)
+                        };
+                        ui.label(format!("Held by {process} since {since}."));
+                    }
+                    None => {
+                        ui.label("The instance holding it could not be identified.");
+                    }
+                }
+                ui.add_space(4.0);
+                ui.label(
+                    "In read-only mode schedules and benchmark results can be viewed, \
+                    but nothing can be changed or run.",
+                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Open Read-Only").clicked() {
+                        self.show_lock_dialog = false;
+                    }
+                    if ui.button("Exit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+            });
+    }
 }
 
 impl App for MainPage {
@@ -71,11 +151,25 @@ impl App for MainPage {
 
         self.draw_top_panel(ctx);
         self.draw_tabs(ctx);
+        if self.read_only {
+            self.draw_read_only_banner(ctx);
+        }
+        if self.show_lock_dialog {
+            self.draw_lock_dialog(ctx);
+        }
 
         match self.current_page {
             PageType::Executions => self.execution_page.update(ctx),
             PageType::Schedules => self.schedule_page.update(ctx),
+            PageType::Search => self.search_page.update(ctx),
+            PageType::Benchmark => self.benchmark_page.update(ctx),
+            PageType::Settings => self.settings_page.update(ctx),
+        }
+
+        if ctx.input(|input| input.key_pressed(egui::Key::F12)) {
+            self.event_monitor.toggle();
         }
+        self.event_monitor.update(ctx);
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
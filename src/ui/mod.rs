@@ -1,4 +1,9 @@
+pub mod benchmark_page;
 pub mod common;
+pub mod config_problem_page;
+pub mod event_monitor;
 pub mod execution_page;
 pub mod main_page;
 pub mod schedule_page;
+pub mod search_page;
+pub mod settings_page;
@@ -1,25 +1,38 @@
+use crate::core::gui::ui_bridge::{Pending, UiBridge};
 use crate::core::infrastructure::app_config::AppConfig;
-use crate::core::infrastructure::communication_manager::CommunicationManager;
-use crate::model::core::backup::communication::BackupCommand;
+use crate::model::core::backup::communication::{BackupCommand, BackupQuery, BackupQueryResponse};
 use crate::model::core::backup::execution::*;
+use crate::model::core::backup::history_statistics::ScheduleErrorCount;
 use crate::model::core::schedule::communication::*;
 use crate::model::core::schedule::schedule::*;
+use crate::model::core::schedule::schedule_version::ScheduleVersion;
 use crate::model::error::Error;
-use crate::ui::common::{ComparisonModeSelection, FolderSelectionMode};
+use crate::ui::common::{
+    ComparisonModeSelection, FolderSelectionMode, TREND_DAYS, history_retention_editor,
+    history_retention_text, locked_file_policy_selector, trend_range,
+};
 use eframe::egui;
 use egui_file_dialog::FileDialog;
-use futures::executor::block_on;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::error;
 use uuid::Uuid;
 
+#[derive(Clone, Copy, PartialEq)]
+enum DetailsTab {
+    Overview,
+    Changes,
+}
+
 pub struct SchedulePage {
     app_config: Arc<AppConfig>,
-    communication_manager: Arc<CommunicationManager>,
+    ui_bridge: UiBridge,
+    read_only: bool,
 
     schedules: Vec<Schedule>,
+    schedules_query: Option<Pending<ScheduleManagerQueryResponse>>,
+    pending_commands: Vec<Pending<()>>,
 
     new_schedule_name: String,
     new_schedule_source: String,
@@ -28,6 +41,13 @@ pub struct SchedulePage {
     new_schedule_mirror: bool,
     new_schedule_backup_permission: bool,
     new_schedule_follow_symlinks: bool,
+    new_schedule_background_io: bool,
+    new_schedule_manifest: bool,
+    new_schedule_strict: bool,
+    new_schedule_catalog: bool,
+    new_schedule_elevated: bool,
+    new_schedule_retention: HistoryRetention,
+    new_schedule_locked_files: LockedFilePolicy,
     new_schedule_comparison_mode: ComparisonModeSelection,
     new_schedule_hash_type: HashType,
     show_add_schedule_dialog: bool,
@@ -42,6 +62,13 @@ pub struct SchedulePage {
     edit_schedule_mirror: bool,
     edit_schedule_backup_permission: bool,
     edit_schedule_follow_symlinks: bool,
+    edit_schedule_background_io: bool,
+    edit_schedule_manifest: bool,
+    edit_schedule_strict: bool,
+    edit_schedule_catalog: bool,
+    edit_schedule_elevated: bool,
+    edit_schedule_retention: HistoryRetention,
+    edit_schedule_locked_files: LockedFilePolicy,
     edit_schedule_comparison_mode: ComparisonModeSelection,
     edit_schedule_hash_type: HashType,
 
@@ -50,18 +77,34 @@ pub struct SchedulePage {
 
     pub show_disabled_schedules: bool,
     viewing_schedule_details: Option<Uuid>,
+    details_tab: DetailsTab,
+    // Schedule the versions below were requested for
+    versions_schedule: Option<Uuid>,
+    versions_query: Option<Pending<ScheduleManagerQueryResponse>>,
+    schedule_versions: Vec<ScheduleVersion>,
+    reverting: Option<Pending<()>>,
+    // Schedule the statistics below were requested for
+    statistics_schedule: Option<Uuid>,
+    average_duration_query: Option<Pending<BackupQueryResponse>>,
+    error_counts_query: Option<Pending<BackupQueryResponse>>,
+    average_duration: Option<Duration>,
+    recent_errors: Option<ScheduleErrorCount>,
     last_refresh: Option<Instant>,
 }
 
 impl SchedulePage {
     pub fn new(
         app_config: Arc<AppConfig>,
-        communication_manager: Arc<CommunicationManager>,
+        ui_bridge: UiBridge,
+        read_only: bool,
     ) -> Result<Self, Error> {
         let schedule_page = Self {
             app_config,
-            communication_manager,
+            ui_bridge,
+            read_only,
             schedules: Vec::new(),
+            schedules_query: None,
+            pending_commands: Vec::new(),
             new_schedule_name: String::new(),
             new_schedule_source: String::new(),
             new_schedule_destination: String::new(),
@@ -69,6 +112,13 @@ impl SchedulePage {
             new_schedule_mirror: false,
             new_schedule_backup_permission: false,
             new_schedule_follow_symlinks: false,
+            new_schedule_background_io: false,
+            new_schedule_manifest: false,
+            new_schedule_strict: false,
+            new_schedule_catalog: false,
+            new_schedule_elevated: false,
+            new_schedule_retention: HistoryRetention::default(),
+            new_schedule_locked_files: LockedFilePolicy::Report,
             new_schedule_comparison_mode: ComparisonModeSelection::Standard,
             new_schedule_hash_type: HashType::BLAKE3,
             show_add_schedule_dialog: false,
@@ -83,6 +133,13 @@ impl SchedulePage {
             edit_schedule_mirror: false,
             edit_schedule_backup_permission: false,
             edit_schedule_follow_symlinks: false,
+            edit_schedule_background_io: false,
+            edit_schedule_manifest: false,
+            edit_schedule_strict: false,
+            edit_schedule_catalog: false,
+            edit_schedule_elevated: false,
+            edit_schedule_retention: HistoryRetention::default(),
+            edit_schedule_locked_files: LockedFilePolicy::Report,
             edit_schedule_comparison_mode: ComparisonModeSelection::Standard,
             edit_schedule_hash_type: HashType::BLAKE3,
 
@@ -90,92 +147,116 @@ impl SchedulePage {
             folder_selection_mode: None,
             show_disabled_schedules: true,
             viewing_schedule_details: None,
+            details_tab: DetailsTab::Overview,
+            versions_schedule: None,
+            versions_query: None,
+            schedule_versions: Vec::new(),
+            reverting: None,
+            statistics_schedule: None,
+            average_duration_query: None,
+            error_counts_query: None,
+            average_duration: None,
+            recent_errors: None,
             last_refresh: None,
         };
         Ok(schedule_page)
     }
 
     fn load_schedules(&mut self) {
-        match block_on(async {
-            self.communication_manager
-                .send_query(ScheduleManagerQuery::GetSchedules)
-                .await
-        }) {
+        if self.schedules_query.is_none() {
+            let query = ScheduleManagerQuery::GetSchedules;
+            self.schedules_query = Some(self.ui_bridge.send_refresh_query(query));
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    fn poll_backend(&mut self) {
+        let mut refresh = false;
+        self.pending_commands.retain_mut(|pending| match pending.poll() {
+            Some(result) => {
+                if let Err(err) = result {
+                    error!("{}", err);
+                }
+                refresh = true;
+                false
+            }
+            None => true,
+        });
+        if refresh {
+            self.last_refresh = None;
+        }
+
+        if let Some(result) = self.average_duration_query.as_mut().and_then(Pending::poll) {
+            self.average_duration_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetAverageDuration(duration)) => {
+                    self.average_duration = duration;
+                }
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.error_counts_query.as_mut().and_then(Pending::poll) {
+            self.error_counts_query = None;
+            match result {
+                Ok(BackupQueryResponse::GetErrorCountsPerSchedule(schedules)) => {
+                    self.recent_errors = schedules
+                        .into_iter()
+                        .find(|schedule| Some(schedule.schedule_uuid) == self.statistics_schedule);
+                }
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+
+        if let Some(result) = self.versions_query.as_mut().and_then(Pending::poll) {
+            self.versions_query = None;
+            match result {
+                Ok(ScheduleManagerQueryResponse::GetScheduleVersions(versions)) => {
+                    self.schedule_versions = versions;
+                }
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+        if let Some(result) = self.reverting.as_mut().and_then(Pending::poll) {
+            self.reverting = None;
+            if let Err(err) = result {
+                error!("{}", err);
+            }
+            self.versions_schedule = None;
+            self.last_refresh = None;
+        }
+
+        let Some(result) = self.schedules_query.as_mut().and_then(Pending::poll) else {
+            return;
+        };
+        self.schedules_query = None;
+        match result {
             Ok(ScheduleManagerQueryResponse::GetSchedules(schedules)) => {
                 self.schedules = schedules;
             }
+            Ok(_) => {}
             Err(err) => {
                 error!("{}", err);
             }
         }
     }
 
-    fn handle_add_schedule(&self, schedule: Schedule) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::AddSchedule(schedule))
-                .await?;
-            Ok(())
-        })
-    }
-
-    fn handle_modify_schedule(&self, schedule: Schedule) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::ModifySchedule(schedule))
-                .await?;
-            Ok(())
-        })
-    }
-
-    fn handle_remove_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::RemoveSchedule(uuid))
-                .await?;
-            Ok(())
-        })
-    }
-
-    fn handle_active_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::ActivateSchedule(uuid))
-                .await?;
-            Ok(())
-        })
-    }
-
-    fn handle_pause_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::PauseSchedule(uuid))
-                .await?;
-            Ok(())
-        })
-    }
-
-    fn handle_disable_schedule(&self, uuid: Uuid) -> Result<(), Error> {
-        block_on(async {
-            self.communication_manager
-                .send_command(ScheduleManagerCommand::DisableSchedule(uuid))
-                .await?;
-            Ok(())
-        })
+    fn send_command(&mut self, command: ScheduleManagerCommand) {
+        let pending = self.ui_bridge.send_command(command);
+        self.pending_commands.push(pending);
     }
 
-    fn handle_run_schedule_now(&self, schedule: Schedule) -> Result<(), Error> {
-        block_on(async {
-            let execution = schedule.to_execution();
-            let uuid = execution.uuid;
-            self.communication_manager
-                .send_command(BackupCommand::AddExecution(execution))
-                .await?;
-            self.communication_manager
-                .send_command(BackupCommand::StartExecution(uuid))
-                .await?;
-            Ok(())
-        })
+    fn run_schedule_now(&mut self, schedule: Schedule) {
+        let execution = schedule.to_execution();
+        let uuid = execution.uuid;
+        let commands = vec![
+            BackupCommand::AddExecution(execution),
+            BackupCommand::StartExecution(uuid),
+        ];
+        let pending = self.ui_bridge.send_commands(commands);
+        self.pending_commands.push(pending);
     }
 
     pub fn update(&mut self, ctx: &egui::Context) {
@@ -186,9 +267,9 @@ impl SchedulePage {
             }
         };
 
+        self.poll_backend();
         if should_refresh {
             self.load_schedules();
-            self.last_refresh = Some(Instant::now());
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
@@ -197,10 +278,10 @@ impl SchedulePage {
             ui.horizontal(|ui| {
                 if ui.button("🔄 Refresh").clicked() {
                     self.load_schedules();
-                    self.last_refresh = Some(Instant::now());
                 }
 
-                if ui.button("➕ Add Schedule").clicked() {
+                let add_button = egui::Button::new("➕ Add Schedule");
+                if ui.add_enabled(!self.read_only, add_button).clicked() {
                     self.show_add_schedule_dialog = true;
                 }
 
@@ -318,26 +399,31 @@ impl SchedulePage {
 
                         ui.separator();
 
+                        if self.read_only {
+                            ui.disable();
+                        }
+
                         match schedule.state {
                             ScheduleState::Active => {
                                 if ui.button("⏸ Pause").clicked() {
-                                    if let Err(err) = self.handle_pause_schedule(schedule.uuid) {
-                                        error!("{}", err);
-                                    }
+                                    let uuid = schedule.uuid;
+                                    self.send_command(ScheduleManagerCommand::PauseSchedule(uuid));
                                 }
                             }
                             ScheduleState::Paused => {
                                 if ui.button("▶ Resume").clicked() {
-                                    if let Err(err) = self.handle_active_schedule(schedule.uuid) {
-                                        error!("{}", err);
-                                    }
+                                    let command = ScheduleManagerCommand::ActivateSchedule(
+                                        schedule.uuid,
+                                    );
+                                    self.send_command(command);
                                 }
                             }
                             ScheduleState::Disabled => {
                                 if ui.button("▶ Enable").clicked() {
-                                    if let Err(err) = self.handle_active_schedule(schedule.uuid) {
-                                        error!("{}", err);
-                                    }
+                                    let command = ScheduleManagerCommand::ActivateSchedule(
+                                        schedule.uuid,
+                                    );
+                                    self.send_command(command);
                                 }
                             }
                         }
@@ -345,15 +431,13 @@ impl SchedulePage {
                         if schedule.state != ScheduleState::Disabled
                             && ui.button("❌ Disable").clicked()
                         {
-                            if let Err(err) = self.handle_disable_schedule(schedule.uuid) {
-                                error!("{}", err);
-                            }
+                            let uuid = schedule.uuid;
+                            self.send_command(ScheduleManagerCommand::DisableSchedule(uuid));
                         }
 
                         if ui.button("🗑").clicked() {
-                            if let Err(err) = self.handle_remove_schedule(schedule.uuid) {
-                                error!("{}", err);
-                            }
+                            let uuid = schedule.uuid;
+                            self.send_command(ScheduleManagerCommand::RemoveSchedule(uuid));
                         }
                     });
                 });
@@ -502,6 +586,20 @@ impl SchedulePage {
                         &mut self.new_schedule_backup_permission,
                         "Backup File Permissions",
                     );
+                    ui.checkbox(&mut self.new_schedule_background_io, "Background IO Priority");
+                    ui.checkbox(&mut self.new_schedule_manifest, "Write Checksum Manifest");
+                    ui.checkbox(&mut self.new_schedule_strict, "Stop at First Error");
+                    ui.checkbox(&mut self.new_schedule_catalog, "Record Changed Files");
+                    ui.checkbox(&mut self.new_schedule_elevated, "Run as Administrator");
+                    locked_file_policy_selector(
+                        ui,
+                        "new_schedule_locked_files",
+                        &mut self.new_schedule_locked_files,
+                    );
+
+                    ui.separator();
+                    ui.label("History Retention:");
+                    history_retention_editor(ui, &mut self.new_schedule_retention);
 
                     ui.separator();
 
@@ -531,17 +629,23 @@ impl SchedulePage {
                                     mirror: self.new_schedule_mirror,
                                     backup_permission: self.new_schedule_backup_permission,
                                     follow_symlinks: self.new_schedule_follow_symlinks,
+                                    background_io: self.new_schedule_background_io,
+                                    locked_files: self.new_schedule_locked_files,
+                                    manifest: self.new_schedule_manifest,
+                                    strict: self.new_schedule_strict,
+                                    catalog: self.new_schedule_catalog,
+                                    elevated: self.new_schedule_elevated,
                                 },
                                 interval: self.new_schedule_interval,
                                 last_run_time: None,
                                 next_run_time: None,
+                                run_time: None,
+                                retention: self.new_schedule_retention,
                                 created_at: chrono::Utc::now().naive_utc(),
                                 updated_at: chrono::Utc::now().naive_utc(),
                             };
 
-                            if let Err(err) = self.handle_add_schedule(schedule) {
-                                error!("{}", err);
-                            }
+                            self.send_command(ScheduleManagerCommand::AddSchedule(schedule));
 
                             self.reset_schedule_form();
                         }
@@ -702,6 +806,20 @@ impl SchedulePage {
                         &mut self.edit_schedule_backup_permission,
                         "Backup File Permissions",
                     );
+                    ui.checkbox(&mut self.edit_schedule_background_io, "Background IO Priority");
+                    ui.checkbox(&mut self.edit_schedule_manifest, "Write Checksum Manifest");
+                    ui.checkbox(&mut self.edit_schedule_strict, "Stop at First Error");
+                    ui.checkbox(&mut self.edit_schedule_catalog, "Record Changed Files");
+                    ui.checkbox(&mut self.edit_schedule_elevated, "Run as Administrator");
+                    locked_file_policy_selector(
+                        ui,
+                        "edit_schedule_locked_files",
+                        &mut self.edit_schedule_locked_files,
+                    );
+
+                    ui.separator();
+                    ui.label("History Retention:");
+                    history_retention_editor(ui, &mut self.edit_schedule_retention);
 
                     ui.separator();
 
@@ -735,12 +853,19 @@ impl SchedulePage {
                                     mirror: self.edit_schedule_mirror,
                                     backup_permission: self.edit_schedule_backup_permission,
                                     follow_symlinks: self.edit_schedule_follow_symlinks,
+                                    background_io: self.edit_schedule_background_io,
+                                    locked_files: self.edit_schedule_locked_files,
+                                    manifest: self.edit_schedule_manifest,
+                                    strict: self.edit_schedule_strict,
+                                    catalog: self.edit_schedule_catalog,
+                                    elevated: self.edit_schedule_elevated,
                                 };
+                                editing_schedule.retention = self.edit_schedule_retention;
                                 editing_schedule.updated_at = chrono::Utc::now().naive_utc();
 
-                                if let Err(err) = self.handle_modify_schedule(editing_schedule) {
-                                    error!("{}", err);
-                                }
+                                self.send_command(ScheduleManagerCommand::ModifySchedule(
+                                    editing_schedule,
+                                ));
 
                                 self.reset_edit_schedule_form();
                             }
@@ -759,11 +884,41 @@ impl SchedulePage {
         }
     }
 
+    fn request_statistics(&mut self, schedule_uuid: Uuid) {
+        self.statistics_schedule = Some(schedule_uuid);
+        self.average_duration = None;
+        self.recent_errors = None;
+        let query = BackupQuery::GetAverageDuration(schedule_uuid);
+        self.average_duration_query = Some(self.ui_bridge.send_query(query));
+        let query = BackupQuery::GetErrorCountsPerSchedule(trend_range());
+        self.error_counts_query = Some(self.ui_bridge.send_query(query));
+    }
+
+    fn request_versions(&mut self, schedule_uuid: Uuid) {
+        self.versions_schedule = Some(schedule_uuid);
+        self.schedule_versions.clear();
+        let query = ScheduleManagerQuery::GetScheduleVersions(schedule_uuid);
+        self.versions_query = Some(self.ui_bridge.send_query(query));
+    }
+
     fn draw_schedule_details_window(&mut self, ctx: &egui::Context) {
+        if let Some(schedule_id) = self.viewing_schedule_details
+            && self.statistics_schedule != Some(schedule_id)
+        {
+            self.request_statistics(schedule_id);
+        }
+        if let Some(schedule_id) = self.viewing_schedule_details
+            && self.details_tab == DetailsTab::Changes
+            && self.versions_schedule != Some(schedule_id)
+        {
+            self.request_versions(schedule_id);
+        }
         if let Some(schedule_id) = self.viewing_schedule_details {
             let mut show_window = true;
             let mut run_now_clicked = false;
             let mut edit_clicked = false;
+            let mut details_tab = self.details_tab;
+            let mut revert_to = None;
 
             // Clone the schedule data we need before entering the closure
             let schedule_data = self.schedules.iter()
@@ -777,6 +932,16 @@ impl SchedulePage {
                     .default_width(500.0)
                     .default_height(350.0)
                     .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut details_tab, DetailsTab::Overview, "Overview");
+                            ui.selectable_value(&mut details_tab, DetailsTab::Changes, "Changes");
+                        });
+                        ui.separator();
+                        if details_tab == DetailsTab::Changes {
+                            revert_to = self.draw_schedule_changes(ui, &schedule);
+                            return;
+                        }
+
                         egui::Grid::new("schedule_details_grid")
                             .num_columns(2)
                             .spacing([10.0, 4.0])
@@ -830,6 +995,26 @@ impl SchedulePage {
                                     ui.end_row();
                                 }
 
+                                if let Some(duration) = self.average_duration {
+                                    ui.label("Average Duration:");
+                                    ui.label(format!("{}s", duration.as_secs()));
+                                    ui.end_row();
+                                }
+
+                                ui.label(format!("Last {TREND_DAYS} Days:"));
+                                ui.label(match &self.recent_errors {
+                                    Some(recent) => format!(
+                                        "{} runs, {} errors",
+                                        recent.runs, recent.error_count
+                                    ),
+                                    None => "No runs".to_string(),
+                                });
+                                ui.end_row();
+
+                                ui.label("History Retention:");
+                                ui.label(history_retention_text(&schedule.retention));
+                                ui.end_row();
+
                                 ui.label("Created:");
                                 ui.label(
                                     schedule.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
@@ -856,11 +1041,30 @@ impl SchedulePage {
                             if schedule.options.follow_symlinks {
                                 ui.label("✅ Follow Symlinks");
                             }
+                            if schedule.options.background_io {
+                                ui.label("✅ Background IO");
+                            }
+                            if schedule.options.manifest {
+                                ui.label("✅ Checksum Manifest");
+                            }
+                            if schedule.options.strict {
+                                ui.label("✅ Stop at First Error");
+                            }
+                            if schedule.options.catalog {
+                                ui.label("✅ Record Changed Files");
+                            }
+                            if schedule.options.elevated {
+                                ui.label("✅ Run as Administrator");
+                            }
                         });
 
                         ui.separator();
 
                         ui.horizontal(|ui| {
+                            if self.read_only {
+                                ui.disable();
+                            }
+
                             // Use local flags to track button clicks
                             if ui.button("▶ Run Now").clicked() {
                                 run_now_clicked = true;
@@ -872,12 +1076,13 @@ impl SchedulePage {
                         });
                     });
 
+                self.details_tab = details_tab;
+                if let Some(version) = revert_to {
+                    let command = ScheduleManagerCommand::RevertSchedule(schedule.uuid, version);
+                    self.reverting = Some(self.ui_bridge.send_command(command));
+                }
                 if run_now_clicked {
-                    if let Err(err) = self.handle_run_schedule_now(schedule.clone()) {
-                        error!("Failed to run schedule now: {}", err);
-                    } else {
-                        self.load_schedules();
-                    }
+                    self.run_schedule_now(schedule.clone());
                 }
                 if edit_clicked {
                     self.start_editing_schedule(schedule);
@@ -885,10 +1090,60 @@ impl SchedulePage {
             }
             if !show_window {
                 self.viewing_schedule_details = None;
+                self.statistics_schedule = None;
+                self.versions_schedule = None;
+                self.details_tab = DetailsTab::Overview;
             }
         }
     }
 
+    // Returns the version picked to revert to
+    fn draw_schedule_changes(&self, ui: &mut egui::Ui, schedule: &Schedule) -> Option<u32> {
+        ui.label("The settings a schedule had are kept each time it is edited, newest first.");
+        if self.versions_query.is_some() || self.reverting.is_some() {
+            ui.spinner();
+            return None;
+        }
+        if self.schedule_versions.is_empty() {
+            ui.label("No earlier versions");
+            return None;
+        }
+
+        let mut revert_to = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("schedule_versions_grid")
+                .num_columns(4)
+                .spacing([10.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Version");
+                    ui.strong("Replaced");
+                    ui.strong("Differs From Current");
+                    ui.label("");
+                    ui.end_row();
+
+                    for version in &self.schedule_versions {
+                        let changed = version.schedule.changed_settings(schedule);
+                        ui.label(format!("#{}", version.version));
+                        ui.label(version.saved_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                        let differences = if changed.is_empty() {
+                            "Same as current".to_string()
+                        } else {
+                            changed.join(", ")
+                        };
+                        ui.label(differences)
+                            .on_hover_text(schedule_settings_text(&version.schedule));
+                        let enabled = !self.read_only && !changed.is_empty();
+                        if ui.add_enabled(enabled, egui::Button::new("↩ Revert")).clicked() {
+                            revert_to = Some(version.version);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        revert_to
+    }
+
     fn start_editing_schedule(&mut self, schedule: Schedule) {
         self.editing_schedule = Some(schedule.clone());
         self.edit_schedule_name = schedule.name.clone();
@@ -898,6 +1153,13 @@ impl SchedulePage {
         self.edit_schedule_mirror = schedule.options.mirror;
         self.edit_schedule_backup_permission = schedule.options.backup_permission;
         self.edit_schedule_follow_symlinks = schedule.options.follow_symlinks;
+        self.edit_schedule_background_io = schedule.options.background_io;
+        self.edit_schedule_locked_files = schedule.options.locked_files;
+        self.edit_schedule_manifest = schedule.options.manifest;
+        self.edit_schedule_strict = schedule.options.strict;
+        self.edit_schedule_catalog = schedule.options.catalog;
+        self.edit_schedule_elevated = schedule.options.elevated;
+        self.edit_schedule_retention = schedule.retention;
 
         if let Some(comparison_mode) = &schedule.comparison_mode {
             match comparison_mode {
@@ -918,6 +1180,7 @@ impl SchedulePage {
 
         self.show_edit_schedule_dialog = true;
         self.viewing_schedule_details = None;
+        self.statistics_schedule = None;
     }
 
     fn reset_edit_schedule_form(&mut self) {
@@ -929,6 +1192,13 @@ impl SchedulePage {
         self.edit_schedule_mirror = false;
         self.edit_schedule_backup_permission = false;
         self.edit_schedule_follow_symlinks = false;
+        self.edit_schedule_background_io = false;
+        self.edit_schedule_manifest = false;
+        self.edit_schedule_strict = false;
+        self.edit_schedule_catalog = false;
+        self.edit_schedule_elevated = false;
+        self.edit_schedule_retention = HistoryRetention::default();
+        self.edit_schedule_locked_files = LockedFilePolicy::Report;
         self.edit_schedule_comparison_mode = ComparisonModeSelection::Standard;
         self.edit_schedule_hash_type = HashType::BLAKE3;
         self.show_edit_schedule_dialog = false;
@@ -978,8 +1248,26 @@ impl SchedulePage {
         self.new_schedule_mirror = false;
         self.new_schedule_backup_permission = false;
         self.new_schedule_follow_symlinks = false;
+        self.new_schedule_background_io = false;
+        self.new_schedule_manifest = false;
+        self.new_schedule_strict = false;
+        self.new_schedule_catalog = false;
+        self.new_schedule_elevated = false;
+        self.new_schedule_retention = HistoryRetention::default();
+        self.new_schedule_locked_files = LockedFilePolicy::Report;
         self.new_schedule_comparison_mode = ComparisonModeSelection::Standard;
         self.new_schedule_hash_type = HashType::BLAKE3;
         self.show_add_schedule_dialog = false;
     }
 }
+
+fn schedule_settings_text(schedule: &Schedule) -> String {
+    format!(
+        "Name: {}\nSource: {}\nDestination: {}\nInterval: {:?}\nHistory Retention: {}",
+        schedule.name,
+        schedule.source_path.display(),
+        schedule.destination_path.display(),
+        schedule.interval,
+        history_retention_text(&schedule.retention),
+    )
+}
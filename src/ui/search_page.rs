@@ -0,0 +1,192 @@
+use crate::core::gui::ui_bridge::{Pending, UiBridge};
+use crate::model::core::backup::catalog::{CatalogMatch, MAX_CATALOG_MATCHES};
+use crate::model::core::backup::communication::*;
+use crate::model::error::Error;
+use eframe::egui;
+use egui_file_dialog::FileDialog;
+use tracing::error;
+
+pub struct SearchPage {
+    ui_bridge: UiBridge,
+
+    pattern: String,
+    results: Vec<CatalogMatch>,
+    search_query: Option<Pending<BackupQueryResponse>>,
+    searched: bool,
+
+    file_dialog: FileDialog,
+    restoring: Option<CatalogMatch>,
+    running_restore: Option<Pending<()>>,
+    restore_message: Option<Result<String, String>>,
+}
+
+impl SearchPage {
+    pub fn new(ui_bridge: UiBridge) -> Result<Self, Error> {
+        let search_page = Self {
+            ui_bridge,
+            pattern: String::new(),
+            results: Vec::new(),
+            search_query: None,
+            searched: false,
+            file_dialog: FileDialog::new(),
+            restoring: None,
+            running_restore: None,
+            restore_message: None,
+        };
+        Ok(search_page)
+    }
+
+    fn search(&mut self) {
+        let query = BackupQuery::SearchCatalogs(self.pattern.clone());
+        self.search_query = Some(self.ui_bridge.send_query(query));
+        self.restore_message = None;
+    }
+
+    fn poll_backend(&mut self) {
+        if let Some(result) = self.running_restore.as_mut().and_then(Pending::poll) {
+            self.running_restore = None;
+            self.restore_message = Some(match result {
+                Ok(()) => Ok("File restored".to_string()),
+                Err(err) => {
+                    error!("{}", err);
+                    Err(err.to_string())
+                }
+            });
+        }
+
+        let Some(result) = self.search_query.as_mut().and_then(Pending::poll) else {
+            return;
+        };
+        self.search_query = None;
+        self.searched = true;
+        match result {
+            Ok(BackupQueryResponse::SearchCatalogs(results)) => {
+                self.results = results;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                error!("{}", err);
+            }
+        }
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context) {
+        self.poll_backend();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Find in Backups");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.pattern)
+                        .hint_text("report.pdf, *.docx, photos/2024/*, /home/user/notes.txt")
+                        .desired_width(400.0),
+                );
+                let submitted =
+                    response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                let idle = self.search_query.is_none();
+                if (ui.add_enabled(idle, egui::Button::new("🔍 Search")).clicked() || submitted)
+                    && idle
+                    && !self.pattern.trim().is_empty()
+                {
+                    self.search();
+                }
+                if !idle {
+                    ui.spinner();
+                }
+            });
+            ui.label(
+                "Searches the changed files recorded by past runs, so only schedules and \
+                executions with Record Changed Files turned on are covered.",
+            );
+
+            match &self.restore_message {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, err);
+                }
+                None => {}
+            }
+            ui.separator();
+
+            if self.searched && self.search_query.is_none() {
+                self.draw_results(ui);
+            }
+        });
+
+        self.handle_file_dialog(ctx);
+    }
+
+    fn draw_results(&mut self, ui: &mut egui::Ui) {
+        if self.results.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.label("No backed up file matches");
+            });
+            return;
+        }
+        if self.results.len() == MAX_CATALOG_MATCHES {
+            ui.label(format!("Showing the first {MAX_CATALOG_MATCHES} files, narrow the search"));
+        }
+
+        let mut restore = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("search_results_grid")
+                .num_columns(6)
+                .striped(true)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.strong("File");
+                    ui.strong("Destination");
+                    ui.strong("Last Backed Up");
+                    ui.strong("Versions");
+                    ui.strong("Status");
+                    ui.strong("");
+                    ui.end_row();
+
+                    for found in &self.results {
+                        ui.label(found.path.display().to_string());
+                        ui.label(found.destination_path.display().to_string());
+                        ui.label(found.finished_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                        ui.label(found.versions.to_string());
+                        if found.newest {
+                            ui.colored_label(egui::Color32::GREEN, "⭐ Newest");
+                        } else if found.present {
+                            ui.label("Older");
+                        } else {
+                            ui.colored_label(ui.visuals().weak_text_color(), "Removed");
+                        }
+                        let idle = self.running_restore.is_none();
+                        if ui
+                            .add_enabled(found.present && idle, egui::Button::new("↩ Restore..."))
+                            .on_hover_text("Copy the file into a folder of your choice")
+                            .clicked()
+                        {
+                            restore = Some(found.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(found) = restore {
+            self.restoring = Some(found);
+            self.file_dialog.pick_directory();
+        }
+    }
+
+    fn handle_file_dialog(&mut self, ctx: &egui::Context) {
+        self.file_dialog.update(ctx);
+
+        if let Some(path) = self.file_dialog.take_picked()
+            && let Some(found) = self.restoring.take()
+        {
+            self.restore_message = None;
+            let command = BackupCommand::RestoreFile(found, path);
+            self.running_restore = Some(self.ui_bridge.send_command(command));
+        }
+    }
+}
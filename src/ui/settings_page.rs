@@ -0,0 +1,434 @@
+use crate::core::gui::ui_bridge::{Pending, UiBridge};
+use crate::core::infrastructure::app_config::AppConfig;
+use crate::model::core::maintenance::communication::*;
+use crate::model::core::maintenance::database_status::DatabaseStatus;
+use crate::model::error::Error;
+use crate::ui::common::MEBIBYTE;
+use eframe::egui;
+use egui_file_dialog::FileDialog;
+use privilege::user::privileged;
+use std::sync::Arc;
+use tracing::error;
+
+#[derive(Clone, Copy, PartialEq)]
+enum ArchiveAction {
+    Export,
+    Import,
+}
+
+pub struct SettingsPage {
+    app_config: Arc<AppConfig>,
+    ui_bridge: UiBridge,
+    read_only: bool,
+
+    status: Option<DatabaseStatus>,
+    status_query: Option<Pending<MaintenanceQueryResponse>>,
+    running_maintenance: Option<Pending<()>>,
+    last_error: Option<String>,
+    loaded: bool,
+
+    file_dialog: FileDialog,
+    archive_action: Option<ArchiveAction>,
+    // The action in flight with the command carrying it out
+    running_archive: Option<(ArchiveAction, Pending<()>)>,
+    archive_message: Option<Result<String, String>>,
+
+    profiles: Vec<String>,
+    profiles_query: Option<Pending<MaintenanceQueryResponse>>,
+    new_profile_name: String,
+    creating_profile: Option<Pending<()>>,
+    switching_profile: Option<Pending<()>>,
+    profile_message: Option<Result<String, String>>,
+    elevated: bool,
+    restarting_elevated: Option<Pending<()>>,
+    // Set once the other instance is started, this one closes on the next frame
+    close_requested: bool,
+}
+
+impl SettingsPage {
+    pub fn new(
+        app_config: Arc<AppConfig>,
+        ui_bridge: UiBridge,
+        read_only: bool,
+    ) -> Result<Self, Error> {
+        let settings_page = Self {
+            app_config,
+            ui_bridge,
+            read_only,
+            status: None,
+            status_query: None,
+            running_maintenance: None,
+            last_error: None,
+            loaded: false,
+            file_dialog: FileDialog::new().default_file_name("mirrorsphere-state.msst"),
+            archive_action: None,
+            running_archive: None,
+            archive_message: None,
+            profiles: Vec::new(),
+            profiles_query: None,
+            new_profile_name: String::new(),
+            creating_profile: None,
+            switching_profile: None,
+            profile_message: None,
+            elevated: privileged(),
+            restarting_elevated: None,
+            close_requested: false,
+        };
+        Ok(settings_page)
+    }
+
+    fn load_status(&mut self) {
+        if self.status_query.is_none() {
+            let query = MaintenanceQuery::GetDatabaseStatus;
+            self.status_query = Some(self.ui_bridge.send_query(query));
+        }
+        self.loaded = true;
+    }
+
+    fn load_profiles(&mut self) {
+        if self.profiles_query.is_none() {
+            let query = MaintenanceQuery::GetProfiles;
+            self.profiles_query = Some(self.ui_bridge.send_query(query));
+        }
+    }
+
+    fn poll_profiles(&mut self) {
+        if let Some(result) = self.profiles_query.as_mut().and_then(Pending::poll) {
+            self.profiles_query = None;
+            match result {
+                Ok(MaintenanceQueryResponse::GetProfiles(profiles)) => self.profiles = profiles,
+                Ok(_) => {}
+                Err(err) => error!("{}", err),
+            }
+        }
+
+        if let Some(result) = self.creating_profile.as_mut().and_then(Pending::poll) {
+            self.creating_profile = None;
+            self.profile_message = Some(match result {
+                Ok(()) => {
+                    let name = std::mem::take(&mut self.new_profile_name);
+                    Ok(format!("Profile {name} created"))
+                }
+                Err(err) => {
+                    error!("{}", err);
+                    Err(err.to_string())
+                }
+            });
+            self.load_profiles();
+        }
+
+        if let Some(result) = self.restarting_elevated.as_mut().and_then(Pending::poll) {
+            self.restarting_elevated = None;
+            match result {
+                Ok(()) => self.close_requested = true,
+                Err(err) => {
+                    error!("{}", err);
+                    self.profile_message = Some(Err(err.to_string()));
+                }
+            }
+        }
+
+        if let Some(result) = self.switching_profile.as_mut().and_then(Pending::poll) {
+            self.switching_profile = None;
+            match result {
+                Ok(()) => self.close_requested = true,
+                Err(err) => {
+                    error!("{}", err);
+                    self.profile_message = Some(Err(err.to_string()));
+                }
+            }
+        }
+    }
+
+    fn poll_backend(&mut self) {
+        self.poll_profiles();
+        if let Some((action, pending)) = &mut self.running_archive
+            && let Some(result) = pending.poll()
+        {
+            self.archive_message = Some(match (result, *action) {
+                (Ok(()), ArchiveAction::Export) => Ok("State exported".to_string()),
+                (Ok(()), ArchiveAction::Import) => {
+                    Ok("State imported, restart MirrorSphere to apply it".to_string())
+                }
+                (Err(err), _) => {
+                    error!("{}", err);
+                    Err(err.to_string())
+                }
+            });
+            self.running_archive = None;
+        }
+
+        if let Some(result) = self.running_maintenance.as_mut().and_then(Pending::poll) {
+            self.running_maintenance = None;
+            if let Err(err) = result {
+                self.last_error = Some(err.to_string());
+                error!("{}", err);
+            }
+            self.load_status();
+        }
+
+        let Some(result) = self.status_query.as_mut().and_then(Pending::poll) else {
+            return;
+        };
+        self.status_query = None;
+        match result {
+            Ok(MaintenanceQueryResponse::GetDatabaseStatus(status)) => {
+                self.status = Some(status);
+            }
+            Ok(_) => {}
+            Err(err) => {
+                error!("{}", err);
+            }
+        }
+    }
+
+    fn run_maintenance(&mut self) {
+        self.last_error = None;
+        let command = MaintenanceCommand::RunMaintenance;
+        self.running_maintenance = Some(self.ui_bridge.send_command(command));
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context) {
+        self.poll_backend();
+        if self.close_requested {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if !self.loaded {
+            self.load_profiles();
+            self.load_status();
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Database");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("🔄 Refresh").clicked() {
+                        self.load_status();
+                    }
+                });
+            });
+            ui.separator();
+
+            let Some(status) = &self.status else {
+                ui.spinner();
+                return;
+            };
+
+            egui::Grid::new("database_status_grid")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Size:");
+                    ui.label(format!("{:.1} MiB", status.size as f64 / MEBIBYTE));
+                    ui.end_row();
+
+                    ui.label("History Retention:");
+                    ui.label(match self.app_config.history_retention_days {
+                        0 => "Every run is kept".to_string(),
+                        days => format!("{days} days"),
+                    });
+                    ui.end_row();
+
+                    ui.label("Encryption:");
+                    ui.label(match self.app_config.database_encryption {
+                        true => "SQLCipher, key in the OS keyring",
+                        false => "Off",
+                    });
+                    ui.end_row();
+
+                    ui.label("Maintenance Interval:");
+                    ui.label(format!("{} hours", self.app_config.maintenance_interval));
+                    ui.end_row();
+
+                    ui.label("Last Maintenance:");
+                    match &status.last_maintenance {
+                        Some(run) => {
+                            ui.label(run.finished_at.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.end_row();
+
+                            ui.label("Pruned Runs:");
+                            ui.label(run.pruned_runs.to_string());
+                            ui.end_row();
+
+                            ui.label("Reclaimed:");
+                            let reclaimed = run.size_before.saturating_sub(run.size_after);
+                            ui.label(format!("{:.1} MiB", reclaimed as f64 / MEBIBYTE));
+                            ui.end_row();
+
+                            ui.label("Integrity:");
+                            if run.integrity_problems.is_empty() {
+                                ui.colored_label(egui::Color32::GREEN, "✅ OK");
+                            } else {
+                                ui.vertical(|ui| {
+                                    for problem in &run.integrity_problems {
+                                        ui.colored_label(ui.visuals().error_fg_color, problem);
+                                    }
+                                });
+                            }
+                        }
+                        None => {
+                            ui.label("Never");
+                        }
+                    }
+                    ui.end_row();
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                let running = self.running_maintenance.is_some();
+                let enabled = !self.read_only && !running;
+                if ui
+                    .add_enabled(enabled, egui::Button::new("🧹 Run Maintenance Now"))
+                    .clicked()
+                {
+                    self.run_maintenance();
+                }
+                if running {
+                    ui.spinner();
+                    ui.label("Pruning history, compacting and checking the database...");
+                }
+            });
+
+            if let Some(err) = &self.last_error {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+
+            ui.add_space(12.0);
+            self.draw_state_archive(ui);
+
+            ui.add_space(12.0);
+            self.draw_profiles(ui);
+        });
+
+        self.handle_file_dialog(ctx);
+    }
+
+    fn draw_state_archive(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Application State");
+        ui.separator();
+        ui.label(
+            "Export the configuration, schedules, history and hash caches into one file, \
+            or import such a file to set up a reinstall or another machine.",
+        );
+
+        ui.horizontal(|ui| {
+            let idle = self.running_archive.is_none();
+            if ui.add_enabled(idle, egui::Button::new("📤 Export...")).clicked() {
+                self.archive_action = Some(ArchiveAction::Export);
+                self.file_dialog.save_file();
+            }
+            let import = egui::Button::new("📥 Import...");
+            if ui.add_enabled(idle && !self.read_only, import).clicked() {
+                self.archive_action = Some(ArchiveAction::Import);
+                self.file_dialog.pick_file();
+            }
+            if !idle {
+                ui.spinner();
+            }
+        });
+
+        match &self.archive_message {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+            None => {}
+        }
+    }
+
+    fn draw_profiles(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Profiles");
+        ui.separator();
+        ui.label(
+            "Each profile has its own config.toml, database and schedules. Switching starts \
+            MirrorSphere again on the chosen profile and closes this window.",
+        );
+
+        let current = self.app_config.profile().map(str::to_string);
+        ui.horizontal(|ui| {
+            ui.label("Current:");
+            ui.strong(current.as_deref().unwrap_or("Default"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Administrator:");
+            if self.elevated {
+                ui.strong("Yes");
+            } else {
+                ui.strong("No");
+                let idle = self.restarting_elevated.is_none();
+                let restart = egui::Button::new("🛡 Restart as Administrator");
+                if ui.add_enabled(idle, restart).clicked() {
+                    self.profile_message = None;
+                    let command = MaintenanceCommand::RestartElevated;
+                    self.restarting_elevated = Some(self.ui_bridge.send_command(command));
+                }
+            }
+        });
+
+        let idle = self.switching_profile.is_none();
+        let mut switch_to = None;
+        egui::Grid::new("profiles_grid")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                let profiles = std::iter::once(None).chain(self.profiles.iter().cloned().map(Some));
+                for profile in profiles {
+                    ui.label(profile.as_deref().unwrap_or("Default"));
+                    let enabled = idle && profile != current;
+                    if ui.add_enabled(enabled, egui::Button::new("Switch")).clicked() {
+                        switch_to = Some(profile);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(profile) = switch_to {
+            self.profile_message = None;
+            let command = MaintenanceCommand::SwitchProfile(profile);
+            self.switching_profile = Some(self.ui_bridge.send_command(command));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("New Profile:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_profile_name)
+                    .hint_text("letters, digits, - and _")
+                    .desired_width(160.0),
+            );
+            let name = self.new_profile_name.trim();
+            let enabled = self.creating_profile.is_none() && !name.is_empty();
+            if ui.add_enabled(enabled, egui::Button::new("➕ Create")).clicked() {
+                self.profile_message = None;
+                let command = MaintenanceCommand::CreateProfile(name.to_string());
+                self.creating_profile = Some(self.ui_bridge.send_command(command));
+            }
+        });
+
+        match &self.profile_message {
+            Some(Ok(message)) => {
+                ui.label(message);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(ui.visuals().error_fg_color, err);
+            }
+            None => {}
+        }
+    }
+
+    fn handle_file_dialog(&mut self, ctx: &egui::Context) {
+        self.file_dialog.update(ctx);
+
+        if let Some(path) = self.file_dialog.take_picked()
+            && let Some(action) = self.archive_action.take()
+        {
+            let command = match action {
+                ArchiveAction::Export => MaintenanceCommand::ExportState(path),
+                ArchiveAction::Import => MaintenanceCommand::ImportState(path),
+            };
+            self.archive_message = None;
+            self.running_archive = Some((action, self.ui_bridge.send_command(command)));
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::platform::file_system::directory_case_insensitive;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File};
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+const PLATFORM_CASE_INSENSITIVE: bool = cfg!(any(windows, target_os = "macos"));
+
+// Probes with a scratch file, falls back to the platform default when it cannot be written
+pub async fn is_case_insensitive(directory: &Path) -> bool {
+    let name = format!(".mirrorsphere-case-{}", Uuid::new_v4().simple());
+    let probe = directory.join(&name);
+    if File::create(&probe).await.is_err() {
+        return PLATFORM_CASE_INSENSITIVE;
+    }
+    let insensitive = fs::symlink_metadata(directory.join(name.to_uppercase()))
+        .await
+        .is_ok();
+    let _ = fs::remove_file(&probe).await;
+    insensitive
+}
+
+// Nothing is written into a source, an existing name is looked up with its case swapped instead
+pub async fn is_source_case_insensitive(directory: &Path) -> bool {
+    let Ok(mut entries) = fs::read_dir(directory).await else {
+        return PLATFORM_CASE_INSENSITIVE;
+    };
+    let mut names = HashSet::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Ok(name) = entry.file_name().into_string() {
+            names.insert(name);
+        }
+    }
+    let swapped = names.iter().find_map(|name| {
+        let upper = name.to_uppercase();
+        let swapped = if upper != *name { upper } else { name.to_lowercase() };
+        (swapped != *name && !names.contains(&swapped)).then_some(swapped)
+    });
+    match swapped {
+        Some(swapped) => fs::symlink_metadata(directory.join(swapped)).await.is_ok(),
+        None => PLATFORM_CASE_INSENSITIVE,
+    }
+}
+
+// A directory can carry its own rule, otherwise the one probed for the volume applies
+pub async fn is_directory_case_insensitive(directory: &Path, volume_insensitive: bool) -> bool {
+    let directory = PathBuf::from(directory);
+    spawn_blocking(move || directory_case_insensitive(&directory))
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(volume_insensitive)
+}
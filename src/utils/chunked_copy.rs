@@ -0,0 +1,90 @@
+use crate::model::core::backup::copy_progress::CopyProgress;
+use std::alloc::{self, Layout};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+// Unbuffered IO needs sector aligned buffers, offsets and lengths
+pub const ALIGNMENT: usize = 4096;
+
+pub struct AlignedBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+unsafe impl Send for AlignedBuffer {}
+
+impl AlignedBuffer {
+    pub fn new(size: usize) -> Self {
+        let size = size.max(ALIGNMENT).next_multiple_of(ALIGNMENT);
+        let layout = Layout::from_size_align(size, ALIGNMENT).unwrap_or_else(|_| {
+            panic!("invalid buffer layout: {size} bytes aligned to {ALIGNMENT}")
+        });
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+        Self { ptr, layout }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+// Calls before_partial_write ahead of the final chunk when it is not aligned
+pub fn copy_chunks(
+    source: &mut File,
+    destination: &mut File,
+    buffer: &mut [u8],
+    length: u64,
+    progress: &CopyProgress,
+    mut before_partial_write: impl FnMut(&File) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut copied = 0_u64;
+    loop {
+        let read = fill_buffer(source, buffer)?;
+        if read == 0 {
+            break;
+        }
+        if read % ALIGNMENT != 0 {
+            before_partial_write(destination)?;
+        }
+        destination.write_all(&buffer[..read])?;
+
+        copied += read as u64;
+        progress.update(copied, length.max(copied));
+        if progress.is_cancelled() {
+            Err(io::Error::from(io::ErrorKind::Interrupted))?
+        }
+    }
+    Ok(())
+}
+
+fn fill_buffer(source: &mut File, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match source.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
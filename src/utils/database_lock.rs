@@ -1,34 +1,75 @@
 use crate::model::error::database::DatabaseError;
 use crate::model::error::Error;
-use crate::platform::constants::DATABASE_LOCK_PATH;
-use std::fs;
-use tokio::fs::File;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{env, fs, io, process};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub user: String,
+    pub since: DateTime<Local>,
+}
 
 #[derive(Debug)]
 pub struct DatabaseLock {
-    _private: (),
+    path: PathBuf,
 }
 
 impl DatabaseLock {
-    pub async fn acquire() -> Result<Self, Error> {
-        let lock = Self { _private: () };
-        if tokio::fs::metadata(DATABASE_LOCK_PATH).await.is_err() {
-            File::create(&DATABASE_LOCK_PATH)
-                .await
-                .map_err(DatabaseError::LockDatabaseFailed)?;
-            Ok(lock)
-        } else {
-            Err(DatabaseError::LockDatabaseFailed("Lock file already exists."))?
-        }
+    // Kept beside the database it guards
+    pub fn lock_path(database_path: &Path) -> PathBuf {
+        database_path.with_extension("lock")
+    }
+
+    pub async fn acquire(database_path: &Path) -> Result<Self, Error> {
+        let path = Self::lock_path(database_path);
+        let mut file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+                Err(DatabaseError::DatabaseInUse)?
+            }
+            Err(err) => Err(DatabaseError::LockDatabaseFailed(err))?,
+        };
+        // Created only once the file is ours, dropping it removes the file
+        let lock = Self { path };
+
+        // Recorded so a second instance can tell the user who holds the database
+        let holder = LockHolder {
+            pid: process::id(),
+            user: env::var("USERNAME")
+                .or_else(|_| env::var("USER"))
+                .unwrap_or_default(),
+            since: Local::now(),
+        };
+        let content = serde_json::to_vec(&holder).map_err(DatabaseError::LockDatabaseFailed)?;
+        file.write_all(&content)
+            .await
+            .map_err(DatabaseError::LockDatabaseFailed)?;
+        Ok(lock)
+    }
+
+    // None when the lock file is unreadable or was left by a version that did not record one
+    pub async fn holder(database_path: &Path) -> Option<LockHolder> {
+        let content = tokio::fs::read(Self::lock_path(database_path)).await.ok()?;
+        serde_json::from_slice(&content).ok()
     }
-    
+
     pub fn release(&self) {
-        let _ = fs::remove_file(DATABASE_LOCK_PATH);
+        let _ = fs::remove_file(&self.path);
     }
 }
 
 impl Drop for DatabaseLock {
     fn drop(&mut self) {
-        let _ = fs::remove_file(DATABASE_LOCK_PATH);
+        let _ = fs::remove_file(&self.path);
     }
 }
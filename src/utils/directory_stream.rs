@@ -0,0 +1,39 @@
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::ReadDir;
+use tokio::sync::Semaphore;
+
+// Yields entries as the OS returns them so huge directories are never held in memory
+pub struct DirectoryStream {
+    path: PathBuf,
+    reader: ReadDir,
+    semaphore: Arc<Semaphore>,
+}
+
+impl DirectoryStream {
+    pub fn new(path: &Path, reader: ReadDir, semaphore: Arc<Semaphore>) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            reader,
+            semaphore,
+        }
+    }
+
+    pub async fn next_entry(&mut self) -> Result<Option<PathBuf>, Error> {
+        let _permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(IOError::SemaphoreClosed)?;
+
+        let entry = self
+            .reader
+            .next_entry()
+            .await
+            .map_err(|err| IOError::ReadDirectoryFailed(&self.path, err))?;
+        Ok(entry.map(|entry| entry.path()))
+    }
+}
@@ -1,50 +1,121 @@
+use crate::model::core::backup::execution::HashType;
 use crate::model::error::io::IOError;
 use crate::model::error::Error;
 use blake2::{Blake2b512, Blake2s256};
 use digest::{Digest, DynDigest, HashMarker};
 use md5::Md5;
 use sha2::Sha256;
-use std::fs::File;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
 
-pub fn md5(path: PathBuf) -> Result<Vec<u8>, Error> {
+const CHUNK_SIZE: usize = 65536;
+
+pub async fn md5(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = Md5::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
 }
 
-pub fn sha3(path: PathBuf) -> Result<Vec<u8>, Error> {
+pub async fn sha3(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = sha3::Sha3_256::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
 }
 
-pub fn sha256(path: PathBuf) -> Result<Vec<u8>, Error> {
+pub async fn sha256(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = Sha256::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
 }
 
-pub fn blake2b(path: PathBuf) -> Result<Vec<u8>, Error> {
+pub async fn blake2b(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = Blake2b512::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
 }
 
-pub fn blake2s(path: PathBuf) -> Result<Vec<u8>, Error> {
+pub async fn blake2s(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = Blake2s256::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
 }
 
-pub fn blake3(path: PathBuf) -> Result<Vec<u8>, Error> {
+pub async fn blake3(path: &Path) -> Result<Vec<u8>, Error> {
     let hasher = blake3::Hasher::new();
-    file_hash(path, hasher)
+    file_hash(path, hasher).await
+}
+
+pub fn buffer_hash(hash_type: HashType, buffer: &[u8]) -> Vec<u8> {
+    match hash_type {
+        HashType::MD5 => Md5::digest(buffer).to_vec(),
+        HashType::SHA3 => sha3::Sha3_256::digest(buffer).to_vec(),
+        HashType::SHA256 => Sha256::digest(buffer).to_vec(),
+        HashType::BLAKE2B => Blake2b512::digest(buffer).to_vec(),
+        HashType::BLAKE2S => Blake2s256::digest(buffer).to_vec(),
+        HashType::BLAKE3 => blake3::hash(buffer).as_bytes().to_vec(),
+    }
+}
+
+// Set once the future awaiting a blocking hash is dropped, the hash stops at its next chunk
+#[derive(Default)]
+pub struct HashCancellation(Arc<AtomicBool>);
+
+impl HashCancellation {
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+impl Drop for HashCancellation {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+// Reads and hashes on the calling thread, for callers running it at a lowered priority
+pub fn blocking_file_hash(
+    path: &Path,
+    hash_type: HashType,
+    cancelled: &AtomicBool,
+) -> Result<Vec<u8>, Error> {
+    let mut hasher: Box<dyn DynDigest> = match hash_type {
+        HashType::MD5 => Box::new(Md5::new()),
+        HashType::SHA3 => Box::new(sha3::Sha3_256::new()),
+        HashType::SHA256 => Box::new(Sha256::new()),
+        HashType::BLAKE2B => Box::new(Blake2b512::new()),
+        HashType::BLAKE2S => Box::new(Blake2s256::new()),
+        HashType::BLAKE3 => Box::new(blake3::Hasher::new()),
+    };
+    let mut file =
+        std::fs::File::open(path).map_err(|err| IOError::ReadFileFailed(path, err))?;
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            Err(IOError::HashFileCancelled(path))?
+        }
+        let bytes_read = file
+            .read(&mut buffer)
+            .map_err(|err| IOError::ReadFileFailed(path, err))?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hasher.finalize().to_vec())
 }
 
-fn file_hash(path: PathBuf, mut hasher: impl HashMarker + DynDigest) -> Result<Vec<u8>, Error> {
-    let mut file = File::open(&path).map_err(|err| IOError::ReadFileFailed(path.clone(), err))?;
-    let mut buffer = [0; 65536];
+async fn file_hash(
+    path: &Path,
+    mut hasher: impl HashMarker + DynDigest + Send,
+) -> Result<Vec<u8>, Error> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|err| IOError::ReadFileFailed(path, err))?;
+    let mut buffer = vec![0; CHUNK_SIZE];
     loop {
         let bytes_read = file
             .read(&mut buffer)
-            .map_err(|err| IOError::ReadFileFailed(path.clone(), err))?;
+            .await
+            .map_err(|err| IOError::ReadFileFailed(path, err))?;
         if bytes_read == 0 {
             break;
         }
@@ -52,3 +123,21 @@ fn file_hash(path: PathBuf, mut hasher: impl HashMarker + DynDigest) -> Result<V
     }
     Ok(Box::new(hasher).finalize().to_vec())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocking_hash_stops_once_cancelled() {
+        let path = std::env::temp_dir().join("mirrorsphere_blocking_hash_stops_once_cancelled");
+        std::fs::write(&path, vec![7; CHUNK_SIZE * 4]).unwrap();
+
+        let hash = blocking_file_hash(&path, HashType::BLAKE3, &AtomicBool::new(false));
+        let cancelled = blocking_file_hash(&path, HashType::BLAKE3, &AtomicBool::new(true));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(hash.unwrap(), blake3::hash(&vec![7; CHUNK_SIZE * 4]).as_bytes().to_vec());
+        assert!(cancelled.is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::model::error::Error;
+use crate::model::error::io::IOError;
+use crate::platform::file_system::is_local_file;
+#[cfg(unix)]
+use memmap2::Advice;
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+const WINDOW_SIZE: usize = 16777216;
+
+// None when the files are below the threshold or not both on local disks, mapping a
+// network file turns a dropped connection into a fault instead of an IO error
+pub fn mapped_compare(
+    source: &Path,
+    destination: &Path,
+    threshold: u64,
+) -> Result<Option<bool>, Error> {
+    let source_file = File::open(source).map_err(|err| IOError::ReadFileFailed(source, err))?;
+    let destination_file =
+        File::open(destination).map_err(|err| IOError::ReadFileFailed(destination, err))?;
+    let length = source_file
+        .metadata()
+        .map_err(|err| IOError::GetMetadataFailed(source, err))?
+        .len();
+    if length < threshold || !is_local_file(&source_file) || !is_local_file(&destination_file) {
+        return Ok(None);
+    }
+    let destination_length = destination_file
+        .metadata()
+        .map_err(|err| IOError::GetMetadataFailed(destination, err))?
+        .len();
+    if destination_length != length {
+        return Ok(Some(false));
+    }
+
+    // Pages are faulted in window by window, so an early difference never reads the rest
+    let source_map =
+        unsafe { Mmap::map(&source_file) }.map_err(|err| IOError::ReadFileFailed(source, err))?;
+    let destination_map = unsafe { Mmap::map(&destination_file) }
+        .map_err(|err| IOError::ReadFileFailed(destination, err))?;
+    #[cfg(unix)]
+    {
+        let _ = source_map.advise(Advice::Sequential);
+        let _ = destination_map.advise(Advice::Sequential);
+    }
+
+    let identical = source_map
+        .chunks(WINDOW_SIZE)
+        .zip(destination_map.chunks(WINDOW_SIZE))
+        .all(|(source_window, destination_window)| source_window == destination_window);
+    Ok(Some(identical))
+}
@@ -1,5 +1,14 @@
 pub mod assets;
+pub mod case_probe;
+pub mod chunked_copy;
 pub mod database_lock;
+pub mod directory_stream;
 pub mod file_hash;
 pub mod font;
 pub mod logging;
+pub mod mapped_compare;
+pub mod operation_metrics;
+pub mod portable_name;
+pub mod timestamp_probe;
+pub mod unicode_name;
+pub mod wildcard;
@@ -0,0 +1,85 @@
+use dashmap::DashMap;
+use std::cmp::Reverse;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+// Filled by #[timed], the functions it wraps have no manager to report to
+static OPERATION_METRICS: LazyLock<DashMap<(&'static str, &'static str), Counters>> =
+    LazyLock::new(DashMap::new);
+
+#[derive(Default)]
+struct Counters {
+    calls: AtomicU64,
+    failed: AtomicU64,
+    total_micros: AtomicU64,
+    max_micros: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OperationStatistics {
+    pub module: &'static str,
+    pub operation: &'static str,
+    pub calls: u64,
+    pub failed: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+pub fn record_operation(
+    module: &'static str,
+    operation: &'static str,
+    elapsed: Duration,
+    success: bool,
+) {
+    let micros = elapsed.as_micros() as u64;
+    let counters = OPERATION_METRICS.entry((module, operation)).or_default();
+    counters.calls.fetch_add(1, Ordering::Relaxed);
+    if !success {
+        counters.failed.fetch_add(1, Ordering::Relaxed);
+    }
+    counters.total_micros.fetch_add(micros, Ordering::Relaxed);
+    counters.max_micros.fetch_max(micros, Ordering::Relaxed);
+}
+
+pub fn operation_statistics() -> Vec<OperationStatistics> {
+    let mut statistics = OPERATION_METRICS
+        .iter()
+        .map(|entry| {
+            let (module, operation) = *entry.key();
+            let counters = entry.value();
+            OperationStatistics {
+                module,
+                operation,
+                calls: counters.calls.load(Ordering::Relaxed),
+                failed: counters.failed.load(Ordering::Relaxed),
+                total: Duration::from_micros(counters.total_micros.load(Ordering::Relaxed)),
+                max: Duration::from_micros(counters.max_micros.load(Ordering::Relaxed)),
+            }
+        })
+        .collect::<Vec<_>>();
+    statistics.sort_by_key(|statistics| Reverse(statistics.total));
+    statistics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_calls_failures_and_durations() {
+        let operation = "records_calls_failures_and_durations";
+        record_operation(module_path!(), operation, Duration::from_millis(3), true);
+        record_operation(module_path!(), operation, Duration::from_millis(5), false);
+
+        let statistics = operation_statistics()
+            .into_iter()
+            .find(|statistics| statistics.operation == operation)
+            .unwrap();
+
+        assert_eq!(statistics.calls, 2);
+        assert_eq!(statistics.failed, 1);
+        assert_eq!(statistics.total, Duration::from_millis(8));
+        assert_eq!(statistics.max, Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+
+const RESERVED_CHARACTERS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+const DEFAULT_REPLACEMENT: &str = "_";
+
+pub fn is_reserved_character(character: char) -> bool {
+    RESERVED_CHARACTERS.contains(&character) || character < ' '
+}
+
+// None when Windows and exFAT accept the name as it is, device names stay reserved with any
+// extension and trailing dots or spaces would be stripped on creation
+pub fn portable_name(name: &OsStr, replacements: &HashMap<String, String>) -> Option<OsString> {
+    let replacement = |character: char| {
+        replacements
+            .get(character.encode_utf8(&mut [0; 4]) as &str)
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_REPLACEMENT)
+    };
+
+    let original = name.to_string_lossy();
+    let trimmed = original.trim_end_matches(['.', ' ']);
+    let mut portable = String::with_capacity(original.len());
+    for character in trimmed.chars() {
+        if is_reserved_character(character) {
+            portable.push_str(replacement(character));
+        } else {
+            portable.push(character);
+        }
+    }
+    for character in original[trimmed.len()..].chars() {
+        portable.push_str(replacement(character));
+    }
+
+    let stem_length = portable.find('.').unwrap_or(portable.len());
+    let stem = portable[..stem_length].trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        portable.insert_str(stem_length, DEFAULT_REPLACEMENT);
+    }
+
+    (OsStr::new(&portable) != name).then(|| OsString::from(portable))
+}
@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use tokio::fs;
+use tokio::task::spawn_blocking;
+
+// NTFS keeps 100ns, exFAT 10ms, many shares whole seconds and FAT 2 seconds
+const RESOLUTION_STEPS: [Duration; 7] = [
+    Duration::from_nanos(1),
+    Duration::from_nanos(100),
+    Duration::from_micros(1),
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+];
+// An odd second with every sub-second digit set shows how much of it gets kept
+const PROBE_TIME: Duration = Duration::new(1_700_000_001, 123_456_789);
+const SAMPLE_ENTRIES: usize = 64;
+// Fewer whole even seconds than this may just be chance
+const EVEN_SECONDS_SAMPLES: usize = 8;
+
+// Writes a modification time to a scratch file and reads back what was stored
+pub async fn written_resolution(probe: &Path) -> Duration {
+    let probe = PathBuf::from(probe);
+    let stored = spawn_blocking(move || {
+        let stored = File::create(&probe)
+            .and_then(|file| {
+                file.set_modified(UNIX_EPOCH + PROBE_TIME)?;
+                file.metadata()?.modified()
+            })
+            .ok();
+        let _ = std::fs::remove_file(&probe);
+        stored
+    })
+    .await
+    .ok()
+    .flatten();
+    let Some(stored) = stored else {
+        return RESOLUTION_STEPS[0];
+    };
+    let written = UNIX_EPOCH + PROBE_TIME;
+    let distance = stored
+        .duration_since(written)
+        .unwrap_or_else(|err| err.duration());
+    RESOLUTION_STEPS
+        .into_iter()
+        .find(|step| distance < *step)
+        .unwrap_or(RESOLUTION_STEPS[RESOLUTION_STEPS.len() - 1])
+}
+
+// Nothing may be written into a source, so the modification times it already holds are read
+pub async fn sampled_resolution(directory: &Path) -> Duration {
+    let Ok(mut entries) = fs::read_dir(directory).await else {
+        return RESOLUTION_STEPS[0];
+    };
+    let mut samples = Vec::new();
+    while samples.len() < SAMPLE_ENTRIES
+        && let Ok(Some(entry)) = entries.next_entry().await
+    {
+        if let Ok(modified) = entry.metadata().await.and_then(|metadata| metadata.modified())
+            && let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH)
+        {
+            samples.push(since_epoch);
+        }
+    }
+    resolution_of(&samples)
+}
+
+fn resolution_of(samples: &[Duration]) -> Duration {
+    if samples.is_empty() {
+        return RESOLUTION_STEPS[0];
+    }
+    if samples.iter().any(|sample| sample.subsec_nanos() != 0) {
+        // The coarsest sub-second step every sample is a multiple of
+        return RESOLUTION_STEPS[..5]
+            .iter()
+            .rev()
+            .copied()
+            .find(|step| {
+                let step = step.as_nanos() as u32;
+                samples.iter().all(|sample| sample.subsec_nanos() % step == 0)
+            })
+            .unwrap_or(RESOLUTION_STEPS[0]);
+    }
+    let even = samples.iter().all(|sample| sample.as_secs() % 2 == 0);
+    match even && samples.len() >= EVEN_SECONDS_SAMPLES {
+        true => RESOLUTION_STEPS[6],
+        false => RESOLUTION_STEPS[5],
+    }
+}
@@ -0,0 +1,47 @@
+use std::ffi::{OsStr, OsString};
+use unicode_normalization::UnicodeNormalization;
+
+// Composed and decomposed spellings of a name, ASCII names have none
+pub fn normalized_variants(name: &OsStr) -> Vec<OsString> {
+    let Some(name) = name.to_str().filter(|name| !name.is_ascii()) else {
+        return Vec::new();
+    };
+    let composed: String = name.nfc().collect();
+    let decomposed: String = name.nfd().collect();
+    let mut variants = Vec::new();
+    for variant in [composed, decomposed] {
+        if variant != name && !variants.contains(&OsString::from(&variant)) {
+            variants.push(OsString::from(variant));
+        }
+    }
+    variants
+}
+
+// Key under which a case-insensitive filesystem treats two names as the same entry
+pub fn folded_name(name: &OsStr) -> String {
+    name.to_string_lossy().nfc().collect::<String>().to_lowercase()
+}
+
+// Composed and decomposed spellings always match, case only where the filesystem ignores it
+pub fn comparison_key(name: &OsStr, case_insensitive: bool) -> String {
+    match case_insensitive {
+        true => folded_name(name),
+        false => name.to_string_lossy().nfc().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composed_and_decomposed_names_share_a_key() {
+        let composed = OsStr::new("caf\u{e9}");
+        let decomposed = OsStr::new("cafe\u{301}");
+
+        assert_eq!(normalized_variants(composed), vec![OsString::from(decomposed)]);
+        assert_eq!(comparison_key(composed, false), comparison_key(decomposed, false));
+        assert_eq!(comparison_key(composed, true), comparison_key(OsStr::new("CAF\u{c9}"), true));
+        assert_ne!(comparison_key(composed, false), comparison_key(OsStr::new("CAF\u{c9}"), false));
+    }
+}